@@ -0,0 +1,148 @@
+//! Privacy classification primitives shared across the A3S stack.
+//!
+//! SafeClaw, a3s-code, and a3s-gateway all need the same answer to "how
+//! sensitive is this text?" — this crate is the single source of truth
+//! for the sensitivity scale, the rule format, and the regex classifier
+//! so the three never drift apart.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// How sensitive a piece of content is. Ordered: a higher level always
+/// demands at least the handling of the levels below it.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitivityLevel {
+    /// Safe to publish.
+    Public,
+    /// Ordinary conversation content.
+    #[default]
+    Normal,
+    /// Personal or confidential; restrict exposure.
+    Sensitive,
+    /// Regulated or secret material; strongest handling applies.
+    HighlySensitive,
+}
+
+/// One classification rule: content matching `pattern` is at least `level`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassificationRule {
+    pub name: String,
+    /// Regex applied to the raw text.
+    pub pattern: String,
+    pub level: SensitivityLevel,
+}
+
+impl ClassificationRule {
+    pub fn new(name: &str, pattern: &str, level: SensitivityLevel) -> Self {
+        Self {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            level,
+        }
+    }
+}
+
+/// Baseline PII rules every consumer starts from; deployments extend the
+/// list through their own config.
+pub fn default_classification_rules() -> Vec<ClassificationRule> {
+    vec![
+        ClassificationRule::new(
+            "email_address",
+            r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+            SensitivityLevel::Sensitive,
+        ),
+        ClassificationRule::new(
+            "us_ssn",
+            r"\b\d{3}-\d{2}-\d{4}\b",
+            SensitivityLevel::HighlySensitive,
+        ),
+        ClassificationRule::new(
+            "payment_card",
+            r"\b(?:\d[ -]?){13,16}\b",
+            SensitivityLevel::HighlySensitive,
+        ),
+        ClassificationRule::new(
+            "phone_number",
+            r"\+\d{7,15}\b",
+            SensitivityLevel::Sensitive,
+        ),
+    ]
+}
+
+/// Rule-based classifier. Rules with invalid regexes are skipped at
+/// construction rather than failing every classification.
+pub struct RegexClassifier {
+    compiled: Vec<(Regex, SensitivityLevel)>,
+}
+
+impl RegexClassifier {
+    pub fn new(rules: &[ClassificationRule]) -> Self {
+        Self {
+            compiled: rules
+                .iter()
+                .filter_map(|rule| {
+                    Regex::new(&rule.pattern)
+                        .ok()
+                        .map(|regex| (regex, rule.level))
+                })
+                .collect(),
+        }
+    }
+
+    /// The highest level of any matching rule; `Normal` when none match.
+    pub fn classify(&self, text: &str) -> SensitivityLevel {
+        self.compiled
+            .iter()
+            .filter(|(regex, _)| regex.is_match(text))
+            .map(|(_, level)| *level)
+            .max()
+            .unwrap_or(SensitivityLevel::Normal)
+    }
+}
+
+impl Default for RegexClassifier {
+    fn default() -> Self {
+        Self::new(&default_classification_rules())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_are_ordered() {
+        assert!(SensitivityLevel::Public < SensitivityLevel::Normal);
+        assert!(SensitivityLevel::Sensitive < SensitivityLevel::HighlySensitive);
+    }
+
+    #[test]
+    fn default_rules_catch_obvious_pii() {
+        let classifier = RegexClassifier::default();
+        assert_eq!(
+            classifier.classify("mail me at sam@example.org"),
+            SensitivityLevel::Sensitive
+        );
+        assert_eq!(
+            classifier.classify("ssn 123-45-6789"),
+            SensitivityLevel::HighlySensitive
+        );
+        assert_eq!(
+            classifier.classify("nothing personal here"),
+            SensitivityLevel::Normal
+        );
+    }
+
+    #[test]
+    fn invalid_rule_patterns_are_skipped() {
+        let classifier = RegexClassifier::new(&[ClassificationRule::new(
+            "broken",
+            "(unclosed",
+            SensitivityLevel::HighlySensitive,
+        )]);
+        assert_eq!(classifier.classify("anything"), SensitivityLevel::Normal);
+    }
+}
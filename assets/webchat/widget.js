@@ -0,0 +1,14 @@
+/* SafeClaw WebChat widget — built artifact. */
+(function () {
+  "use strict";
+  window.SafeClawWidget = {
+    mount: function (opts) {
+      var root = document.createElement("div");
+      root.id = "safeclaw-webchat";
+      root.dataset.endpoint = opts.endpoint;
+      document.body.appendChild(root);
+      var ws = new WebSocket(opts.endpoint.replace(/^http/, "ws") + "/ws");
+      root.__safeclawSocket = ws;
+    },
+  };
+})();
@@ -0,0 +1,467 @@
+//! Bulk session operations — archive, delete, re-model many at once.
+//!
+//! The UI lists dozens of stale sessions and acting on them one by one is
+//! miserable. `POST /api/agent/sessions/bulk` (admin role; the route is
+//! mounted behind the admin auth middleware) takes one operation plus
+//! either an explicit ID list or a filter (idle longer than X, archived,
+//! model, persona), applies it with bounded concurrency so a huge delete
+//! never monopolizes the engine's session locks, and reports per-session
+//! success/failure with a summary. `dry_run` returns what would be
+//! affected without touching anything — destructive operations are meant
+//! to be previewed first. Every affected session gets its own audit entry,
+//! plus one batch summary entry.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::audit::log::{AuditLog, AuditSeverity};
+use crate::error::Result;
+
+/// Audit category for bulk session operations.
+pub const AUDIT_CATEGORY_BULK: &str = "bulk_session";
+
+/// Sessions mutated at once; keeps the per-session locks breathing.
+const BULK_CONCURRENCY: usize = 8;
+
+/// The session attributes the filter evaluates — the engine projects its
+/// session map into these.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMeta {
+    pub session_id: String,
+    pub last_activity: i64,
+    pub archived: bool,
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+}
+
+/// One bulk operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "operation")]
+pub enum BulkOperation {
+    Archive,
+    Unarchive,
+    Delete,
+    SetModel { model: String },
+    SetPermissionMode { mode: String },
+}
+
+impl BulkOperation {
+    /// Destructive operations are the ones `dry_run` exists for.
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, Self::Delete)
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Archive => "archive".into(),
+            Self::Unarchive => "unarchive".into(),
+            Self::Delete => "delete".into(),
+            Self::SetModel { model } => format!("set_model={model}"),
+            Self::SetPermissionMode { mode } => format!("set_permission_mode={mode}"),
+        }
+    }
+}
+
+/// Selects sessions by attributes; all set conditions must hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionFilter {
+    /// Idle longer than this many seconds.
+    pub older_than_secs: Option<i64>,
+    pub archived: Option<bool>,
+    pub model: Option<String>,
+    pub persona: Option<String>,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, meta: &SessionMeta, now: i64) -> bool {
+        self.older_than_secs
+            .is_none_or(|secs| now - meta.last_activity > secs)
+            && self.archived.is_none_or(|archived| meta.archived == archived)
+            && self.model.as_ref().is_none_or(|model| &meta.model == model)
+            && self
+                .persona
+                .as_ref()
+                .is_none_or(|persona| meta.persona.as_ref() == Some(persona))
+    }
+}
+
+/// `POST /api/agent/sessions/bulk` body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkRequest {
+    #[serde(flatten)]
+    pub operation: BulkOperation,
+    /// Explicit session IDs; takes precedence over `filter`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<SessionFilter>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Outcome for one session.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionOutcome {
+    pub session_id: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The response: per-session outcomes plus the summary counts.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkResult {
+    pub dry_run: bool,
+    pub matched: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<SessionOutcome>,
+}
+
+/// The engine's side of a bulk run: enumerate the session map and apply
+/// one operation to one session. `apply` takes the per-session lock
+/// internally, so the executor never holds the whole map.
+#[async_trait]
+pub trait SessionOperations: Send + Sync {
+    async fn list_sessions(&self) -> Vec<SessionMeta>;
+    async fn apply(&self, session_id: &str, operation: &BulkOperation) -> Result<()>;
+}
+
+/// Resolve the request's selector against the session map.
+async fn select_sessions(ops: &dyn SessionOperations, request: &BulkRequest, now: i64) -> Vec<String> {
+    if !request.ids.is_empty() {
+        return request.ids.clone();
+    }
+    let filter = request.filter.clone().unwrap_or_default();
+    let mut ids: Vec<String> = ops
+        .list_sessions()
+        .await
+        .into_iter()
+        .filter(|meta| filter.matches(meta, now))
+        .map(|meta| meta.session_id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Execute one bulk request. Dry runs select but never apply, so a
+/// `dry_run` preview and the real run over unchanged state affect the same
+/// sessions.
+pub async fn execute(
+    ops: Arc<dyn SessionOperations>,
+    audit: &AuditLog,
+    request: &BulkRequest,
+    now: i64,
+) -> BulkResult {
+    let ids = select_sessions(ops.as_ref(), request, now).await;
+    if request.dry_run {
+        return BulkResult {
+            dry_run: true,
+            matched: ids.len(),
+            succeeded: 0,
+            failed: 0,
+            results: ids
+                .into_iter()
+                .map(|session_id| SessionOutcome {
+                    session_id,
+                    ok: true,
+                    error: None,
+                })
+                .collect(),
+        };
+    }
+
+    let semaphore = Arc::new(Semaphore::new(BULK_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for session_id in &ids {
+        let ops = Arc::clone(&ops);
+        let operation = request.operation.clone();
+        let semaphore = Arc::clone(&semaphore);
+        let session_id = session_id.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire().await.expect("bulk semaphore closed");
+            let outcome = ops.apply(&session_id, &operation).await;
+            (session_id, outcome)
+        });
+    }
+
+    let mut results: Vec<SessionOutcome> = Vec::with_capacity(ids.len());
+    while let Some(joined) = tasks.join_next().await {
+        let (session_id, outcome) = joined.expect("bulk task panicked");
+        results.push(SessionOutcome {
+            ok: outcome.is_ok(),
+            error: outcome.err().map(|e| e.to_string()),
+            session_id,
+        });
+    }
+    results.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+
+    let succeeded = results.iter().filter(|r| r.ok).count();
+    let describe = request.operation.describe();
+    for result in &results {
+        let (severity, message) = if result.ok {
+            (AuditSeverity::Info, format!("bulk {describe}"))
+        } else {
+            (
+                AuditSeverity::Warning,
+                format!(
+                    "bulk {describe} failed: {}",
+                    result.error.as_deref().unwrap_or("unknown")
+                ),
+            )
+        };
+        audit.record("bulk", &result.session_id, severity, AUDIT_CATEGORY_BULK, &message, now);
+    }
+    audit.record(
+        "bulk",
+        "-",
+        AuditSeverity::Info,
+        AUDIT_CATEGORY_BULK,
+        &format!(
+            "bulk {describe}: {} of {} sessions succeeded",
+            succeeded,
+            results.len()
+        ),
+        now,
+    );
+
+    BulkResult {
+        dry_run: false,
+        matched: results.len(),
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    }
+}
+
+/// State behind the bulk route.
+pub struct BulkState {
+    pub ops: Arc<dyn SessionOperations>,
+    pub audit: Arc<AuditLog>,
+}
+
+/// Routes mounted under `/api/agent/sessions` behind the admin auth
+/// middleware.
+pub fn bulk_routes(state: Arc<BulkState>) -> Router {
+    Router::new()
+        .route("/bulk", post(bulk_handler))
+        .with_state(state)
+}
+
+/// `POST /api/agent/sessions/bulk`.
+async fn bulk_handler(
+    State(state): State<Arc<BulkState>>,
+    Json(request): Json<BulkRequest>,
+) -> Json<BulkResult> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    Json(execute(Arc::clone(&state.ops), &state.audit, &request, now).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::audit::log::AuditIngestionConfig;
+    use crate::error::SafeClawError;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn meta(id: &str, idle_secs: i64, archived: bool, model: &str) -> SessionMeta {
+        SessionMeta {
+            session_id: id.into(),
+            last_activity: NOW - idle_secs,
+            archived,
+            model: model.into(),
+            persona: None,
+        }
+    }
+
+    struct MockOps {
+        sessions: Vec<SessionMeta>,
+        fail_ids: HashSet<String>,
+        applied: Mutex<Vec<(String, BulkOperation)>>,
+    }
+
+    impl MockOps {
+        fn new(sessions: Vec<SessionMeta>) -> Self {
+            Self {
+                sessions,
+                fail_ids: HashSet::new(),
+                applied: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SessionOperations for MockOps {
+        async fn list_sessions(&self) -> Vec<SessionMeta> {
+            self.sessions.clone()
+        }
+
+        async fn apply(&self, session_id: &str, operation: &BulkOperation) -> Result<()> {
+            if self.fail_ids.contains(session_id) {
+                return Err(SafeClawError::Session(format!("{session_id} is busy")));
+            }
+            self.applied
+                .lock()
+                .unwrap()
+                .push((session_id.to_string(), operation.clone()));
+            Ok(())
+        }
+    }
+
+    fn request(operation: BulkOperation, filter: SessionFilter, dry_run: bool) -> BulkRequest {
+        BulkRequest {
+            operation,
+            ids: Vec::new(),
+            filter: Some(filter),
+            dry_run,
+        }
+    }
+
+    #[test]
+    fn filters_combine_idle_archived_model_and_persona() {
+        let filter = SessionFilter {
+            older_than_secs: Some(3_600),
+            archived: Some(false),
+            model: Some("claude".into()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&meta("s1", 7_200, false, "claude"), NOW));
+        assert!(!filter.matches(&meta("s2", 60, false, "claude"), NOW));
+        assert!(!filter.matches(&meta("s3", 7_200, true, "claude"), NOW));
+        assert!(!filter.matches(&meta("s4", 7_200, false, "gpt"), NOW));
+
+        let persona_only = SessionFilter {
+            persona: Some("support".into()),
+            ..Default::default()
+        };
+        let mut with_persona = meta("s5", 0, false, "claude");
+        with_persona.persona = Some("support".into());
+        assert!(persona_only.matches(&with_persona, NOW));
+        assert!(!persona_only.matches(&meta("s6", 0, false, "claude"), NOW));
+    }
+
+    #[tokio::test]
+    async fn dry_run_previews_exactly_what_the_real_run_affects() {
+        let ops = Arc::new(MockOps::new(vec![
+            meta("s1", 7_200, false, "claude"),
+            meta("s2", 60, false, "claude"),
+            meta("s3", 9_000, false, "claude"),
+        ]));
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        let filter = SessionFilter {
+            older_than_secs: Some(3_600),
+            ..Default::default()
+        };
+
+        let preview = execute(
+            Arc::clone(&ops) as Arc<dyn SessionOperations>,
+            &audit,
+            &request(BulkOperation::Delete, filter.clone(), true),
+            NOW,
+        )
+        .await;
+        assert!(preview.dry_run);
+        assert_eq!(preview.matched, 2);
+        // Nothing was touched and nothing audited.
+        assert!(ops.applied.lock().unwrap().is_empty());
+        assert!(audit.events().is_empty());
+
+        let real = execute(
+            Arc::clone(&ops) as Arc<dyn SessionOperations>,
+            &audit,
+            &request(BulkOperation::Delete, filter, false),
+            NOW,
+        )
+        .await;
+        let previewed: Vec<&str> = preview.results.iter().map(|r| r.session_id.as_str()).collect();
+        let affected: Vec<&str> = real.results.iter().map(|r| r.session_id.as_str()).collect();
+        assert_eq!(previewed, affected);
+        assert_eq!(real.succeeded, 2);
+    }
+
+    #[tokio::test]
+    async fn partial_failures_are_reported_per_session_and_audited() {
+        let mut mock = MockOps::new(vec![
+            meta("s1", 7_200, false, "claude"),
+            meta("s2", 7_200, false, "claude"),
+            meta("s3", 7_200, false, "claude"),
+        ]);
+        mock.fail_ids.insert("s2".into());
+        let ops = Arc::new(mock);
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+
+        let result = execute(
+            Arc::clone(&ops) as Arc<dyn SessionOperations>,
+            &audit,
+            &request(BulkOperation::Archive, SessionFilter::default(), false),
+            NOW,
+        )
+        .await;
+
+        assert_eq!(result.matched, 3);
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(result.failed, 1);
+        let failed: Vec<&SessionOutcome> = result.results.iter().filter(|r| !r.ok).collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].session_id, "s2");
+        assert!(failed[0].error.as_deref().unwrap().contains("busy"));
+
+        // One entry per session plus the batch summary.
+        let events = audit.events();
+        assert_eq!(events.len(), 4);
+        assert!(events
+            .iter()
+            .any(|e| e.session_id == "-" && e.message.contains("2 of 3 sessions succeeded")));
+        assert!(events
+            .iter()
+            .any(|e| e.session_id == "s2" && e.severity == AuditSeverity::Warning));
+    }
+
+    #[tokio::test]
+    async fn explicit_ids_take_precedence_and_operations_carry_arguments() {
+        let ops = Arc::new(MockOps::new(vec![meta("s1", 0, false, "claude")]));
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        let request = BulkRequest {
+            operation: BulkOperation::SetModel {
+                model: "claude-fast".into(),
+            },
+            ids: vec!["s1".into()],
+            filter: Some(SessionFilter {
+                // The filter would match nothing; the ID list wins.
+                model: Some("nope".into()),
+                ..Default::default()
+            }),
+            dry_run: false,
+        };
+        let result = execute(Arc::clone(&ops) as Arc<dyn SessionOperations>, &audit, &request, NOW).await;
+        assert_eq!(result.succeeded, 1);
+        let applied = ops.applied.lock().unwrap();
+        assert_eq!(applied[0].0, "s1");
+        assert_eq!(
+            applied[0].1,
+            BulkOperation::SetModel {
+                model: "claude-fast".into()
+            }
+        );
+    }
+}
@@ -0,0 +1,709 @@
+//! CalDAV calendar tools for scheduling-aware assistance.
+//!
+//! Calendar questions dominate real usage, and guessing is worse than
+//! useless. Accounts are configured with a URL and a credential reference;
+//! the agent gets three tools — query a range, find free slots, create or
+//! update an event — backed by a [`CalDavBackend`] (a CalDAV
+//! calendar-query REPORT in production). Results are cached with a short
+//! TTL so a conversation's follow-up questions don't hammer the server.
+//! Calendar data is sensitive by definition: every returned summary is
+//! taint-registered (`Sensitive`, or `HighlySensitive` for a calendar
+//! marked `tee_only`, which also routes the turn to the TEE), so event
+//! details can't leak into non-sensitive outputs. Event creation is gated
+//! behind HITL confirmation by default. All times are epoch seconds UTC;
+//! timezone conversion happens at the parsing edge.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::{Result, SafeClawError};
+use crate::guard::taint::TaintRegistry;
+use crate::privacy::SensitivityLevel;
+
+/// Tool names exposed to the agent.
+pub const QUERY_TOOL: &str = "calendar_query";
+pub const FREE_SLOTS_TOOL: &str = "calendar_free_slots";
+pub const CREATE_TOOL: &str = "calendar_create_event";
+
+/// One configured CalDAV account.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarAccount {
+    pub name: String,
+    /// Collection URL, e.g. `https://dav.example.org/cal/work/`.
+    pub url: String,
+    pub username: String,
+    /// Credential-store reference for the account password.
+    pub password_ref: Option<String>,
+    /// Event details from this calendar only surface on TEE-routed turns.
+    pub tee_only: bool,
+}
+
+/// Configuration under `agent.calendar`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    pub accounts: Vec<CalendarAccount>,
+    /// Repeated queries within the TTL are answered from cache.
+    pub cache_ttl_secs: i64,
+    /// Gate event creation behind HITL confirmation.
+    pub confirm_creates: bool,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            accounts: Vec::new(),
+            cache_ttl_secs: 60,
+            confirm_creates: true,
+        }
+    }
+}
+
+/// One calendar event, times in epoch seconds UTC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub calendar: String,
+    pub summary: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// The CalDAV wire protocol — HTTP REPORT in production, mocked in tests.
+#[async_trait]
+pub trait CalDavBackend: Send + Sync {
+    async fn list_events(
+        &self,
+        account: &CalendarAccount,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<CalendarEvent>>;
+    async fn create_event(&self, account: &CalendarAccount, event: &CalendarEvent) -> Result<()>;
+}
+
+/// Production backend: a CalDAV `calendar-query` REPORT with basic auth,
+/// parsing the minimal ICS subset we need (UID, SUMMARY, UTC
+/// DTSTART/DTEND). Servers are asked to expand recurrences into UTC
+/// instances, so the parser never sees timezone-qualified times.
+pub struct HttpCalDav {
+    http: reqwest::Client,
+    /// Resolves a `password_ref` to the actual secret.
+    resolve_secret: SecretResolver,
+}
+
+/// Resolves a credential reference to the secret it names.
+pub type SecretResolver = Box<dyn Fn(&str) -> Result<String> + Send + Sync>;
+
+impl HttpCalDav {
+    pub fn new(resolve_secret: SecretResolver) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            resolve_secret,
+        }
+    }
+
+    fn password(&self, account: &CalendarAccount) -> Result<String> {
+        match &account.password_ref {
+            Some(reference) => (self.resolve_secret)(reference),
+            None => Ok(String::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl CalDavBackend for HttpCalDav {
+    async fn list_events(
+        &self,
+        account: &CalendarAccount,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<CalendarEvent>> {
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><c:calendar-data/></d:prop>
+  <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VEVENT">
+    <c:time-range start="{}" end="{}"/>
+  </c:comp-filter></c:comp-filter></c:filter>
+</c:calendar-query>"#,
+            format_ics_utc(start),
+            format_ics_utc(end)
+        );
+        let response = self
+            .http
+            .request(
+                reqwest::Method::from_bytes(b"REPORT").expect("REPORT is a valid method"),
+                &account.url,
+            )
+            .basic_auth(&account.username, Some(self.password(account)?))
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| SafeClawError::Channel(format!("CalDAV query failed: {e}")))?;
+        if !response.status().is_success() {
+            return Err(SafeClawError::Channel(format!(
+                "CalDAV server returned {} for `{}`",
+                response.status(),
+                account.name
+            )));
+        }
+        let text = response
+            .text()
+            .await
+            .map_err(|e| SafeClawError::Channel(format!("CalDAV response unreadable: {e}")))?;
+        parse_ics_events(&text, &account.name)
+    }
+
+    async fn create_event(&self, account: &CalendarAccount, event: &CalendarEvent) -> Result<()> {
+        let ics = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:{}\r\n\
+             SUMMARY:{}\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            event.uid,
+            event.summary,
+            format_ics_utc(event.start),
+            format_ics_utc(event.end)
+        );
+        let url = format!("{}/{}.ics", account.url.trim_end_matches('/'), event.uid);
+        let response = self
+            .http
+            .put(&url)
+            .basic_auth(&account.username, Some(self.password(account)?))
+            .header("Content-Type", "text/calendar")
+            .body(ics)
+            .send()
+            .await
+            .map_err(|e| SafeClawError::Channel(format!("CalDAV create failed: {e}")))?;
+        if !response.status().is_success() {
+            return Err(SafeClawError::Channel(format!(
+                "CalDAV server returned {} creating `{}`",
+                response.status(),
+                event.uid
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Pull the VEVENTs we understand out of a REPORT response body. The body
+/// is multistatus XML wrapping ICS text; scanning ICS content lines is
+/// robust to both wrapped and raw forms. Events with non-UTC or missing
+/// times are skipped rather than misplaced.
+fn parse_ics_events(body: &str, calendar: &str) -> Result<Vec<CalendarEvent>> {
+    #[derive(Default)]
+    struct PartialEvent {
+        uid: Option<String>,
+        summary: Option<String>,
+        start: Option<i64>,
+        end: Option<i64>,
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<PartialEvent> = None;
+    for line in body.lines().map(str::trim) {
+        if line.starts_with("BEGIN:VEVENT") {
+            current = Some(PartialEvent::default());
+            continue;
+        }
+        let Some(event) = current.as_mut() else {
+            continue;
+        };
+        if line.starts_with("END:VEVENT") {
+            if let Some(PartialEvent {
+                uid: Some(uid),
+                summary,
+                start: Some(start),
+                end: Some(end),
+            }) = current.take()
+            {
+                events.push(CalendarEvent {
+                    uid,
+                    calendar: calendar.to_string(),
+                    summary: summary.unwrap_or_default(),
+                    start,
+                    end,
+                });
+            }
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("UID:") {
+            event.uid = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("SUMMARY:") {
+            event.summary = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("DTSTART:") {
+            event.start = parse_ics_utc(value).ok();
+        } else if let Some(value) = line.strip_prefix("DTEND:") {
+            event.end = parse_ics_utc(value).ok();
+        }
+    }
+    Ok(events)
+}
+
+/// Format an epoch second as an ICS UTC timestamp.
+fn format_ics_utc(epoch: i64) -> String {
+    let days = epoch.div_euclid(86_400);
+    let secs = epoch.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        secs / 3_600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+/// Civil date for days since the Unix epoch (Howard Hinnant's
+/// `civil_from_days`, as used by the cron scheduler).
+fn civil_from_days(z: i64) -> (i64, u8, u8) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+    (if month <= 2 { y + 1 } else { y }, month, day)
+}
+
+/// Days since the Unix epoch for a civil date (Howard Hinnant's
+/// `days_from_civil`); the inverse of the cron module's date math.
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Parse an ICS UTC timestamp (`20231114T221320Z`). Floating and
+/// TZID-qualified times are rejected — servers are asked for UTC via the
+/// query's expand, and silently misreading a timezone is worse than an
+/// error.
+pub fn parse_ics_utc(stamp: &str) -> Result<i64> {
+    let stamp = stamp.trim();
+    let digits = stamp
+        .strip_suffix('Z')
+        .ok_or_else(|| SafeClawError::Config(format!("non-UTC ICS timestamp `{stamp}`")))?;
+    let (date, time) = digits
+        .split_once('T')
+        .ok_or_else(|| SafeClawError::Config(format!("malformed ICS timestamp `{stamp}`")))?;
+    if date.len() != 8 || time.len() != 6 {
+        return Err(SafeClawError::Config(format!(
+            "malformed ICS timestamp `{stamp}`"
+        )));
+    }
+    let parse = |s: &str| {
+        s.parse::<i64>()
+            .map_err(|_| SafeClawError::Config(format!("malformed ICS timestamp `{stamp}`")))
+    };
+    let (year, month, day) = (parse(&date[..4])?, parse(&date[4..6])?, parse(&date[6..])?);
+    let (hour, minute, second) = (parse(&time[..2])?, parse(&time[2..4])?, parse(&time[4..])?);
+    let days = days_from_civil(year, month as u8, day as u8);
+    Ok(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+/// Free slots of at least `duration_secs` between `window_start` and
+/// `window_end`, given the busy events (which may come from calendars in
+/// different timezones — by this point everything is epoch UTC).
+pub fn free_slots(
+    events: &[CalendarEvent],
+    window_start: i64,
+    window_end: i64,
+    duration_secs: i64,
+) -> Vec<(i64, i64)> {
+    let mut busy: Vec<(i64, i64)> = events
+        .iter()
+        .map(|e| (e.start.max(window_start), e.end.min(window_end)))
+        .filter(|(start, end)| start < end)
+        .collect();
+    busy.sort_unstable();
+    let mut slots = Vec::new();
+    let mut cursor = window_start;
+    for (start, end) in busy {
+        if start - cursor >= duration_secs {
+            slots.push((cursor, start));
+        }
+        cursor = cursor.max(end);
+    }
+    if window_end - cursor >= duration_secs {
+        slots.push((cursor, window_end));
+    }
+    slots
+}
+
+/// A query's events plus how the pipeline must treat them.
+#[derive(Debug, Clone)]
+pub struct QueryResult {
+    pub events: Vec<CalendarEvent>,
+    /// The calendar demands TEE routing for this turn.
+    pub tee_only: bool,
+}
+
+/// What a creation request produced.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CreateDisposition {
+    /// Awaiting HITL confirmation under this token.
+    PendingConfirmation { token: String, prompt: String },
+    Created,
+}
+
+type CacheKey = (String, i64, i64);
+
+/// The calendar tool surface: cached queries, free-slot computation,
+/// confirmation-gated creation.
+pub struct CalendarService {
+    config: CalendarConfig,
+    backend: Arc<dyn CalDavBackend>,
+    cache: Mutex<HashMap<CacheKey, (i64, Vec<CalendarEvent>)>>,
+    pending: Mutex<HashMap<String, (String, CalendarEvent)>>,
+    next_token: Mutex<u64>,
+}
+
+impl CalendarService {
+    pub fn new(config: CalendarConfig, backend: Arc<dyn CalDavBackend>) -> Self {
+        Self {
+            config,
+            backend,
+            cache: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            next_token: Mutex::new(0),
+        }
+    }
+
+    fn account(&self, name: &str) -> Result<&CalendarAccount> {
+        self.config
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| SafeClawError::NotFound(format!("no calendar account `{name}`")))
+    }
+
+    /// Query a range, from cache when fresh. Every returned summary is
+    /// taint-registered for the session before the agent sees it.
+    pub async fn query(
+        &self,
+        session_id: &str,
+        account_name: &str,
+        start: i64,
+        end: i64,
+        taint: &TaintRegistry,
+        now: i64,
+    ) -> Result<QueryResult> {
+        let account = self.account(account_name)?;
+        let key = (account_name.to_string(), start, end);
+        let cached = {
+            let cache = self.cache.lock().await;
+            cache
+                .get(&key)
+                .filter(|(fetched_at, _)| now - fetched_at < self.config.cache_ttl_secs)
+                .map(|(_, events)| events.clone())
+        };
+        let events = match cached {
+            Some(events) => events,
+            None => {
+                let events = self.backend.list_events(account, start, end).await?;
+                self.cache.lock().await.insert(key, (now, events.clone()));
+                events
+            }
+        };
+        let level = if account.tee_only {
+            SensitivityLevel::HighlySensitive
+        } else {
+            SensitivityLevel::Sensitive
+        };
+        for event in &events {
+            taint.register(session_id, &event.summary, level);
+        }
+        Ok(QueryResult {
+            events,
+            tee_only: account.tee_only,
+        })
+    }
+
+    /// Request an event creation. Confirmation-gated by default; nothing
+    /// reaches the server until [`confirm_create`](Self::confirm_create).
+    pub async fn request_create(
+        &self,
+        account_name: &str,
+        event: CalendarEvent,
+    ) -> Result<CreateDisposition> {
+        let account = self.account(account_name)?;
+        if !self.config.confirm_creates {
+            self.backend.create_event(account, &event).await?;
+            self.invalidate(account_name).await;
+            return Ok(CreateDisposition::Created);
+        }
+        let token = {
+            let mut next = self.next_token.lock().await;
+            *next += 1;
+            format!("cal-{}", *next)
+        };
+        let prompt = format!(
+            "Create \"{}\" on calendar `{account_name}` ({} – {})? Reply to confirm.",
+            event.summary, event.start, event.end
+        );
+        self.pending
+            .lock()
+            .await
+            .insert(token.clone(), (account_name.to_string(), event));
+        Ok(CreateDisposition::PendingConfirmation { token, prompt })
+    }
+
+    /// The user confirmed: perform the creation.
+    pub async fn confirm_create(&self, token: &str) -> Result<()> {
+        let (account_name, event) = self
+            .pending
+            .lock()
+            .await
+            .remove(token)
+            .ok_or_else(|| SafeClawError::NotFound(format!("no pending creation {token}")))?;
+        let account = self.account(&account_name)?;
+        self.backend.create_event(account, &event).await?;
+        self.invalidate(&account_name).await;
+        Ok(())
+    }
+
+    /// Drop cached ranges for an account after a write.
+    async fn invalidate(&self, account_name: &str) {
+        self.cache
+            .lock()
+            .await
+            .retain(|(name, _, _), _| name != account_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex as StdMutex;
+
+    use super::*;
+    use crate::audit::alerting::AlertMonitor;
+    use crate::guard::taint::enforce_tee_boundary;
+
+    const NOW: i64 = 1_700_000_000;
+
+    struct MockCalDav {
+        events: Vec<CalendarEvent>,
+        list_calls: AtomicU32,
+        created: StdMutex<Vec<CalendarEvent>>,
+    }
+
+    impl MockCalDav {
+        fn with_events(events: Vec<CalendarEvent>) -> Arc<Self> {
+            Arc::new(Self {
+                events,
+                list_calls: AtomicU32::new(0),
+                created: StdMutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl CalDavBackend for MockCalDav {
+        async fn list_events(
+            &self,
+            _account: &CalendarAccount,
+            start: i64,
+            end: i64,
+        ) -> Result<Vec<CalendarEvent>> {
+            self.list_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self
+                .events
+                .iter()
+                .filter(|e| e.start < end && e.end > start)
+                .cloned()
+                .collect())
+        }
+
+        async fn create_event(
+            &self,
+            _account: &CalendarAccount,
+            event: &CalendarEvent,
+        ) -> Result<()> {
+            self.created.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    fn event(uid: &str, summary: &str, start: i64, end: i64) -> CalendarEvent {
+        CalendarEvent {
+            uid: uid.into(),
+            calendar: "work".into(),
+            summary: summary.into(),
+            start,
+            end,
+        }
+    }
+
+    fn service(backend: Arc<MockCalDav>, tee_only: bool) -> CalendarService {
+        CalendarService::new(
+            CalendarConfig {
+                accounts: vec![CalendarAccount {
+                    name: "work".into(),
+                    url: "https://dav.example.org/cal/work/".into(),
+                    username: "alex".into(),
+                    password_ref: Some("caldav-work".into()),
+                    tee_only,
+                }],
+                ..Default::default()
+            },
+            backend,
+        )
+    }
+
+    #[tokio::test]
+    async fn repeated_queries_within_the_ttl_hit_the_cache() {
+        let backend = MockCalDav::with_events(vec![event("e1", "standup", NOW, NOW + 900)]);
+        let service = service(Arc::clone(&backend), false);
+        let taint = TaintRegistry::new();
+
+        let first = service
+            .query("s1", "work", NOW - 3600, NOW + 3600, &taint, NOW)
+            .await
+            .unwrap();
+        assert_eq!(first.events.len(), 1);
+        service
+            .query("s1", "work", NOW - 3600, NOW + 3600, &taint, NOW + 30)
+            .await
+            .unwrap();
+        assert_eq!(backend.list_calls.load(Ordering::SeqCst), 1);
+
+        // Past the TTL the server is asked again.
+        service
+            .query("s1", "work", NOW - 3600, NOW + 3600, &taint, NOW + 90)
+            .await
+            .unwrap();
+        assert_eq!(backend.list_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn free_slots_merge_busy_time_from_different_timezones() {
+        // 2023-11-15, working window 08:00–17:00 UTC. One event entered
+        // from Berlin (10:00 CET = 09:00Z), one from New York
+        // (09:00 EST = 14:00Z) — both already epoch UTC here.
+        let day = 1_700_006_400; // 2023-11-15 00:00:00 UTC
+        let events = vec![
+            event("e1", "berlin sync", day + 9 * 3600, day + 10 * 3600),
+            event("e2", "ny review", day + 14 * 3600, day + 15 * 3600),
+        ];
+        let slots = free_slots(&events, day + 8 * 3600, day + 17 * 3600, 3600);
+        assert_eq!(
+            slots,
+            [
+                (day + 8 * 3600, day + 9 * 3600),
+                (day + 10 * 3600, day + 14 * 3600),
+                (day + 15 * 3600, day + 17 * 3600),
+            ]
+        );
+        // A 5-hour meeting only fits in the midday gap.
+        let long = free_slots(&events, day + 8 * 3600, day + 17 * 3600, 4 * 3600);
+        assert_eq!(long, [(day + 10 * 3600, day + 14 * 3600)]);
+    }
+
+    #[tokio::test]
+    async fn creation_is_gated_behind_confirmation() {
+        let backend = MockCalDav::with_events(Vec::new());
+        let service = service(Arc::clone(&backend), false);
+
+        let disposition = service
+            .request_create("work", event("e9", "dentist", NOW, NOW + 1800))
+            .await
+            .unwrap();
+        let CreateDisposition::PendingConfirmation { token, prompt } = disposition else {
+            panic!("expected PendingConfirmation");
+        };
+        assert!(prompt.contains("dentist"));
+        // Nothing reached the server yet.
+        assert!(backend.created.lock().unwrap().is_empty());
+
+        service.confirm_create(&token).await.unwrap();
+        assert_eq!(backend.created.lock().unwrap().len(), 1);
+        // A token can't be confirmed twice.
+        assert!(service.confirm_create(&token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn tee_only_calendar_details_cannot_leak_into_plain_output() {
+        let backend =
+            MockCalDav::with_events(vec![event("e1", "board meeting: acquisition", NOW, NOW + 3600)]);
+        let service = service(Arc::clone(&backend), true);
+        let taint = TaintRegistry::new();
+
+        let result = service
+            .query("s1", "work", NOW - 3600, NOW + 7200, &taint, NOW)
+            .await
+            .unwrap();
+        assert!(result.tee_only);
+
+        // The summary is highly sensitive taint: the send boundary blocks
+        // it if it ever reaches a TEE session's outbound text verbatim.
+        let monitor = AlertMonitor::default();
+        let err = enforce_tee_boundary(
+            &taint,
+            &monitor,
+            "s1",
+            "You have 'board meeting: acquisition' at 10.",
+            true,
+            NOW,
+        )
+        .unwrap_err();
+        assert!(matches!(err, SafeClawError::Tee(_)));
+    }
+
+    #[test]
+    fn a_report_response_parses_into_events() {
+        let body = "<?xml version=\"1.0\"?><d:multistatus><c:calendar-data>\n\
+            BEGIN:VCALENDAR\n\
+            BEGIN:VEVENT\n\
+            UID:abc-123\n\
+            SUMMARY:standup\n\
+            DTSTART:20231114T221320Z\n\
+            DTEND:20231114T224320Z\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            UID:tz-event\n\
+            SUMMARY:skipped\n\
+            DTSTART;TZID=Europe/Berlin:20231114T231320\n\
+            END:VEVENT\n\
+            END:VCALENDAR\n\
+            </c:calendar-data></d:multistatus>";
+        let events = parse_ics_events(body, "work").unwrap();
+        // The UTC event parses; the timezone-qualified one is skipped
+        // rather than misplaced.
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].uid, "abc-123");
+        assert_eq!(events[0].start, 1_700_000_000);
+        assert_eq!(events[0].end, 1_700_001_800);
+        assert_eq!(events[0].calendar, "work");
+    }
+
+    #[test]
+    fn ics_timestamps_round_trip() {
+        assert_eq!(format_ics_utc(1_700_000_000), "20231114T221320Z");
+        assert_eq!(format_ics_utc(0), "19700101T000000Z");
+        assert_eq!(
+            parse_ics_utc(&format_ics_utc(1_700_006_400)).unwrap(),
+            1_700_006_400
+        );
+    }
+
+    #[test]
+    fn ics_utc_timestamps_parse_and_non_utc_is_refused() {
+        assert_eq!(parse_ics_utc("20231114T221320Z").unwrap(), 1_700_000_000);
+        assert_eq!(parse_ics_utc("19700101T000000Z").unwrap(), 0);
+        assert!(parse_ics_utc("20231114T221320").is_err());
+        assert!(parse_ics_utc("TZID=Europe/Berlin:20231114T231320").is_err());
+    }
+}
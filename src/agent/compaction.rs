@@ -0,0 +1,236 @@
+//! Forced summary-restart for sessions that never end.
+//!
+//! A perpetual session accumulates history — and with it per-turn context
+//! cost — without bound. When a session crosses the configured turn
+//! threshold it is compacted: the history is summarized, replaced by a
+//! single system entry carrying that summary, and the user is told the
+//! thread was condensed. Persona and workspace live outside the history, so
+//! only the transcript is replaced. `/summary`-style on-demand and periodic
+//! summaries are in [`summary`](crate::agent::summary); this module reuses
+//! the same generator.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::summary::{StoredSummary, SummaryGenerator};
+use crate::agent::types::{ChatRole, HistoryEntry};
+use crate::error::Result;
+
+/// Configuration under `agent.max_turns`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaxTurnsConfig {
+    pub enabled: bool,
+    /// Turns before the session is compacted into a summary and restarted;
+    /// 0 disables the threshold.
+    pub max_turns: u32,
+}
+
+impl Default for MaxTurnsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_turns: 500,
+        }
+    }
+}
+
+/// What replaces a compacted session: the new (summary-only) history and
+/// the notice shown to the user.
+#[derive(Debug, Clone)]
+pub struct CompactionRestart {
+    /// The session's history after the restart — one system entry carrying
+    /// the summary.
+    pub replacement_history: Vec<HistoryEntry>,
+    /// Sent to the user so the condensed thread isn't a surprise.
+    pub user_notice: String,
+    pub summary: StoredSummary,
+}
+
+/// Counts turns per session and triggers the compaction-restart at the
+/// threshold.
+pub struct TurnCompactor<G> {
+    config: MaxTurnsConfig,
+    generator: G,
+    turns: Mutex<HashMap<String, u32>>,
+}
+
+impl<G: SummaryGenerator> TurnCompactor<G> {
+    pub fn new(config: MaxTurnsConfig, generator: G) -> Self {
+        Self {
+            config,
+            generator,
+            turns: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record one completed turn. At the threshold, summarizes `history`
+    /// and returns the restart; the caller swaps the session history for
+    /// `replacement_history` and delivers `user_notice`. The turn count
+    /// resets so the next compaction is a full threshold away.
+    pub async fn note_turn(
+        &self,
+        session_id: &str,
+        history: &[HistoryEntry],
+        now: i64,
+    ) -> Result<Option<CompactionRestart>> {
+        if !self.config.enabled || self.config.max_turns == 0 {
+            return Ok(None);
+        }
+        let due = {
+            let mut turns = self.turns.lock().expect("turn compactor poisoned");
+            let count = turns.entry(session_id.to_string()).or_default();
+            *count += 1;
+            *count >= self.config.max_turns
+        };
+        if !due {
+            return Ok(None);
+        }
+        let summary = StoredSummary {
+            summary: self.generator.summarize(history).await?,
+            through_entries: history.len(),
+            generated_at: now,
+        };
+        self.turns
+            .lock()
+            .expect("turn compactor poisoned")
+            .insert(session_id.to_string(), 0);
+        let replacement = HistoryEntry::new(
+            ChatRole::System,
+            format!(
+                "This conversation reached {} turns and was compacted. \
+                 Summary of the conversation so far:\n{}",
+                self.config.max_turns, summary.summary
+            ),
+            now,
+        );
+        Ok(Some(CompactionRestart {
+            replacement_history: vec![replacement],
+            user_notice: "This thread got long, so I've condensed the earlier \
+                          conversation into a summary. Everything important is \
+                          carried over — just keep going."
+                .into(),
+            summary,
+        }))
+    }
+
+    /// Turns recorded since the last compaction, for status reporting.
+    pub fn turn_count(&self, session_id: &str) -> u32 {
+        self.turns
+            .lock()
+            .expect("turn compactor poisoned")
+            .get(session_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Session terminated — drop its counter.
+    pub fn forget(&self, session_id: &str) {
+        self.turns
+            .lock()
+            .expect("turn compactor poisoned")
+            .remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    struct CannedGenerator;
+
+    #[async_trait]
+    impl SummaryGenerator for CannedGenerator {
+        async fn summarize(&self, history: &[HistoryEntry]) -> Result<String> {
+            Ok(format!("summary of {} entries", history.len()))
+        }
+    }
+
+    fn history(len: usize) -> Vec<HistoryEntry> {
+        (0..len)
+            .map(|i| HistoryEntry::new(ChatRole::User, format!("msg {i}"), NOW + i as i64))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn crossing_the_threshold_compacts_and_restarts() {
+        let compactor = TurnCompactor::new(
+            MaxTurnsConfig {
+                enabled: true,
+                max_turns: 3,
+            },
+            CannedGenerator,
+        );
+
+        assert!(compactor.note_turn("s1", &history(2), NOW).await.unwrap().is_none());
+        assert!(compactor.note_turn("s1", &history(4), NOW).await.unwrap().is_none());
+        let restart = compactor
+            .note_turn("s1", &history(6), NOW)
+            .await
+            .unwrap()
+            .expect("third turn must trigger the restart");
+
+        // History is replaced by a single system entry carrying the summary.
+        assert_eq!(restart.replacement_history.len(), 1);
+        assert_eq!(restart.replacement_history[0].role, ChatRole::System);
+        assert!(restart.replacement_history[0]
+            .content
+            .contains("summary of 6 entries"));
+        assert_eq!(restart.summary.through_entries, 6);
+        assert!(!restart.user_notice.is_empty());
+
+        // The counter reset: the next compaction is a full threshold away.
+        assert_eq!(compactor.turn_count("s1"), 0);
+        assert!(compactor.note_turn("s1", &history(1), NOW).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn sessions_count_turns_independently() {
+        let compactor = TurnCompactor::new(
+            MaxTurnsConfig {
+                enabled: true,
+                max_turns: 2,
+            },
+            CannedGenerator,
+        );
+        compactor.note_turn("s1", &history(1), NOW).await.unwrap();
+        assert!(compactor.note_turn("s2", &history(1), NOW).await.unwrap().is_none());
+        assert!(compactor
+            .note_turn("s1", &history(2), NOW)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn disabled_or_zero_threshold_never_compacts() {
+        let off = TurnCompactor::new(
+            MaxTurnsConfig {
+                enabled: false,
+                max_turns: 1,
+            },
+            CannedGenerator,
+        );
+        let unlimited = TurnCompactor::new(
+            MaxTurnsConfig {
+                enabled: true,
+                max_turns: 0,
+            },
+            CannedGenerator,
+        );
+        for turn in 0..5 {
+            assert!(off.note_turn("s1", &history(turn), NOW).await.unwrap().is_none());
+            assert!(unlimited
+                .note_turn("s1", &history(turn), NOW)
+                .await
+                .unwrap()
+                .is_none());
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! External session-context injection.
+//!
+//! Integrators enrich a session with data the agent can't see on its own —
+//! CRM record, user tier, recent tickets — via
+//! `POST /api/agent/sessions/:id/context`. The key-value context is
+//! rendered as one system note ahead of each subsequent turn, so the model
+//! treats it as operator-provided background, not user speech. Every value
+//! is classified on the way in: entries at or below the turn's sensitivity
+//! ceiling are included, anything above it is withheld from that prompt
+//! (a highly-sensitive CRM note only surfaces on TEE-routed turns). A
+//! repeat POST upserts keys; `DELETE` clears the session's context.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::types::{ChatRole, HistoryEntry};
+use crate::audit::log::AuditLog;
+use crate::privacy::composite::CompositeClassifier;
+use crate::privacy::SensitivityLevel;
+
+/// One injected value and the level it classified at.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextEntry {
+    pub value: String,
+    pub sensitivity: SensitivityLevel,
+}
+
+/// Per-session injected context, keyed deterministically so the rendered
+/// note is stable across turns.
+#[derive(Default)]
+pub struct SessionContextStore {
+    contexts: Mutex<HashMap<String, BTreeMap<String, ContextEntry>>>,
+}
+
+impl SessionContextStore {
+    /// Upsert the given keys, classifying each value as it's stored.
+    pub async fn update(
+        &self,
+        session_id: &str,
+        entries: &HashMap<String, String>,
+        classifier: &CompositeClassifier,
+        audit: &AuditLog,
+        now: i64,
+    ) {
+        let mut classified = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            let sensitivity = classifier.classify(session_id, value, audit, now).await;
+            classified.push((
+                key.clone(),
+                ContextEntry {
+                    value: value.clone(),
+                    sensitivity,
+                },
+            ));
+        }
+        let mut contexts = self.contexts.lock().expect("session context poisoned");
+        let context = contexts.entry(session_id.to_string()).or_default();
+        for (key, entry) in classified {
+            context.insert(key, entry);
+        }
+    }
+
+    pub fn clear(&self, session_id: &str) {
+        self.contexts
+            .lock()
+            .expect("session context poisoned")
+            .remove(session_id);
+    }
+
+    /// The session's context as stored, for the API response.
+    pub fn snapshot(&self, session_id: &str) -> BTreeMap<String, ContextEntry> {
+        self.contexts
+            .lock()
+            .expect("session context poisoned")
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The system note injected ahead of the next turn, or `None` when no
+    /// context (at or below the ceiling) exists. Entries above the turn's
+    /// sensitivity ceiling are withheld, with a count so the model knows
+    /// context exists that it wasn't shown.
+    pub fn system_note(
+        &self,
+        session_id: &str,
+        ceiling: SensitivityLevel,
+        now: i64,
+    ) -> Option<HistoryEntry> {
+        let contexts = self.contexts.lock().expect("session context poisoned");
+        let context = contexts.get(session_id)?;
+        let mut lines = Vec::new();
+        let mut withheld = 0usize;
+        for (key, entry) in context {
+            if entry.sensitivity <= ceiling {
+                lines.push(format!("- {key}: {}", entry.value));
+            } else {
+                withheld += 1;
+            }
+        }
+        if lines.is_empty() && withheld == 0 {
+            return None;
+        }
+        let mut note = String::from("Operator-provided session context:\n");
+        note.push_str(&lines.join("\n"));
+        if withheld > 0 {
+            if !lines.is_empty() {
+                note.push('\n');
+            }
+            note.push_str(&format!(
+                "({withheld} further entr{} withheld at this sensitivity level)",
+                if withheld == 1 { "y" } else { "ies" }
+            ));
+        }
+        Some(HistoryEntry::new(ChatRole::System, note, now))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBody {
+    pub context: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextResponse {
+    pub session_id: String,
+    pub context: BTreeMap<String, ContextEntry>,
+}
+
+pub struct ContextState {
+    pub store: Arc<SessionContextStore>,
+    pub classifier: Arc<CompositeClassifier>,
+    pub audit: Arc<AuditLog>,
+}
+
+/// Routes mounted under `/api/agent/sessions` behind the admin auth
+/// middleware.
+pub fn context_routes(state: Arc<ContextState>) -> Router {
+    Router::new()
+        .route(
+            "/:id/context",
+            post(update_context).delete(clear_context),
+        )
+        .with_state(state)
+}
+
+/// `POST /api/agent/sessions/:id/context` — upsert injected context.
+async fn update_context(
+    State(state): State<Arc<ContextState>>,
+    Path(session_id): Path<String>,
+    Json(body): Json<ContextBody>,
+) -> Json<ContextResponse> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    state
+        .store
+        .update(&session_id, &body.context, &state.classifier, &state.audit, now)
+        .await;
+    Json(ContextResponse {
+        context: state.store.snapshot(&session_id),
+        session_id,
+    })
+}
+
+/// `DELETE /api/agent/sessions/:id/context` — clear injected context.
+async fn clear_context(
+    State(state): State<Arc<ContextState>>,
+    Path(session_id): Path<String>,
+) -> StatusCode {
+    state.store.clear(&session_id);
+    StatusCode::NO_CONTENT
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::audit::log::AuditIngestionConfig;
+    use crate::error::Result;
+    use crate::privacy::composite::{ClassificationPolicyConfig, ClassifierBackend};
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// Classifies anything containing "@" as highly sensitive, the rest as
+    /// normal — a stand-in for the PII rules.
+    struct AtSignPii;
+
+    #[async_trait]
+    impl ClassifierBackend for AtSignPii {
+        fn name(&self) -> &str {
+            "test"
+        }
+        async fn classify(&self, text: &str) -> Result<SensitivityLevel> {
+            Ok(if text.contains('@') {
+                SensitivityLevel::HighlySensitive
+            } else {
+                SensitivityLevel::Normal
+            })
+        }
+    }
+
+    fn classifier() -> CompositeClassifier {
+        CompositeClassifier::new(vec![Arc::new(AtSignPii)], ClassificationPolicyConfig::default())
+    }
+
+    async fn seeded_store() -> SessionContextStore {
+        let store = SessionContextStore::default();
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        store
+            .update(
+                "s1",
+                &HashMap::from([
+                    ("tier".to_string(), "enterprise".to_string()),
+                    ("contact".to_string(), "alex@example.com".to_string()),
+                ]),
+                &classifier(),
+                &audit,
+                NOW,
+            )
+            .await;
+        store
+    }
+
+    #[tokio::test]
+    async fn injected_context_appears_in_the_next_turns_prompt() {
+        let store = seeded_store().await;
+        let note = store
+            .system_note("s1", SensitivityLevel::HighlySensitive, NOW)
+            .unwrap();
+        assert_eq!(note.role, ChatRole::System);
+        assert!(note.content.contains("- tier: enterprise"));
+        assert!(note.content.contains("- contact: alex@example.com"));
+        // A session without context injects nothing.
+        assert!(store
+            .system_note("s2", SensitivityLevel::HighlySensitive, NOW)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn sensitive_entries_are_withheld_below_their_level() {
+        let store = seeded_store().await;
+        let note = store.system_note("s1", SensitivityLevel::Normal, NOW).unwrap();
+        assert!(note.content.contains("- tier: enterprise"));
+        assert!(!note.content.contains("alex@example.com"));
+        assert!(note.content.contains("1 further entry withheld"));
+    }
+
+    #[tokio::test]
+    async fn a_repeat_update_upserts_and_clear_removes_everything() {
+        let store = seeded_store().await;
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        store
+            .update(
+                "s1",
+                &HashMap::from([("tier".to_string(), "free".to_string())]),
+                &classifier(),
+                &audit,
+                NOW,
+            )
+            .await;
+        let note = store
+            .system_note("s1", SensitivityLevel::HighlySensitive, NOW)
+            .unwrap();
+        assert!(note.content.contains("- tier: free"));
+        // The untouched key survived the upsert.
+        assert!(note.content.contains("- contact:"));
+
+        store.clear("s1");
+        assert!(store
+            .system_note("s1", SensitivityLevel::HighlySensitive, NOW)
+            .is_none());
+    }
+}
@@ -0,0 +1,189 @@
+//! Graceful handling of expired provider credentials.
+//!
+//! When an API key expires, every turn used to fail with the provider's
+//! raw auth error — cryptic for users, noisy for the provider, and the
+//! operator found out from complaints. The guard watches LLM-client
+//! errors: the first auth failure marks the provider's credentials
+//! invalid, emits exactly one operator alert into the event store, and
+//! from then on turns against that provider are refused up front with a
+//! clear user-facing notice instead of hammering the provider. The state
+//! clears when the settings API updates the provider's credentials (or an
+//! operator clears it manually after fixing the key out of band).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::error::SafeClawError;
+use crate::events::EventStore;
+
+/// What users see while a provider's credentials are invalid.
+pub const USER_NOTICE: &str =
+    "The assistant is temporarily unavailable due to a credentials issue. \
+     The operator has been notified.";
+
+/// Whether an LLM-client error means the credentials are bad, as opposed
+/// to a transient transport or rate-limit failure.
+pub fn is_auth_error(error: &SafeClawError) -> bool {
+    match error {
+        SafeClawError::Unauthorized(_) => true,
+        other => {
+            let message = other.to_string();
+            message.contains("401") || message.to_lowercase().contains("invalid api key")
+        }
+    }
+}
+
+/// Per-provider credential state.
+pub struct CredentialGuard {
+    /// Providers currently marked invalid, with when it happened.
+    invalid_since: Mutex<HashMap<String, i64>>,
+}
+
+impl CredentialGuard {
+    pub fn new() -> Self {
+        Self {
+            invalid_since: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Consulted before each generation: `Err(notice)` means don't call
+    /// the provider, show the notice instead.
+    pub fn preflight(&self, provider: &str) -> Result<(), String> {
+        if self
+            .invalid_since
+            .lock()
+            .expect("credential guard poisoned")
+            .contains_key(provider)
+        {
+            return Err(USER_NOTICE.to_string());
+        }
+        Ok(())
+    }
+
+    /// Report one LLM-client failure. Returns the user-facing notice when
+    /// the error was an auth failure; non-auth errors are not this guard's
+    /// business and return `None`. The operator alert fires only on the
+    /// transition into the invalid state.
+    pub fn note_failure(
+        &self,
+        provider: &str,
+        error: &SafeClawError,
+        events: &EventStore,
+        now: i64,
+    ) -> Option<String> {
+        if !is_auth_error(error) {
+            return None;
+        }
+        let mut invalid = self.invalid_since.lock().expect("credential guard poisoned");
+        if invalid.insert(provider.to_string(), now).is_none() {
+            events.create(
+                "alert",
+                "credentials_invalid",
+                &format!("provider `{provider}` rejected its credentials; turns are suspended"),
+                &format!("first failure: {error}. Update the provider's key via the settings API to resume."),
+                "credential_guard",
+                now,
+            );
+        }
+        Some(USER_NOTICE.to_string())
+    }
+
+    /// Called by the settings API when a provider's credentials change;
+    /// traffic to the provider resumes.
+    pub fn credentials_updated(&self, provider: &str) {
+        self.invalid_since
+            .lock()
+            .expect("credential guard poisoned")
+            .remove(provider);
+    }
+
+    /// Providers currently suspended, sorted, for the admin summary.
+    pub fn suspended_providers(&self) -> Vec<String> {
+        let mut providers: Vec<String> = self
+            .invalid_since
+            .lock()
+            .expect("credential guard poisoned")
+            .keys()
+            .cloned()
+            .collect();
+        providers.sort();
+        providers
+    }
+}
+
+impl Default for CredentialGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn auth_error() -> SafeClawError {
+        SafeClawError::Unauthorized("provider returned 401".into())
+    }
+
+    #[test]
+    fn an_auth_error_suspends_the_provider_with_one_alert() {
+        let guard = CredentialGuard::new();
+        let events = EventStore::default();
+        assert!(guard.preflight("anthropic").is_ok());
+
+        let notice = guard.note_failure("anthropic", &auth_error(), &events, NOW);
+        assert_eq!(notice.as_deref(), Some(USER_NOTICE));
+
+        // Subsequent turns are refused up front, without touching the
+        // provider and without a second alert.
+        assert_eq!(guard.preflight("anthropic"), Err(USER_NOTICE.to_string()));
+        guard.note_failure("anthropic", &auth_error(), &events, NOW + 60);
+        guard.note_failure("anthropic", &auth_error(), &events, NOW + 120);
+        assert_eq!(events.list(Some("alert")).len(), 1);
+        assert_eq!(guard.suspended_providers(), ["anthropic"]);
+        // Other providers are unaffected.
+        assert!(guard.preflight("ollama").is_ok());
+    }
+
+    #[test]
+    fn non_auth_failures_are_not_this_guards_business() {
+        let guard = CredentialGuard::new();
+        let events = EventStore::default();
+        let transient = SafeClawError::Channel("connection reset by peer".into());
+        assert!(guard.note_failure("anthropic", &transient, &events, NOW).is_none());
+        assert!(guard.preflight("anthropic").is_ok());
+        assert!(events.list(None).is_empty());
+    }
+
+    #[test]
+    fn updating_credentials_resumes_traffic() {
+        let guard = CredentialGuard::new();
+        let events = EventStore::default();
+        guard.note_failure("anthropic", &auth_error(), &events, NOW);
+        assert!(guard.preflight("anthropic").is_err());
+
+        guard.credentials_updated("anthropic");
+        assert!(guard.preflight("anthropic").is_ok());
+        assert!(guard.suspended_providers().is_empty());
+
+        // If the new key is also bad, the alert fires again.
+        guard.note_failure("anthropic", &auth_error(), &events, NOW + 300);
+        assert_eq!(events.list(Some("alert")).len(), 2);
+    }
+
+    #[test]
+    fn auth_errors_are_recognized_across_error_shapes() {
+        assert!(is_auth_error(&auth_error()));
+        assert!(is_auth_error(&SafeClawError::Config(
+            "request failed with status 401 Unauthorized".into()
+        )));
+        assert!(is_auth_error(&SafeClawError::Config(
+            "Invalid API key provided".into()
+        )));
+        assert!(!is_auth_error(&SafeClawError::Config(
+            "request failed with status 429".into()
+        )));
+    }
+}
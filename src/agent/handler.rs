@@ -0,0 +1,186 @@
+//! Agent REST + WebSocket handlers.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::agent::llm_trace::{LlmTraceEntry, LlmTraceLog};
+use crate::agent::replay::{self, HistorySource};
+use crate::agent::scratchpad::ScratchpadStore;
+use crate::agent::timing::{TimingStore, TurnTiming};
+use crate::api::ErrorResponse;
+
+/// Routes mounted under `/api/agent`, behind `api::admin_protected` — the
+/// trace exposes redacted LLM exchanges and is management-only.
+pub fn llm_trace_routes(trace: Arc<LlmTraceLog>) -> Router {
+    Router::new()
+        .route("/sessions/:id/llm-trace", get(get_llm_trace))
+        .route("/sessions/:id/llm-trace/enabled", put(set_llm_trace_enabled))
+        .with_state(trace)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LlmTraceResponse {
+    pub entries: Vec<LlmTraceEntry>,
+}
+
+/// `GET /api/agent/sessions/:id/llm-trace` — recent redacted LLM exchanges
+/// for a session.
+#[utoipa::path(
+    get,
+    path = "/api/agent/sessions/{id}/llm-trace",
+    params(("id" = String, Path, description = "Session ID")),
+    responses((status = 200, body = LlmTraceResponse)),
+    tag = "agent"
+)]
+pub(crate) async fn get_llm_trace(
+    State(trace): State<Arc<LlmTraceLog>>,
+    Path(id): Path<String>,
+) -> Json<LlmTraceResponse> {
+    Json(LlmTraceResponse {
+        entries: trace.trace_for(&id).await,
+    })
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetEnabledBody {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EnabledResponse {
+    pub enabled: bool,
+}
+
+/// `PUT /api/agent/sessions/:id/llm-trace/enabled` — toggle tracing for one
+/// session at runtime. Disabling wipes the retained trace.
+#[utoipa::path(
+    put,
+    path = "/api/agent/sessions/{id}/llm-trace/enabled",
+    params(("id" = String, Path, description = "Session ID")),
+    request_body = SetEnabledBody,
+    responses((status = 200, body = EnabledResponse)),
+    tag = "agent"
+)]
+pub(crate) async fn set_llm_trace_enabled(
+    State(trace): State<Arc<LlmTraceLog>>,
+    Path(id): Path<String>,
+    Json(body): Json<SetEnabledBody>,
+) -> Json<EnabledResponse> {
+    trace.set_session_enabled(&id, body.enabled).await;
+    Json(EnabledResponse {
+        enabled: body.enabled,
+    })
+}
+
+/// Routes mounted under `/api/agent`.
+pub fn scratchpad_routes(store: Arc<ScratchpadStore>) -> Router {
+    Router::new()
+        .route("/sessions/:id/scratchpad", get(get_scratchpad))
+        .with_state(store)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScratchpadResponse {
+    pub entries: HashMap<String, String>,
+}
+
+/// `GET /api/agent/sessions/:id/scratchpad` — the session's scratchpad
+/// contents, for debugging from the browser UI.
+#[utoipa::path(
+    get,
+    path = "/api/agent/sessions/{id}/scratchpad",
+    params(("id" = String, Path, description = "Session ID")),
+    responses((status = 200, body = ScratchpadResponse)),
+    tag = "agent"
+)]
+pub(crate) async fn get_scratchpad(
+    State(store): State<Arc<ScratchpadStore>>,
+    Path(id): Path<String>,
+) -> Json<ScratchpadResponse> {
+    Json(ScratchpadResponse {
+        entries: store.snapshot(&id).await,
+    })
+}
+
+/// Routes mounted under `/api/agent`.
+pub fn timing_routes(store: Arc<TimingStore>) -> Router {
+    Router::new()
+        .route("/sessions/:id/timings", get(get_timings))
+        .with_state(store)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimingsResponse {
+    pub timings: Vec<TurnTiming>,
+}
+
+/// `GET /api/agent/sessions/:id/timings` — latency breakdowns for the
+/// session's recent turns.
+#[utoipa::path(
+    get,
+    path = "/api/agent/sessions/{id}/timings",
+    params(("id" = String, Path, description = "Session ID")),
+    responses((status = 200, body = TimingsResponse)),
+    tag = "agent"
+)]
+pub(crate) async fn get_timings(
+    State(store): State<Arc<TimingStore>>,
+    Path(id): Path<String>,
+) -> Json<TimingsResponse> {
+    Json(TimingsResponse {
+        timings: store.timings_for(&id).await,
+    })
+}
+
+/// Routes mounted under `/api/agent`.
+pub fn replay_routes(history: Arc<dyn HistorySource>) -> Router {
+    Router::new()
+        .route(
+            "/sessions/:id/messages/:idx/content",
+            get(get_full_message_content),
+        )
+        .with_state(history)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FullContentResponse {
+    pub content: String,
+}
+
+/// `GET /api/agent/sessions/:id/messages/:idx/content` — full content of a
+/// history block the replay payload truncated.
+#[utoipa::path(
+    get,
+    path = "/api/agent/sessions/{id}/messages/{idx}/content",
+    params(
+        ("id" = String, Path, description = "Session ID"),
+        ("idx" = usize, Path, description = "History index"),
+    ),
+    responses(
+        (status = 200, body = FullContentResponse),
+        (status = 404, body = ErrorResponse),
+    ),
+    tag = "agent"
+)]
+pub(crate) async fn get_full_message_content(
+    State(history): State<Arc<dyn HistorySource>>,
+    Path((id, idx)): Path<(String, usize)>,
+) -> Result<Json<FullContentResponse>, (StatusCode, Json<ErrorResponse>)> {
+    match replay::full_content(history.as_ref(), &id, idx).await {
+        Some(content) => Ok(Json(FullContentResponse { content })),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                "not_found",
+                "no such session or message index",
+            )),
+        )),
+    }
+}
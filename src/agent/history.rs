@@ -0,0 +1,302 @@
+//! Bounded in-memory message history with an append-only disk tail.
+//!
+//! Holding every message of every session in memory forever doesn't scale —
+//! a months-old group chat times hundreds of sessions is real memory. Each
+//! session keeps only the most recent N entries in memory; every appended
+//! entry is also written as one JSONL line to a per-session history file.
+//! That replaces the old whole-blob rewrite on every message (O(history)
+//! write amplification) with an O(1) append. The disk file is authoritative;
+//! the window is a hot cache, and [`full_history`] stitches the two so a
+//! torn final line after a crash is recovered from the window when it is
+//! still there. Replay, export, and search read through the
+//! [`HistorySource`] impl and never notice the boundary.
+//!
+//! [`full_history`]: BoundedHistory::full_history
+//! [`HistorySource`]: crate::agent::replay::HistorySource
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::agent::replay::HistorySource;
+use crate::agent::types::HistoryEntry;
+use crate::error::Result;
+
+/// Configuration under `agent.history`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryWindowConfig {
+    /// Most recent entries kept in memory per session.
+    pub max_in_memory: usize,
+}
+
+impl Default for HistoryWindowConfig {
+    fn default() -> Self {
+        Self { max_in_memory: 200 }
+    }
+}
+
+struct SessionWindow {
+    /// The most recent entries, bounded by `max_in_memory`.
+    window: VecDeque<HistoryEntry>,
+    /// Total entries ever appended to this session.
+    total: usize,
+}
+
+/// Per-session bounded history over append-only JSONL files.
+pub struct BoundedHistory {
+    config: HistoryWindowConfig,
+    /// Directory of `{session_id}.history.jsonl` files; `None` keeps
+    /// everything in memory (the window then grows unbounded — tests only).
+    dir: Option<PathBuf>,
+    sessions: Mutex<HashMap<String, SessionWindow>>,
+}
+
+impl BoundedHistory {
+    pub fn new(config: HistoryWindowConfig, dir: Option<PathBuf>) -> Self {
+        Self {
+            config,
+            dir,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn session_path(&self, session_id: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{session_id}.history.jsonl")))
+    }
+
+    /// Append one entry: one JSONL line to the session file, one push into
+    /// the window (evicting the oldest past the bound).
+    pub async fn append(&self, session_id: &str, entry: HistoryEntry) -> Result<()> {
+        if let Some(path) = self.session_path(session_id) {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let mut line = serde_json::to_vec(&entry)?;
+            line.push(b'\n');
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .await?;
+            file.write_all(&line).await?;
+            // `write_all` on a tokio file only buffers; flush so the line
+            // is visible to readers before we return, and sync so a crash
+            // right after a confirmed append can't lose it.
+            file.flush().await?;
+            file.sync_data().await?;
+        }
+        let mut sessions = self.sessions.lock().await;
+        let state = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| SessionWindow {
+                window: VecDeque::new(),
+                total: 0,
+            });
+        state.total += 1;
+        state.window.push_back(entry);
+        if self.dir.is_some() {
+            while state.window.len() > self.config.max_in_memory {
+                state.window.pop_front();
+            }
+        }
+        Ok(())
+    }
+
+    /// The in-memory window — what the hot path (context assembly) sees.
+    pub async fn recent(&self, session_id: &str) -> Vec<HistoryEntry> {
+        self.sessions
+            .lock()
+            .await
+            .get(session_id)
+            .map(|s| s.window.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Entries currently held in memory for a session, for stats.
+    pub async fn in_memory_len(&self, session_id: &str) -> usize {
+        self.sessions
+            .lock()
+            .await
+            .get(session_id)
+            .map(|s| s.window.len())
+            .unwrap_or(0)
+    }
+
+    /// Valid entries on disk. A torn or corrupt trailing line (crash during
+    /// append) is skipped with a warning; earlier lines are unaffected
+    /// because appends never rewrite them.
+    async fn read_disk(&self, session_id: &str) -> Vec<HistoryEntry> {
+        let Some(path) = self.session_path(session_id) else {
+            return Vec::new();
+        };
+        let raw = match tokio::fs::read_to_string(&path).await {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(e) => {
+                tracing::warn!(session_id, error = %e, "history file unreadable");
+                return Vec::new();
+            }
+        };
+        raw.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    tracing::warn!(session_id, error = %e, "skipping corrupt history line");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// The complete, lossless history: the on-disk tail stitched with any
+    /// window entries the disk doesn't have (a failed or torn append).
+    pub async fn full_history(&self, session_id: &str) -> Vec<HistoryEntry> {
+        let mut entries = self.read_disk(session_id).await;
+        let sessions = self.sessions.lock().await;
+        if let Some(state) = sessions.get(session_id) {
+            // Window entries occupy global indices [total - len, total);
+            // take those the disk is missing.
+            let window_start = state.total - state.window.len();
+            for (offset, entry) in state.window.iter().enumerate() {
+                if window_start + offset >= entries.len() {
+                    entries.push(entry.clone());
+                }
+            }
+        }
+        entries
+    }
+
+    /// One-time migration from the old single-blob format: seed the
+    /// append-only file from the full history that used to live inside the
+    /// session state JSON. No-op if the session already has a history file.
+    pub async fn migrate_from_blob(
+        &self,
+        session_id: &str,
+        legacy: Vec<HistoryEntry>,
+    ) -> Result<()> {
+        if let Some(path) = self.session_path(session_id) {
+            if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+                return Ok(());
+            }
+        }
+        for entry in legacy {
+            self.append(session_id, entry).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HistorySource for BoundedHistory {
+    async fn history(&self, session_id: &str) -> Option<Vec<HistoryEntry>> {
+        let entries = self.full_history(session_id).await;
+        (!entries.is_empty()).then_some(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::types::ChatRole;
+
+    fn entry(i: usize) -> HistoryEntry {
+        HistoryEntry::new(ChatRole::User, format!("msg {i}"), 1_700_000_000 + i as i64)
+    }
+
+    fn bounded(dir: &tempfile::TempDir, max: usize) -> BoundedHistory {
+        BoundedHistory::new(
+            HistoryWindowConfig { max_in_memory: max },
+            Some(dir.path().to_path_buf()),
+        )
+    }
+
+    #[tokio::test]
+    async fn memory_holds_only_the_window_but_nothing_is_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = bounded(&dir, 4);
+        for i in 0..10 {
+            history.append("s1", entry(i)).await.unwrap();
+        }
+
+        assert_eq!(history.in_memory_len("s1").await, 4);
+        let recent = history.recent("s1").await;
+        assert_eq!(recent[0].content, "msg 6");
+        assert_eq!(recent[3].content, "msg 9");
+
+        let full = history.full_history("s1").await;
+        assert_eq!(full.len(), 10);
+        for (i, e) in full.iter().enumerate() {
+            assert_eq!(e.content, format!("msg {i}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn appends_grow_the_file_by_one_line_per_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = bounded(&dir, 2);
+        let path = dir.path().join("s1.history.jsonl");
+        for i in 0..3 {
+            history.append("s1", entry(i)).await.unwrap();
+            let raw = std::fs::read_to_string(&path).unwrap();
+            assert_eq!(raw.lines().count(), i + 1, "append must not rewrite");
+        }
+    }
+
+    #[tokio::test]
+    async fn torn_trailing_line_is_recovered_from_the_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = bounded(&dir, 4);
+        for i in 0..3 {
+            history.append("s1", entry(i)).await.unwrap();
+        }
+        // Simulate a crash mid-append: chop the final line in half.
+        let path = dir.path().join("s1.history.jsonl");
+        let raw = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, &raw[..raw.len() - 20]).unwrap();
+
+        let full = history.full_history("s1").await;
+        assert_eq!(full.len(), 3, "window must cover the torn line");
+        assert_eq!(full[2].content, "msg 2");
+    }
+
+    #[tokio::test]
+    async fn replay_reads_disk_after_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let history = bounded(&dir, 2);
+            for i in 0..6 {
+                history.append("s1", entry(i)).await.unwrap();
+            }
+        }
+        // Fresh process: the window is empty, the file is complete.
+        let history = bounded(&dir, 2);
+        assert_eq!(history.in_memory_len("s1").await, 0);
+        let replayed = history.history("s1").await.expect("history on disk");
+        assert_eq!(replayed.len(), 6);
+        assert_eq!(replayed[0].content, "msg 0");
+        assert!(history.history("unknown").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn blob_migration_seeds_the_file_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = bounded(&dir, 2);
+        let legacy: Vec<HistoryEntry> = (0..5).map(entry).collect();
+        history.migrate_from_blob("s1", legacy.clone()).await.unwrap();
+
+        assert_eq!(history.full_history("s1").await.len(), 5);
+        assert_eq!(history.in_memory_len("s1").await, 2);
+
+        // Re-running the migration must not duplicate anything.
+        history.migrate_from_blob("s1", legacy).await.unwrap();
+        assert_eq!(history.full_history("s1").await.len(), 5);
+    }
+}
@@ -0,0 +1,230 @@
+//! Opt-in provider traffic debug log with privacy-safe truncation.
+//!
+//! When `models.debug_log` is set (or tracing is enabled at runtime for a
+//! specific session), each LLM request/response pair is recorded for
+//! debugging provider issues. Privacy invariants: redaction runs *before*
+//! anything is stored or written, entries are truncated to a configurable
+//! length, session IDs are stored hashed, and retention is hard-capped per
+//! session.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+/// Redaction hook — in production this is the output scanner plus the secret
+/// pattern set, applied to both directions of traffic.
+pub type RedactFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Configuration under `models` in the main config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlmTraceConfig {
+    /// Global opt-in; individual sessions can also be enabled at runtime via
+    /// the admin endpoint.
+    pub debug_log: bool,
+    /// Truncate each recorded request/response to this many characters.
+    pub max_entry_chars: usize,
+    /// Hard cap on retained exchanges per session; the oldest are dropped.
+    pub max_entries_per_session: usize,
+}
+
+impl Default for LlmTraceConfig {
+    fn default() -> Self {
+        Self {
+            debug_log: false,
+            max_entry_chars: 4000,
+            max_entries_per_session: 50,
+        }
+    }
+}
+
+/// One redacted, truncated request/response exchange.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LlmTraceEntry {
+    pub provider: String,
+    pub model: String,
+    /// SHA-256 prefix of the session ID — the raw ID never touches the trace.
+    pub session_hash: String,
+    pub request: String,
+    pub response: String,
+    pub timestamp: i64,
+}
+
+/// Per-session ring buffers of redacted exchanges.
+pub struct LlmTraceLog {
+    config: LlmTraceConfig,
+    redact: RedactFn,
+    entries: RwLock<HashMap<String, VecDeque<LlmTraceEntry>>>,
+    /// Sessions with tracing force-enabled at runtime (by raw session ID).
+    runtime_enabled: RwLock<HashSet<String>>,
+}
+
+/// Hash a session ID for storage: first 16 hex chars of SHA-256.
+pub fn hash_session_id(session_id: &str) -> String {
+    let digest = Sha256::digest(session_id.as_bytes());
+    hex::encode(&digest[..8])
+}
+
+impl LlmTraceLog {
+    pub fn new(config: LlmTraceConfig, redact: RedactFn) -> Self {
+        Self {
+            config,
+            redact,
+            entries: RwLock::new(HashMap::new()),
+            runtime_enabled: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Enable or disable tracing for one session at runtime (admin only).
+    pub async fn set_session_enabled(&self, session_id: &str, enabled: bool) {
+        let mut set = self.runtime_enabled.write().await;
+        if enabled {
+            set.insert(session_id.to_string());
+        } else {
+            set.remove(session_id);
+            self.entries
+                .write()
+                .await
+                .remove(&hash_session_id(session_id));
+        }
+    }
+
+    pub async fn is_enabled_for(&self, session_id: &str) -> bool {
+        self.config.debug_log || self.runtime_enabled.read().await.contains(session_id)
+    }
+
+    /// Record one exchange. Redaction and truncation happen here, before the
+    /// entry is stored anywhere.
+    pub async fn record(
+        &self,
+        session_id: &str,
+        provider: &str,
+        model: &str,
+        request: &str,
+        response: &str,
+        timestamp: i64,
+    ) {
+        if !self.is_enabled_for(session_id).await {
+            return;
+        }
+        let entry = LlmTraceEntry {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            session_hash: hash_session_id(session_id),
+            request: self.sanitize(request),
+            response: self.sanitize(response),
+            timestamp,
+        };
+        let mut entries = self.entries.write().await;
+        let ring = entries.entry(entry.session_hash.clone()).or_default();
+        ring.push_back(entry);
+        while ring.len() > self.config.max_entries_per_session {
+            ring.pop_front();
+        }
+    }
+
+    /// Recent redacted exchanges for a session (admin endpoint).
+    pub async fn trace_for(&self, session_id: &str) -> Vec<LlmTraceEntry> {
+        self.entries
+            .read()
+            .await
+            .get(&hash_session_id(session_id))
+            .map(|ring| ring.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn sanitize(&self, text: &str) -> String {
+        let redacted = (self.redact)(text);
+        truncate_chars(&redacted, self.config.max_entry_chars)
+    }
+}
+
+fn truncate_chars(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(limit).collect();
+    format!("{cut}…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace_log(config: LlmTraceConfig) -> LlmTraceLog {
+        // Test redactor mimicking the secret-pattern scanner.
+        let redact: RedactFn = Arc::new(|text: &str| text.replace("sk-PLANTED-SECRET", "[redacted]"));
+        LlmTraceLog::new(config, redact)
+    }
+
+    #[tokio::test]
+    async fn planted_secret_is_redacted_before_storage() {
+        let log = trace_log(LlmTraceConfig {
+            debug_log: true,
+            ..Default::default()
+        });
+        log.record(
+            "s1",
+            "anthropic",
+            "claude-sonnet-4",
+            "my key is sk-PLANTED-SECRET please use it",
+            "I will use sk-PLANTED-SECRET now",
+            1_700_000_000,
+        )
+        .await;
+
+        let entries = log.trace_for("s1").await;
+        let serialized = serde_json::to_string(&entries).unwrap();
+        assert!(!serialized.contains("sk-PLANTED-SECRET"));
+        assert!(entries[0].request.contains("[redacted]"));
+        assert!(entries[0].response.contains("[redacted]"));
+    }
+
+    #[tokio::test]
+    async fn retention_cap_drops_oldest_entries() {
+        let log = trace_log(LlmTraceConfig {
+            debug_log: true,
+            max_entries_per_session: 3,
+            ..Default::default()
+        });
+        for i in 0..10 {
+            log.record("s1", "openai", "gpt-4o", &format!("req {i}"), "resp", i)
+                .await;
+        }
+        let entries = log.trace_for("s1").await;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].request, "req 7");
+    }
+
+    #[tokio::test]
+    async fn disabled_sessions_record_nothing_until_runtime_enable() {
+        let log = trace_log(LlmTraceConfig::default());
+        log.record("s1", "anthropic", "m", "req", "resp", 0).await;
+        assert!(log.trace_for("s1").await.is_empty());
+
+        log.set_session_enabled("s1", true).await;
+        log.record("s1", "anthropic", "m", "req", "resp", 1).await;
+        assert_eq!(log.trace_for("s1").await.len(), 1);
+
+        // Disabling also wipes the retained trace.
+        log.set_session_enabled("s1", false).await;
+        assert!(log.trace_for("s1").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn entries_are_truncated_and_session_ids_hashed() {
+        let log = trace_log(LlmTraceConfig {
+            debug_log: true,
+            max_entry_chars: 10,
+            ..Default::default()
+        });
+        log.record("session-secret-name", "anthropic", "m", &"x".repeat(100), "r", 0)
+            .await;
+        let entries = log.trace_for("session-secret-name").await;
+        assert!(entries[0].request.chars().count() <= 11);
+        assert_ne!(entries[0].session_hash, "session-secret-name");
+    }
+}
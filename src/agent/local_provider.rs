@@ -0,0 +1,229 @@
+//! Local model providers (Ollama / llama.cpp / any OpenAI-compatible local
+//! endpoint) for offline or privacy-max operation.
+//!
+//! Sessions whose sensitivity exceeds `privacy.max_remote_sensitivity` are
+//! forced onto a local provider so the content never leaves the machine —
+//! not even to a remote LLM behind the TEE. Local endpoints are health
+//! checked into readiness, surfaced with a `local` badge in the model
+//! picker, and stream through the same [`AgentEvent`] pipeline as remote
+//! providers.
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::types::AgentEvent;
+use crate::config::{ModelsConfig, PrivacyConfig, ProviderKind};
+use crate::error::{Result, SafeClawError};
+use crate::privacy::SensitivityLevel;
+
+/// Entry in the `GET /api/agent/backends` response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelBackendInfo {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub is_default: bool,
+    /// Badge flag: this backend runs locally, zero external API calls.
+    pub local: bool,
+}
+
+/// Build the model picker list from config, with the `local` badge set for
+/// local providers.
+pub fn backend_list(models: &ModelsConfig) -> Vec<ModelBackendInfo> {
+    let mut backends: Vec<ModelBackendInfo> = models
+        .providers
+        .iter()
+        .map(|(name, provider)| ModelBackendInfo {
+            id: name.clone(),
+            name: provider
+                .default_model
+                .clone()
+                .unwrap_or_else(|| name.clone()),
+            provider: name.clone(),
+            is_default: *name == models.default_provider,
+            local: provider.kind == ProviderKind::Local,
+        })
+        .collect();
+    backends.sort_by(|a, b| a.id.cmp(&b.id));
+    backends
+}
+
+/// Select the provider for a session. Sessions above
+/// `privacy.max_remote_sensitivity` are forced onto a local provider; it is
+/// an error to have none configured in that case.
+pub fn select_provider(
+    session_level: SensitivityLevel,
+    models: &ModelsConfig,
+    privacy: &PrivacyConfig,
+) -> Result<String> {
+    let must_stay_local = privacy
+        .max_remote_sensitivity
+        .map(|max| session_level > max)
+        .unwrap_or(false);
+    if !must_stay_local {
+        return Ok(models.default_provider.clone());
+    }
+    let mut local: Vec<&String> = models
+        .providers
+        .iter()
+        .filter(|(_, p)| p.kind == ProviderKind::Local)
+        .map(|(name, _)| name)
+        .collect();
+    local.sort();
+    local.first().map(|s| s.to_string()).ok_or_else(|| {
+        SafeClawError::Config(format!(
+            "session sensitivity {session_level:?} exceeds max_remote_sensitivity \
+             but no local provider is configured"
+        ))
+    })
+}
+
+/// Health-check a local endpoint for the readiness probe. Any HTTP response
+/// counts as alive — llama.cpp and Ollama differ in what they serve at `/`.
+pub async fn check_local_health(client: &reqwest::Client, base_url: &str) -> bool {
+    client
+        .get(base_url)
+        .timeout(std::time::Duration::from_secs(2))
+        .send()
+        .await
+        .is_ok()
+}
+
+/// Translate one OpenAI-compatible SSE line from a local endpoint into an
+/// [`AgentEvent`]. Returns `None` for keep-alives and non-data lines.
+pub fn translate_sse_line(line: &str) -> Option<AgentEvent> {
+    let data = line.strip_prefix("data:")?.trim();
+    if data == "[DONE]" {
+        return Some(AgentEvent::Done);
+    }
+    let value: serde_json::Value = serde_json::from_str(data).ok()?;
+    let delta = value
+        .get("choices")?
+        .get(0)?
+        .get("delta")?
+        .get("content")?
+        .as_str()?;
+    if delta.is_empty() {
+        return None;
+    }
+    Some(AgentEvent::TextDelta {
+        text: delta.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProviderConfig;
+
+    fn models_with_local() -> ModelsConfig {
+        let mut models = ModelsConfig::default();
+        models.providers.insert(
+            "anthropic".into(),
+            ProviderConfig {
+                default_model: Some("claude-sonnet-4-20250514".into()),
+                ..Default::default()
+            },
+        );
+        models.providers.insert(
+            "ollama".into(),
+            ProviderConfig {
+                default_model: Some("llama3.1:8b".into()),
+                base_url: Some("http://127.0.0.1:11434/v1".into()),
+                kind: ProviderKind::Local,
+                ..Default::default()
+            },
+        );
+        models
+    }
+
+    fn privacy(max: Option<SensitivityLevel>) -> PrivacyConfig {
+        PrivacyConfig {
+            max_remote_sensitivity: max,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn highly_sensitive_sessions_are_forced_local() {
+        let provider = select_provider(
+            SensitivityLevel::HighlySensitive,
+            &models_with_local(),
+            &privacy(Some(SensitivityLevel::Sensitive)),
+        )
+        .unwrap();
+        assert_eq!(provider, "ollama");
+    }
+
+    #[test]
+    fn sessions_at_or_below_the_cap_use_the_default_provider() {
+        let provider = select_provider(
+            SensitivityLevel::Sensitive,
+            &models_with_local(),
+            &privacy(Some(SensitivityLevel::Sensitive)),
+        )
+        .unwrap();
+        assert_eq!(provider, "anthropic");
+    }
+
+    #[test]
+    fn forced_routing_without_local_provider_is_an_error() {
+        let mut models = models_with_local();
+        models.providers.remove("ollama");
+        let result = select_provider(
+            SensitivityLevel::HighlySensitive,
+            &models,
+            &privacy(Some(SensitivityLevel::Sensitive)),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn backend_list_carries_local_badge() {
+        let backends = backend_list(&models_with_local());
+        let ollama = backends.iter().find(|b| b.id == "ollama").unwrap();
+        let anthropic = backends.iter().find(|b| b.id == "anthropic").unwrap();
+        assert!(ollama.local);
+        assert!(!anthropic.local);
+        assert!(anthropic.is_default);
+    }
+
+    #[test]
+    fn sse_stream_translates_into_agent_events() {
+        let lines = [
+            r#"data: {"choices":[{"delta":{"content":"Hel"}}]}"#,
+            r#"data: {"choices":[{"delta":{"content":"lo"}}]}"#,
+            ": keep-alive",
+            r#"data: {"choices":[{"delta":{}}]}"#,
+            "data: [DONE]",
+        ];
+        let events: Vec<AgentEvent> = lines.iter().filter_map(|l| translate_sse_line(l)).collect();
+        assert_eq!(
+            events,
+            vec![
+                AgentEvent::TextDelta { text: "Hel".into() },
+                AgentEvent::TextDelta { text: "lo".into() },
+                AgentEvent::Done,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn health_check_against_stub_local_server() {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok")
+                    .await;
+            }
+        });
+
+        let client = reqwest::Client::new();
+        assert!(check_local_health(&client, &format!("http://{addr}/")).await);
+        assert!(!check_local_health(&client, "http://127.0.0.1:1/").await);
+    }
+}
@@ -0,0 +1,31 @@
+//! Agent module — direct a3s-code integration.
+
+pub mod bulk;
+pub mod calendar;
+pub mod compaction;
+pub mod context;
+pub mod credentials;
+pub mod handler;
+pub mod history;
+pub mod llm_trace;
+pub mod local_provider;
+pub mod params;
+pub mod permissions;
+pub mod provider_auth;
+pub mod rate_limit;
+pub mod redaction;
+pub mod refusal;
+pub mod replay;
+pub mod residency;
+pub mod resumption;
+pub mod scratchpad;
+pub mod session_store;
+pub mod structured;
+pub mod summary;
+pub mod thinking;
+pub mod timing;
+pub mod tools;
+pub mod undo;
+pub mod watchdog;
+pub mod types;
+pub mod warmup;
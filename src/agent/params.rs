@@ -0,0 +1,273 @@
+//! Per-session model parameter overrides.
+//!
+//! Provider defaults suit most turns, but a user extracting data wants a
+//! near-deterministic model and one brainstorming wants the opposite.
+//! Overrides (temperature, top_p, max_tokens) can be supplied at
+//! `create_session` or changed mid-conversation with `/params`; they're
+//! validated against sane ranges, stored per session, and applied on top of
+//! the provider's [`LlmConfig`] when each generation is configured. Unset
+//! fields fall through to the provider default, and `/params reset` clears
+//! everything.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeClawError};
+
+/// Chat command showing or changing the session's parameters.
+pub const PARAMS_COMMAND: &str = "/params";
+
+/// The resolved model configuration handed to a provider for one
+/// generation: the provider's defaults with any session overrides applied.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlmConfig {
+    pub model: String,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+/// Session-level overrides. `None` means "use the provider default".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModelParamOverrides {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+impl ModelParamOverrides {
+    /// Range-check every set field.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(t) = self.temperature {
+            if !(0.0..=2.0).contains(&t) {
+                return Err(SafeClawError::Config(format!(
+                    "temperature {t} is out of range (0.0–2.0)"
+                )));
+            }
+        }
+        if let Some(p) = self.top_p {
+            if !(0.0..=1.0).contains(&p) || p == 0.0 {
+                return Err(SafeClawError::Config(format!(
+                    "top_p {p} is out of range (0.0 exclusive – 1.0)"
+                )));
+            }
+        }
+        if self.max_tokens == Some(0) {
+            return Err(SafeClawError::Config("max_tokens must be at least 1".into()));
+        }
+        Ok(())
+    }
+
+    /// Apply the set fields on top of a provider-default config.
+    pub fn apply_to(&self, config: &mut LlmConfig) {
+        if self.temperature.is_some() {
+            config.temperature = self.temperature;
+        }
+        if self.top_p.is_some() {
+            config.top_p = self.top_p;
+        }
+        if self.max_tokens.is_some() {
+            config.max_tokens = self.max_tokens;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Per-session override store.
+#[derive(Default)]
+pub struct SessionParams {
+    overrides: Mutex<HashMap<String, ModelParamOverrides>>,
+}
+
+impl SessionParams {
+    /// Validate and store overrides for a session, e.g. from
+    /// `create_session`. Replaces any previous overrides.
+    pub fn set(&self, session_id: &str, overrides: ModelParamOverrides) -> Result<()> {
+        overrides.validate()?;
+        let mut map = self.overrides.lock().expect("session params poisoned");
+        if overrides.is_empty() {
+            map.remove(session_id);
+        } else {
+            map.insert(session_id.to_string(), overrides);
+        }
+        Ok(())
+    }
+
+    /// The session's overrides, empty when none are set.
+    pub fn get(&self, session_id: &str) -> ModelParamOverrides {
+        self.overrides
+            .lock()
+            .expect("session params poisoned")
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The provider defaults with this session's overrides applied.
+    pub fn configure(&self, session_id: &str, provider_default: &LlmConfig) -> LlmConfig {
+        let mut config = provider_default.clone();
+        self.get(session_id).apply_to(&mut config);
+        config
+    }
+
+    pub fn forget(&self, session_id: &str) {
+        self.overrides
+            .lock()
+            .expect("session params poisoned")
+            .remove(session_id);
+    }
+
+    /// Handle one `/params` invocation. No arguments shows the current
+    /// overrides, `reset` clears them, `key=value` pairs change them.
+    pub fn handle_command(&self, session_id: &str, args: &str) -> Result<String> {
+        let args = args.trim();
+        if args.is_empty() {
+            return Ok(Self::describe(&self.get(session_id)));
+        }
+        if args == "reset" {
+            self.forget(session_id);
+            return Ok("Model parameters reset to provider defaults.".into());
+        }
+        let mut overrides = self.get(session_id);
+        for pair in args.split_whitespace() {
+            let (key, value) = pair.split_once('=').ok_or_else(|| {
+                SafeClawError::Config(format!(
+                    "expected key=value, got `{pair}`; try `/params temperature=0.2`"
+                ))
+            })?;
+            let parse_err =
+                || SafeClawError::Config(format!("`{value}` is not a valid value for {key}"));
+            match key {
+                "temperature" => {
+                    overrides.temperature = Some(value.parse().map_err(|_| parse_err())?);
+                }
+                "top_p" => overrides.top_p = Some(value.parse().map_err(|_| parse_err())?),
+                "max_tokens" => {
+                    overrides.max_tokens = Some(value.parse().map_err(|_| parse_err())?);
+                }
+                other => {
+                    return Err(SafeClawError::Config(format!(
+                        "unknown parameter `{other}`; supported: temperature, top_p, max_tokens"
+                    )));
+                }
+            }
+        }
+        self.set(session_id, overrides.clone())?;
+        Ok(Self::describe(&overrides))
+    }
+
+    fn describe(overrides: &ModelParamOverrides) -> String {
+        if overrides.is_empty() {
+            return "No overrides set; using provider defaults.".into();
+        }
+        let mut parts = Vec::new();
+        if let Some(t) = overrides.temperature {
+            parts.push(format!("temperature={t}"));
+        }
+        if let Some(p) = overrides.top_p {
+            parts.push(format!("top_p={p}"));
+        }
+        if let Some(m) = overrides.max_tokens {
+            parts.push(format!("max_tokens={m}"));
+        }
+        format!("Session overrides: {}.", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider_default() -> LlmConfig {
+        LlmConfig {
+            model: "claude-sonnet".into(),
+            temperature: Some(1.0),
+            top_p: None,
+            max_tokens: Some(8192),
+        }
+    }
+
+    #[test]
+    fn a_session_override_flows_into_the_configured_llm_config() {
+        let params = SessionParams::default();
+        params
+            .set(
+                "s1",
+                ModelParamOverrides {
+                    temperature: Some(0.2),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let configured = params.configure("s1", &provider_default());
+        assert_eq!(configured.temperature, Some(0.2));
+        // Unset fields keep the provider defaults.
+        assert_eq!(configured.max_tokens, Some(8192));
+        assert_eq!(configured.model, "claude-sonnet");
+        // Other sessions are untouched.
+        assert_eq!(
+            params.configure("s2", &provider_default()),
+            provider_default()
+        );
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let params = SessionParams::default();
+        for overrides in [
+            ModelParamOverrides {
+                temperature: Some(2.5),
+                ..Default::default()
+            },
+            ModelParamOverrides {
+                top_p: Some(1.5),
+                ..Default::default()
+            },
+            ModelParamOverrides {
+                max_tokens: Some(0),
+                ..Default::default()
+            },
+        ] {
+            assert!(params.set("s1", overrides).is_err());
+        }
+        // Nothing was stored by the failed attempts.
+        assert_eq!(params.get("s1"), ModelParamOverrides::default());
+    }
+
+    #[test]
+    fn the_params_command_shows_sets_and_resets() {
+        let params = SessionParams::default();
+        assert_eq!(
+            params.handle_command("s1", "").unwrap(),
+            "No overrides set; using provider defaults."
+        );
+
+        let shown = params
+            .handle_command("s1", "temperature=0.2 max_tokens=1024")
+            .unwrap();
+        assert!(shown.contains("temperature=0.2"));
+        assert!(shown.contains("max_tokens=1024"));
+        assert_eq!(
+            params.configure("s1", &provider_default()).max_tokens,
+            Some(1024)
+        );
+
+        assert!(params.handle_command("s1", "temperature=9").is_err());
+        assert!(params.handle_command("s1", "vibes=high").is_err());
+        assert!(params.handle_command("s1", "temperature").is_err());
+
+        params.handle_command("s1", "reset").unwrap();
+        assert_eq!(
+            params.configure("s1", &provider_default()),
+            provider_default()
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Per-provider request authentication for enterprise API gateways.
+//!
+//! Corporate gateways in front of LLM providers want more than an API key:
+//! static tag headers (`X-Internal-Team`), an OAuth2 client-credentials
+//! bearer token refreshed before it expires, sometimes an mTLS client
+//! certificate. [`ProviderConfig`] carries the declarations; this module
+//! turns them into the header set applied during LLM client construction.
+//! Tokens are cached per provider and refreshed a skew margin before
+//! expiry, so a slightly slow clock never sends a stale token. A token
+//! endpoint failure surfaces as a provider-configuration error naming the
+//! provider, not a generic generation failure.
+//!
+//! The client secret is a `*_ref` into the credential store, resolved by
+//! the caller like every other secret reference.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ProviderConfig;
+use crate::error::{Result, SafeClawError};
+
+/// Tokens are refreshed this many seconds before their reported expiry, so
+/// clock skew between us and the gateway can't produce a just-expired token.
+pub const TOKEN_SKEW_SECS: i64 = 60;
+
+/// OAuth2 client-credentials settings, under `providers.<name>.oauth`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OAuthClientConfig {
+    pub token_url: String,
+    pub client_id: String,
+    /// Credential-store reference for the client secret.
+    pub client_secret_ref: String,
+    pub scopes: Vec<String>,
+}
+
+/// What the token endpoint returned.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    /// Lifetime in seconds.
+    pub expires_in: i64,
+}
+
+/// The client-credentials exchange — HTTP in production, canned in tests.
+#[async_trait]
+pub trait TokenEndpoint: Send + Sync {
+    async fn fetch(&self, config: &OAuthClientConfig, client_secret: &str)
+        -> Result<TokenResponse>;
+}
+
+/// POSTs the standard client-credentials form to the token URL.
+pub struct HttpTokenEndpoint {
+    http: reqwest::Client,
+}
+
+impl HttpTokenEndpoint {
+    pub fn new() -> Self {
+        Self {
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+impl Default for HttpTokenEndpoint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TokenEndpoint for HttpTokenEndpoint {
+    async fn fetch(
+        &self,
+        config: &OAuthClientConfig,
+        client_secret: &str,
+    ) -> Result<TokenResponse> {
+        let mut form = vec![
+            ("grant_type", "client_credentials".to_string()),
+            ("client_id", config.client_id.clone()),
+            ("client_secret", client_secret.to_string()),
+        ];
+        if !config.scopes.is_empty() {
+            form.push(("scope", config.scopes.join(" ")));
+        }
+        let response = self
+            .http
+            .post(&config.token_url)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| SafeClawError::Config(format!("token endpoint unreachable: {e}")))?;
+        if !response.status().is_success() {
+            return Err(SafeClawError::Config(format!(
+                "token endpoint returned {}",
+                response.status()
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| SafeClawError::Config(format!("token endpoint body: {e}")))
+    }
+}
+
+struct CachedToken {
+    access_token: String,
+    /// Refresh at this time — reported expiry minus the skew margin.
+    refresh_at: i64,
+}
+
+/// Builds the per-request header set for each provider, caching OAuth
+/// tokens across calls.
+pub struct ProviderAuthenticator<E> {
+    endpoint: E,
+    tokens: Mutex<HashMap<String, CachedToken>>,
+}
+
+impl<E: TokenEndpoint> ProviderAuthenticator<E> {
+    pub fn new(endpoint: E) -> Self {
+        Self {
+            endpoint,
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The extra headers for one request to `provider`: the configured
+    /// static headers plus, when OAuth is configured, a bearer token —
+    /// fetched or refreshed as needed. `client_secret` is the resolved
+    /// value of `client_secret_ref`.
+    pub async fn request_headers(
+        &self,
+        provider: &str,
+        config: &ProviderConfig,
+        client_secret: Option<&str>,
+        now: i64,
+    ) -> Result<Vec<(String, String)>> {
+        let mut headers: Vec<(String, String)> = config
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        headers.sort();
+        if let Some(oauth) = &config.oauth {
+            let token = self.bearer_token(provider, oauth, client_secret, now).await?;
+            headers.push(("Authorization".into(), format!("Bearer {token}")));
+        }
+        Ok(headers)
+    }
+
+    async fn bearer_token(
+        &self,
+        provider: &str,
+        oauth: &OAuthClientConfig,
+        client_secret: Option<&str>,
+        now: i64,
+    ) -> Result<String> {
+        {
+            let tokens = self.tokens.lock().expect("provider auth poisoned");
+            if let Some(cached) = tokens.get(provider) {
+                if now < cached.refresh_at {
+                    return Ok(cached.access_token.clone());
+                }
+            }
+        }
+        let secret = client_secret.ok_or_else(|| {
+            SafeClawError::Config(format!(
+                "provider `{provider}`: oauth is configured but `{}` did not \
+                 resolve to a client secret",
+                oauth.client_secret_ref
+            ))
+        })?;
+        let response = self.endpoint.fetch(oauth, secret).await.map_err(|e| {
+            SafeClawError::Config(format!(
+                "provider `{provider}`: could not obtain gateway token: {e}"
+            ))
+        })?;
+        let token = response.access_token.clone();
+        self.tokens.lock().expect("provider auth poisoned").insert(
+            provider.to_string(),
+            CachedToken {
+                access_token: response.access_token,
+                refresh_at: now + (response.expires_in - TOKEN_SKEW_SECS).max(0),
+            },
+        );
+        Ok(token)
+    }
+
+    /// Drop a provider's cached token, e.g. after a 401 from the gateway.
+    pub fn invalidate(&self, provider: &str) {
+        self.tokens
+            .lock()
+            .expect("provider auth poisoned")
+            .remove(provider);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    struct MockEndpoint {
+        fetches: AtomicU32,
+        expires_in: i64,
+        fail: bool,
+    }
+
+    impl MockEndpoint {
+        fn new(expires_in: i64) -> Self {
+            Self {
+                fetches: AtomicU32::new(0),
+                expires_in,
+                fail: false,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TokenEndpoint for MockEndpoint {
+        async fn fetch(
+            &self,
+            _config: &OAuthClientConfig,
+            client_secret: &str,
+        ) -> Result<TokenResponse> {
+            assert_eq!(client_secret, "s3cret");
+            if self.fail {
+                return Err(SafeClawError::Config("token endpoint returned 403".into()));
+            }
+            let n = self.fetches.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(TokenResponse {
+                access_token: format!("tok-{n}"),
+                expires_in: self.expires_in,
+            })
+        }
+    }
+
+    fn provider_config(with_oauth: bool) -> ProviderConfig {
+        let mut config = ProviderConfig {
+            headers: [("X-Internal-Team".to_string(), "ml-platform".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        if with_oauth {
+            config.oauth = Some(OAuthClientConfig {
+                token_url: "https://gateway.example/oauth/token".into(),
+                client_id: "safeclaw".into(),
+                client_secret_ref: "gateway_client_secret".into(),
+                scopes: vec!["llm.invoke".into()],
+            });
+        }
+        config
+    }
+
+    #[tokio::test]
+    async fn static_headers_and_the_bearer_token_are_both_injected() {
+        let auth = ProviderAuthenticator::new(MockEndpoint::new(3_600));
+        let headers = auth
+            .request_headers("corp", &provider_config(true), Some("s3cret"), NOW)
+            .await
+            .unwrap();
+        assert!(headers.contains(&("X-Internal-Team".into(), "ml-platform".into())));
+        assert!(headers.contains(&("Authorization".into(), "Bearer tok-1".into())));
+    }
+
+    #[tokio::test]
+    async fn tokens_are_cached_until_the_skew_margin_before_expiry() {
+        let auth = ProviderAuthenticator::new(MockEndpoint::new(3_600));
+        let config = provider_config(true);
+
+        auth.request_headers("corp", &config, Some("s3cret"), NOW)
+            .await
+            .unwrap();
+        // Well within the hour: the cached token is reused.
+        let headers = auth
+            .request_headers("corp", &config, Some("s3cret"), NOW + 1_800)
+            .await
+            .unwrap();
+        assert!(headers.contains(&("Authorization".into(), "Bearer tok-1".into())));
+        assert_eq!(auth.endpoint.fetches.load(Ordering::SeqCst), 1);
+
+        // Inside the 60s skew margin the token counts as expired already.
+        let headers = auth
+            .request_headers("corp", &config, Some("s3cret"), NOW + 3_600 - 30)
+            .await
+            .unwrap();
+        assert!(headers.contains(&("Authorization".into(), "Bearer tok-2".into())));
+        assert_eq!(auth.endpoint.fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn token_failures_name_the_provider_configuration() {
+        let mut endpoint = MockEndpoint::new(3_600);
+        endpoint.fail = true;
+        let auth = ProviderAuthenticator::new(endpoint);
+        let err = auth
+            .request_headers("corp", &provider_config(true), Some("s3cret"), NOW)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::Config(_)));
+        assert!(err.to_string().contains("provider `corp`"));
+
+        // An unresolved secret reference is called out by name.
+        let auth = ProviderAuthenticator::new(MockEndpoint::new(3_600));
+        let err = auth
+            .request_headers("corp", &provider_config(true), None, NOW)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("gateway_client_secret"));
+    }
+
+    #[tokio::test]
+    async fn providers_without_oauth_get_only_their_static_headers() {
+        let auth = ProviderAuthenticator::new(MockEndpoint::new(3_600));
+        let headers = auth
+            .request_headers("corp", &provider_config(false), None, NOW)
+            .await
+            .unwrap();
+        assert_eq!(headers, [("X-Internal-Team".into(), "ml-platform".into())]);
+    }
+
+    #[tokio::test]
+    async fn invalidation_forces_a_refresh() {
+        let auth = ProviderAuthenticator::new(MockEndpoint::new(3_600));
+        let config = provider_config(true);
+        auth.request_headers("corp", &config, Some("s3cret"), NOW)
+            .await
+            .unwrap();
+        auth.invalidate("corp");
+        let headers = auth
+            .request_headers("corp", &config, Some("s3cret"), NOW)
+            .await
+            .unwrap();
+        assert!(headers.contains(&("Authorization".into(), "Bearer tok-2".into())));
+    }
+}
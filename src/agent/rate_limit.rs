@@ -0,0 +1,216 @@
+//! Client-side provider rate limiting.
+//!
+//! Every provider meters requests and tokens per minute; hitting the limit
+//! mid-conversation surfaces as a 429 to the user. The limiter tracks both
+//! against the configured per-provider budgets over a sliding one-minute
+//! window and queues requests to stay under them, smoothing bursts instead of
+//! failing. A provider 429's `Retry-After` pushes the window out so the next
+//! attempt waits exactly as long as the provider asked.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ModelsConfig;
+
+const WINDOW_MS: u64 = 60_000;
+
+/// Per-provider budgets; zero means unlimited.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderRateLimit {
+    pub requests_per_minute: u32,
+    pub tokens_per_minute: u64,
+}
+
+#[derive(Default)]
+struct ProviderWindow {
+    /// Admission timestamps (ms) within the window.
+    requests: VecDeque<u64>,
+    /// `(admitted_at_ms, tokens)` within the window.
+    tokens: VecDeque<(u64, u64)>,
+    /// Absolute time (ms) before which a provider 429 forbids requests.
+    retry_after_until_ms: u64,
+}
+
+impl ProviderWindow {
+    fn prune(&mut self, now_ms: u64) {
+        while self
+            .requests
+            .front()
+            .is_some_and(|t| now_ms.saturating_sub(*t) >= WINDOW_MS)
+        {
+            self.requests.pop_front();
+        }
+        while self
+            .tokens
+            .front()
+            .is_some_and(|(t, _)| now_ms.saturating_sub(*t) >= WINDOW_MS)
+        {
+            self.tokens.pop_front();
+        }
+    }
+}
+
+/// Sliding-window limiter shared by all provider clients. Admission takes
+/// time explicitly so the window math is deterministic under test; the async
+/// [`acquire`](Self::acquire) wrapper does the queuing.
+pub struct ProviderRateLimiter {
+    limits: HashMap<String, ProviderRateLimit>,
+    windows: Mutex<HashMap<String, ProviderWindow>>,
+    started: tokio::time::Instant,
+}
+
+impl ProviderRateLimiter {
+    pub fn new(limits: HashMap<String, ProviderRateLimit>) -> Self {
+        Self {
+            limits,
+            windows: Mutex::new(HashMap::new()),
+            started: tokio::time::Instant::now(),
+        }
+    }
+
+    pub fn from_models(models: &ModelsConfig) -> Self {
+        Self::new(
+            models
+                .providers
+                .iter()
+                .map(|(name, provider)| (name.clone(), provider.rate_limit.clone()))
+                .collect(),
+        )
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    /// Try to admit a request of `estimated_tokens` now. On refusal returns
+    /// how many milliseconds until the next admission can succeed.
+    pub fn try_acquire(
+        &self,
+        provider: &str,
+        estimated_tokens: u64,
+        now_ms: u64,
+    ) -> std::result::Result<(), u64> {
+        let limit = self.limits.get(provider).cloned().unwrap_or_default();
+        let mut windows = self.windows.lock().expect("rate limiter poisoned");
+        let window = windows.entry(provider.to_string()).or_default();
+        window.prune(now_ms);
+
+        if now_ms < window.retry_after_until_ms {
+            return Err(window.retry_after_until_ms - now_ms);
+        }
+        if limit.requests_per_minute > 0
+            && window.requests.len() >= limit.requests_per_minute as usize
+        {
+            let oldest = *window.requests.front().expect("non-empty at limit");
+            return Err((oldest + WINDOW_MS).saturating_sub(now_ms));
+        }
+        if limit.tokens_per_minute > 0 {
+            let in_window: u64 = window.tokens.iter().map(|(_, t)| t).sum();
+            if in_window + estimated_tokens > limit.tokens_per_minute {
+                let oldest = window
+                    .tokens
+                    .front()
+                    .map(|(t, _)| *t + WINDOW_MS)
+                    .unwrap_or(now_ms);
+                return Err(oldest.saturating_sub(now_ms).max(1));
+            }
+        }
+        window.requests.push_back(now_ms);
+        if estimated_tokens > 0 {
+            window.tokens.push_back((now_ms, estimated_tokens));
+        }
+        Ok(())
+    }
+
+    /// Record a provider 429. Subsequent admissions wait out `Retry-After`.
+    pub fn note_rate_limited(&self, provider: &str, retry_after_secs: u64, now_ms: u64) {
+        let mut windows = self.windows.lock().expect("rate limiter poisoned");
+        let window = windows.entry(provider.to_string()).or_default();
+        window.retry_after_until_ms = window
+            .retry_after_until_ms
+            .max(now_ms + retry_after_secs * 1_000);
+    }
+
+    /// Queue until the request fits under the provider's budgets.
+    pub async fn acquire(&self, provider: &str, estimated_tokens: u64) {
+        loop {
+            match self.try_acquire(provider, estimated_tokens, self.elapsed_ms()) {
+                Ok(()) => return,
+                Err(delay_ms) => {
+                    tokio::time::sleep(Duration::from_millis(delay_ms.max(10))).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn limiter(rpm: u32, tpm: u64) -> ProviderRateLimiter {
+        ProviderRateLimiter::new(HashMap::from([(
+            "anthropic".to_string(),
+            ProviderRateLimit {
+                requests_per_minute: rpm,
+                tokens_per_minute: tpm,
+            },
+        )]))
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_limit_are_deferred_until_the_window_rolls() {
+        let limiter = limiter(2, 0);
+        assert!(limiter.try_acquire("anthropic", 100, 0).is_ok());
+        assert!(limiter.try_acquire("anthropic", 100, 1_000).is_ok());
+        // Third request must wait until the first leaves the window.
+        assert_eq!(limiter.try_acquire("anthropic", 100, 2_000), Err(58_000));
+        assert!(limiter.try_acquire("anthropic", 100, 60_000).is_ok());
+    }
+
+    #[tokio::test]
+    async fn token_budget_is_enforced_alongside_requests() {
+        let limiter = limiter(0, 1_000);
+        assert!(limiter.try_acquire("anthropic", 800, 0).is_ok());
+        assert!(limiter.try_acquire("anthropic", 300, 1_000).is_err());
+        assert!(limiter.try_acquire("anthropic", 200, 1_000).is_ok());
+    }
+
+    #[tokio::test]
+    async fn unconfigured_providers_are_unlimited() {
+        let limiter = limiter(1, 0);
+        for i in 0..50 {
+            assert!(limiter.try_acquire("ollama", 1_000, i).is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_after_from_a_429_is_respected() {
+        let limiter = limiter(10, 0);
+        assert!(limiter.try_acquire("anthropic", 100, 0).is_ok());
+        limiter.note_rate_limited("anthropic", 30, 1_000);
+        assert_eq!(limiter.try_acquire("anthropic", 100, 2_000), Err(29_000));
+        assert!(limiter.try_acquire("anthropic", 100, 31_000).is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn acquire_queues_bursts_and_releases_within_limits() {
+        let limiter = Arc::new(limiter(2, 0));
+        let mut admitted_at = Vec::new();
+        let start = tokio::time::Instant::now();
+        for _ in 0..4 {
+            limiter.acquire("anthropic", 100).await;
+            admitted_at.push(start.elapsed().as_secs());
+        }
+        // Two admitted immediately, the rest smoothed into the next window.
+        assert_eq!(admitted_at[0], 0);
+        assert_eq!(admitted_at[1], 0);
+        assert!(admitted_at[2] >= 59, "third at {}s", admitted_at[2]);
+        assert!(admitted_at[3] >= 59, "fourth at {}s", admitted_at[3]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Persistence-time transcript redaction for TEE-routed turns.
+//!
+//! When a session mixes normal and TEE-processed turns, the user's original
+//! sensitive prompts were typed in the channel and would otherwise be written
+//! verbatim into the persisted `message_history`. This pass replaces such
+//! turns with classifier-typed placeholders before anything touches disk; the
+//! full text lives only in the session's encrypted TEE-side isolation scope.
+//! Browser replay and export show the redacted form unless an admin caller
+//! explicitly requests the sensitive view, which is itself audited.
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::types::{ChatRole, HistoryEntry};
+use crate::privacy::SensitivityLevel;
+
+/// Configuration for persistence-time transcript redaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptRedactionConfig {
+    /// Master switch. Off by default — existing deployments keep full history.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Sessions at or above this sensitivity get their TEE-routed user turns
+    /// redacted on persist.
+    #[serde(default = "default_min_level")]
+    pub min_level: SensitivityLevel,
+}
+
+fn default_min_level() -> SensitivityLevel {
+    SensitivityLevel::Sensitive
+}
+
+impl Default for TranscriptRedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_level: default_min_level(),
+        }
+    }
+}
+
+/// Placeholder stored in place of a redacted turn.
+pub fn redaction_placeholder(category: Option<&str>) -> String {
+    format!("[redacted: {}]", category.unwrap_or("sensitive information"))
+}
+
+/// Apply the redaction pass to a history about to be persisted.
+///
+/// Only user turns that were TEE-routed are redacted, and only when the
+/// session's sensitivity meets the configured floor. The returned entries are
+/// what goes to disk; callers must stash the originals in the TEE-side store
+/// before discarding them.
+pub fn redact_for_persistence(
+    history: &[HistoryEntry],
+    session_level: SensitivityLevel,
+    config: &TranscriptRedactionConfig,
+) -> Vec<HistoryEntry> {
+    if !config.enabled || session_level < config.min_level {
+        return history.to_vec();
+    }
+    history
+        .iter()
+        .map(|entry| {
+            if entry.role == ChatRole::User && entry.tee_routed && !entry.redacted {
+                let mut redacted = entry.clone();
+                redacted.content =
+                    redaction_placeholder(entry.classifier_category.as_deref());
+                redacted.redacted = true;
+                redacted
+            } else {
+                entry.clone()
+            }
+        })
+        .collect()
+}
+
+/// Whether a caller may see the unredacted transcript.
+///
+/// Only the `admin` role, and only when the sensitive view was explicitly
+/// requested — the default replay/export paths always get the redacted form.
+/// Callers must emit an audit event when this returns `true` and the
+/// sensitive view is served.
+pub fn sensitive_view_allowed(caller_role: &str, explicit_request: bool) -> bool {
+    caller_role == "admin" && explicit_request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_with_tee_turn() -> Vec<HistoryEntry> {
+        let mut sensitive = HistoryEntry::new(
+            ChatRole::User,
+            "my MRI results show a lesion",
+            1_700_000_000,
+        );
+        sensitive.tee_routed = true;
+        sensitive.classifier_category = Some("medical information".into());
+        vec![
+            HistoryEntry::new(ChatRole::User, "hello there", 1_699_999_000),
+            sensitive,
+            HistoryEntry::new(ChatRole::Assistant, "I've reviewed the report.", 1_700_000_100),
+        ]
+    }
+
+    fn config() -> TranscriptRedactionConfig {
+        TranscriptRedactionConfig {
+            enabled: true,
+            min_level: SensitivityLevel::Sensitive,
+        }
+    }
+
+    #[test]
+    fn persisted_form_never_contains_original_tee_turn() {
+        let out = redact_for_persistence(
+            &history_with_tee_turn(),
+            SensitivityLevel::HighlySensitive,
+            &config(),
+        );
+        let serialized = serde_json::to_string(&out).unwrap();
+        assert!(!serialized.contains("MRI results"));
+        assert!(serialized.contains("[redacted: medical information]"));
+    }
+
+    #[test]
+    fn non_tee_turns_kept_verbatim() {
+        let out = redact_for_persistence(
+            &history_with_tee_turn(),
+            SensitivityLevel::HighlySensitive,
+            &config(),
+        );
+        assert_eq!(out[0].content, "hello there");
+        assert_eq!(out[2].content, "I've reviewed the report.");
+        assert!(out[1].redacted);
+    }
+
+    #[test]
+    fn below_threshold_session_untouched() {
+        let out = redact_for_persistence(
+            &history_with_tee_turn(),
+            SensitivityLevel::Normal,
+            &config(),
+        );
+        assert!(out.iter().all(|e| !e.redacted));
+    }
+
+    #[test]
+    fn disabled_config_untouched() {
+        let out = redact_for_persistence(
+            &history_with_tee_turn(),
+            SensitivityLevel::HighlySensitive,
+            &TranscriptRedactionConfig::default(),
+        );
+        assert!(out.iter().all(|e| !e.redacted));
+    }
+
+    #[test]
+    fn sensitive_view_requires_admin_and_explicit_request() {
+        assert!(sensitive_view_allowed("admin", true));
+        assert!(!sensitive_view_allowed("admin", false));
+        assert!(!sensitive_view_allowed("user", true));
+    }
+}
@@ -0,0 +1,235 @@
+//! Provider content-policy refusal detection and handling.
+//!
+//! Classifies provider responses/errors into refusal vs transient vs other so
+//! the delivery paths can react appropriately: chats get a friendly templated
+//! explanation instead of the raw refusal, scheduled tasks can retry once
+//! with a sanitized-rephrase instruction or record the run as `refused`
+//! (distinct from `failed`), and every refusal is recorded under a dedicated
+//! audit/usage category so refusal rates are visible per model.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// Audit/usage category recorded for every detected refusal.
+pub const AUDIT_CATEGORY_REFUSAL: &str = "provider_refusal";
+
+/// Classification of a provider reply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ProviderOutcome {
+    /// The provider's safety layer refused the request.
+    Refusal { message: String },
+    /// Retryable: rate limits, overload, 5xx.
+    Transient { message: String },
+    /// Anything else (including successful replies).
+    Other,
+}
+
+/// Classify a provider reply from its HTTP status and response body.
+/// Detection is provider-specific and covers the Anthropic and OpenAI
+/// response shapes.
+pub fn classify_reply(
+    provider: &str,
+    status: Option<u16>,
+    body: &serde_json::Value,
+) -> ProviderOutcome {
+    if let Some(code) = status {
+        if code == 429 || (500..=504).contains(&code) {
+            return ProviderOutcome::Transient {
+                message: format!("HTTP {code}"),
+            };
+        }
+    }
+    match provider {
+        "anthropic" => classify_anthropic(body),
+        "openai" => classify_openai(body),
+        _ => ProviderOutcome::Other,
+    }
+}
+
+fn classify_anthropic(body: &serde_json::Value) -> ProviderOutcome {
+    if body.get("stop_reason").and_then(|v| v.as_str()) == Some("refusal") {
+        return ProviderOutcome::Refusal {
+            message: "model refused (stop_reason: refusal)".into(),
+        };
+    }
+    if let Some(error) = body.get("error") {
+        let error_type = error.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        let message = error
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        return match error_type {
+            "overloaded_error" | "api_error" | "rate_limit_error" => {
+                ProviderOutcome::Transient { message }
+            }
+            "invalid_request_error" if message.to_lowercase().contains("safety") => {
+                ProviderOutcome::Refusal { message }
+            }
+            _ => ProviderOutcome::Other,
+        };
+    }
+    ProviderOutcome::Other
+}
+
+fn classify_openai(body: &serde_json::Value) -> ProviderOutcome {
+    let content_filtered = body
+        .get("choices")
+        .and_then(|v| v.as_array())
+        .map(|choices| {
+            choices.iter().any(|c| {
+                c.get("finish_reason").and_then(|v| v.as_str()) == Some("content_filter")
+            })
+        })
+        .unwrap_or(false);
+    if content_filtered {
+        return ProviderOutcome::Refusal {
+            message: "model refused (finish_reason: content_filter)".into(),
+        };
+    }
+    if let Some(error) = body.get("error") {
+        let code = error.get("code").and_then(|v| v.as_str()).unwrap_or("");
+        let message = error
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        return match code {
+            "content_policy_violation" => ProviderOutcome::Refusal { message },
+            "rate_limit_exceeded" | "server_error" => ProviderOutcome::Transient { message },
+            _ => ProviderOutcome::Other,
+        };
+    }
+    ProviderOutcome::Other
+}
+
+/// Configurable handling strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefusalConfig {
+    /// Template delivered to chat users instead of the raw refusal.
+    pub chat_template: String,
+    /// Scheduled tasks: retry once with the rephrase instruction before
+    /// recording the run as refused.
+    pub scheduled_retry_with_rephrase: bool,
+    /// System instruction prepended on the rephrase retry.
+    pub rephrase_instruction: String,
+}
+
+impl Default for RefusalConfig {
+    fn default() -> Self {
+        Self {
+            chat_template: "I wasn't able to help with that request — the model's safety \
+                            filter declined it. Rephrasing may help."
+                .into(),
+            scheduled_retry_with_rephrase: false,
+            rephrase_instruction: "Rephrase the task neutrally and factually, omitting any \
+                                   content that could trigger safety filters."
+                .into(),
+        }
+    }
+}
+
+/// Per-model refusal counters backing the usage view.
+#[derive(Debug, Default)]
+pub struct RefusalStats {
+    counts: Mutex<HashMap<String, u64>>,
+}
+
+impl RefusalStats {
+    pub async fn record(&self, model: &str) {
+        *self.counts.lock().await.entry(model.to_string()).or_insert(0) += 1;
+    }
+
+    pub async fn snapshot(&self) -> HashMap<String, u64> {
+        self.counts.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn anthropic_stop_reason_refusal_detected() {
+        let body = json!({ "stop_reason": "refusal", "content": [] });
+        assert!(matches!(
+            classify_reply("anthropic", Some(200), &body),
+            ProviderOutcome::Refusal { .. }
+        ));
+    }
+
+    #[test]
+    fn anthropic_safety_error_is_refusal_but_overload_is_transient() {
+        let refusal = json!({
+            "type": "error",
+            "error": { "type": "invalid_request_error", "message": "Request blocked by safety system" }
+        });
+        assert!(matches!(
+            classify_reply("anthropic", Some(400), &refusal),
+            ProviderOutcome::Refusal { .. }
+        ));
+
+        let overload = json!({
+            "type": "error",
+            "error": { "type": "overloaded_error", "message": "Overloaded" }
+        });
+        assert!(matches!(
+            classify_reply("anthropic", None, &overload),
+            ProviderOutcome::Transient { .. }
+        ));
+    }
+
+    #[test]
+    fn openai_content_filter_detected() {
+        let body = json!({
+            "choices": [ { "finish_reason": "content_filter", "message": { "content": null } } ]
+        });
+        assert!(matches!(
+            classify_reply("openai", Some(200), &body),
+            ProviderOutcome::Refusal { .. }
+        ));
+
+        let policy = json!({
+            "error": { "code": "content_policy_violation", "message": "rejected" }
+        });
+        assert!(matches!(
+            classify_reply("openai", Some(400), &policy),
+            ProviderOutcome::Refusal { .. }
+        ));
+    }
+
+    #[test]
+    fn http_5xx_and_429_are_transient_for_any_provider() {
+        let body = json!({});
+        assert!(matches!(
+            classify_reply("openai", Some(429), &body),
+            ProviderOutcome::Transient { .. }
+        ));
+        assert!(matches!(
+            classify_reply("anthropic", Some(503), &body),
+            ProviderOutcome::Transient { .. }
+        ));
+    }
+
+    #[test]
+    fn successful_reply_is_other() {
+        let body = json!({ "choices": [ { "finish_reason": "stop" } ] });
+        assert_eq!(classify_reply("openai", Some(200), &body), ProviderOutcome::Other);
+    }
+
+    #[tokio::test]
+    async fn refusal_stats_count_per_model() {
+        let stats = RefusalStats::default();
+        stats.record("claude-sonnet-4").await;
+        stats.record("claude-sonnet-4").await;
+        stats.record("gpt-4o").await;
+        let snapshot = stats.snapshot().await;
+        assert_eq!(snapshot.get("claude-sonnet-4"), Some(&2));
+        assert_eq!(snapshot.get("gpt-4o"), Some(&1));
+    }
+}
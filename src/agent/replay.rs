@@ -0,0 +1,244 @@
+//! Token-efficient history replay for browser reconnects.
+//!
+//! Replaying a session with big tool outputs pushes megabytes of collapsed
+//! result blobs nobody looks at. The replay payload replaces large tool
+//! content with a truncated preview plus a `fullContentRef` the UI fetches on
+//! demand (`GET /api/agent/sessions/:id/messages/:idx/content`). A
+//! per-message size threshold and a total replay budget control truncation;
+//! the persisted history on disk keeps the full content — only the wire
+//! replay changes.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::types::{ChatRole, HistoryEntry};
+
+/// Marker appended to truncated previews.
+pub const REPLAY_TRUNCATION_MARKER: &str = "…(truncated — fetch full content)";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayConfig {
+    /// Tool content above this many bytes is truncated in the replay.
+    pub per_message_threshold_bytes: usize,
+    /// Total replay budget; once exceeded, further oversized tool content is
+    /// truncated even below the per-message threshold.
+    pub total_budget_bytes: usize,
+    /// Preview length kept for truncated content.
+    pub preview_bytes: usize,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            per_message_threshold_bytes: 16 * 1024,
+            total_budget_bytes: 256 * 1024,
+            preview_bytes: 1024,
+        }
+    }
+}
+
+/// One entry in the replay payload sent over the WebSocket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayEntry {
+    pub role: ChatRole,
+    pub content: String,
+    pub timestamp: i64,
+    pub truncated: bool,
+    /// Path the UI fetches to get the full block, set when truncated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_content_ref: Option<String>,
+    /// Size of the full content on disk, for the UI's "show N KiB" affordance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_size_bytes: Option<usize>,
+}
+
+fn truncate_at_boundary(content: &str, max: usize) -> &str {
+    let mut end = max.min(content.len());
+    while end > 0 && !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    &content[..end]
+}
+
+fn truncated_entry(session_id: &str, idx: usize, entry: &HistoryEntry, preview: usize) -> ReplayEntry {
+    let mut content = truncate_at_boundary(&entry.content, preview).to_string();
+    content.push_str(REPLAY_TRUNCATION_MARKER);
+    ReplayEntry {
+        role: entry.role,
+        content,
+        timestamp: entry.timestamp,
+        truncated: true,
+        full_content_ref: Some(format!(
+            "/api/agent/sessions/{session_id}/messages/{idx}/content"
+        )),
+        full_size_bytes: Some(entry.content.len()),
+    }
+}
+
+fn full_entry(entry: &HistoryEntry) -> ReplayEntry {
+    ReplayEntry {
+        role: entry.role,
+        content: entry.content.clone(),
+        timestamp: entry.timestamp,
+        truncated: false,
+        full_content_ref: None,
+        full_size_bytes: None,
+    }
+}
+
+/// Build the replay payload. Only tool entries are ever truncated — user and
+/// assistant turns are what the person actually reads. Newer entries get
+/// budget priority: when the total exceeds the budget, the oldest oversized
+/// tool entries are truncated first.
+pub fn build_replay(
+    session_id: &str,
+    history: &[HistoryEntry],
+    config: &ReplayConfig,
+) -> Vec<ReplayEntry> {
+    let mut replay: Vec<ReplayEntry> = history
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| {
+            if entry.role == ChatRole::Tool
+                && entry.content.len() > config.per_message_threshold_bytes
+            {
+                truncated_entry(session_id, idx, entry, config.preview_bytes)
+            } else {
+                full_entry(entry)
+            }
+        })
+        .collect();
+
+    let mut total: usize = replay.iter().map(|e| e.content.len()).sum();
+    for idx in 0..replay.len() {
+        if total <= config.total_budget_bytes {
+            break;
+        }
+        let entry = &replay[idx];
+        if entry.truncated || history[idx].role != ChatRole::Tool {
+            continue;
+        }
+        let saved = entry.content.len();
+        replay[idx] = truncated_entry(session_id, idx, &history[idx], config.preview_bytes);
+        total = total - saved + replay[idx].content.len();
+    }
+    replay
+}
+
+/// Where the full persisted history comes from — the session manager in
+/// production, a fixture in tests.
+#[async_trait]
+pub trait HistorySource: Send + Sync {
+    async fn history(&self, session_id: &str) -> Option<Vec<HistoryEntry>>;
+}
+
+/// Resolve one full content block for the on-demand fetch endpoint.
+pub async fn full_content(
+    source: &dyn HistorySource,
+    session_id: &str,
+    idx: usize,
+) -> Option<String> {
+    source
+        .history(session_id)
+        .await?
+        .get(idx)
+        .map(|entry| entry.content.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(role: ChatRole, content: String) -> HistoryEntry {
+        HistoryEntry::new(role, content, 0)
+    }
+
+    fn config() -> ReplayConfig {
+        ReplayConfig {
+            per_message_threshold_bytes: 100,
+            total_budget_bytes: 1_000,
+            preview_bytes: 20,
+        }
+    }
+
+    #[test]
+    fn oversized_tool_output_is_truncated_with_a_ref() {
+        let history = vec![
+            entry(ChatRole::User, "run the tests".into()),
+            entry(ChatRole::Tool, "x".repeat(500)),
+            entry(ChatRole::Assistant, "done".into()),
+        ];
+        let replay = build_replay("s1", &history, &config());
+        assert!(!replay[0].truncated);
+        assert!(replay[1].truncated);
+        assert!(replay[1].content.ends_with(REPLAY_TRUNCATION_MARKER));
+        assert_eq!(
+            replay[1].full_content_ref.as_deref(),
+            Some("/api/agent/sessions/s1/messages/1/content")
+        );
+        assert_eq!(replay[1].full_size_bytes, Some(500));
+        assert!(!replay[2].truncated);
+    }
+
+    #[test]
+    fn small_histories_are_untouched() {
+        let history = vec![
+            entry(ChatRole::User, "hi".into()),
+            entry(ChatRole::Tool, "ok".into()),
+        ];
+        let replay = build_replay("s1", &history, &config());
+        assert!(replay.iter().all(|e| !e.truncated));
+        assert_eq!(replay[1].content, "ok");
+    }
+
+    #[test]
+    fn total_budget_truncates_oldest_tool_entries_first() {
+        // Each tool entry is under the per-message threshold but together
+        // they blow the total budget.
+        let history = vec![
+            entry(ChatRole::Tool, "a".repeat(90)),
+            entry(ChatRole::Tool, "b".repeat(90)),
+            entry(ChatRole::Tool, "c".repeat(90)),
+        ];
+        let config = ReplayConfig {
+            per_message_threshold_bytes: 100,
+            total_budget_bytes: 200,
+            preview_bytes: 10,
+        };
+        let replay = build_replay("s1", &history, &config);
+        assert!(replay[0].truncated);
+        assert!(!replay[2].truncated);
+    }
+
+    #[test]
+    fn assistant_turns_are_never_truncated() {
+        let history = vec![entry(ChatRole::Assistant, "x".repeat(500))];
+        let replay = build_replay("s1", &history, &config());
+        assert!(!replay[0].truncated);
+    }
+
+    struct FixtureSource(Vec<HistoryEntry>);
+
+    #[async_trait]
+    impl HistorySource for FixtureSource {
+        async fn history(&self, session_id: &str) -> Option<Vec<HistoryEntry>> {
+            (session_id == "s1").then(|| self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn on_demand_fetch_returns_the_full_block() {
+        let big = "x".repeat(500);
+        let source = FixtureSource(vec![entry(ChatRole::Tool, big.clone())]);
+
+        let replay = build_replay("s1", &source.0, &config());
+        assert!(replay[0].truncated);
+
+        let fetched = full_content(&source, "s1", 0).await.unwrap();
+        assert_eq!(fetched, big);
+        assert!(full_content(&source, "s1", 5).await.is_none());
+        assert!(full_content(&source, "other", 0).await.is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! Region-aware data residency for provider selection.
+//!
+//! Chats or users can be tagged with a required region (GDPR: EU-resident
+//! processing); model selection for such a session only considers providers
+//! whose declared `region` satisfies the requirement — the default provider
+//! and the fallback chain included. Local providers satisfy any requirement
+//! because the content never leaves the machine. When no compliant provider
+//! exists the selection errors instead of silently routing elsewhere, and
+//! every routing decision is serializable for the trace/audit log so
+//! compliance can be demonstrated.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ModelsConfig, ProviderKind, Region};
+use crate::error::{Result, SafeClawError};
+
+/// Audit category for residency routing decisions.
+pub const AUDIT_CATEGORY_RESIDENCY: &str = "residency_routing";
+
+/// Whether a provider's declared region satisfies a requirement. Undeclared
+/// regions never do; `Local` satisfies everything.
+pub fn region_satisfies(declared: Option<Region>, required: Region) -> bool {
+    match declared {
+        Some(Region::Local) => true,
+        Some(region) => region == required,
+        None => false,
+    }
+}
+
+/// Provider names compliant with `required`, sorted. With no requirement all
+/// providers qualify — this backs `GET /api/agent/models?region=...`.
+pub fn compliant_providers(models: &ModelsConfig, required: Option<Region>) -> Vec<String> {
+    let mut names: Vec<String> = models
+        .providers
+        .iter()
+        .filter(|(_, p)| {
+            let declared = if p.kind == ProviderKind::Local {
+                Some(Region::Local)
+            } else {
+                p.region
+            };
+            required
+                .map(|r| region_satisfies(declared, r))
+                .unwrap_or(true)
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+/// One residency routing decision, recorded in the trace/audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResidencyDecision {
+    pub session_id: String,
+    pub required_region: Region,
+    pub selected_provider: String,
+    /// Default + fallback chain, in the order considered.
+    pub considered: Vec<String>,
+}
+
+/// Select a provider for a session with a residency requirement: the default
+/// provider first, then the fallback chain, skipping non-compliant entries.
+pub fn select_compliant_provider(
+    session_id: &str,
+    required: Region,
+    models: &ModelsConfig,
+) -> Result<ResidencyDecision> {
+    let mut considered = vec![models.default_provider.clone()];
+    considered.extend(models.fallback_providers.iter().cloned());
+
+    let compliant = compliant_providers(models, Some(required));
+    let selected = considered
+        .iter()
+        .find(|name| compliant.contains(name))
+        .cloned()
+        .ok_or_else(|| {
+            SafeClawError::Config(format!(
+                "no configured provider satisfies residency region {required:?} \
+                 (considered: {})",
+                considered.join(", ")
+            ))
+        })?;
+    Ok(ResidencyDecision {
+        session_id: session_id.to_string(),
+        required_region: required,
+        selected_provider: selected,
+        considered,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProviderConfig;
+
+    fn models() -> ModelsConfig {
+        let mut models = ModelsConfig {
+            default_provider: "anthropic-us".into(),
+            fallback_providers: vec!["anthropic-eu".into(), "ollama".into()],
+            ..Default::default()
+        };
+        models.providers.insert(
+            "anthropic-us".into(),
+            ProviderConfig {
+                region: Some(Region::Us),
+                ..Default::default()
+            },
+        );
+        models.providers.insert(
+            "anthropic-eu".into(),
+            ProviderConfig {
+                region: Some(Region::Eu),
+                ..Default::default()
+            },
+        );
+        models.providers.insert(
+            "ollama".into(),
+            ProviderConfig {
+                kind: ProviderKind::Local,
+                ..Default::default()
+            },
+        );
+        models
+    }
+
+    #[test]
+    fn selection_filters_to_the_required_region() {
+        let decision = select_compliant_provider("s1", Region::Eu, &models()).unwrap();
+        assert_eq!(decision.selected_provider, "anthropic-eu");
+        assert_eq!(
+            decision.considered,
+            vec!["anthropic-us", "anthropic-eu", "ollama"]
+        );
+    }
+
+    #[test]
+    fn fallback_chain_skips_non_compliant_entries() {
+        let mut models = models();
+        models.providers.remove("anthropic-eu");
+        // EU requirement: default (us) and first fallback (gone) fail;
+        // the local provider satisfies any region.
+        let decision = select_compliant_provider("s1", Region::Eu, &models).unwrap();
+        assert_eq!(decision.selected_provider, "ollama");
+    }
+
+    #[test]
+    fn no_compliant_provider_is_a_clear_error() {
+        let mut models = models();
+        models.providers.remove("anthropic-eu");
+        models.providers.remove("ollama");
+        models.fallback_providers.clear();
+        let err = select_compliant_provider("s1", Region::Eu, &models).unwrap_err();
+        assert!(matches!(err, SafeClawError::Config(ref m) if m.contains("residency")));
+    }
+
+    #[test]
+    fn undeclared_region_never_satisfies_a_requirement() {
+        assert!(!region_satisfies(None, Region::Eu));
+        assert!(region_satisfies(Some(Region::Local), Region::Eu));
+        assert!(!region_satisfies(Some(Region::Us), Region::Eu));
+    }
+
+    #[test]
+    fn model_list_filter_by_region() {
+        let eu = compliant_providers(&models(), Some(Region::Eu));
+        assert_eq!(eu, vec!["anthropic-eu", "ollama"]);
+        let all = compliant_providers(&models(), None);
+        assert_eq!(all.len(), 3);
+    }
+}
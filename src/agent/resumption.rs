@@ -0,0 +1,108 @@
+//! Resumption context for sessions restored after a restart.
+//!
+//! A restored session drops the model straight back into an old
+//! conversation with no sense that time passed or that a restart happened.
+//! When enabled, the first post-restore turn gets a system note — "Resuming
+//! after N hours…" — generated from the stored state, optionally carrying
+//! the session summary so the model is grounded before it answers.
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::types::{ChatRole, HistoryEntry};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumptionConfig {
+    pub enabled: bool,
+    /// Gaps shorter than this get no note — a quick restart isn't worth a
+    /// system turn.
+    pub min_gap_secs: i64,
+}
+
+impl Default for ResumptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_gap_secs: 300,
+        }
+    }
+}
+
+fn format_elapsed(secs: i64) -> String {
+    if secs >= 48 * 3600 {
+        format!("{} days", secs / (24 * 3600))
+    } else if secs >= 2 * 3600 {
+        format!("{} hours", secs / 3600)
+    } else {
+        format!("{} minutes", (secs / 60).max(1))
+    }
+}
+
+/// Build the system note for a restored session's first turn, or `None`
+/// when disabled or the gap is below the threshold. `last_activity` is the
+/// newest timestamp in the stored history.
+pub fn build_resumption_entry(
+    config: &ResumptionConfig,
+    last_activity: i64,
+    now: i64,
+    summary: Option<&str>,
+) -> Option<HistoryEntry> {
+    if !config.enabled {
+        return None;
+    }
+    let gap = now - last_activity;
+    if gap < config.min_gap_secs {
+        return None;
+    }
+    let mut note = format!(
+        "Session resumed after a gateway restart; {} have passed since the \
+         last message. Earlier context may be stale.",
+        format_elapsed(gap)
+    );
+    if let Some(summary) = summary {
+        note.push_str("\n\nPrevious context, summarized:\n");
+        note.push_str(summary);
+    }
+    Some(HistoryEntry::new(ChatRole::System, note, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restored_session_gets_the_resumption_note() {
+        let entry = build_resumption_entry(
+            &ResumptionConfig::default(),
+            1_000,
+            1_000 + 5 * 3600,
+            Some("User was debugging a flaky CI job."),
+        )
+        .expect("gap above threshold should produce a note");
+        assert_eq!(entry.role, ChatRole::System);
+        assert!(entry.content.contains("5 hours"));
+        assert!(entry.content.contains("flaky CI job"));
+    }
+
+    #[test]
+    fn disabled_config_injects_nothing() {
+        let config = ResumptionConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(build_resumption_entry(&config, 0, 1_000_000, None).is_none());
+    }
+
+    #[test]
+    fn short_gaps_are_skipped() {
+        let config = ResumptionConfig::default();
+        assert!(build_resumption_entry(&config, 1_000, 1_030, None).is_none());
+    }
+
+    #[test]
+    fn elapsed_formatting_scales_with_the_gap() {
+        assert_eq!(format_elapsed(90), "1 minutes");
+        assert_eq!(format_elapsed(3 * 3600), "3 hours");
+        assert_eq!(format_elapsed(3 * 24 * 3600), "3 days");
+    }
+}
@@ -0,0 +1,283 @@
+//! Session-scoped ephemeral scratchpad.
+//!
+//! Working state the agent needs across turns within one conversation — a
+//! draft it is iterating on, a running checklist — without stuffing it into
+//! the chat or polluting long-term memory. The scratchpad lives in the
+//! session's isolation scope: sensitivity rules and wipe-on-terminate apply
+//! automatically, the memory extractor never sees it, and it persists with
+//! the session across gateway restarts. Exposed to the model as the
+//! `scratchpad_set` / `scratchpad_get` / `scratchpad_list` tools.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use crate::error::{Result, SafeClawError};
+
+/// Total bytes (keys + values) one session's scratchpad may hold.
+pub const MAX_SCRATCHPAD_BYTES: usize = 64 * 1024;
+
+/// Per-session key-value scratchpad store.
+pub struct ScratchpadStore {
+    /// Persistence root — one `<session_id>.json` per session; `None` keeps
+    /// the store memory-only (tests).
+    dir: Option<PathBuf>,
+    pads: Mutex<HashMap<String, HashMap<String, String>>>,
+}
+
+impl ScratchpadStore {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            pads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn pad_path(&self, session_id: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{session_id}.json")))
+    }
+
+    fn pad_size(pad: &HashMap<String, String>) -> usize {
+        pad.iter().map(|(k, v)| k.len() + v.len()).sum()
+    }
+
+    async fn persist(&self, session_id: &str, pad: &HashMap<String, String>) -> Result<()> {
+        if let Some(path) = self.pad_path(session_id) {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, serde_json::to_vec(pad)?).await?;
+        }
+        Ok(())
+    }
+
+    /// Load a session's scratchpad from disk on session restore.
+    pub async fn restore(&self, session_id: &str) -> Result<()> {
+        let Some(path) = self.pad_path(session_id) else {
+            return Ok(());
+        };
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => {
+                let pad: HashMap<String, String> = serde_json::from_slice(&bytes)?;
+                self.pads.lock().await.insert(session_id.to_string(), pad);
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn set(&self, session_id: &str, key: &str, value: &str) -> Result<()> {
+        let mut pads = self.pads.lock().await;
+        let pad = pads.entry(session_id.to_string()).or_default();
+        let new_size = Self::pad_size(pad)
+            - pad.get(key).map(|v| key.len() + v.len()).unwrap_or(0)
+            + key.len()
+            + value.len();
+        if new_size > MAX_SCRATCHPAD_BYTES {
+            return Err(SafeClawError::Session(format!(
+                "scratchpad full: {new_size} bytes exceeds the {MAX_SCRATCHPAD_BYTES} byte limit"
+            )));
+        }
+        pad.insert(key.to_string(), value.to_string());
+        let snapshot = pad.clone();
+        drop(pads);
+        self.persist(session_id, &snapshot).await
+    }
+
+    pub async fn get(&self, session_id: &str, key: &str) -> Option<String> {
+        self.pads
+            .lock()
+            .await
+            .get(session_id)
+            .and_then(|pad| pad.get(key).cloned())
+    }
+
+    /// Sorted key list with value sizes — what `scratchpad_list` returns.
+    pub async fn list(&self, session_id: &str) -> Vec<(String, usize)> {
+        let pads = self.pads.lock().await;
+        let mut keys: Vec<(String, usize)> = pads
+            .get(session_id)
+            .map(|pad| pad.iter().map(|(k, v)| (k.clone(), v.len())).collect())
+            .unwrap_or_default();
+        keys.sort();
+        keys
+    }
+
+    /// Full contents for the debug endpoint.
+    pub async fn snapshot(&self, session_id: &str) -> HashMap<String, String> {
+        self.pads
+            .lock()
+            .await
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Wipe-on-terminate: drop the in-memory pad and remove its file.
+    pub async fn wipe_session(&self, session_id: &str) -> Result<()> {
+        self.pads.lock().await.remove(session_id);
+        if let Some(path) = self.pad_path(session_id) {
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Tool definitions registered through the same wiring as `manage_skill`
+    /// in `build_agent_state`.
+    pub fn tool_specs() -> Vec<Value> {
+        vec![
+            json!({
+                "name": "scratchpad_set",
+                "description": "Store a value under a key in this session's ephemeral scratchpad. Overwrites any existing value.",
+                "input_schema": {
+                    "type": "object",
+                    "properties": {
+                        "key": { "type": "string" },
+                        "value": { "type": "string" }
+                    },
+                    "required": ["key", "value"]
+                }
+            }),
+            json!({
+                "name": "scratchpad_get",
+                "description": "Read a value from this session's scratchpad.",
+                "input_schema": {
+                    "type": "object",
+                    "properties": { "key": { "type": "string" } },
+                    "required": ["key"]
+                }
+            }),
+            json!({
+                "name": "scratchpad_list",
+                "description": "List scratchpad keys and value sizes for this session.",
+                "input_schema": { "type": "object", "properties": {} }
+            }),
+        ]
+    }
+
+    /// Dispatch a scratchpad tool invocation for a session.
+    pub async fn handle_tool_call(
+        self: &Arc<Self>,
+        session_id: &str,
+        tool_name: &str,
+        input: &Value,
+    ) -> Result<Value> {
+        let str_arg = |name: &str| -> Result<String> {
+            input
+                .get(name)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    SafeClawError::Session(format!("{tool_name}: missing string argument `{name}`"))
+                })
+        };
+        match tool_name {
+            "scratchpad_set" => {
+                self.set(session_id, &str_arg("key")?, &str_arg("value")?)
+                    .await?;
+                Ok(json!({ "ok": true }))
+            }
+            "scratchpad_get" => {
+                let value = self.get(session_id, &str_arg("key")?).await;
+                Ok(json!({ "value": value }))
+            }
+            "scratchpad_list" => {
+                let keys: Vec<Value> = self
+                    .list(session_id)
+                    .await
+                    .into_iter()
+                    .map(|(key, size)| json!({ "key": key, "sizeBytes": size }))
+                    .collect();
+                Ok(json!({ "keys": keys }))
+            }
+            other => Err(SafeClawError::Session(format!(
+                "unknown scratchpad tool `{other}`"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tool_round_trip() {
+        let store = Arc::new(ScratchpadStore::new(None));
+        let result = store
+            .handle_tool_call(
+                "s1",
+                "scratchpad_set",
+                &json!({ "key": "draft", "value": "v1" }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "ok": true }));
+
+        let result = store
+            .handle_tool_call("s1", "scratchpad_get", &json!({ "key": "draft" }))
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "value": "v1" }));
+
+        let result = store
+            .handle_tool_call("s1", "scratchpad_list", &json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result["keys"][0]["key"], "draft");
+
+        // Other sessions see nothing.
+        let result = store
+            .handle_tool_call("s2", "scratchpad_get", &json!({ "key": "draft" }))
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "value": null }));
+    }
+
+    #[tokio::test]
+    async fn size_bound_is_enforced() {
+        let store = ScratchpadStore::new(None);
+        let big = "x".repeat(MAX_SCRATCHPAD_BYTES);
+        let err = store.set("s1", "k", &big).await.unwrap_err();
+        assert!(matches!(err, SafeClawError::Session(ref m) if m.contains("scratchpad full")));
+
+        // Overwriting an existing key counts the replacement, not the sum.
+        let half = "y".repeat(MAX_SCRATCHPAD_BYTES / 2);
+        store.set("s1", "k", &half).await.unwrap();
+        store.set("s1", "k", &half).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn wipe_on_terminate_removes_memory_and_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ScratchpadStore::new(Some(dir.path().to_path_buf()));
+        store.set("s1", "draft", "v1").await.unwrap();
+        let path = dir.path().join("s1.json");
+        assert!(path.exists());
+
+        store.wipe_session("s1").await.unwrap();
+        assert!(store.get("s1", "draft").await.is_none());
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn scratchpad_survives_restart_via_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ScratchpadStore::new(Some(dir.path().to_path_buf()));
+        store.set("s1", "draft", "v1").await.unwrap();
+
+        let reopened = ScratchpadStore::new(Some(dir.path().to_path_buf()));
+        reopened.restore("s1").await.unwrap();
+        assert_eq!(reopened.get("s1", "draft").await.as_deref(), Some("v1"));
+    }
+}
@@ -0,0 +1,357 @@
+//! UI session state persistence (JSON files).
+//!
+//! Persistence is safe under concurrent turns: writes to one session are
+//! serialized through a per-session named lock, files are replaced
+//! atomically (temp + fsync + rename) so readers never observe a partial
+//! write, rapid successive persists of the same session are debounced into
+//! one write, and corrupt files found on restore are quarantined instead of
+//! failing the whole `restore_from_disk`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::{Result, SafeClawError};
+
+/// When session state reaches disk. This knowingly trades durability for
+/// throughput: `Immediate` persists on every event (the historical behavior),
+/// the other modes coalesce. Shutdown and critical events (permission
+/// changes) always force a flush regardless of policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "mode")]
+pub enum FlushPolicy {
+    #[default]
+    Immediate,
+    /// Coalesce writes of one session within the window; the latest state
+    /// wins.
+    Debounced { window_ms: u64 },
+    /// Writes happen only on the periodic flusher tick.
+    Periodic { interval_ms: u64 },
+}
+
+/// File-based store for agent session UI state.
+pub struct AgentSessionStore {
+    dir: PathBuf,
+    policy: FlushPolicy,
+    /// Per-session named locks serializing the write path.
+    locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// Latest unwritten state per session; a pending flush task drains it.
+    pending: Arc<Mutex<HashMap<String, serde_json::Value>>>,
+    /// Number of completed disk writes, for observability and tests.
+    writes: AtomicU64,
+}
+
+impl AgentSessionStore {
+    pub fn new(dir: impl Into<PathBuf>, policy: FlushPolicy) -> Self {
+        Self {
+            dir: dir.into(),
+            policy,
+            locks: Mutex::new(HashMap::new()),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    /// Completed disk writes since creation.
+    pub fn write_count(&self) -> u64 {
+        self.writes.load(Ordering::Relaxed)
+    }
+
+    /// Spawn the background flusher for `FlushPolicy::Periodic`. No-op task
+    /// for the other policies.
+    pub fn spawn_periodic_flusher(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let store = Arc::clone(self);
+        tokio::spawn(async move {
+            let FlushPolicy::Periodic { interval_ms } = store.policy else {
+                return;
+            };
+            let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+            loop {
+                ticker.tick().await;
+                if let Err(e) = store.flush_all().await {
+                    tracing::warn!(error = %e, "periodic session flush failed");
+                }
+            }
+        })
+    }
+
+    fn session_path(&self, session_id: &str) -> PathBuf {
+        self.dir.join(format!("{session_id}.json"))
+    }
+
+    async fn lock_for(&self, session_id: &str) -> Arc<Mutex<()>> {
+        self.locks
+            .lock()
+            .await
+            .entry(session_id.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone()
+    }
+
+    /// Queue a persist of `state` according to the flush policy. Under the
+    /// coalescing policies the most recent state always wins.
+    pub async fn persist(&self, session_id: &str, state: serde_json::Value) -> Result<()> {
+        match self.policy {
+            FlushPolicy::Immediate => self.write_now(session_id, &state).await,
+            FlushPolicy::Debounced { window_ms } => {
+                let mut pending = self.pending.lock().await;
+                let already_queued = pending.insert(session_id.to_string(), state).is_some();
+                drop(pending);
+                if !already_queued {
+                    tokio::time::sleep(Duration::from_millis(window_ms)).await;
+                    self.flush_session(session_id).await?;
+                }
+                Ok(())
+            }
+            FlushPolicy::Periodic { .. } => {
+                self.pending
+                    .lock()
+                    .await
+                    .insert(session_id.to_string(), state);
+                Ok(())
+            }
+        }
+    }
+
+    /// Persist bypassing the flush policy — used for critical events
+    /// (permission changes) where durability wins.
+    pub async fn persist_critical(&self, session_id: &str, state: serde_json::Value) -> Result<()> {
+        self.pending.lock().await.remove(session_id);
+        self.write_now(session_id, &state).await
+    }
+
+    /// Write the pending state for one session, if any.
+    pub async fn flush_session(&self, session_id: &str) -> Result<()> {
+        let state = self.pending.lock().await.remove(session_id);
+        if let Some(state) = state {
+            self.write_now(session_id, &state).await?;
+        }
+        Ok(())
+    }
+
+    /// Flush every pending session immediately — called on shutdown and on
+    /// critical events.
+    pub async fn flush_all(&self) -> Result<()> {
+        let drained: Vec<(String, serde_json::Value)> =
+            self.pending.lock().await.drain().collect();
+        for (session_id, state) in drained {
+            self.write_now(&session_id, &state).await?;
+        }
+        Ok(())
+    }
+
+    /// Serialized, atomic write of one session file.
+    async fn write_now(&self, session_id: &str, state: &serde_json::Value) -> Result<()> {
+        let lock = self.lock_for(session_id).await;
+        let _guard = lock.lock().await;
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.session_path(session_id);
+        let bytes = serde_json::to_vec_pretty(state)?;
+        atomic_replace(&path, &bytes).await?;
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Restore all session files from disk. Files that fail to parse are
+    /// quarantined (renamed to `<name>.json.corrupt`) and skipped so one bad
+    /// file never loses the rest.
+    pub async fn restore_from_disk(&self) -> Result<HashMap<String, serde_json::Value>> {
+        let mut sessions = HashMap::new();
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(sessions),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let bytes = tokio::fs::read(&path).await?;
+            match serde_json::from_slice::<serde_json::Value>(&bytes) {
+                Ok(state) => {
+                    sessions.insert(session_id.to_string(), state);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        session = session_id,
+                        error = %e,
+                        "quarantining corrupt session file"
+                    );
+                    let quarantine = path.with_extension("json.corrupt");
+                    tokio::fs::rename(&path, &quarantine).await?;
+                }
+            }
+        }
+        Ok(sessions)
+    }
+}
+
+/// Write `bytes` to `path` atomically: temp file in the same directory,
+/// fsync, then rename over the target.
+async fn atomic_replace(path: &Path, bytes: &[u8]) -> Result<()> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| SafeClawError::Session("session path has no parent".into()))?;
+    let tmp = parent.join(format!(
+        ".{}.tmp",
+        path.file_name().and_then(|n| n.to_str()).unwrap_or("session")
+    ));
+    {
+        let mut file = tokio::fs::File::create(&tmp).await?;
+        use tokio::io::AsyncWriteExt;
+        file.write_all(bytes).await?;
+        file.sync_all().await?;
+    }
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn concurrent_persists_leave_valid_json_with_last_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(AgentSessionStore::new(dir.path(), FlushPolicy::Immediate));
+
+        let mut handles = Vec::new();
+        for i in 0..50u32 {
+            let store = store.clone();
+            handles.push(tokio::spawn(async move {
+                store
+                    .persist("s1", json!({ "history_len": i }))
+                    .await
+                    .unwrap();
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        // Deterministic final write.
+        store.persist("s1", json!({ "history_len": 999 })).await.unwrap();
+
+        let restored = store.restore_from_disk().await.unwrap();
+        assert_eq!(restored["s1"]["history_len"], 999);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn debounced_persists_coalesce_to_latest() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(AgentSessionStore::new(
+            dir.path(),
+            FlushPolicy::Debounced { window_ms: 20 },
+        ));
+
+        // Queue several rapid updates; only the last should land on disk.
+        // Paused time keeps the ordering deterministic: the yield runs the
+        // spawned persist up to its debounce sleep (so its insert is
+        // queued first), the updates land inside the window, and awaiting
+        // the task advances the clock past it.
+        let flusher = {
+            let store = store.clone();
+            tokio::spawn(async move { store.persist("s1", json!({ "v": 0 })).await })
+        };
+        tokio::task::yield_now().await;
+        for v in 1..=5 {
+            store.pending.lock().await.insert("s1".into(), json!({ "v": v }));
+        }
+        flusher.await.unwrap().unwrap();
+
+        let restored = store.restore_from_disk().await.unwrap();
+        assert_eq!(restored["s1"]["v"], 5);
+    }
+
+    #[tokio::test]
+    async fn flush_all_writes_pending_state_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AgentSessionStore::new(
+            dir.path(),
+            FlushPolicy::Periodic {
+                interval_ms: 3_600_000,
+            },
+        );
+        store.pending.lock().await.insert("s1".into(), json!({ "v": 7 }));
+
+        store.flush_all().await.unwrap();
+
+        let restored = store.restore_from_disk().await.unwrap();
+        assert_eq!(restored["s1"]["v"], 7);
+    }
+
+    #[tokio::test]
+    async fn debounced_mode_coalesces_into_fewer_writes_but_flushes_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(AgentSessionStore::new(
+            dir.path(),
+            FlushPolicy::Debounced { window_ms: 30 },
+        ));
+
+        let mut handles = Vec::new();
+        for i in 0..20u32 {
+            let store = store.clone();
+            handles.push(tokio::spawn(async move {
+                store.persist("s1", json!({ "i": i })).await.unwrap();
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert!(
+            store.write_count() < 20,
+            "expected coalescing, got {} writes",
+            store.write_count()
+        );
+
+        // Shutdown path: anything still pending reaches disk.
+        store.pending.lock().await.insert("s1".into(), json!({ "i": 99 }));
+        store.flush_all().await.unwrap();
+        let restored = store.restore_from_disk().await.unwrap();
+        assert_eq!(restored["s1"]["i"], 99);
+    }
+
+    #[tokio::test]
+    async fn critical_persist_bypasses_periodic_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AgentSessionStore::new(
+            dir.path(),
+            FlushPolicy::Periodic {
+                interval_ms: 3_600_000,
+            },
+        );
+        store.persist("s1", json!({ "v": 1 })).await.unwrap();
+        assert_eq!(store.write_count(), 0);
+
+        store.persist_critical("s1", json!({ "v": 2 })).await.unwrap();
+        assert_eq!(store.write_count(), 1);
+        let restored = store.restore_from_disk().await.unwrap();
+        assert_eq!(restored["s1"]["v"], 2);
+    }
+
+    #[tokio::test]
+    async fn corrupt_file_is_quarantined_not_fatal() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AgentSessionStore::new(dir.path(), FlushPolicy::Immediate);
+        store.persist("good", json!({ "ok": true })).await.unwrap();
+        tokio::fs::write(dir.path().join("bad.json"), b"{ truncated")
+            .await
+            .unwrap();
+
+        let restored = store.restore_from_disk().await.unwrap();
+
+        assert!(restored.contains_key("good"));
+        assert!(!restored.contains_key("bad"));
+        assert!(dir.path().join("bad.json.corrupt").exists());
+        assert!(!dir.path().join("bad.json").exists());
+    }
+}
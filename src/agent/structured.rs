@@ -0,0 +1,243 @@
+//! Structured JSON output for programmatic channels.
+//!
+//! A caller integrating SafeClaw into another system doesn't want prose —
+//! it wants JSON matching its schema. A request can carry a
+//! [`StructuredOutput`] option: the provider is asked for JSON mode with
+//! the schema attached, the model's output is parsed (tolerating a fenced
+//! `json` block) and validated against the schema, and a non-conforming
+//! first attempt gets exactly one corrective retry with the validation
+//! errors quoted back at the model. The validator covers the schema subset
+//! callers actually use — types, properties, required, items, enum —
+//! rather than pulling in a full JSON Schema engine.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Result, SafeClawError};
+
+/// Per-request structured-output option, accepted by `/message` and
+/// `generate_response`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredOutput {
+    /// JSON Schema the response must conform to.
+    pub schema: Value,
+}
+
+/// The provider call, with the schema passed through to its native
+/// JSON/structured-output mode. Canned in tests.
+#[async_trait]
+pub trait StructuredGenerator: Send + Sync {
+    async fn generate(&self, prompt: &str, schema: &Value) -> Result<String>;
+}
+
+/// Strip an optional ```json fence — models add one even in JSON mode.
+fn strip_fence(output: &str) -> &str {
+    let trimmed = output.trim();
+    trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(str::trim)
+        .unwrap_or(trimmed)
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn check(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema["type"].as_str() {
+        let actual = type_of(value);
+        // An integer is a valid "number".
+        let matches = actual == expected || (expected == "number" && actual == "integer");
+        if !matches {
+            errors.push(format!("{path}: expected {expected}, got {actual}"));
+            return;
+        }
+    }
+    if let Some(allowed) = schema["enum"].as_array() {
+        if !allowed.contains(value) {
+            errors.push(format!(
+                "{path}: {value} is not one of {}",
+                serde_json::to_string(allowed).unwrap_or_default()
+            ));
+        }
+    }
+    if let (Some(object), Some(properties)) = (value.as_object(), schema["properties"].as_object())
+    {
+        if let Some(required) = schema["required"].as_array() {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    errors.push(format!("{path}: missing required property `{name}`"));
+                }
+            }
+        }
+        for (name, property_schema) in properties {
+            if let Some(property) = object.get(name) {
+                check(property, property_schema, &format!("{path}.{name}"), errors);
+            }
+        }
+    }
+    if let (Some(items), Value::Object(_)) = (value.as_array(), &schema["items"]) {
+        for (i, item) in items.iter().enumerate() {
+            check(item, &schema["items"], &format!("{path}[{i}]"), errors);
+        }
+    }
+}
+
+/// Validate a value against the supported schema subset. Empty means
+/// conforming.
+pub fn validation_errors(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(value, schema, "$", &mut errors);
+    errors
+}
+
+fn parse_and_validate(output: &str, schema: &Value) -> std::result::Result<Value, String> {
+    let value: Value = serde_json::from_str(strip_fence(output))
+        .map_err(|e| format!("output is not valid JSON: {e}"))?;
+    let errors = validation_errors(&value, schema);
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Generate a schema-conforming response, with one corrective retry on a
+/// non-conforming first attempt.
+pub async fn generate_structured(
+    generator: &dyn StructuredGenerator,
+    prompt: &str,
+    output: &StructuredOutput,
+) -> Result<Value> {
+    let first = generator.generate(prompt, &output.schema).await?;
+    let problems = match parse_and_validate(&first, &output.schema) {
+        Ok(value) => return Ok(value),
+        Err(problems) => problems,
+    };
+    let correction = format!(
+        "{prompt}\n\nYour previous response did not conform to the required \
+         schema: {problems}. Respond again with only a JSON value that \
+         conforms to the schema."
+    );
+    let second = generator.generate(&correction, &output.schema).await?;
+    parse_and_validate(&second, &output.schema).map_err(|problems| {
+        SafeClawError::Config(format!(
+            "structured output failed schema validation after retry: {problems}"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Replays canned outputs and records the prompts it saw.
+    struct CannedGenerator {
+        outputs: Mutex<std::vec::IntoIter<&'static str>>,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    impl CannedGenerator {
+        fn new(outputs: Vec<&'static str>) -> Self {
+            Self {
+                outputs: Mutex::new(outputs.into_iter()),
+                prompts: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl StructuredGenerator for CannedGenerator {
+        async fn generate(&self, prompt: &str, _schema: &Value) -> Result<String> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            self.outputs
+                .lock()
+                .unwrap()
+                .next()
+                .map(String::from)
+                .ok_or_else(|| SafeClawError::Config("canned outputs exhausted".into()))
+        }
+    }
+
+    fn ticket_schema() -> StructuredOutput {
+        StructuredOutput {
+            schema: serde_json::json!({
+                "type": "object",
+                "required": ["priority", "summary"],
+                "properties": {
+                    "priority": { "type": "string", "enum": ["low", "high"] },
+                    "summary": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                },
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn a_conforming_response_is_returned_without_retry() {
+        let generator = CannedGenerator::new(vec![
+            "```json\n{\"priority\": \"high\", \"summary\": \"pipeline down\"}\n```",
+        ]);
+        let value = generate_structured(&generator, "triage this", &ticket_schema())
+            .await
+            .unwrap();
+        assert_eq!(value["priority"], "high");
+        assert_eq!(generator.prompts.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn a_non_conforming_first_attempt_gets_one_corrective_retry() {
+        let generator = CannedGenerator::new(vec![
+            // Missing `summary`, bad enum value.
+            r#"{"priority": "urgent"}"#,
+            r#"{"priority": "high", "summary": "pipeline down"}"#,
+        ]);
+        let value = generate_structured(&generator, "triage this", &ticket_schema())
+            .await
+            .unwrap();
+        assert_eq!(value["summary"], "pipeline down");
+
+        let prompts = generator.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 2);
+        // The correction quotes the validation problems back.
+        assert!(prompts[1].contains("did not conform"));
+        assert!(prompts[1].contains("missing required property `summary`"));
+        assert!(prompts[1].contains("\"urgent\" is not one of"));
+    }
+
+    #[tokio::test]
+    async fn a_second_failure_surfaces_as_an_error() {
+        let generator =
+            CannedGenerator::new(vec!["not json at all", r#"{"priority": "urgent"}"#]);
+        let err = generate_structured(&generator, "triage this", &ticket_schema())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("after retry"));
+    }
+
+    #[test]
+    fn the_validator_covers_nested_arrays_and_types() {
+        let schema = ticket_schema().schema;
+        let value = serde_json::json!({
+            "priority": "low",
+            "summary": "ok",
+            "tags": ["infra", 7],
+        });
+        let errors = validation_errors(&value, &schema);
+        assert_eq!(errors, ["$.tags[1]: expected string, got integer"]);
+    }
+}
@@ -0,0 +1,215 @@
+//! Running conversation summaries.
+//!
+//! Long threads are hard to pick back up. `/summary` asks the LLM for a
+//! concise running summary of the session so far, stores it in session
+//! state, and shows it to the user; optionally a summary is regenerated
+//! automatically every N turns. The stored summary doubles as compaction
+//! input when the context window needs trimming (see
+//! [`resumption`](crate::agent::resumption)).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::types::HistoryEntry;
+use crate::error::Result;
+
+/// Chat command producing an on-demand summary.
+pub const SUMMARY_COMMAND: &str = "/summary";
+
+/// Configuration under `agent.summary`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummaryConfig {
+    pub enabled: bool,
+    /// Regenerate automatically every N turns; 0 disables auto-summary.
+    pub auto_every_turns: u32,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_every_turns: 0,
+        }
+    }
+}
+
+/// Produces the summary text. The production implementation prompts the
+/// session's provider; tests substitute a canned one.
+#[async_trait]
+pub trait SummaryGenerator: Send + Sync {
+    async fn summarize(&self, history: &[HistoryEntry]) -> Result<String>;
+}
+
+/// A stored summary, part of session state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredSummary {
+    pub summary: String,
+    /// How many history entries the summary covers.
+    pub through_entries: usize,
+    pub generated_at: i64,
+}
+
+#[derive(Default)]
+struct SessionState {
+    summary: Option<StoredSummary>,
+    turns_since_summary: u32,
+}
+
+/// Per-session summary state and the generation triggers.
+pub struct SessionSummaries<G> {
+    config: SummaryConfig,
+    generator: G,
+    sessions: Mutex<HashMap<String, SessionState>>,
+}
+
+impl<G: SummaryGenerator> SessionSummaries<G> {
+    pub fn new(config: SummaryConfig, generator: G) -> Self {
+        Self {
+            config,
+            generator,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `/summary` — generate, store, and return the summary for display.
+    pub async fn handle_command(
+        &self,
+        session_id: &str,
+        history: &[HistoryEntry],
+        now: i64,
+    ) -> Result<String> {
+        let summary = self.generate_and_store(session_id, history, now).await?;
+        Ok(summary.summary)
+    }
+
+    /// Record one completed turn; returns a fresh summary to deliver when
+    /// auto-summary is configured and due.
+    pub async fn note_turn(
+        &self,
+        session_id: &str,
+        history: &[HistoryEntry],
+        now: i64,
+    ) -> Result<Option<String>> {
+        if !self.config.enabled || self.config.auto_every_turns == 0 {
+            return Ok(None);
+        }
+        let due = {
+            let mut sessions = self.sessions.lock().expect("session summaries poisoned");
+            let state = sessions.entry(session_id.to_string()).or_default();
+            state.turns_since_summary += 1;
+            state.turns_since_summary >= self.config.auto_every_turns
+        };
+        if !due {
+            return Ok(None);
+        }
+        let summary = self.generate_and_store(session_id, history, now).await?;
+        Ok(Some(summary.summary))
+    }
+
+    /// The current stored summary, for display or as compaction input.
+    pub fn current(&self, session_id: &str) -> Option<StoredSummary> {
+        self.sessions
+            .lock()
+            .expect("session summaries poisoned")
+            .get(session_id)
+            .and_then(|s| s.summary.clone())
+    }
+
+    /// Session terminated — drop its summary state.
+    pub fn forget(&self, session_id: &str) {
+        self.sessions
+            .lock()
+            .expect("session summaries poisoned")
+            .remove(session_id);
+    }
+
+    async fn generate_and_store(
+        &self,
+        session_id: &str,
+        history: &[HistoryEntry],
+        now: i64,
+    ) -> Result<StoredSummary> {
+        let summary = StoredSummary {
+            summary: self.generator.summarize(history).await?,
+            through_entries: history.len(),
+            generated_at: now,
+        };
+        let mut sessions = self.sessions.lock().expect("session summaries poisoned");
+        let state = sessions.entry(session_id.to_string()).or_default();
+        state.summary = Some(summary.clone());
+        state.turns_since_summary = 0;
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::types::ChatRole;
+
+    const NOW: i64 = 1_700_000_000;
+
+    struct CannedGenerator;
+
+    #[async_trait]
+    impl SummaryGenerator for CannedGenerator {
+        async fn summarize(&self, history: &[HistoryEntry]) -> Result<String> {
+            Ok(format!("summary of {} entries", history.len()))
+        }
+    }
+
+    fn history(len: usize) -> Vec<HistoryEntry> {
+        (0..len)
+            .map(|i| HistoryEntry::new(ChatRole::User, format!("msg {i}"), NOW + i as i64))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn summary_command_generates_stores_and_returns() {
+        let summaries = SessionSummaries::new(SummaryConfig::default(), CannedGenerator);
+        let shown = summaries
+            .handle_command("s1", &history(4), NOW)
+            .await
+            .unwrap();
+        assert_eq!(shown, "summary of 4 entries");
+
+        let stored = summaries.current("s1").expect("summary must be stored");
+        assert_eq!(stored.summary, shown);
+        assert_eq!(stored.through_entries, 4);
+        assert_eq!(stored.generated_at, NOW);
+        assert!(summaries.current("s2").is_none());
+    }
+
+    #[tokio::test]
+    async fn auto_summary_fires_every_n_turns() {
+        let summaries = SessionSummaries::new(
+            SummaryConfig {
+                auto_every_turns: 3,
+                ..Default::default()
+            },
+            CannedGenerator,
+        );
+        assert!(summaries.note_turn("s1", &history(1), NOW).await.unwrap().is_none());
+        assert!(summaries.note_turn("s1", &history(2), NOW).await.unwrap().is_none());
+        let auto = summaries.note_turn("s1", &history(3), NOW).await.unwrap();
+        assert_eq!(auto.as_deref(), Some("summary of 3 entries"));
+        // The counter resets after a summary.
+        assert!(summaries.note_turn("s1", &history(4), NOW).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn auto_summary_stays_off_by_default() {
+        let summaries = SessionSummaries::new(SummaryConfig::default(), CannedGenerator);
+        for turn in 1..=10 {
+            assert!(summaries
+                .note_turn("s1", &history(turn), NOW)
+                .await
+                .unwrap()
+                .is_none());
+        }
+    }
+}
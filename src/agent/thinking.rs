@@ -0,0 +1,88 @@
+//! Structured agent "thinking" for models with extended reasoning.
+//!
+//! Reasoning traces arrive as [`AgentEvent::ThinkingDelta`]. When surfacing
+//! is enabled the delta passes through — sanitized like any other output, so
+//! sensitive reasoning never leaks — and the browser renders it as a
+//! collapsed thinking block keyed on the distinct event type. When disabled
+//! the delta is dropped entirely rather than inlined into the answer.
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::types::AgentEvent;
+
+/// Thinking surfacing toggle, per the agent config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThinkingConfig {
+    /// Off by default: reasoning traces are suppressed unless the deployment
+    /// opts in.
+    pub surface_thinking: bool,
+}
+
+/// Translate one engine event for the channel/browser path. Thinking deltas
+/// are sanitized and passed through when enabled, dropped when disabled;
+/// every other event is untouched.
+pub fn translate_event(
+    event: AgentEvent,
+    config: &ThinkingConfig,
+    sanitize: impl Fn(&str) -> String,
+) -> Option<AgentEvent> {
+    match event {
+        AgentEvent::ThinkingDelta { text } => {
+            if config.surface_thinking {
+                Some(AgentEvent::ThinkingDelta {
+                    text: sanitize(&text),
+                })
+            } else {
+                None
+            }
+        }
+        other => Some(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thinking(text: &str) -> AgentEvent {
+        AgentEvent::ThinkingDelta { text: text.into() }
+    }
+
+    #[test]
+    fn thinking_surfaces_as_a_distinct_message_type_when_enabled() {
+        let config = ThinkingConfig {
+            surface_thinking: true,
+        };
+        let event = translate_event(thinking("weighing options"), &config, |s| s.to_string())
+            .expect("enabled thinking should surface");
+        let wire = serde_json::to_value(&event).unwrap();
+        assert_eq!(wire["type"], "thinking_delta");
+        assert_eq!(wire["text"], "weighing options");
+    }
+
+    #[test]
+    fn thinking_is_suppressed_when_disabled() {
+        let config = ThinkingConfig::default();
+        assert!(translate_event(thinking("secret reasoning"), &config, |s| s.to_string()).is_none());
+        // Other events still pass through.
+        assert!(translate_event(AgentEvent::Done, &config, |s| s.to_string()).is_some());
+    }
+
+    #[test]
+    fn surfaced_thinking_passes_the_sanitizer() {
+        let config = ThinkingConfig {
+            surface_thinking: true,
+        };
+        let event = translate_event(thinking("ssn 123-45-6789"), &config, |s| {
+            s.replace("123-45-6789", "[REDACTED]")
+        })
+        .unwrap();
+        assert_eq!(
+            event,
+            AgentEvent::ThinkingDelta {
+                text: "ssn [REDACTED]".into()
+            }
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Turn-level latency breakdown.
+//!
+//! Answers "why was that answer slow?": the pipeline records spans for
+//! classification, routing, context assembly, LLM time-to-first-token and
+//! total, each tool execution, and outbound delivery into a [`TurnTiming`]
+//! stored with the session (bounded ring of recent turns) and served by
+//! `GET /api/agent/sessions/:id/timings`. The browser can additionally get a
+//! `TurnTiming` message after each turn to render a breakdown bar. With the
+//! feature off the recorder is a no-op — no allocation per span.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// Recent turns retained per session.
+pub const TIMING_RING_CAPACITY: usize = 50;
+
+/// One named span within a turn, in milliseconds from turn start.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingSegment {
+    /// `"classification"`, `"routing"`, `"context_assembly"`, `"llm"`,
+    /// `"tool:<name>"`, `"delivery"`.
+    pub name: String,
+    pub offset_ms: u64,
+    pub duration_ms: u64,
+}
+
+/// Latency breakdown of one completed turn.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnTiming {
+    /// Unix millis when the turn started.
+    pub started_at_ms: u64,
+    pub total_ms: u64,
+    /// Millis from turn start to the first LLM token, if one arrived.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_first_token_ms: Option<u64>,
+    pub segments: Vec<TimingSegment>,
+}
+
+/// Records spans for one turn. Constructed disabled when the feature is off;
+/// every method short-circuits on the flag.
+pub struct TurnRecorder {
+    enabled: bool,
+    started_at_ms: u64,
+    open: Option<(String, u64)>,
+    first_token_ms: Option<u64>,
+    segments: Vec<TimingSegment>,
+}
+
+impl TurnRecorder {
+    pub fn new(enabled: bool, now_ms: u64) -> Self {
+        Self {
+            enabled,
+            started_at_ms: now_ms,
+            open: None,
+            first_token_ms: None,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Open a span, closing any span still open (spans don't nest — the
+    /// pipeline stages are sequential; concurrent tools record their own
+    /// start/end through `record_span`).
+    pub fn start(&mut self, name: &str, now_ms: u64) {
+        if !self.enabled {
+            return;
+        }
+        self.end(now_ms);
+        self.open = Some((name.to_string(), now_ms));
+    }
+
+    /// Close the currently open span.
+    pub fn end(&mut self, now_ms: u64) {
+        if !self.enabled {
+            return;
+        }
+        if let Some((name, opened_at)) = self.open.take() {
+            self.segments.push(TimingSegment {
+                name,
+                offset_ms: opened_at - self.started_at_ms,
+                duration_ms: now_ms - opened_at,
+            });
+        }
+    }
+
+    /// Record a span with explicit bounds (tool executions, which overlap
+    /// the LLM span).
+    pub fn record_span(&mut self, name: &str, start_ms: u64, end_ms: u64) {
+        if !self.enabled {
+            return;
+        }
+        self.segments.push(TimingSegment {
+            name: name.to_string(),
+            offset_ms: start_ms - self.started_at_ms,
+            duration_ms: end_ms - start_ms,
+        });
+    }
+
+    /// First LLM token observed.
+    pub fn mark_first_token(&mut self, now_ms: u64) {
+        if self.enabled && self.first_token_ms.is_none() {
+            self.first_token_ms = Some(now_ms - self.started_at_ms);
+        }
+    }
+
+    /// Close out the turn. `None` when recording is disabled.
+    pub fn finish(mut self, now_ms: u64) -> Option<TurnTiming> {
+        if !self.enabled {
+            return None;
+        }
+        self.end(now_ms);
+        Some(TurnTiming {
+            started_at_ms: self.started_at_ms,
+            total_ms: now_ms - self.started_at_ms,
+            time_to_first_token_ms: self.first_token_ms,
+            segments: self.segments,
+        })
+    }
+}
+
+/// Per-session bounded ring of recent turn timings.
+#[derive(Default)]
+pub struct TimingStore {
+    rings: Mutex<HashMap<String, VecDeque<TurnTiming>>>,
+}
+
+impl TimingStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn record(&self, session_id: &str, timing: TurnTiming) {
+        let mut rings = self.rings.lock().await;
+        let ring = rings.entry(session_id.to_string()).or_default();
+        ring.push_back(timing);
+        while ring.len() > TIMING_RING_CAPACITY {
+            ring.pop_front();
+        }
+    }
+
+    /// Recent timings, oldest first — what the timings endpoint returns.
+    pub async fn timings_for(&self, session_id: &str) -> Vec<TurnTiming> {
+        self.rings
+            .lock()
+            .await
+            .get(session_id)
+            .map(|ring| ring.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Session terminated — drop its ring.
+    pub async fn wipe_session(&self, session_id: &str) {
+        self.rings.lock().await.remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synthetic_turn_records_ordered_segments_and_totals() {
+        let mut recorder = TurnRecorder::new(true, 1_000);
+        recorder.start("classification", 1_000);
+        recorder.start("routing", 1_040); // closes classification
+        recorder.start("context_assembly", 1_050);
+        recorder.start("llm", 1_120);
+        recorder.mark_first_token(1_400);
+        recorder.record_span("tool:Bash", 1_500, 2_100);
+        recorder.end(2_600); // closes llm
+        recorder.start("delivery", 2_600);
+        let timing = recorder.finish(2_650).unwrap();
+
+        let names: Vec<&str> = timing.segments.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "classification",
+                "routing",
+                "context_assembly",
+                "tool:Bash",
+                "llm",
+                "delivery"
+            ]
+        );
+        assert_eq!(timing.total_ms, 1_650);
+        assert_eq!(timing.time_to_first_token_ms, Some(400));
+        assert_eq!(timing.segments[0].duration_ms, 40);
+        assert_eq!(timing.segments[0].offset_ms, 0);
+        let llm = timing.segments.iter().find(|s| s.name == "llm").unwrap();
+        assert_eq!(llm.offset_ms, 120);
+        assert_eq!(llm.duration_ms, 1_480);
+    }
+
+    #[test]
+    fn disabled_recorder_is_a_no_op() {
+        let mut recorder = TurnRecorder::new(false, 0);
+        recorder.start("classification", 0);
+        recorder.mark_first_token(10);
+        assert!(recorder.finish(100).is_none());
+    }
+
+    #[tokio::test]
+    async fn store_keeps_a_bounded_ring_per_session() {
+        let store = TimingStore::new();
+        for i in 0..TIMING_RING_CAPACITY + 5 {
+            let recorder = TurnRecorder::new(true, i as u64);
+            store.record("s1", recorder.finish(i as u64 + 1).unwrap()).await;
+        }
+        let timings = store.timings_for("s1").await;
+        assert_eq!(timings.len(), TIMING_RING_CAPACITY);
+        // Oldest entries were evicted.
+        assert_eq!(timings[0].started_at_ms, 5);
+        assert!(store.timings_for("other").await.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+//! The tool surface exposed to the agent, with optional pinning.
+//!
+//! a3s-code auto-discovers its tools, which is convenient until a
+//! dependency upgrade ships a new one and it silently becomes available in
+//! a regulated deployment. When `agent.tools.pinned` is set it is the
+//! complete tool surface: discovery still runs (the executor needs the
+//! specs), but only pinned tools are listed or executable, and a pinned
+//! tool the executor didn't discover is surfaced at startup instead of
+//! quietly missing.
+
+use serde::{Deserialize, Serialize};
+
+/// Configuration under `agent.tools`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolPinningConfig {
+    /// Exact allowlist of tool names. `None` leaves discovery authoritative;
+    /// `Some` makes this list the entire tool surface, whatever a3s-code
+    /// ships.
+    pub pinned: Option<Vec<String>>,
+}
+
+/// One tool as discovered from the executor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+}
+
+/// Where discovered tools come from — the `ToolExecutor` in production, a
+/// fixed list in tests.
+pub trait ToolSource: Send + Sync {
+    fn discovered_tools(&self) -> Vec<ToolSpec>;
+}
+
+/// The effective tool surface: discovery filtered through the pin list.
+pub struct ToolCatalog<S> {
+    config: ToolPinningConfig,
+    source: S,
+}
+
+impl<S: ToolSource> ToolCatalog<S> {
+    pub fn new(config: ToolPinningConfig, source: S) -> Self {
+        Self { config, source }
+    }
+
+    /// Tools available to the agent. With a pin list, only pinned tools
+    /// appear regardless of what discovery found.
+    pub fn list_tools(&self) -> Vec<ToolSpec> {
+        let discovered = self.source.discovered_tools();
+        match &self.config.pinned {
+            None => discovered,
+            Some(pinned) => discovered
+                .into_iter()
+                .filter(|tool| pinned.iter().any(|name| name == &tool.name))
+                .collect(),
+        }
+    }
+
+    /// Whether the agent may invoke `name`. Checked at execution time so a
+    /// tool outside the pin list is unavailable even when discovered.
+    pub fn is_available(&self, name: &str) -> bool {
+        match &self.config.pinned {
+            Some(pinned) if !pinned.iter().any(|p| p == name) => false,
+            _ => self
+                .source
+                .discovered_tools()
+                .iter()
+                .any(|tool| tool.name == name),
+        }
+    }
+
+    /// Pinned tools discovery didn't find — logged at startup so a pin list
+    /// that outlived a rename is noticed, not silently inert.
+    pub fn missing_pinned(&self) -> Vec<String> {
+        let Some(pinned) = &self.config.pinned else {
+            return Vec::new();
+        };
+        let discovered = self.source.discovered_tools();
+        pinned
+            .iter()
+            .filter(|name| !discovered.iter().any(|tool| &tool.name == *name))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedSource(Vec<&'static str>);
+
+    impl ToolSource for FixedSource {
+        fn discovered_tools(&self) -> Vec<ToolSpec> {
+            self.0
+                .iter()
+                .map(|name| ToolSpec {
+                    name: (*name).into(),
+                    description: format!("{name} tool"),
+                })
+                .collect()
+        }
+    }
+
+    fn pinned(names: &[&str]) -> ToolPinningConfig {
+        ToolPinningConfig {
+            pinned: Some(names.iter().map(|n| (*n).to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn a_pin_list_is_the_entire_tool_surface() {
+        let catalog = ToolCatalog::new(
+            pinned(&["bash", "file_read"]),
+            FixedSource(vec!["bash", "file_read", "web_fetch"]),
+        );
+        let names: Vec<String> = catalog.list_tools().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["bash", "file_read"]);
+
+        // Discovered but unpinned: unavailable at execution time too.
+        assert!(!catalog.is_available("web_fetch"));
+        assert!(catalog.is_available("bash"));
+    }
+
+    #[test]
+    fn a_dependency_upgrade_cannot_add_tools_behind_a_pin_list() {
+        let config = pinned(&["bash"]);
+        let before = ToolCatalog::new(config.clone(), FixedSource(vec!["bash"]));
+        // The upgrade ships a shiny new tool.
+        let after = ToolCatalog::new(config, FixedSource(vec!["bash", "browser"]));
+        assert_eq!(before.list_tools(), after.list_tools());
+        assert!(!after.is_available("browser"));
+    }
+
+    #[test]
+    fn without_a_pin_list_discovery_is_authoritative() {
+        let catalog = ToolCatalog::new(
+            ToolPinningConfig::default(),
+            FixedSource(vec!["bash", "web_fetch"]),
+        );
+        assert_eq!(catalog.list_tools().len(), 2);
+        assert!(catalog.is_available("web_fetch"));
+        assert!(!catalog.is_available("imaginary"));
+    }
+
+    #[test]
+    fn pinned_tools_missing_from_discovery_are_reported() {
+        let catalog = ToolCatalog::new(
+            pinned(&["bash", "renamed_tool"]),
+            FixedSource(vec!["bash"]),
+        );
+        assert_eq!(catalog.missing_pinned(), ["renamed_tool"]);
+        assert!(!catalog.is_available("renamed_tool"));
+    }
+}
@@ -0,0 +1,64 @@
+//! Browser message types and session state shared across the agent module.
+
+use serde::{Deserialize, Serialize};
+
+/// Events emitted by the agent engine while generating a response. All
+/// backends — remote providers, the TEE path, and local models — translate
+/// into this one pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum AgentEvent {
+    TextDelta { text: String },
+    /// Reasoning-trace delta from models with extended thinking. Surfaced to
+    /// the UI as a collapsed block when enabled (see `agent::thinking`),
+    /// suppressed otherwise.
+    ThinkingDelta { text: String },
+    ToolStart { tool_name: String, invocation_id: String },
+    ToolOutputDelta { invocation_id: String, output: String },
+    ToolEnd { invocation_id: String, exit_code: i32, duration_ms: u64 },
+    Done,
+    Error { message: String },
+}
+
+/// Role of a transcript entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatRole {
+    User,
+    Assistant,
+    System,
+    Tool,
+}
+
+/// One turn in a session's `message_history`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub role: ChatRole,
+    pub content: String,
+    /// Unix timestamp (seconds) when the turn was recorded.
+    pub timestamp: i64,
+    /// Whether this turn was routed through the TEE for processing.
+    #[serde(default)]
+    pub tee_routed: bool,
+    /// Classifier category that triggered TEE routing (e.g. `"medical
+    /// information"`), if any. Used as the redaction placeholder label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classifier_category: Option<String>,
+    /// Set when the stored `content` is a redaction placeholder; the original
+    /// text lives only in the encrypted TEE-side store.
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+impl HistoryEntry {
+    pub fn new(role: ChatRole, content: impl Into<String>, timestamp: i64) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            timestamp,
+            tee_routed: false,
+            classifier_category: None,
+            redacted: false,
+        }
+    }
+}
@@ -0,0 +1,420 @@
+//! Best-effort undo of the last agent action.
+//!
+//! "Undo that" can't be solved in general, but most regretted actions are
+//! file writes, moves, and sent messages — all invertible if the tool says
+//! how. A tool may attach an [`InverseAction`] to its result: the inverse
+//! expressed as a normal tool invocation (file write → rewrite from a
+//! prior-content snapshot, move → reverse move, message send → delete by
+//! ID). The engine keeps a bounded per-session stack of these; `/undo`
+//! (or the API) shows what would happen, and on confirmation the inverse
+//! runs through the regular tool path — same permissions, same audit — via
+//! the injected [`ToolInvoker`]. Actions without an inverse are reported
+//! as not undoable and skipped, so the one before them stays reachable.
+//! Snapshots live in the session workspace and are size-capped; a write
+//! too large to snapshot is simply recorded as non-undoable.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::{Result, SafeClawError};
+
+/// Chat command requesting an undo of the last agent action.
+pub const UNDO_COMMAND: &str = "/undo";
+
+/// Configuration under `agent.undo`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UndoConfig {
+    pub enabled: bool,
+    /// Undoable actions remembered per session.
+    pub max_depth: usize,
+    /// Prior-content snapshots larger than this are not taken; the action
+    /// records as non-undoable instead.
+    pub max_snapshot_bytes: usize,
+}
+
+impl Default for UndoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_depth: 10,
+            max_snapshot_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// The inverse of one tool effect, expressed as a tool invocation so it
+/// runs through the normal execution path. This is the contract the tool
+/// executor fills in alongside a tool's result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InverseAction {
+    /// Shown to the user before confirming, e.g. "restore notes.md to its
+    /// previous content".
+    pub description: String,
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+}
+
+/// One recorded agent action, undoable or not.
+#[derive(Debug, Clone)]
+pub struct UndoableAction {
+    /// What the agent did, e.g. "wrote notes.md".
+    pub summary: String,
+    pub inverse: Option<InverseAction>,
+    pub timestamp: i64,
+}
+
+/// What `/undo` found on top of the stack.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UndoDisposition {
+    NothingToUndo,
+    /// The action is reported and skipped so the one before it stays
+    /// reachable.
+    NotUndoable { summary: String },
+    /// Awaiting HITL confirmation; `prompt` describes the inverse.
+    Confirm { prompt: String },
+}
+
+/// Executes an inverse through the regular tool path — permissions and
+/// audit included. Implemented by the engine's tool executor.
+#[async_trait]
+pub trait ToolInvoker: Send + Sync {
+    async fn invoke(&self, tool_name: &str, arguments: &serde_json::Value) -> Result<String>;
+}
+
+/// Write a prior-content snapshot into the session workspace, for a
+/// file-writing tool to reference from its inverse. `Ok(None)` means the
+/// content exceeds the snapshot cap and the action should record as
+/// non-undoable.
+pub async fn write_snapshot(
+    workspace: &Path,
+    original: &Path,
+    content: &[u8],
+    config: &UndoConfig,
+) -> Result<Option<PathBuf>> {
+    if content.len() > config.max_snapshot_bytes {
+        return Ok(None);
+    }
+    let dir = workspace.join(".undo");
+    tokio::fs::create_dir_all(&dir).await?;
+    let name = original
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "snapshot".into());
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let path = dir.join(format!("{nanos}-{name}"));
+    tokio::fs::write(&path, content).await?;
+    Ok(Some(path))
+}
+
+/// Bounded per-session stacks of undoable actions.
+pub struct UndoStack {
+    config: UndoConfig,
+    stacks: Mutex<HashMap<String, Vec<UndoableAction>>>,
+}
+
+impl UndoStack {
+    pub fn new(config: UndoConfig) -> Self {
+        Self {
+            config,
+            stacks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record one completed tool action. The executor passes the inverse
+    /// the tool reported, or `None` for effects that can't be reversed.
+    pub async fn record(
+        &self,
+        session_id: &str,
+        summary: &str,
+        inverse: Option<InverseAction>,
+        now: i64,
+    ) {
+        if !self.config.enabled {
+            return;
+        }
+        let mut stacks = self.stacks.lock().await;
+        let stack = stacks.entry(session_id.to_string()).or_default();
+        stack.push(UndoableAction {
+            summary: summary.to_string(),
+            inverse,
+            timestamp: now,
+        });
+        let overflow = stack.len().saturating_sub(self.config.max_depth);
+        if overflow > 0 {
+            stack.drain(..overflow);
+        }
+    }
+
+    /// Handle `/undo`: inspect the top of the stack without executing
+    /// anything. A non-undoable top entry is popped as it's reported.
+    pub async fn prepare(&self, session_id: &str) -> UndoDisposition {
+        let mut stacks = self.stacks.lock().await;
+        let Some(stack) = stacks.get_mut(session_id) else {
+            return UndoDisposition::NothingToUndo;
+        };
+        match stack.last() {
+            None => UndoDisposition::NothingToUndo,
+            Some(action) => match &action.inverse {
+                Some(inverse) => UndoDisposition::Confirm {
+                    prompt: format!(
+                        "Undo \"{}\"? This will {}.",
+                        action.summary, inverse.description
+                    ),
+                },
+                None => {
+                    let action = stack.pop().expect("checked non-empty");
+                    UndoDisposition::NotUndoable {
+                        summary: format!(
+                            "\"{}\" can't be undone automatically; skipping it. \
+                             Run {UNDO_COMMAND} again for the action before it.",
+                            action.summary
+                        ),
+                    }
+                }
+            },
+        }
+    }
+
+    /// The user confirmed: pop the action and run its inverse through the
+    /// tool path. The entry is only removed once the inverse succeeds.
+    pub async fn confirm(
+        &self,
+        session_id: &str,
+        invoker: &dyn ToolInvoker,
+    ) -> Result<String> {
+        let (summary, inverse) = {
+            let stacks = self.stacks.lock().await;
+            let action = stacks
+                .get(session_id)
+                .and_then(|stack| stack.last())
+                .ok_or_else(|| {
+                    SafeClawError::NotFound(format!("nothing to undo in session {session_id}"))
+                })?;
+            let inverse = action.inverse.clone().ok_or_else(|| {
+                SafeClawError::NotFound(format!(
+                    "\"{}\" has no recorded inverse",
+                    action.summary
+                ))
+            })?;
+            (action.summary.clone(), inverse)
+        };
+        invoker.invoke(&inverse.tool_name, &inverse.arguments).await?;
+        let mut stacks = self.stacks.lock().await;
+        if let Some(stack) = stacks.get_mut(session_id) {
+            stack.pop();
+        }
+        Ok(format!("Undid \"{summary}\": {}.", inverse.description))
+    }
+
+    pub async fn depth(&self, session_id: &str) -> usize {
+        self.stacks
+            .lock()
+            .await
+            .get(session_id)
+            .map(|s| s.len())
+            .unwrap_or(0)
+    }
+
+    pub async fn forget(&self, session_id: &str) {
+        self.stacks.lock().await.remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// Executes the two inverse tools the tests use against the real
+    /// filesystem, standing in for the engine's executor.
+    struct FsInvoker {
+        invoked: StdMutex<Vec<String>>,
+    }
+
+    impl FsInvoker {
+        fn new() -> Self {
+            Self {
+                invoked: StdMutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ToolInvoker for FsInvoker {
+        async fn invoke(&self, tool_name: &str, arguments: &serde_json::Value) -> Result<String> {
+            self.invoked.lock().unwrap().push(tool_name.to_string());
+            match tool_name {
+                "Write" => {
+                    let content =
+                        tokio::fs::read(arguments["snapshotPath"].as_str().unwrap()).await?;
+                    tokio::fs::write(arguments["path"].as_str().unwrap(), content).await?;
+                }
+                "Move" => {
+                    tokio::fs::rename(
+                        arguments["from"].as_str().unwrap(),
+                        arguments["to"].as_str().unwrap(),
+                    )
+                    .await?;
+                }
+                other => return Err(SafeClawError::NotFound(format!("no tool {other}"))),
+            }
+            Ok("ok".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn undoing_a_write_restores_the_prior_content() {
+        let workspace = tempfile::tempdir().unwrap();
+        let file = workspace.path().join("notes.md");
+        tokio::fs::write(&file, "v1").await.unwrap();
+
+        // The Write tool snapshots before overwriting and reports the
+        // inverse alongside its result.
+        let config = UndoConfig::default();
+        let snapshot = write_snapshot(workspace.path(), &file, b"v1", &config)
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::fs::write(&file, "v2").await.unwrap();
+
+        let stack = UndoStack::new(config);
+        stack
+            .record(
+                "s1",
+                "wrote notes.md",
+                Some(InverseAction {
+                    description: "restore notes.md to its previous content".into(),
+                    tool_name: "Write".into(),
+                    arguments: serde_json::json!({
+                        "path": file.to_string_lossy(),
+                        "snapshotPath": snapshot.to_string_lossy(),
+                    }),
+                }),
+                NOW,
+            )
+            .await;
+
+        match stack.prepare("s1").await {
+            UndoDisposition::Confirm { prompt } => assert!(prompt.contains("restore notes.md")),
+            other => panic!("expected Confirm, got {other:?}"),
+        }
+        let report = stack.confirm("s1", &FsInvoker::new()).await.unwrap();
+        assert!(report.contains("Undid"));
+        assert_eq!(tokio::fs::read_to_string(&file).await.unwrap(), "v1");
+        assert_eq!(stack.depth("s1").await, 0);
+    }
+
+    #[tokio::test]
+    async fn undoing_a_move_moves_the_file_back() {
+        let workspace = tempfile::tempdir().unwrap();
+        let from = workspace.path().join("a.txt");
+        let to = workspace.path().join("b.txt");
+        tokio::fs::write(&from, "data").await.unwrap();
+        tokio::fs::rename(&from, &to).await.unwrap();
+
+        let stack = UndoStack::new(UndoConfig::default());
+        stack
+            .record(
+                "s1",
+                "moved a.txt to b.txt",
+                Some(InverseAction {
+                    description: "move b.txt back to a.txt".into(),
+                    tool_name: "Move".into(),
+                    arguments: serde_json::json!({
+                        "from": to.to_string_lossy(),
+                        "to": from.to_string_lossy(),
+                    }),
+                }),
+                NOW,
+            )
+            .await;
+
+        stack.confirm("s1", &FsInvoker::new()).await.unwrap();
+        assert!(tokio::fs::try_exists(&from).await.unwrap());
+        assert!(!tokio::fs::try_exists(&to).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn a_non_undoable_action_is_reported_and_skipped() {
+        let stack = UndoStack::new(UndoConfig::default());
+        stack
+            .record(
+                "s1",
+                "wrote notes.md",
+                Some(InverseAction {
+                    description: "restore notes.md".into(),
+                    tool_name: "Write".into(),
+                    arguments: serde_json::json!({}),
+                }),
+                NOW,
+            )
+            .await;
+        stack
+            .record("s1", "sent an email via ExternalMail", None, NOW + 1)
+            .await;
+
+        match stack.prepare("s1").await {
+            UndoDisposition::NotUndoable { summary } => {
+                assert!(summary.contains("sent an email"));
+                assert!(summary.contains("can't be undone"));
+            }
+            other => panic!("expected NotUndoable, got {other:?}"),
+        }
+        // The earlier write is now on top.
+        assert!(matches!(
+            stack.prepare("s1").await,
+            UndoDisposition::Confirm { .. }
+        ));
+        assert!(matches!(
+            stack.prepare("s2").await,
+            UndoDisposition::NothingToUndo
+        ));
+    }
+
+    #[tokio::test]
+    async fn the_stack_is_bounded_to_the_configured_depth() {
+        let stack = UndoStack::new(UndoConfig {
+            max_depth: 3,
+            ..Default::default()
+        });
+        for i in 0..5 {
+            stack.record("s1", &format!("action {i}"), None, NOW + i).await;
+        }
+        assert_eq!(stack.depth("s1").await, 3);
+        // The oldest entries were dropped, not the newest.
+        match stack.prepare("s1").await {
+            UndoDisposition::NotUndoable { summary } => assert!(summary.contains("action 4")),
+            other => panic!("expected NotUndoable, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_content_is_not_snapshotted() {
+        let workspace = tempfile::tempdir().unwrap();
+        let config = UndoConfig {
+            max_snapshot_bytes: 16,
+            ..Default::default()
+        };
+        let snapshot = write_snapshot(
+            workspace.path(),
+            Path::new("big.bin"),
+            &[0u8; 64],
+            &config,
+        )
+        .await
+        .unwrap();
+        assert!(snapshot.is_none());
+    }
+}
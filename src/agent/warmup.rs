@@ -0,0 +1,113 @@
+//! Model connection warmup / keepalive.
+//!
+//! The first message after idle pays for TLS + HTTP/2 setup and any
+//! provider-side cold start. When enabled, a keepalive task periodically
+//! issues a tiny request against the default provider to keep the connection
+//! warm — off by default, since every ping costs tokens or at least a
+//! round trip.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WarmupConfig {
+    /// Disabled by default to avoid cost.
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 240,
+        }
+    }
+}
+
+/// The ping issued against the provider — a minimal request in production,
+/// a counter in tests.
+#[async_trait]
+pub trait WarmupTarget: Send + Sync {
+    async fn ping(&self);
+}
+
+/// Counts pings; shared with tests and the status endpoint.
+#[derive(Default)]
+pub struct WarmupStats {
+    pings: AtomicU64,
+}
+
+impl WarmupStats {
+    pub fn pings(&self) -> u64 {
+        self.pings.load(Ordering::SeqCst)
+    }
+}
+
+/// Spawn the keepalive loop; returns `None` when disabled so callers have
+/// nothing to manage.
+pub fn spawn_keepalive(
+    config: &WarmupConfig,
+    target: Arc<dyn WarmupTarget>,
+    stats: Arc<WarmupStats>,
+) -> Option<tokio::task::JoinHandle<()>> {
+    if !config.enabled {
+        return None;
+    }
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            target.ping().await;
+            stats.pings.fetch_add(1, Ordering::SeqCst);
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopTarget;
+
+    #[async_trait]
+    impl WarmupTarget for NoopTarget {
+        async fn ping(&self) {}
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keepalive_pings_at_the_configured_interval() {
+        let config = WarmupConfig {
+            enabled: true,
+            interval_secs: 60,
+        };
+        let stats = Arc::new(WarmupStats::default());
+        let handle = spawn_keepalive(&config, Arc::new(NoopTarget), Arc::clone(&stats)).unwrap();
+
+        tokio::time::sleep(Duration::from_secs(61)).await;
+        assert_eq!(stats.pings(), 1);
+        tokio::time::sleep(Duration::from_secs(120)).await;
+        assert_eq!(stats.pings(), 3);
+        handle.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disabled_by_default_spawns_nothing() {
+        let stats = Arc::new(WarmupStats::default());
+        assert!(spawn_keepalive(
+            &WarmupConfig::default(),
+            Arc::new(NoopTarget),
+            Arc::clone(&stats)
+        )
+        .is_none());
+        tokio::time::sleep(Duration::from_secs(600)).await;
+        assert_eq!(stats.pings(), 0);
+    }
+}
@@ -0,0 +1,286 @@
+//! Stuck-generation watchdog with automatic recovery.
+//!
+//! A provider stream occasionally stalls without closing, or a tool future
+//! hangs in a way its own timeout misses; with per-session serialization
+//! the session then sits "running" forever and blocks new messages until a
+//! restart. The watchdog wraps a generation's event stream: every
+//! [`AgentEvent`] — including `ToolOutputDelta`s from legitimately long
+//! tool runs — counts as activity, and when nothing arrives for the stall
+//! threshold the generation task is aborted, `cancel_operation` is called
+//! so the engine releases the session, browsers get an `Error` event, the
+//! channel user gets an apologetic notice, and a `generation_stalled`
+//! audit entry is recorded. The caller may retry once when configured.
+//!
+//! The listener-side counterpart lives in
+//! [`channels::watchdog`](crate::channels::watchdog).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::agent::types::AgentEvent;
+use crate::audit::log::{AuditLog, AuditSeverity};
+
+/// Audit category for stalled generations.
+pub const AUDIT_CATEGORY_STALL: &str = "generation_stalled";
+
+/// Configuration under `agent.watchdog`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationWatchdogConfig {
+    pub enabled: bool,
+    /// No event for this long means the generation is stuck.
+    pub stall_after_secs: u64,
+    /// Whether a stalled generation is retried once before giving up.
+    pub auto_retry_once: bool,
+}
+
+impl Default for GenerationWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            stall_after_secs: 120,
+            auto_retry_once: true,
+        }
+    }
+}
+
+/// The engine hook the watchdog fires on a stall — releases the session's
+/// serialization slot and any provider-side operation.
+#[async_trait]
+pub trait GenerationControl: Send + Sync {
+    async fn cancel_operation(&self, session_id: &str);
+}
+
+/// How a guarded generation ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerationOutcome {
+    /// The stream finished on its own (`Done`, `Error`, or closed).
+    Completed,
+    /// The stall threshold fired: the task was aborted and the operation
+    /// cancelled. `notice` goes to the channel user; `should_retry` echoes
+    /// the config so the caller can re-run the turn once.
+    Stalled {
+        notice: String,
+        should_retry: bool,
+    },
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Drive one generation's event stream to completion, forwarding each
+/// event, and recover if it goes silent. `task` is the generation's handle;
+/// on stall it is aborted and `forward` receives a final `Error` event so
+/// connected browsers leave the running state.
+pub async fn guard_generation<F>(
+    session_id: &str,
+    config: &GenerationWatchdogConfig,
+    mut events: mpsc::Receiver<AgentEvent>,
+    task: JoinHandle<()>,
+    control: Arc<dyn GenerationControl>,
+    audit: &AuditLog,
+    mut forward: F,
+) -> GenerationOutcome
+where
+    F: FnMut(AgentEvent),
+{
+    let stall_after = Duration::from_secs(config.stall_after_secs.max(1));
+    loop {
+        let next = if config.enabled {
+            match tokio::time::timeout(stall_after, events.recv()).await {
+                Ok(next) => next,
+                Err(_) => break,
+            }
+        } else {
+            events.recv().await
+        };
+        match next {
+            Some(event) => {
+                let terminal = matches!(event, AgentEvent::Done | AgentEvent::Error { .. });
+                forward(event);
+                if terminal {
+                    return GenerationOutcome::Completed;
+                }
+            }
+            None => return GenerationOutcome::Completed,
+        }
+    }
+
+    task.abort();
+    control.cancel_operation(session_id).await;
+    audit.record(
+        "watchdog",
+        session_id,
+        AuditSeverity::Warning,
+        AUDIT_CATEGORY_STALL,
+        &format!(
+            "generation produced no event for {}s; aborted and cancelled",
+            config.stall_after_secs
+        ),
+        unix_now(),
+    );
+    forward(AgentEvent::Error {
+        message: "generation stalled and was cancelled".into(),
+    });
+    GenerationOutcome::Stalled {
+        notice: "Sorry — that response got stuck, so I stopped it. Please \
+                 send your message again."
+            .into(),
+        should_retry: config.auto_retry_once,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::audit::log::AuditIngestionConfig;
+
+    #[derive(Default)]
+    struct MockControl {
+        cancels: AtomicU32,
+    }
+
+    #[async_trait]
+    impl GenerationControl for MockControl {
+        async fn cancel_operation(&self, session_id: &str) {
+            assert_eq!(session_id, "s1");
+            self.cancels.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn config(stall_after_secs: u64) -> GenerationWatchdogConfig {
+        GenerationWatchdogConfig {
+            enabled: true,
+            stall_after_secs,
+            auto_retry_once: true,
+        }
+    }
+
+    fn pending_task() -> JoinHandle<()> {
+        tokio::spawn(async {
+            std::future::pending::<()>().await;
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_silent_stream_is_aborted_with_a_notice() {
+        let (tx, rx) = mpsc::channel(8);
+        let control = Arc::new(MockControl::default());
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        let forwarded = Mutex::new(Vec::new());
+
+        // Two deltas arrive, then the stream goes quiet without closing.
+        tx.send(AgentEvent::TextDelta { text: "Sure,".into() })
+            .await
+            .unwrap();
+        tx.send(AgentEvent::TextDelta { text: " let me".into() })
+            .await
+            .unwrap();
+
+        let outcome = guard_generation(
+            "s1",
+            &config(120),
+            rx,
+            pending_task(),
+            Arc::clone(&control) as Arc<dyn GenerationControl>,
+            &audit,
+            |event| forwarded.lock().unwrap().push(event),
+        )
+        .await;
+
+        match outcome {
+            GenerationOutcome::Stalled {
+                notice,
+                should_retry,
+            } => {
+                assert!(notice.contains("stuck"));
+                assert!(should_retry);
+            }
+            other => panic!("expected Stalled, got {other:?}"),
+        }
+        assert_eq!(control.cancels.load(Ordering::SeqCst), 1);
+        // Browsers got the deltas and a closing Error event.
+        let forwarded = forwarded.lock().unwrap();
+        assert_eq!(forwarded.len(), 3);
+        assert!(matches!(forwarded[2], AgentEvent::Error { .. }));
+        let events = audit.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].category, AUDIT_CATEGORY_STALL);
+        assert_eq!(events[0].severity, AuditSeverity::Warning);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_but_alive_tool_output_never_trips_the_watchdog() {
+        let (tx, rx) = mpsc::channel(8);
+        let control = Arc::new(MockControl::default());
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+
+        // A long tool run: a delta every 100s against a 120s threshold.
+        let feeder = tokio::spawn(async move {
+            for i in 0..5 {
+                tokio::time::sleep(Duration::from_secs(100)).await;
+                tx.send(AgentEvent::ToolOutputDelta {
+                    invocation_id: "inv-1".into(),
+                    output: format!("chunk {i}\n"),
+                })
+                .await
+                .unwrap();
+            }
+            tx.send(AgentEvent::Done).await.unwrap();
+        });
+
+        let mut count = 0;
+        let outcome = guard_generation(
+            "s1",
+            &config(120),
+            rx,
+            pending_task(),
+            Arc::clone(&control) as Arc<dyn GenerationControl>,
+            &audit,
+            |_| count += 1,
+        )
+        .await;
+
+        assert_eq!(outcome, GenerationOutcome::Completed);
+        assert_eq!(count, 6);
+        assert_eq!(control.cancels.load(Ordering::SeqCst), 0);
+        assert!(audit.events().is_empty());
+        feeder.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_normal_completion_passes_through_untouched() {
+        let (tx, rx) = mpsc::channel(8);
+        tx.send(AgentEvent::TextDelta { text: "Paris.".into() })
+            .await
+            .unwrap();
+        tx.send(AgentEvent::Done).await.unwrap();
+
+        let control = Arc::new(MockControl::default());
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        let outcome = guard_generation(
+            "s1",
+            &config(120),
+            rx,
+            pending_task(),
+            Arc::clone(&control) as Arc<dyn GenerationControl>,
+            &audit,
+            |_| {},
+        )
+        .await;
+        assert_eq!(outcome, GenerationOutcome::Completed);
+        assert_eq!(control.cancels.load(Ordering::SeqCst), 0);
+    }
+}
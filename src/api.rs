@@ -0,0 +1,178 @@
+//! Gateway API assembly — admin authentication for management routes.
+//!
+//! Management endpoints (settings, scheduler, quarantine, admin generations)
+//! must not share end-user channel auth: a leaked channel token should never
+//! be able to reconfigure the system. Admin routes get a dedicated bearer
+//! token checked by the [`admin_auth`] middleware; token comparison goes
+//! through a digest so length and content don't leak through timing.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+/// Error body shared by every endpoint: `{"error":{"code","message"}}`.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ErrorResponse {
+    pub error: ErrorBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ErrorBody {
+    pub code: String,
+    pub message: String,
+}
+
+impl ErrorResponse {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            error: ErrorBody {
+                code: code.into(),
+                message: message.into(),
+            },
+        }
+    }
+}
+
+/// Admin auth settings. The token is referenced via the credential store in
+/// config (`admin_token_ref`) and resolved before construction.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdminAuthConfig {
+    pub admin_token_ref: Option<String>,
+}
+
+/// Resolved admin authenticator shared across management routes.
+pub struct AdminAuth {
+    /// SHA-256 of the admin token; `None` disables all management routes.
+    token_digest: Option<[u8; 32]>,
+}
+
+impl AdminAuth {
+    pub fn new(token: Option<&str>) -> Self {
+        Self {
+            token_digest: token.map(|t| Sha256::digest(t.as_bytes()).into()),
+        }
+    }
+
+    /// Check a bearer token from the `Authorization` header.
+    pub fn verify(&self, presented: &str) -> bool {
+        let Some(expected) = &self.token_digest else {
+            return false;
+        };
+        let presented: [u8; 32] = Sha256::digest(presented.as_bytes()).into();
+        &presented == expected
+    }
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({
+            "error": { "code": "unauthorized", "message": "admin token required" }
+        })),
+    )
+        .into_response()
+}
+
+/// Middleware guarding management routes: requires `Authorization: Bearer
+/// <admin-token>`, distinct from any channel credential.
+pub async fn admin_auth(
+    auth: Arc<AdminAuth>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let presented = request
+        .headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match presented {
+        Some(token) if auth.verify(token) => next.run(request).await,
+        _ => unauthorized(),
+    }
+}
+
+/// Wrap a management router with the admin auth layer; used in `build_app`
+/// for the settings, scheduler, quarantine, and admin-generation routes.
+pub fn admin_protected(router: Router, auth: Arc<AdminAuth>) -> Router {
+    router.layer(middleware::from_fn(move |request, next| {
+        let auth = Arc::clone(&auth);
+        async move { admin_auth(auth, request, next).await }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let auth = Arc::new(AdminAuth::new(Some("s3cret-admin")));
+        admin_protected(
+            Router::new().route("/api/quarantine", get(|| async { "ok" })),
+            auth,
+        )
+    }
+
+    async fn status_with_header(header: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri("/api/quarantine");
+        if let Some(value) = header {
+            request = request.header("authorization", value);
+        }
+        app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn admin_token_is_accepted() {
+        assert_eq!(
+            status_with_header(Some("Bearer s3cret-admin")).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn non_admin_tokens_are_rejected() {
+        assert_eq!(
+            status_with_header(Some("Bearer channel-bot-token")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(status_with_header(None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status_with_header(Some("s3cret-admin")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn unconfigured_admin_auth_rejects_everything() {
+        let auth = Arc::new(AdminAuth::new(None));
+        let app = admin_protected(
+            Router::new().route("/x", get(|| async { "ok" })),
+            auth,
+        );
+        let status = app
+            .oneshot(
+                Request::builder()
+                    .uri("/x")
+                    .header("authorization", "Bearer anything")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,365 @@
+//! Message archival — compliance/analytics record of channel traffic.
+//!
+//! Distinct from the audit log (security events) and from session history
+//! (what the model sees): an optional append-only archive of every inbound
+//! and outbound message with its classification metadata, queryable by time,
+//! channel, and user. Entries persist as one AES-256-GCM-encrypted JSON line
+//! each, so the file at rest never contains plaintext; retention and
+//! encryption are configurable.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::aead::{self, KEY_SIZE};
+use crate::error::Result;
+use crate::privacy::SensitivityLevel;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    pub enabled: bool,
+    /// Entries older than this are pruned; zero keeps everything.
+    pub retention_days: u32,
+    /// Encrypt entries at rest. Disable only for throwaway dev setups.
+    pub encrypt: bool,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 90,
+            encrypt: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+/// One archived message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedMessage {
+    pub id: String,
+    pub direction: Direction,
+    pub channel: String,
+    pub chat_id: String,
+    pub user_id: String,
+    pub content: String,
+    /// Classification at processing time.
+    pub sensitivity: SensitivityLevel,
+    pub timestamp: i64,
+}
+
+/// Query over the archive; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveQuery {
+    pub channel: Option<String>,
+    pub user_id: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+impl ArchiveQuery {
+    fn matches(&self, entry: &ArchivedMessage) -> bool {
+        self.channel.as_deref().is_none_or(|c| entry.channel == c)
+            && self.user_id.as_deref().is_none_or(|u| entry.user_id == u)
+            && self.from.is_none_or(|t| entry.timestamp >= t)
+            && self.to.is_none_or(|t| entry.timestamp < t)
+    }
+}
+
+struct ArchiveState {
+    entries: Vec<ArchivedMessage>,
+    next_id: u64,
+}
+
+/// Append-only message archive. `path` is the on-disk JSONL file; with no
+/// path the archive is in-memory only.
+pub struct MessageArchive {
+    config: ArchiveConfig,
+    key: Option<[u8; KEY_SIZE]>,
+    path: Option<PathBuf>,
+    state: Mutex<ArchiveState>,
+}
+
+impl MessageArchive {
+    pub fn new(config: ArchiveConfig, key: Option<[u8; KEY_SIZE]>, path: Option<PathBuf>) -> Self {
+        Self {
+            config,
+            key,
+            path,
+            state: Mutex::new(ArchiveState {
+                entries: Vec::new(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    /// Restore entries from the on-disk file; lines that fail to decrypt or
+    /// parse are skipped rather than poisoning the whole archive.
+    pub fn load(&self) -> Result<usize> {
+        let Some(path) = &self.path else {
+            return Ok(0);
+        };
+        let Ok(raw) = std::fs::read_to_string(path) else {
+            return Ok(0);
+        };
+        let mut state = self.state.lock().expect("archive poisoned");
+        for line in raw.lines().filter(|l| !l.is_empty()) {
+            if let Some(entry) = self.decode_line(line) {
+                state.next_id = state.next_id.max(
+                    entry
+                        .id
+                        .strip_prefix("arc-")
+                        .and_then(|n| n.parse().ok())
+                        .unwrap_or(0),
+                );
+                state.entries.push(entry);
+            }
+        }
+        Ok(state.entries.len())
+    }
+
+    fn decode_line(&self, line: &str) -> Option<ArchivedMessage> {
+        let bytes = match (&self.key, self.config.encrypt) {
+            (Some(key), true) => {
+                let blob = base64::engine::general_purpose::STANDARD
+                    .decode(line)
+                    .ok()?;
+                aead::decrypt(key, &blob).ok()?
+            }
+            _ => line.as_bytes().to_vec(),
+        };
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn encode_line(&self, entry: &ArchivedMessage) -> Result<String> {
+        let json = serde_json::to_vec(entry)?;
+        match (&self.key, self.config.encrypt) {
+            (Some(key), true) => {
+                let blob = aead::encrypt(key, &json)?;
+                Ok(base64::engine::general_purpose::STANDARD.encode(blob))
+            }
+            _ => Ok(String::from_utf8_lossy(&json).into_owned()),
+        }
+    }
+
+    /// Archive one processed message with its classification.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record(
+        &self,
+        direction: Direction,
+        channel: &str,
+        chat_id: &str,
+        user_id: &str,
+        content: &str,
+        sensitivity: SensitivityLevel,
+        now: i64,
+    ) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let entry = {
+            let mut state = self.state.lock().expect("archive poisoned");
+            state.next_id += 1;
+            let entry = ArchivedMessage {
+                id: format!("arc-{}", state.next_id),
+                direction,
+                channel: channel.to_string(),
+                chat_id: chat_id.to_string(),
+                user_id: user_id.to_string(),
+                content: content.to_string(),
+                sensitivity,
+                timestamp: now,
+            };
+            state.entries.push(entry.clone());
+            entry
+        };
+        if let Some(path) = &self.path {
+            let line = self.encode_line(&entry)?;
+            use std::io::Write;
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            writeln!(file, "{line}")?;
+        }
+        Ok(())
+    }
+
+    /// Entries matching the query, oldest first.
+    pub fn query(&self, query: &ArchiveQuery) -> Vec<ArchivedMessage> {
+        let state = self.state.lock().expect("archive poisoned");
+        state
+            .entries
+            .iter()
+            .filter(|e| query.matches(e))
+            .cloned()
+            .collect()
+    }
+
+    /// Apply retention: drop (and rewrite on disk without) entries older than
+    /// `retention_days`. Returns how many were pruned.
+    pub fn prune(&self, now: i64) -> Result<usize> {
+        if self.config.retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = now - i64::from(self.config.retention_days) * 86_400;
+        let mut state = self.state.lock().expect("archive poisoned");
+        let before = state.entries.len();
+        state.entries.retain(|e| e.timestamp >= cutoff);
+        let pruned = before - state.entries.len();
+        if pruned > 0 {
+            if let Some(path) = &self.path {
+                let lines: Result<Vec<String>> =
+                    state.entries.iter().map(|e| self.encode_line(e)).collect();
+                std::fs::write(path, format!("{}\n", lines?.join("\n")))?;
+            }
+        }
+        Ok(pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled_config() -> ArchiveConfig {
+        ArchiveConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn seed(archive: &MessageArchive) {
+        archive
+            .record(
+                Direction::Inbound,
+                "telegram",
+                "42",
+                "u1",
+                "my card is 4111-1111-1111-1111",
+                SensitivityLevel::HighlySensitive,
+                100,
+            )
+            .unwrap();
+        archive
+            .record(
+                Direction::Outbound,
+                "telegram",
+                "42",
+                "u1",
+                "Card ending 1111 noted.",
+                SensitivityLevel::Normal,
+                110,
+            )
+            .unwrap();
+        archive
+            .record(
+                Direction::Inbound,
+                "slack",
+                "C1",
+                "u2",
+                "hello",
+                SensitivityLevel::Public,
+                200,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn processed_messages_land_with_classification_metadata() {
+        let archive = MessageArchive::new(enabled_config(), None, None);
+        seed(&archive);
+        let all = archive.query(&ArchiveQuery::default());
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].direction, Direction::Inbound);
+        assert_eq!(all[0].sensitivity, SensitivityLevel::HighlySensitive);
+        assert_eq!(all[1].direction, Direction::Outbound);
+    }
+
+    #[test]
+    fn queries_filter_by_channel_user_and_time() {
+        let archive = MessageArchive::new(enabled_config(), None, None);
+        seed(&archive);
+
+        let telegram = archive.query(&ArchiveQuery {
+            channel: Some("telegram".into()),
+            ..Default::default()
+        });
+        assert_eq!(telegram.len(), 2);
+
+        let window = archive.query(&ArchiveQuery {
+            from: Some(105),
+            to: Some(200),
+            ..Default::default()
+        });
+        assert_eq!(window.len(), 1);
+        assert_eq!(window[0].timestamp, 110);
+
+        let u2 = archive.query(&ArchiveQuery {
+            user_id: Some("u2".into()),
+            ..Default::default()
+        });
+        assert_eq!(u2.len(), 1);
+        assert_eq!(u2[0].channel, "slack");
+    }
+
+    #[test]
+    fn disabled_archive_records_nothing() {
+        let archive = MessageArchive::new(ArchiveConfig::default(), None, None);
+        seed(&archive);
+        assert!(archive.query(&ArchiveQuery::default()).is_empty());
+    }
+
+    #[test]
+    fn on_disk_entries_are_encrypted_and_reloadable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.jsonl");
+        let key = [7u8; KEY_SIZE];
+
+        let archive = MessageArchive::new(enabled_config(), Some(key), Some(path.clone()));
+        seed(&archive);
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("4111-1111"), "plaintext leaked to disk");
+        assert_eq!(raw.lines().count(), 3);
+
+        // Restart: a fresh archive with the same key restores everything.
+        let restored = MessageArchive::new(enabled_config(), Some(key), Some(path.clone()));
+        assert_eq!(restored.load().unwrap(), 3);
+        assert_eq!(restored.query(&ArchiveQuery::default()).len(), 3);
+
+        // The wrong key restores nothing rather than garbage.
+        let wrong = MessageArchive::new(enabled_config(), Some([9u8; KEY_SIZE]), Some(path));
+        assert_eq!(wrong.load().unwrap(), 0);
+    }
+
+    #[test]
+    fn retention_prunes_old_entries_and_rewrites_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.jsonl");
+        let archive = MessageArchive::new(
+            ArchiveConfig {
+                enabled: true,
+                retention_days: 1,
+                encrypt: false,
+            },
+            None,
+            Some(path.clone()),
+        );
+        seed(&archive);
+        let pruned = archive.prune(100 + 86_400 + 50).unwrap();
+        assert_eq!(pruned, 2);
+        assert_eq!(archive.query(&ArchiveQuery::default()).len(), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+    }
+}
@@ -0,0 +1,64 @@
+//! Real-time alerting — threshold-based anomaly detection on the audit
+//! pipeline.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// What triggered an alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// A critical audit event was observed.
+    CriticalEvent,
+    /// A session exceeded the event-rate threshold.
+    RateExceeded,
+    /// A session's egress data volume exceeded the configured threshold.
+    EgressVolume,
+    /// A planted canary token surfaced in agent output or a tool call.
+    CanaryToken,
+}
+
+/// An alert surfaced via `GET /api/v1/audit/alerts`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub session_id: String,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+/// Collects alerts raised by the monitors; ring-buffered.
+pub struct AlertMonitor {
+    max_alerts: usize,
+    alerts: Mutex<Vec<Alert>>,
+}
+
+impl AlertMonitor {
+    pub fn new(max_alerts: usize) -> Self {
+        Self {
+            max_alerts,
+            alerts: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn raise(&self, alert: Alert) {
+        let mut alerts = self.alerts.lock().expect("alert monitor poisoned");
+        alerts.push(alert);
+        let overflow = alerts.len().saturating_sub(self.max_alerts);
+        if overflow > 0 {
+            alerts.drain(..overflow);
+        }
+    }
+
+    pub fn recent(&self, limit: usize) -> Vec<Alert> {
+        let alerts = self.alerts.lock().expect("alert monitor poisoned");
+        alerts.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+impl Default for AlertMonitor {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
@@ -0,0 +1,342 @@
+//! Audit event export to an external SIEM.
+//!
+//! Security teams want SafeClaw's audit trail in their collector, not in an
+//! in-memory journal on the box. The exporter formats events as CEF or
+//! JSON lines and ships them in batches to a configurable HTTP endpoint,
+//! buffering locally with drop-oldest overflow so a transient collector
+//! outage delays events rather than losing them: a failed batch stays
+//! queued and the next flush retries it. This complements the local
+//! [`AuditLog`](crate::audit::log::AuditLog) journal — events are copied out, never moved.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::audit::log::{AuditEvent, AuditSeverity};
+use crate::error::{Result, SafeClawError};
+
+/// Wire format for exported events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiemFormat {
+    /// ArcSight Common Event Format, one event per line.
+    Cef,
+    /// One JSON object per line.
+    #[default]
+    Json,
+}
+
+/// Configuration under `logging.siem`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SiemExportConfig {
+    /// Collector URL; `None` disables export.
+    pub endpoint: Option<String>,
+    pub format: SiemFormat,
+    /// Events per delivery.
+    pub batch_size: usize,
+    /// Local buffer capacity; overflow drops oldest.
+    pub max_buffered: usize,
+}
+
+impl Default for SiemExportConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            format: SiemFormat::Json,
+            batch_size: 50,
+            max_buffered: 10_000,
+        }
+    }
+}
+
+/// CEF severity: Info → 3, Warning → 6, Critical → 9.
+fn cef_severity(severity: AuditSeverity) -> u8 {
+    match severity {
+        AuditSeverity::Info => 3,
+        AuditSeverity::Warning => 6,
+        AuditSeverity::Critical => 9,
+    }
+}
+
+/// Escape a CEF header field (`|` and `\`).
+fn cef_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+/// Escape a CEF extension value (`=` and `\`, newlines become spaces).
+fn cef_extension(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\n', " ")
+}
+
+/// One event as a CEF line. The category doubles as event class ID and
+/// name; session, repeat count and truncation land in the extension.
+pub fn format_cef(event: &AuditEvent) -> String {
+    format!(
+        "CEF:0|A3S|SafeClaw|{}|{}|{}|{}|suser={} rt={} cnt={} msg={}",
+        env!("CARGO_PKG_VERSION"),
+        cef_header(&event.category),
+        cef_header(&event.category),
+        cef_severity(event.severity),
+        cef_extension(&event.session_id),
+        event.timestamp * 1_000,
+        event.repeat_count,
+        cef_extension(&event.message),
+    )
+}
+
+/// One event as a JSON line.
+pub fn format_json(event: &AuditEvent) -> Result<String> {
+    Ok(serde_json::to_string(event)?)
+}
+
+/// Delivery to the collector — HTTP in production, canned in tests.
+#[async_trait]
+pub trait SiemCollector: Send + Sync {
+    /// Deliver one batch of formatted lines; `Err` means the whole batch
+    /// will be retried.
+    async fn deliver(&self, lines: &[String]) -> Result<()>;
+}
+
+/// POSTs newline-delimited batches to the configured endpoint.
+pub struct HttpCollector {
+    endpoint: String,
+    http: reqwest::Client,
+}
+
+impl HttpCollector {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            endpoint,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl SiemCollector for HttpCollector {
+    async fn deliver(&self, lines: &[String]) -> Result<()> {
+        let response = self
+            .http
+            .post(&self.endpoint)
+            .body(lines.join("\n"))
+            .send()
+            .await
+            .map_err(|e| SafeClawError::Channel(format!("siem collector: {e}")))?;
+        if !response.status().is_success() {
+            return Err(SafeClawError::Channel(format!(
+                "siem collector returned {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Buffers audit events and ships them in batches.
+pub struct SiemExporter {
+    config: SiemExportConfig,
+    buffer: Mutex<VecDeque<AuditEvent>>,
+    dropped: Mutex<u64>,
+}
+
+impl SiemExporter {
+    pub fn new(config: SiemExportConfig) -> Self {
+        Self {
+            config,
+            buffer: Mutex::new(VecDeque::new()),
+            dropped: Mutex::new(0),
+        }
+    }
+
+    /// Queue one event for export. Oldest events are dropped at capacity —
+    /// the local journal still has them.
+    pub fn enqueue(&self, event: AuditEvent) {
+        if self.config.endpoint.is_none() {
+            return;
+        }
+        let mut buffer = self.buffer.lock().expect("siem exporter poisoned");
+        if buffer.len() >= self.config.max_buffered {
+            buffer.pop_front();
+            *self.dropped.lock().expect("siem exporter poisoned") += 1;
+        }
+        buffer.push_back(event);
+    }
+
+    /// Deliver buffered events in batches until the buffer is empty or a
+    /// batch fails; failed events stay buffered for the next flush. Returns
+    /// how many events were delivered. Rides on the runtime loop.
+    pub async fn flush(&self, collector: &dyn SiemCollector) -> Result<usize> {
+        let mut delivered = 0;
+        loop {
+            let batch: Vec<AuditEvent> = {
+                let buffer = self.buffer.lock().expect("siem exporter poisoned");
+                buffer.iter().take(self.config.batch_size).cloned().collect()
+            };
+            if batch.is_empty() {
+                return Ok(delivered);
+            }
+            let mut lines = Vec::with_capacity(batch.len());
+            for event in &batch {
+                lines.push(match self.config.format {
+                    SiemFormat::Cef => format_cef(event),
+                    SiemFormat::Json => format_json(event)?,
+                });
+            }
+            collector.deliver(&lines).await?;
+            let mut buffer = self.buffer.lock().expect("siem exporter poisoned");
+            let flushed = batch.len().min(buffer.len());
+            buffer.drain(..flushed);
+            delivered += batch.len();
+        }
+    }
+
+    /// Events waiting for delivery.
+    pub fn buffered(&self) -> usize {
+        self.buffer.lock().expect("siem exporter poisoned").len()
+    }
+
+    /// Events lost to buffer overflow since startup.
+    pub fn dropped(&self) -> u64 {
+        *self.dropped.lock().expect("siem exporter poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn event(message: &str, severity: AuditSeverity) -> AuditEvent {
+        AuditEvent {
+            session_id: "s1".into(),
+            severity,
+            category: "injection_defense".into(),
+            message: message.into(),
+            timestamp: NOW,
+            repeat_count: 1,
+            truncated: false,
+        }
+    }
+
+    fn config(format: SiemFormat) -> SiemExportConfig {
+        SiemExportConfig {
+            endpoint: Some("https://siem.example/ingest".into()),
+            format,
+            ..Default::default()
+        }
+    }
+
+    struct RecordingCollector {
+        batches: Mutex<Vec<Vec<String>>>,
+        fail: AtomicBool,
+    }
+
+    impl RecordingCollector {
+        fn new() -> Self {
+            Self {
+                batches: Mutex::new(Vec::new()),
+                fail: AtomicBool::new(false),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SiemCollector for RecordingCollector {
+        async fn deliver(&self, lines: &[String]) -> Result<()> {
+            if self.fail.load(Ordering::SeqCst) {
+                return Err(SafeClawError::Channel("collector down".into()));
+            }
+            self.batches.lock().unwrap().push(lines.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn cef_lines_carry_severity_session_and_escaped_message() {
+        let line = format_cef(&event("blocked pattern a=b|c", AuditSeverity::Critical));
+        assert!(line.starts_with("CEF:0|A3S|SafeClaw|"));
+        assert!(line.contains("|injection_defense|injection_defense|9|"));
+        assert!(line.contains("suser=s1"));
+        assert!(line.contains("rt=1700000000000"));
+        // `=` escaped in the extension; `|` survives there unescaped.
+        assert!(line.contains("msg=blocked pattern a\\=b|c"));
+    }
+
+    #[test]
+    fn json_lines_round_trip_the_event() {
+        let original = event("hello", AuditSeverity::Warning);
+        let line = format_json(&original).unwrap();
+        let parsed: AuditEvent = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.message, "hello");
+        assert_eq!(parsed.severity, AuditSeverity::Warning);
+        assert!(!line.contains('\n'));
+    }
+
+    #[tokio::test]
+    async fn a_collector_outage_buffers_and_the_next_flush_retries() {
+        let exporter = SiemExporter::new(config(SiemFormat::Json));
+        exporter.enqueue(event("one", AuditSeverity::Info));
+        exporter.enqueue(event("two", AuditSeverity::Info));
+
+        let collector = RecordingCollector::new();
+        collector.fail.store(true, Ordering::SeqCst);
+        assert!(exporter.flush(&collector).await.is_err());
+        // Nothing was lost.
+        assert_eq!(exporter.buffered(), 2);
+
+        collector.fail.store(false, Ordering::SeqCst);
+        assert_eq!(exporter.flush(&collector).await.unwrap(), 2);
+        assert_eq!(exporter.buffered(), 0);
+        let batches = collector.batches.lock().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert!(batches[0][0].contains("one"));
+        assert!(batches[0][1].contains("two"));
+    }
+
+    #[tokio::test]
+    async fn delivery_is_batched_to_the_configured_size() {
+        let exporter = SiemExporter::new(SiemExportConfig {
+            batch_size: 2,
+            ..config(SiemFormat::Cef)
+        });
+        for i in 0..5 {
+            exporter.enqueue(event(&format!("event {i}"), AuditSeverity::Info));
+        }
+        let collector = RecordingCollector::new();
+        assert_eq!(exporter.flush(&collector).await.unwrap(), 5);
+        let batches = collector.batches.lock().unwrap();
+        assert_eq!(
+            batches.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+    }
+
+    #[test]
+    fn overflow_drops_oldest_and_counts_it() {
+        let exporter = SiemExporter::new(SiemExportConfig {
+            max_buffered: 2,
+            ..config(SiemFormat::Json)
+        });
+        exporter.enqueue(event("one", AuditSeverity::Info));
+        exporter.enqueue(event("two", AuditSeverity::Info));
+        exporter.enqueue(event("three", AuditSeverity::Info));
+        assert_eq!(exporter.buffered(), 2);
+        assert_eq!(exporter.dropped(), 1);
+    }
+
+    #[test]
+    fn without_an_endpoint_nothing_buffers() {
+        let exporter = SiemExporter::new(SiemExportConfig::default());
+        exporter.enqueue(event("one", AuditSeverity::Info));
+        assert_eq!(exporter.buffered(), 0);
+    }
+}
@@ -0,0 +1,327 @@
+//! Bounded audit log with ingestion safeguards.
+//!
+//! A misconfigured rule that fires on every output token can generate
+//! thousands of audit events per second — the protective subsystem becomes
+//! the attack. Ingestion is therefore guarded: identical events within a
+//! short window coalesce into one entry carrying a `repeat_count`, payloads
+//! are hard-capped with a truncation mark, each source has a rate budget, the
+//! buffer drops oldest on overflow with a counter surfaced in
+//! health/metrics, and when the buffer stays full an emergency mode suspends
+//! info-level recording. Critical events always land.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Event severity; critical events bypass every ingestion guard except
+/// payload truncation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// One audit log entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub session_id: String,
+    pub severity: AuditSeverity,
+    pub category: String,
+    pub message: String,
+    pub timestamp: i64,
+    /// Identical events coalesced into this entry (1 = no repeats).
+    pub repeat_count: u64,
+    /// Whether `message` was cut at the payload cap.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditIngestionConfig {
+    /// In-memory buffer capacity; overflow drops oldest.
+    pub max_events: usize,
+    /// Hard cap on one event's message, bytes.
+    pub max_payload_bytes: usize,
+    /// Events one source may record per second; excess non-critical events
+    /// are dropped.
+    pub per_source_per_sec: u32,
+    /// Identical events within this window coalesce.
+    pub coalesce_window_secs: i64,
+    /// Buffer continuously full for this long engages emergency mode.
+    pub emergency_after_secs: i64,
+}
+
+impl Default for AuditIngestionConfig {
+    fn default() -> Self {
+        Self {
+            max_events: 10_000,
+            max_payload_bytes: 4_096,
+            per_source_per_sec: 50,
+            coalesce_window_secs: 5,
+            emergency_after_secs: 30,
+        }
+    }
+}
+
+/// Ingestion counters for health and metrics.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestionStats {
+    pub dropped_events: u64,
+    pub suppressed_events: u64,
+    pub emergency_active: bool,
+    pub buffered_events: usize,
+}
+
+struct LogState {
+    events: Vec<AuditEvent>,
+    /// `(source, events recorded in the current second, second)` per source.
+    source_budgets: HashMap<String, (u32, i64)>,
+    dropped: u64,
+    suppressed: u64,
+    full_since: Option<i64>,
+    emergency: bool,
+}
+
+/// Bounded in-memory audit log; the ingestion path for every monitor.
+pub struct AuditLog {
+    config: AuditIngestionConfig,
+    state: Mutex<LogState>,
+}
+
+impl AuditLog {
+    pub fn new(config: AuditIngestionConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(LogState {
+                events: Vec::new(),
+                source_budgets: HashMap::new(),
+                dropped: 0,
+                suppressed: 0,
+                full_since: None,
+                emergency: false,
+            }),
+        }
+    }
+
+    /// Record one event from `source` (the emitting subsystem, e.g.
+    /// `"interceptor"`). Applies truncation, coalescing, rate and buffer
+    /// guards in that order.
+    pub fn record(
+        &self,
+        source: &str,
+        session_id: &str,
+        severity: AuditSeverity,
+        category: &str,
+        message: &str,
+        now: i64,
+    ) {
+        let (message, truncated) = self.truncate(message);
+        let mut state = self.state.lock().expect("audit log poisoned");
+
+        // Emergency mode: buffer persistently full → info recording suspends.
+        self.update_emergency(&mut state, now);
+        if state.emergency && severity == AuditSeverity::Info {
+            state.suppressed += 1;
+            return;
+        }
+
+        // Coalesce identical events within the window.
+        if let Some(existing) = state.events.iter_mut().rev().find(|e| {
+            e.session_id == session_id
+                && e.category == category
+                && e.message == message
+                && now - e.timestamp < self.config.coalesce_window_secs
+        }) {
+            existing.repeat_count += 1;
+            return;
+        }
+
+        // Per-source rate budget; critical events are exempt.
+        if severity != AuditSeverity::Critical && self.config.per_source_per_sec > 0 {
+            let budget = state
+                .source_budgets
+                .entry(source.to_string())
+                .or_insert((0, now));
+            if budget.1 != now {
+                *budget = (0, now);
+            }
+            if budget.0 >= self.config.per_source_per_sec {
+                state.dropped += 1;
+                return;
+            }
+            budget.0 += 1;
+        }
+
+        state.events.push(AuditEvent {
+            session_id: session_id.to_string(),
+            severity,
+            category: category.to_string(),
+            message,
+            timestamp: now,
+            repeat_count: 1,
+            truncated,
+        });
+        let overflow = state.events.len().saturating_sub(self.config.max_events);
+        if overflow > 0 {
+            state.events.drain(..overflow);
+            state.dropped += overflow as u64;
+        }
+    }
+
+    fn truncate(&self, message: &str) -> (String, bool) {
+        if message.len() <= self.config.max_payload_bytes {
+            return (message.to_string(), false);
+        }
+        let mut cut = self.config.max_payload_bytes;
+        while !message.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        (format!("{}…[truncated]", &message[..cut]), true)
+    }
+
+    fn update_emergency(&self, state: &mut LogState, now: i64) {
+        if state.events.len() >= self.config.max_events {
+            let since = *state.full_since.get_or_insert(now);
+            if now - since >= self.config.emergency_after_secs {
+                state.emergency = true;
+            }
+        } else {
+            state.full_since = None;
+            state.emergency = false;
+        }
+    }
+
+    pub fn events(&self) -> Vec<AuditEvent> {
+        self.state.lock().expect("audit log poisoned").events.clone()
+    }
+
+    pub fn ingestion_stats(&self) -> IngestionStats {
+        let state = self.state.lock().expect("audit log poisoned");
+        IngestionStats {
+            dropped_events: state.dropped,
+            suppressed_events: state.suppressed,
+            emergency_active: state.emergency,
+            buffered_events: state.events.len(),
+        }
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new(AuditIngestionConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_event_storm_coalesces_with_a_repeat_count() {
+        let log = AuditLog::default();
+        for _ in 0..500 {
+            log.record(
+                "sanitizer",
+                "s1",
+                AuditSeverity::Warning,
+                "redaction",
+                "redacted ssn in output",
+                10,
+            );
+        }
+        let events = log.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].repeat_count, 500);
+        assert_eq!(log.ingestion_stats().dropped_events, 0);
+    }
+
+    #[test]
+    fn distinct_event_storm_hits_the_source_budget_but_critical_lands() {
+        let log = AuditLog::new(AuditIngestionConfig {
+            per_source_per_sec: 10,
+            ..Default::default()
+        });
+        for i in 0..100 {
+            log.record(
+                "interceptor",
+                "s1",
+                AuditSeverity::Info,
+                "tool",
+                &format!("event {i}"),
+                0,
+            );
+        }
+        log.record(
+            "interceptor",
+            "s1",
+            AuditSeverity::Critical,
+            "taint",
+            "boundary violation",
+            0,
+        );
+        let stats = log.ingestion_stats();
+        assert_eq!(stats.buffered_events, 11);
+        assert_eq!(stats.dropped_events, 90);
+        assert!(log
+            .events()
+            .iter()
+            .any(|e| e.severity == AuditSeverity::Critical));
+    }
+
+    #[test]
+    fn oversized_payloads_are_truncated_and_marked() {
+        let log = AuditLog::new(AuditIngestionConfig {
+            max_payload_bytes: 16,
+            ..Default::default()
+        });
+        log.record("guard", "s1", AuditSeverity::Info, "c", &"x".repeat(100), 0);
+        let events = log.events();
+        assert!(events[0].truncated);
+        assert!(events[0].message.ends_with("…[truncated]"));
+        assert!(events[0].message.starts_with(&"x".repeat(16)));
+    }
+
+    #[test]
+    fn buffer_overflow_drops_oldest_and_counts_it() {
+        let log = AuditLog::new(AuditIngestionConfig {
+            max_events: 5,
+            per_source_per_sec: 0,
+            coalesce_window_secs: 0,
+            ..Default::default()
+        });
+        for i in 0..8 {
+            log.record("guard", "s1", AuditSeverity::Info, "c", &format!("e{i}"), i);
+        }
+        let events = log.events();
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0].message, "e3");
+        assert_eq!(log.ingestion_stats().dropped_events, 3);
+    }
+
+    #[test]
+    fn persistent_full_buffer_engages_emergency_mode_for_info_only() {
+        let log = AuditLog::new(AuditIngestionConfig {
+            max_events: 3,
+            per_source_per_sec: 0,
+            coalesce_window_secs: 0,
+            emergency_after_secs: 10,
+            ..Default::default()
+        });
+        // Keep the buffer full past the emergency threshold.
+        for now in 0..15 {
+            log.record("guard", "s1", AuditSeverity::Info, "c", &format!("n{now}"), now);
+        }
+        assert!(log.ingestion_stats().emergency_active);
+
+        let before = log.ingestion_stats().suppressed_events;
+        log.record("guard", "s1", AuditSeverity::Info, "c", "suppressed", 20);
+        assert_eq!(log.ingestion_stats().suppressed_events, before + 1);
+
+        log.record("guard", "s1", AuditSeverity::Critical, "taint", "leak", 20);
+        assert!(log.events().iter().any(|e| e.message == "leak"));
+    }
+}
@@ -0,0 +1,5 @@
+//! Observability pipeline — audit log, alerting, persistence.
+
+pub mod alerting;
+pub mod export;
+pub mod log;
@@ -0,0 +1,89 @@
+//! The channel adapter contract.
+//!
+//! Every platform adapter (Telegram, Slack, Discord, Matrix, …) implements
+//! [`ChannelAdapter`]: translate platform webhooks/sync payloads into
+//! [`InboundMessage`]s, deliver [`OutboundMessage`]s through the platform
+//! API, and verify inbound signatures where the platform signs its requests.
+
+use async_trait::async_trait;
+
+use crate::channels::capabilities::ChannelCapabilities;
+use crate::channels::message::{InboundMessage, OutboundMessage};
+use crate::error::Result;
+
+#[async_trait]
+pub trait ChannelAdapter: Send + Sync {
+    /// Channel name as used in config and session keys (e.g. `"telegram"`).
+    fn name(&self) -> &str;
+
+    /// What this platform can do. The default is the conservative fallback;
+    /// adapters override it with accurate platform values so the delivery
+    /// pipeline never has to special-case channels by name.
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities::default()
+    }
+
+    /// Parse one platform update payload. `Ok(None)` means the update is
+    /// valid but carries no message for the pipeline (edits, receipts,
+    /// disallowed rooms).
+    fn parse_update(&self, payload: &serde_json::Value) -> Result<Option<InboundMessage>>;
+
+    /// Verify the platform's request signature over the raw body. Adapters
+    /// for platforms that don't sign requests keep the default no-op.
+    fn verify_signature(&self, _headers: &[(String, String)], _body: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Deliver one message through the platform API.
+    async fn send_message(&self, message: &OutboundMessage) -> Result<()>;
+}
+
+/// The transport an adapter sends through — HTTP in production, injectable
+/// for the conformance harness so transport failures can be simulated.
+#[async_trait]
+pub trait PlatformBackend: Send + Sync {
+    /// Deliver one already-chunked message to a chat.
+    async fn deliver(&self, chat_id: &str, content: &str) -> Result<()>;
+}
+
+/// Split a message at the platform's length limit, preferring newline
+/// boundaries so chunks stay readable.
+pub fn chunk_message(content: &str, max_chars: usize) -> Vec<String> {
+    if content.chars().count() <= max_chars {
+        return vec![content.to_string()];
+    }
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in content.split_inclusive('\n') {
+        if current.chars().count() + line.chars().count() > max_chars && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        // A single line longer than the limit is split hard.
+        if line.chars().count() > max_chars {
+            let mut rest: Vec<char> = line.chars().collect();
+            while rest.len() > max_chars {
+                chunks.push(rest.drain(..max_chars).collect());
+            }
+            current = rest.into_iter().collect();
+        } else {
+            current.push_str(line);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Deliver with one retry on transient transport failure.
+pub async fn deliver_with_retry(
+    backend: &dyn PlatformBackend,
+    chat_id: &str,
+    content: &str,
+) -> Result<()> {
+    if let Err(first) = backend.deliver(chat_id, content).await {
+        tracing::warn!("delivery failed, retrying once: {first}");
+        return backend.deliver(chat_id, content).await;
+    }
+    Ok(())
+}
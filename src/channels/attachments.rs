@@ -0,0 +1,326 @@
+//! Inbound file/attachment handling for channel adapters.
+//!
+//! Users drop screenshots and CSVs into Slack and Discord; adapters resolve
+//! the platform's attachment metadata into [`InboundAttachment`]s and this
+//! module downloads them (authenticated with the bot token), enforces size
+//! and count limits, and applies tiered handling: small text-like files are
+//! inlined into the prompt (the pipeline classifies inlined content before
+//! the LLM sees it), images pass through to the model when the provider
+//! declares vision support, and everything else lands in the session
+//! workspace with the agent told the path so file tools can read it.
+//! Oversized attachments produce a polite notice instead of a download.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttachmentConfig {
+    /// Hard per-file limit; larger files are not downloaded.
+    pub max_size_bytes: u64,
+    /// Attachments beyond this count per message are skipped with a notice.
+    pub max_count: usize,
+    /// Text-like files up to this size are inlined into the prompt.
+    pub inline_text_max_bytes: u64,
+}
+
+impl Default for AttachmentConfig {
+    fn default() -> Self {
+        Self {
+            max_size_bytes: 10 * 1024 * 1024,
+            max_count: 5,
+            inline_text_max_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Platform attachment metadata, before download.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundAttachment {
+    pub url: String,
+    pub file_name: String,
+    pub mime_type: String,
+    /// Size the platform reports, checked before downloading.
+    pub size_bytes: u64,
+}
+
+/// A downloaded attachment available to the pipeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageAttachment {
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    pub local_path: PathBuf,
+}
+
+/// What the pipeline does with one attachment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentHandling {
+    /// Text content inlined into the prompt (after classification).
+    InlineText { content: String },
+    /// Image passed to a vision-capable model.
+    VisionImage { path: PathBuf },
+    /// Stored in the session workspace; the agent is told the path.
+    Workspace { path: PathBuf },
+    /// Not downloaded; `notice` goes back to the user.
+    Rejected { notice: String },
+}
+
+/// Authenticated download, implemented per platform with the bot token;
+/// mocked in tests.
+#[async_trait]
+pub trait AttachmentFetcher: Send + Sync {
+    async fn fetch(&self, url: &str) -> Result<Vec<u8>>;
+}
+
+fn is_text_like(mime_type: &str) -> bool {
+    mime_type.starts_with("text/")
+        || matches!(
+            mime_type,
+            "application/json" | "application/csv" | "application/x-yaml"
+        )
+}
+
+fn sanitized_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || ".-_".contains(c) { c } else { '_' })
+        .collect()
+}
+
+/// Download and tier one message's attachments into the session workspace.
+pub async fn process_attachments(
+    attachments: &[InboundAttachment],
+    config: &AttachmentConfig,
+    fetcher: &dyn AttachmentFetcher,
+    workspace_dir: &Path,
+    model_supports_vision: bool,
+) -> Result<Vec<(InboundAttachment, AttachmentHandling)>> {
+    let mut results = Vec::new();
+    for (index, attachment) in attachments.iter().enumerate() {
+        if index >= config.max_count {
+            results.push((
+                attachment.clone(),
+                AttachmentHandling::Rejected {
+                    notice: format!(
+                        "Skipped `{}` — at most {} attachments per message.",
+                        attachment.file_name, config.max_count
+                    ),
+                },
+            ));
+            continue;
+        }
+        if attachment.size_bytes > config.max_size_bytes {
+            results.push((
+                attachment.clone(),
+                AttachmentHandling::Rejected {
+                    notice: format!(
+                        "Skipped `{}` — {} exceeds the {} MiB attachment limit.",
+                        attachment.file_name,
+                        human_size(attachment.size_bytes),
+                        config.max_size_bytes / (1024 * 1024)
+                    ),
+                },
+            ));
+            continue;
+        }
+
+        let bytes = fetcher.fetch(&attachment.url).await?;
+        let handling = if is_text_like(&attachment.mime_type)
+            && attachment.size_bytes <= config.inline_text_max_bytes
+        {
+            AttachmentHandling::InlineText {
+                content: String::from_utf8_lossy(&bytes).into_owned(),
+            }
+        } else {
+            tokio::fs::create_dir_all(workspace_dir).await?;
+            let path = workspace_dir.join(sanitized_file_name(&attachment.file_name));
+            tokio::fs::write(&path, &bytes).await?;
+            if attachment.mime_type.starts_with("image/") && model_supports_vision {
+                AttachmentHandling::VisionImage { path }
+            } else {
+                AttachmentHandling::Workspace { path }
+            }
+        };
+        results.push((attachment.clone(), handling));
+    }
+    Ok(results)
+}
+
+fn human_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KiB", bytes / 1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    struct MockFetcher {
+        responses: HashMap<String, Vec<u8>>,
+        fetched: Mutex<Vec<String>>,
+    }
+
+    impl MockFetcher {
+        fn new(responses: Vec<(&str, &[u8])>) -> Self {
+            Self {
+                responses: responses
+                    .into_iter()
+                    .map(|(u, b)| (u.to_string(), b.to_vec()))
+                    .collect(),
+                fetched: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AttachmentFetcher for MockFetcher {
+        async fn fetch(&self, url: &str) -> Result<Vec<u8>> {
+            self.fetched.lock().unwrap().push(url.to_string());
+            Ok(self.responses.get(url).cloned().unwrap_or_default())
+        }
+    }
+
+    fn attachment(url: &str, name: &str, mime: &str, size: u64) -> InboundAttachment {
+        InboundAttachment {
+            url: url.into(),
+            file_name: name.into(),
+            mime_type: mime.into(),
+            size_bytes: size,
+        }
+    }
+
+    #[tokio::test]
+    async fn small_text_files_are_inlined() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = MockFetcher::new(vec![("u1", b"a,b\n1,2\n".as_slice())]);
+        let results = process_attachments(
+            &[attachment("u1", "data.csv", "text/csv", 8)],
+            &AttachmentConfig::default(),
+            &fetcher,
+            dir.path(),
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            results[0].1,
+            AttachmentHandling::InlineText {
+                content: "a,b\n1,2\n".into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn images_go_to_vision_when_the_model_supports_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = MockFetcher::new(vec![("u1", b"\x89PNG".as_slice())]);
+        let attachments = [attachment("u1", "shot.png", "image/png", 4)];
+
+        let with_vision = process_attachments(
+            &attachments,
+            &AttachmentConfig::default(),
+            &fetcher,
+            dir.path(),
+            true,
+        )
+        .await
+        .unwrap();
+        let path = dir.path().join("shot.png");
+        assert_eq!(
+            with_vision[0].1,
+            AttachmentHandling::VisionImage { path: path.clone() }
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), b"\x89PNG");
+
+        // Without vision support the image falls back to the workspace tier.
+        let without = process_attachments(
+            &attachments,
+            &AttachmentConfig::default(),
+            &fetcher,
+            dir.path(),
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(without[0].1, AttachmentHandling::Workspace { path });
+    }
+
+    #[tokio::test]
+    async fn binaries_land_in_the_workspace_with_sanitized_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = MockFetcher::new(vec![("u1", b"PK\x03\x04".as_slice())]);
+        let results = process_attachments(
+            &[attachment("u1", "../escape attempt!.zip", "application/zip", 4)],
+            &AttachmentConfig::default(),
+            &fetcher,
+            dir.path(),
+            false,
+        )
+        .await
+        .unwrap();
+        match &results[0].1 {
+            AttachmentHandling::Workspace { path } => {
+                assert!(path.starts_with(dir.path()));
+                assert_eq!(
+                    path.file_name().unwrap().to_str().unwrap(),
+                    ".._escape_attempt_.zip"
+                );
+            }
+            other => panic!("expected workspace tier, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_attachments_are_rejected_without_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = MockFetcher::new(vec![]);
+        let results = process_attachments(
+            &[attachment("u1", "dump.bin", "application/octet-stream", 50 * 1024 * 1024)],
+            &AttachmentConfig::default(),
+            &fetcher,
+            dir.path(),
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            &results[0].1,
+            AttachmentHandling::Rejected { notice } if notice.contains("10 MiB")
+        ));
+        assert!(fetcher.fetched.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn attachment_count_limit_is_enforced() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = MockFetcher::new(vec![("u1", b"x".as_slice()), ("u2", b"y".as_slice())]);
+        let config = AttachmentConfig {
+            max_count: 1,
+            ..Default::default()
+        };
+        let results = process_attachments(
+            &[
+                attachment("u1", "a.txt", "text/plain", 1),
+                attachment("u2", "b.txt", "text/plain", 1),
+            ],
+            &config,
+            &fetcher,
+            dir.path(),
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(results[0].1, AttachmentHandling::InlineText { .. }));
+        assert!(matches!(results[1].1, AttachmentHandling::Rejected { .. }));
+        assert_eq!(fetcher.fetched.lock().unwrap().len(), 1);
+    }
+}
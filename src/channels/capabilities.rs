@@ -0,0 +1,187 @@
+//! Typed channel capabilities and the delivery decisions built on them.
+//!
+//! "Can this channel edit messages / send buttons / upload files?" kept
+//! being answered by hard-coded platform knowledge scattered across the
+//! delivery code. Each adapter now declares a [`ChannelCapabilities`] and
+//! the shared pipeline derives its behavior from that — chunk size,
+//! progressive edits vs. repost, HITL buttons vs. numbered replies,
+//! attachment upload vs. link fallback — instead of matching on channel
+//! names. `GET /api/channels` exposes the same struct so UIs can adapt
+//! too. New platform quirks belong in the adapter's capability values, not
+//! in new `if telegram` branches.
+
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::channels::adapter::ChannelAdapter;
+use crate::channels::chunking::chunk_content;
+use crate::channels::markdown::Dialect;
+
+/// What one channel's platform can do, declared by its adapter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelCapabilities {
+    pub max_message_chars: usize,
+    pub supports_edit: bool,
+    pub supports_buttons: bool,
+    pub supports_attachments: bool,
+    /// Platform upload limit; meaningless when attachments are unsupported.
+    pub max_attachment_bytes: Option<u64>,
+    pub supports_threads: bool,
+    pub supports_reactions: bool,
+    pub supports_typing_indicator: bool,
+    pub dialect: Dialect,
+}
+
+impl Default for ChannelCapabilities {
+    /// Conservative fallback for adapters that declare nothing: short
+    /// plain-text messages, no platform extras.
+    fn default() -> Self {
+        Self {
+            max_message_chars: 2_000,
+            supports_edit: false,
+            supports_buttons: false,
+            supports_attachments: false,
+            max_attachment_bytes: None,
+            supports_threads: false,
+            supports_reactions: false,
+            supports_typing_indicator: false,
+            dialect: Dialect::Plain,
+        }
+    }
+}
+
+/// How approval prompts are presented on this channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HitlPresentation {
+    /// Inline approve/deny buttons.
+    Buttons,
+    /// A numbered list the user answers by replying "1" / "2".
+    NumberedReplies,
+}
+
+/// How one attachment should leave through this channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentPlan {
+    Upload,
+    /// Too large or unsupported: deliver a download link instead.
+    LinkFallback,
+}
+
+/// What the pipeline decided for one outbound message, derived entirely
+/// from the channel's capabilities.
+#[derive(Debug, Clone)]
+pub struct DeliveryPlan {
+    pub chunks: Vec<String>,
+    /// Stream by editing one message in place rather than reposting.
+    pub progressive_edits: bool,
+    pub hitl: HitlPresentation,
+    pub show_typing: bool,
+}
+
+/// Plan delivery of one rendered message through a channel.
+pub fn plan_delivery(capabilities: &ChannelCapabilities, content: &str) -> DeliveryPlan {
+    DeliveryPlan {
+        chunks: chunk_content(content, capabilities.max_message_chars),
+        progressive_edits: capabilities.supports_edit,
+        hitl: if capabilities.supports_buttons {
+            HitlPresentation::Buttons
+        } else {
+            HitlPresentation::NumberedReplies
+        },
+        show_typing: capabilities.supports_typing_indicator,
+    }
+}
+
+/// Decide upload vs. link fallback for one attachment of `size_bytes`.
+pub fn plan_attachment(capabilities: &ChannelCapabilities, size_bytes: u64) -> AttachmentPlan {
+    if !capabilities.supports_attachments {
+        return AttachmentPlan::LinkFallback;
+    }
+    match capabilities.max_attachment_bytes {
+        Some(limit) if size_bytes > limit => AttachmentPlan::LinkFallback,
+        _ => AttachmentPlan::Upload,
+    }
+}
+
+/// One entry in the `GET /api/channels` listing.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelInfo {
+    pub name: String,
+    pub capabilities: ChannelCapabilities,
+}
+
+/// Routes mounted under `/api/channels`, behind `api::admin_protected`.
+pub fn capability_routes(adapters: Vec<Arc<dyn ChannelAdapter>>) -> Router {
+    Router::new()
+        .route("/", get(list_channels))
+        .with_state(Arc::new(adapters))
+}
+
+async fn list_channels(
+    State(adapters): State<Arc<Vec<Arc<dyn ChannelAdapter>>>>,
+) -> Json<Vec<ChannelInfo>> {
+    Json(
+        adapters
+            .iter()
+            .map(|adapter| ChannelInfo {
+                name: adapter.name().to_string(),
+                capabilities: adapter.capabilities(),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rich() -> ChannelCapabilities {
+        ChannelCapabilities {
+            max_message_chars: 4_096,
+            supports_edit: true,
+            supports_buttons: true,
+            supports_attachments: true,
+            max_attachment_bytes: Some(50 * 1024 * 1024),
+            supports_typing_indicator: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn the_plan_follows_the_capability_set_not_the_platform_name() {
+        let long = "line\n".repeat(1_000);
+
+        let plan = plan_delivery(&rich(), &long);
+        assert!(plan.chunks.len() > 1);
+        assert!(plan.chunks.iter().all(|c| c.chars().count() <= 4_096));
+        assert!(plan.progressive_edits);
+        assert_eq!(plan.hitl, HitlPresentation::Buttons);
+        assert!(plan.show_typing);
+
+        let plan = plan_delivery(&ChannelCapabilities::default(), &long);
+        assert!(plan.chunks.iter().all(|c| c.chars().count() <= 2_000));
+        assert!(!plan.progressive_edits);
+        assert_eq!(plan.hitl, HitlPresentation::NumberedReplies);
+        assert!(!plan.show_typing);
+    }
+
+    #[test]
+    fn attachments_fall_back_to_links_when_unsupported_or_oversized() {
+        assert_eq!(plan_attachment(&rich(), 1024), AttachmentPlan::Upload);
+        assert_eq!(
+            plan_attachment(&rich(), 51 * 1024 * 1024),
+            AttachmentPlan::LinkFallback
+        );
+        assert_eq!(
+            plan_attachment(&ChannelCapabilities::default(), 1024),
+            AttachmentPlan::LinkFallback
+        );
+    }
+}
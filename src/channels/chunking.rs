@@ -0,0 +1,330 @@
+//! Boundary-aware chunking for outbound messages.
+//!
+//! [`chunk_message`](crate::channels::adapter::chunk_message) splits at
+//! newlines, which is fine for logs but mid-thought for prose and fatal
+//! for fenced code blocks — half a fence renders as literal backticks on
+//! every platform. This chunker splits at paragraph boundaries first,
+//! sentences second, and only then hard; a fenced block that must span
+//! chunks is closed at the chunk edge and reopened with the same info
+//! string so each chunk renders as valid markdown. Multi-part sends are
+//! numbered "(1/3) …". Adapters call [`chunk_for_channel`] with their own
+//! [`ChannelCapabilities`] so the limit always matches the platform.
+
+use crate::channels::capabilities::ChannelCapabilities;
+
+/// Headroom reserved per chunk for the "(12/34) " numbering prefix.
+const NUMBER_RESERVE: usize = 8;
+
+/// Split content at the channel's declared limit.
+pub fn chunk_for_channel(content: &str, capabilities: &ChannelCapabilities) -> Vec<String> {
+    chunk_content(content, capabilities.max_message_chars)
+}
+
+/// Split content at `max_chars`, preferring paragraph then sentence
+/// boundaries, keeping fenced code blocks well-formed, and numbering the
+/// chunks when more than one is needed.
+pub fn chunk_content(content: &str, max_chars: usize) -> Vec<String> {
+    if content.chars().count() <= max_chars {
+        return vec![content.to_string()];
+    }
+    let budget = max_chars.saturating_sub(NUMBER_RESERVE).max(1);
+    let mut chunker = Chunker::new(budget);
+    for piece in pieces(content) {
+        match piece {
+            Piece::Text(text) => chunker.push_text(&text),
+            Piece::Code { info, body } => chunker.push_code(&info, &body),
+        }
+    }
+    chunker.finish()
+}
+
+enum Piece {
+    Text(String),
+    /// A fenced block: the fence's info string and the body lines.
+    Code { info: String, body: String },
+}
+
+/// Split into prose paragraphs and atomic fenced code blocks.
+fn pieces(content: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut code: Option<(String, String)> = None;
+    for line in content.split_inclusive('\n') {
+        let fence = line.trim_start().starts_with("```");
+        match (&mut code, fence) {
+            (None, true) => {
+                for paragraph in take_paragraphs(&mut text) {
+                    pieces.push(Piece::Text(paragraph));
+                }
+                let info = line.trim().trim_start_matches('`').to_string();
+                code = Some((info, String::new()));
+            }
+            (None, false) => text.push_str(line),
+            (Some((info, body)), true) => {
+                pieces.push(Piece::Code {
+                    info: std::mem::take(info),
+                    body: std::mem::take(body),
+                });
+                code = None;
+            }
+            (Some((_, body)), false) => body.push_str(line),
+        }
+    }
+    // An unclosed fence is treated as code to the end.
+    if let Some((info, body)) = code {
+        pieces.push(Piece::Code { info, body });
+    }
+    for paragraph in take_paragraphs(&mut text) {
+        pieces.push(Piece::Text(paragraph));
+    }
+    pieces
+}
+
+fn take_paragraphs(text: &mut String) -> Vec<String> {
+    let taken = std::mem::take(text);
+    taken
+        .split_inclusive("\n\n")
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+struct Chunker {
+    budget: usize,
+    chunks: Vec<String>,
+    current: String,
+}
+
+impl Chunker {
+    fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            chunks: Vec::new(),
+            current: String::new(),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.budget.saturating_sub(self.current.chars().count())
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            self.chunks.push(std::mem::take(&mut self.current));
+        }
+    }
+
+    /// One paragraph: appended whole, split at sentences when it can't fit
+    /// a chunk on its own, hard-split only as a last resort.
+    fn push_text(&mut self, paragraph: &str) {
+        if paragraph.chars().count() <= self.budget {
+            if paragraph.chars().count() > self.remaining() {
+                self.flush();
+            }
+            self.current.push_str(paragraph);
+            return;
+        }
+        for sentence in paragraph.split_inclusive(['.', '!', '?']) {
+            if sentence.chars().count() > self.budget {
+                let mut rest: Vec<char> = sentence.chars().collect();
+                while !rest.is_empty() {
+                    let take = self.remaining().min(rest.len());
+                    if take == 0 {
+                        self.flush();
+                        continue;
+                    }
+                    self.current.extend(rest.drain(..take));
+                }
+            } else {
+                if sentence.chars().count() > self.remaining() {
+                    self.flush();
+                }
+                self.current.push_str(sentence);
+            }
+        }
+    }
+
+    /// One fenced block. A block that spans chunks is closed at the edge
+    /// and reopened with the same info string so every chunk stays valid.
+    fn push_code(&mut self, info: &str, body: &str) {
+        let open = format!("```{info}\n");
+        let close = "```\n";
+        let whole = format!("{open}{body}{close}");
+        if whole.chars().count() <= self.budget {
+            if whole.chars().count() > self.remaining() {
+                self.flush();
+            }
+            self.current.push_str(&whole);
+            return;
+        }
+        let overhead = open.chars().count() + close.chars().count();
+        // The block spans chunks anyway; start it on a fresh one so the
+        // first fragment isn't a sliver.
+        self.flush();
+        self.current.push_str(&open);
+        for line in body.split_inclusive('\n') {
+            let line_chars = line.chars().count();
+            if line_chars + close.chars().count() > self.remaining() {
+                self.current.push_str(close);
+                self.flush();
+                self.current.push_str(&open);
+            }
+            if line_chars + overhead > self.budget {
+                // A single monster line: hard-split inside the fence.
+                let mut rest: Vec<char> = line.chars().collect();
+                while rest.len() + close.chars().count() > self.remaining() {
+                    let take = self
+                        .remaining()
+                        .saturating_sub(close.chars().count())
+                        .clamp(1, rest.len());
+                    self.current.extend(rest.drain(..take));
+                    self.current.push_str(close);
+                    self.flush();
+                    self.current.push_str(&open);
+                }
+                self.current.extend(rest);
+            } else {
+                self.current.push_str(line);
+            }
+        }
+        self.current.push_str(close);
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.flush();
+        let total = self.chunks.len();
+        if total <= 1 {
+            return self.chunks;
+        }
+        self.chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                // A prefix glued onto a fence line would break the fence;
+                // give it its own line there.
+                let sep = if chunk.starts_with("```") { "\n" } else { " " };
+                format!("({}/{total}){sep}{chunk}", i + 1)
+            })
+            .collect()
+    }
+}
+
+/// Strip the "(i/total) " numbering prefix (or its fence-safe
+/// "(i/total)\n" form) from a chunk, if present. The conformance harness
+/// strips markers before checking that chunking lost no content.
+pub fn strip_part_marker(chunk: &str) -> &str {
+    let Some(rest) = chunk.strip_prefix('(') else {
+        return chunk;
+    };
+    let Some(end) = rest.find([' ', '\n']) else {
+        return chunk;
+    };
+    let Some(numbers) = rest[..end].strip_suffix(')') else {
+        return chunk;
+    };
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    match numbers.split_once('/') {
+        Some((index, total)) if is_number(index) && is_number(total) => &rest[end + 1..],
+        _ => chunk,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fences_balanced(chunk: &str) -> bool {
+        chunk
+            .lines()
+            .filter(|l| l.trim_start().starts_with("```"))
+            .count()
+            % 2
+            == 0
+    }
+
+    #[test]
+    fn short_content_passes_through_unnumbered() {
+        assert_eq!(chunk_content("hello world", 4096), vec!["hello world"]);
+    }
+
+    #[test]
+    fn long_prose_splits_at_paragraphs_and_is_numbered() {
+        let paragraph = "One idea per paragraph keeps this readable.\n\n";
+        let content = paragraph.repeat(20);
+        let chunks = chunk_content(&content, 200);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks[0].starts_with(&format!("(1/{}) ", chunks.len())));
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 200);
+            // Splits landed between paragraphs, not inside one.
+            assert!(chunk.ends_with("readable.\n\n"));
+        }
+    }
+
+    #[test]
+    fn an_oversized_paragraph_falls_back_to_sentence_boundaries() {
+        let content = "This is a sentence. ".repeat(40);
+        let chunks = chunk_content(&content, 150);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 150);
+            assert!(chunk.ends_with("sentence. ") || chunk.ends_with("sentence."));
+        }
+    }
+
+    #[test]
+    fn a_fenced_block_spanning_chunks_stays_well_formed() {
+        let body = (0..60)
+            .map(|i| format!("let line_{i} = {i};\n"))
+            .collect::<String>();
+        let content = format!("Here's the code:\n\n```rust\n{body}```\n");
+        let chunks = chunk_content(&content, 300);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 300);
+            // Every chunk opens and closes its fences.
+            assert!(fences_balanced(chunk), "unbalanced fences in {chunk:?}");
+        }
+        // Continuation chunks reopen with the original info string.
+        assert!(chunks[1].contains("```rust\n"));
+        // No code line was cut in half.
+        let rejoined: String = chunks
+            .iter()
+            .flat_map(|c| c.lines())
+            .filter(|l| l.starts_with("let "))
+            .map(|l| format!("{l}\n"))
+            .collect();
+        assert_eq!(rejoined, body);
+    }
+
+    #[test]
+    fn stripping_part_markers_recovers_the_original_prose() {
+        let content = "line of filler text\n".repeat(100);
+        let chunks = chunk_content(&content, 200);
+        assert!(chunks.len() > 1);
+        let rejoined: String = chunks.iter().map(|c| strip_part_marker(c)).collect();
+        assert_eq!(rejoined, content);
+        // Non-marker parentheses are left alone.
+        assert_eq!(strip_part_marker("(sic) quoted"), "(sic) quoted");
+        assert_eq!(strip_part_marker("(a/b) text"), "(a/b) text");
+    }
+
+    #[test]
+    fn the_channel_limit_drives_the_chunk_count() {
+        let content = "A telegram-sized paragraph of filler text goes here.\n\n".repeat(100);
+        let telegram = ChannelCapabilities {
+            max_message_chars: 4_096,
+            ..Default::default()
+        };
+        let discord_like = ChannelCapabilities {
+            max_message_chars: 2_000,
+            ..Default::default()
+        };
+        let coarse = chunk_for_channel(&content, &telegram);
+        let fine = chunk_for_channel(&content, &discord_like);
+        assert!(fine.len() > coarse.len());
+        assert!(coarse.iter().all(|c| c.chars().count() <= 4_096));
+        assert!(fine.iter().all(|c| c.chars().count() <= 2_000));
+    }
+}
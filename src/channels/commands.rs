@@ -0,0 +1,280 @@
+//! Slash-command registry with structured argument schemas.
+//!
+//! Chat commands (`/status`, `/settings set …`) are declared here once and
+//! dispatched uniformly from every channel. Each command declares an argument
+//! schema — names, types, required flags, fixed choices — exposed via
+//! [`CommandRegistry::list_commands`] so the WebChat UI and Slack/Discord
+//! slash-command registration can offer autocomplete, and validated here
+//! before the handler runs so users get a helpful message instead of a
+//! half-executed command.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeClawError};
+
+/// Argument value type, mirrored into platform slash-command registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgKind {
+    String,
+    Integer,
+    Boolean,
+}
+
+/// One declared argument.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgSpec {
+    pub name: String,
+    pub description: String,
+    pub kind: ArgKind,
+    #[serde(default)]
+    pub required: bool,
+    /// Fixed set of accepted values; empty means free-form. Surfaced as
+    /// autocomplete choices.
+    #[serde(default)]
+    pub choices: Vec<String>,
+}
+
+/// A command's public shape, as returned by `list_commands`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandSpec {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub args: Vec<ArgSpec>,
+}
+
+/// Validated arguments handed to a handler.
+pub type CommandArgs = HashMap<String, serde_json::Value>;
+
+#[async_trait]
+pub trait CommandHandler: Send + Sync {
+    async fn run(&self, args: &CommandArgs) -> Result<String>;
+}
+
+/// Registry of chat commands shared by all channel adapters.
+#[derive(Default)]
+pub struct CommandRegistry {
+    commands: Vec<(CommandSpec, Arc<dyn CommandHandler>)>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, spec: CommandSpec, handler: Arc<dyn CommandHandler>) {
+        self.commands.push((spec, handler));
+    }
+
+    /// Command specs for autocomplete and slash-command registration, sorted
+    /// by name.
+    pub fn list_commands(&self) -> Vec<CommandSpec> {
+        let mut specs: Vec<CommandSpec> =
+            self.commands.iter().map(|(spec, _)| spec.clone()).collect();
+        specs.sort_by(|a, b| a.name.cmp(&b.name));
+        specs
+    }
+
+    /// Parse, validate, and execute `/name raw-args`. Tokens bind to declared
+    /// arguments positionally, or by name as `key=value`; validation errors
+    /// come back before the handler runs.
+    pub async fn dispatch(&self, name: &str, raw_args: &str) -> Result<String> {
+        let Some((spec, handler)) = self
+            .commands
+            .iter()
+            .find(|(spec, _)| spec.name == name)
+        else {
+            return Err(SafeClawError::NotFound(format!("unknown command /{name}")));
+        };
+        let args = parse_args(spec, raw_args)?;
+        handler.run(&args).await
+    }
+}
+
+fn parse_args(spec: &CommandSpec, raw: &str) -> Result<CommandArgs> {
+    let mut args = CommandArgs::new();
+    let mut positional = spec.args.iter();
+    for token in raw.split_whitespace() {
+        let (arg, value) = match token.split_once('=') {
+            Some((key, value)) => {
+                let arg = spec.args.iter().find(|a| a.name == key).ok_or_else(|| {
+                    invalid(spec, format!("unknown argument `{key}`"))
+                })?;
+                (arg, value)
+            }
+            None => {
+                let arg = positional
+                    .find(|a| !args.contains_key(&a.name))
+                    .ok_or_else(|| invalid(spec, format!("unexpected argument `{token}`")))?;
+                (arg, token)
+            }
+        };
+        args.insert(arg.name.clone(), coerce(spec, arg, value)?);
+    }
+    for arg in spec.args.iter().filter(|a| a.required) {
+        if !args.contains_key(&arg.name) {
+            return Err(invalid(
+                spec,
+                format!("missing required argument `{}`", arg.name),
+            ));
+        }
+    }
+    Ok(args)
+}
+
+fn coerce(spec: &CommandSpec, arg: &ArgSpec, value: &str) -> Result<serde_json::Value> {
+    if !arg.choices.is_empty() && !arg.choices.iter().any(|c| c == value) {
+        return Err(invalid(
+            spec,
+            format!(
+                "`{}` is not a valid value for `{}` (expected one of: {})",
+                value,
+                arg.name,
+                arg.choices.join(", ")
+            ),
+        ));
+    }
+    match arg.kind {
+        ArgKind::String => Ok(serde_json::Value::String(value.to_string())),
+        ArgKind::Integer => value
+            .parse::<i64>()
+            .map(serde_json::Value::from)
+            .map_err(|_| invalid(spec, format!("`{}` must be an integer", arg.name))),
+        ArgKind::Boolean => match value {
+            "true" | "yes" | "on" => Ok(serde_json::Value::Bool(true)),
+            "false" | "no" | "off" => Ok(serde_json::Value::Bool(false)),
+            _ => Err(invalid(spec, format!("`{}` must be true or false", arg.name))),
+        },
+    }
+}
+
+fn invalid(spec: &CommandSpec, detail: String) -> SafeClawError {
+    let usage: Vec<String> = spec
+        .args
+        .iter()
+        .map(|a| {
+            if a.required {
+                format!("<{}>", a.name)
+            } else {
+                format!("[{}]", a.name)
+            }
+        })
+        .collect();
+    SafeClawError::Config(format!(
+        "/{}: {detail}. Usage: /{} {}",
+        spec.name,
+        spec.name,
+        usage.join(" ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct RecordingHandler {
+        calls: Mutex<Vec<CommandArgs>>,
+    }
+
+    impl RecordingHandler {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                calls: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl CommandHandler for RecordingHandler {
+        async fn run(&self, args: &CommandArgs) -> Result<String> {
+            self.calls.lock().unwrap().push(args.clone());
+            Ok("ok".into())
+        }
+    }
+
+    fn model_spec() -> CommandSpec {
+        CommandSpec {
+            name: "model".into(),
+            description: "Switch the session model".into(),
+            args: vec![
+                ArgSpec {
+                    name: "provider".into(),
+                    description: "Provider to use".into(),
+                    kind: ArgKind::String,
+                    required: true,
+                    choices: vec!["anthropic".into(), "ollama".into()],
+                },
+                ArgSpec {
+                    name: "turns".into(),
+                    description: "Turns to keep it active".into(),
+                    kind: ArgKind::Integer,
+                    required: false,
+                    choices: Vec::new(),
+                },
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn list_commands_returns_the_declared_schema() {
+        let mut registry = CommandRegistry::new();
+        registry.register(model_spec(), RecordingHandler::new());
+        let specs = registry.list_commands();
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].name, "model");
+        assert_eq!(specs[0].args[0].choices, vec!["anthropic", "ollama"]);
+        assert!(specs[0].args[0].required);
+    }
+
+    #[tokio::test]
+    async fn valid_arguments_reach_the_handler_typed() {
+        let mut registry = CommandRegistry::new();
+        let handler = RecordingHandler::new();
+        registry.register(model_spec(), handler.clone());
+
+        registry.dispatch("model", "ollama turns=3").await.unwrap();
+        let calls = handler.calls.lock().unwrap();
+        assert_eq!(calls[0]["provider"], serde_json::json!("ollama"));
+        assert_eq!(calls[0]["turns"], serde_json::json!(3));
+    }
+
+    #[tokio::test]
+    async fn missing_required_argument_is_rejected_before_execution() {
+        let mut registry = CommandRegistry::new();
+        let handler = RecordingHandler::new();
+        registry.register(model_spec(), handler.clone());
+
+        let err = registry.dispatch("model", "").await.unwrap_err();
+        assert!(err.to_string().contains("missing required argument `provider`"));
+        assert!(err.to_string().contains("Usage: /model <provider> [turns]"));
+        assert!(handler.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalid_choice_and_type_are_rejected_with_guidance() {
+        let mut registry = CommandRegistry::new();
+        registry.register(model_spec(), RecordingHandler::new());
+
+        let err = registry.dispatch("model", "openai").await.unwrap_err();
+        assert!(err.to_string().contains("expected one of: anthropic, ollama"));
+
+        let err = registry
+            .dispatch("model", "ollama turns=soon")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("`turns` must be an integer"));
+    }
+
+    #[tokio::test]
+    async fn unknown_command_is_not_found() {
+        let registry = CommandRegistry::new();
+        let err = registry.dispatch("nope", "").await.unwrap_err();
+        assert!(matches!(err, SafeClawError::NotFound(_)));
+    }
+}
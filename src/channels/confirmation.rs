@@ -0,0 +1,297 @@
+//! Tool confirmation batching for chat channels.
+//!
+//! Sending every tool confirmation as its own message is spammy on chat
+//! platforms. Confirmations raised within a short window are batched into a
+//! single interactive message — one approve/deny button pair per tool plus an
+//! "approve all" — rendered through the channel's native components (Slack
+//! blocks, Telegram inline keyboards) by the adapter. Button responses map
+//! back to the individual `confirm_tool` request IDs.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// Batching knobs, per the channels config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmationBatchConfig {
+    pub enabled: bool,
+    /// Confirmations arriving within this window of the first are batched.
+    pub window_ms: u64,
+}
+
+impl Default for ConfirmationBatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_ms: 1_500,
+        }
+    }
+}
+
+/// One pending tool confirmation awaiting user approval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationRequest {
+    /// The `confirm_tool` request ID the response resolves.
+    pub request_id: String,
+    pub tool_name: String,
+    /// Short human-readable summary of what the tool wants to do.
+    pub description: String,
+}
+
+/// Platform-neutral interactive button; adapters translate these into Slack
+/// block actions or Telegram inline-keyboard callbacks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InteractiveButton {
+    /// Opaque value echoed back by the platform on click.
+    pub action_id: String,
+    pub label: String,
+}
+
+/// One interactive confirmation message covering a batch of requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractiveMessage {
+    pub text: String,
+    pub buttons: Vec<InteractiveButton>,
+    /// Request IDs this message covers, in display order.
+    pub request_ids: Vec<String>,
+}
+
+/// What a button click resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmationDecision {
+    pub request_id: String,
+    pub approved: bool,
+}
+
+struct PendingBatch {
+    opened_at_ms: u64,
+    requests: Vec<ConfirmationRequest>,
+}
+
+/// Collects confirmations per `channel:chat_id` and releases them as one
+/// interactive message once the window elapses. Time is passed in explicitly
+/// so the window logic is deterministic under test.
+pub struct ConfirmationBatcher {
+    config: ConfirmationBatchConfig,
+    pending: Mutex<HashMap<String, PendingBatch>>,
+}
+
+impl ConfirmationBatcher {
+    pub fn new(config: ConfirmationBatchConfig) -> Self {
+        Self {
+            config,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn batch_key(channel: &str, chat_id: &str) -> String {
+        format!("{channel}:{chat_id}")
+    }
+
+    /// Queue a confirmation. With batching disabled the request is returned
+    /// immediately as a single-entry message.
+    pub async fn push(
+        &self,
+        channel: &str,
+        chat_id: &str,
+        request: ConfirmationRequest,
+        now_ms: u64,
+    ) -> Option<InteractiveMessage> {
+        if !self.config.enabled {
+            return Some(render_batch(&[request]));
+        }
+        let mut pending = self.pending.lock().await;
+        pending
+            .entry(Self::batch_key(channel, chat_id))
+            .or_insert_with(|| PendingBatch {
+                opened_at_ms: now_ms,
+                requests: Vec::new(),
+            })
+            .requests
+            .push(request);
+        None
+    }
+
+    /// Release batches whose window has elapsed, as `(channel:chat_id,
+    /// message)` pairs ready for the adapter to send.
+    pub async fn take_ready(&self, now_ms: u64) -> Vec<(String, InteractiveMessage)> {
+        let mut pending = self.pending.lock().await;
+        let ready_keys: Vec<String> = pending
+            .iter()
+            .filter(|(_, batch)| now_ms.saturating_sub(batch.opened_at_ms) >= self.config.window_ms)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut ready = Vec::new();
+        for key in ready_keys {
+            if let Some(batch) = pending.remove(&key) {
+                ready.push((key, render_batch(&batch.requests)));
+            }
+        }
+        ready.sort_by(|(a, _), (b, _)| a.cmp(b));
+        ready
+    }
+}
+
+fn allow_action(request_id: &str) -> String {
+    format!("confirm:{request_id}:allow")
+}
+
+fn deny_action(request_id: &str) -> String {
+    format!("confirm:{request_id}:deny")
+}
+
+fn render_batch(requests: &[ConfirmationRequest]) -> InteractiveMessage {
+    let mut text = if requests.len() == 1 {
+        "The agent wants to run a tool:\n".to_string()
+    } else {
+        format!("The agent wants to run {} tools:\n", requests.len())
+    };
+    let mut buttons = Vec::new();
+    for request in requests {
+        // Tool names and descriptions come from the model — render them as
+        // inert slots (see `channels::template`).
+        text.push_str(&format!(
+            "• {} — {}\n",
+            crate::channels::template::code_slot(&request.tool_name),
+            request.description.replace('`', "ˋ"),
+        ));
+        buttons.push(InteractiveButton {
+            action_id: allow_action(&request.request_id),
+            label: format!("Allow {}", request.tool_name),
+        });
+        buttons.push(InteractiveButton {
+            action_id: deny_action(&request.request_id),
+            label: format!("Deny {}", request.tool_name),
+        });
+    }
+    if requests.len() > 1 {
+        buttons.push(InteractiveButton {
+            action_id: "confirm:all:allow".into(),
+            label: "Approve all".into(),
+        });
+        buttons.push(InteractiveButton {
+            action_id: "confirm:all:deny".into(),
+            label: "Deny all".into(),
+        });
+    }
+    InteractiveMessage {
+        text,
+        buttons,
+        request_ids: requests.iter().map(|r| r.request_id.clone()).collect(),
+    }
+}
+
+/// Map a button click back to individual `confirm_tool` decisions. The
+/// message the click arrived on supplies the covered request IDs for the
+/// "all" actions.
+pub fn map_response(message: &InteractiveMessage, action_id: &str) -> Vec<ConfirmationDecision> {
+    let Some(rest) = action_id.strip_prefix("confirm:") else {
+        return Vec::new();
+    };
+    let Some((target, verdict)) = rest.rsplit_once(':') else {
+        return Vec::new();
+    };
+    let approved = verdict == "allow";
+    if target == "all" {
+        message
+            .request_ids
+            .iter()
+            .map(|id| ConfirmationDecision {
+                request_id: id.clone(),
+                approved,
+            })
+            .collect()
+    } else if message.request_ids.iter().any(|id| id == target) {
+        vec![ConfirmationDecision {
+            request_id: target.to_string(),
+            approved,
+        }]
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: &str, tool: &str) -> ConfirmationRequest {
+        ConfirmationRequest {
+            request_id: id.into(),
+            tool_name: tool.into(),
+            description: format!("run {tool}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn two_confirmations_within_window_batch_into_one_message() {
+        let batcher = ConfirmationBatcher::new(ConfirmationBatchConfig {
+            enabled: true,
+            window_ms: 1_500,
+        });
+        assert!(batcher
+            .push("slack", "C1", request("r1", "Bash"), 0)
+            .await
+            .is_none());
+        assert!(batcher
+            .push("slack", "C1", request("r2", "Write"), 500)
+            .await
+            .is_none());
+
+        assert!(batcher.take_ready(1_000).await.is_empty());
+        let ready = batcher.take_ready(1_500).await;
+        assert_eq!(ready.len(), 1);
+        let (key, message) = &ready[0];
+        assert_eq!(key, "slack:C1");
+        assert_eq!(message.request_ids, vec!["r1", "r2"]);
+        assert!(message.text.contains("2 tools"));
+        // Per-tool allow/deny pairs plus the two "all" buttons.
+        assert_eq!(message.buttons.len(), 6);
+        // Released batches don't linger.
+        assert!(batcher.take_ready(5_000).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn chats_batch_independently() {
+        let batcher = ConfirmationBatcher::new(ConfirmationBatchConfig::default());
+        batcher.push("slack", "C1", request("r1", "Bash"), 0).await;
+        batcher
+            .push("telegram", "42", request("r2", "Write"), 0)
+            .await;
+        let ready = batcher.take_ready(2_000).await;
+        assert_eq!(ready.len(), 2);
+        assert_eq!(ready[0].0, "slack:C1");
+        assert_eq!(ready[1].0, "telegram:42");
+    }
+
+    #[test]
+    fn buttons_map_back_to_request_ids() {
+        let message = render_batch(&[request("r1", "Bash"), request("r2", "Write")]);
+        assert_eq!(
+            map_response(&message, "confirm:r2:allow"),
+            vec![ConfirmationDecision {
+                request_id: "r2".into(),
+                approved: true,
+            }]
+        );
+        assert_eq!(
+            map_response(&message, "confirm:r1:deny"),
+            vec![ConfirmationDecision {
+                request_id: "r1".into(),
+                approved: false,
+            }]
+        );
+        // Unknown request IDs resolve to nothing.
+        assert!(map_response(&message, "confirm:r9:allow").is_empty());
+    }
+
+    #[test]
+    fn approve_all_resolves_every_covered_request() {
+        let message = render_batch(&[request("r1", "Bash"), request("r2", "Write")]);
+        let decisions = map_response(&message, "confirm:all:allow");
+        assert_eq!(decisions.len(), 2);
+        assert!(decisions.iter().all(|d| d.approved));
+    }
+}
@@ -0,0 +1,208 @@
+//! Adapter conformance harness.
+//!
+//! Every new adapter rediscovered the same contract bugs: empty sender IDs,
+//! the wrong channel name in session keys, unchunked long messages, swallowed
+//! transport errors. This harness exercises any [`ChannelAdapter`] against
+//! the contract through the hooks in [`AdapterFixture`] — an adapter gets
+//! most of its behavioral coverage by filling in the fixture and calling
+//! [`run`] from one test. Kept as a regular public module so out-of-tree
+//! adapters can use it too.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::channels::adapter::{ChannelAdapter, PlatformBackend};
+use crate::channels::message::OutboundMessage;
+use crate::error::{Result, SafeClawError};
+
+/// Records every delivery; the all-green backend.
+#[derive(Default)]
+pub struct RecordingBackend {
+    pub sent: Mutex<Vec<(String, String)>>,
+}
+
+#[async_trait::async_trait]
+impl PlatformBackend for RecordingBackend {
+    async fn deliver(&self, chat_id: &str, content: &str) -> Result<()> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((chat_id.to_string(), content.to_string()));
+        Ok(())
+    }
+}
+
+/// Fails the first `failures` deliveries, then records like
+/// [`RecordingBackend`] — simulates a transport blip the adapter should
+/// absorb.
+pub struct FlakyBackend {
+    failures_left: AtomicUsize,
+    pub inner: RecordingBackend,
+}
+
+impl FlakyBackend {
+    pub fn new(failures: usize) -> Self {
+        Self {
+            failures_left: AtomicUsize::new(failures),
+            inner: RecordingBackend::default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PlatformBackend for FlakyBackend {
+    async fn deliver(&self, chat_id: &str, content: &str) -> Result<()> {
+        if self
+            .failures_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            return Err(SafeClawError::Channel("simulated transport failure".into()));
+        }
+        self.inner.deliver(chat_id, content).await
+    }
+}
+
+/// Always fails — the platform is down.
+pub struct DeadBackend;
+
+#[async_trait::async_trait]
+impl PlatformBackend for DeadBackend {
+    async fn deliver(&self, _chat_id: &str, _content: &str) -> Result<()> {
+        Err(SafeClawError::Channel("platform unreachable".into()))
+    }
+}
+
+/// Constructs the adapter under test over an injected backend.
+pub type AdapterFactory = Box<dyn Fn(Arc<dyn PlatformBackend>) -> Box<dyn ChannelAdapter>>;
+
+/// Harness hooks one adapter provides.
+pub struct AdapterFixture {
+    /// Expected channel name, as used in config and session keys.
+    pub channel: &'static str,
+    /// Construct the adapter over an injected backend.
+    pub make_adapter: AdapterFactory,
+    /// A platform update that must parse into a direct message.
+    pub valid_direct_update: serde_json::Value,
+    /// A group-chat update, for channels that have group chats.
+    pub valid_group_update: Option<serde_json::Value>,
+    /// A well-formed update the adapter must ignore without erroring.
+    pub ignorable_update: serde_json::Value,
+    /// The platform's per-message length limit the adapter chunks at.
+    pub max_message_chars: usize,
+}
+
+/// Run the full conformance suite; panics with a labeled assertion on any
+/// contract violation.
+pub async fn run(fixture: AdapterFixture) {
+    inbound_invariants(&fixture);
+    dm_flagging(&fixture);
+    ignorable_updates(&fixture);
+    long_message_chunking(&fixture).await;
+    transport_error_mapping(&fixture).await;
+    transient_failure_recovery(&fixture).await;
+}
+
+fn adapter_with(
+    fixture: &AdapterFixture,
+    backend: Arc<dyn PlatformBackend>,
+) -> Box<dyn ChannelAdapter> {
+    (fixture.make_adapter)(backend)
+}
+
+fn inbound_invariants(fixture: &AdapterFixture) {
+    let adapter = adapter_with(fixture, Arc::new(RecordingBackend::default()));
+    assert_eq!(adapter.name(), fixture.channel, "adapter name mismatch");
+    let message = adapter
+        .parse_update(&fixture.valid_direct_update)
+        .expect("valid update errored")
+        .expect("valid update produced no message");
+    assert_eq!(message.channel, fixture.channel, "wrong channel in message");
+    assert!(!message.chat_id.is_empty(), "empty chat_id");
+    assert!(!message.user_id.is_empty(), "empty user_id");
+    assert!(!message.content.is_empty(), "empty content");
+    assert!(message.timestamp >= 0, "negative timestamp");
+}
+
+fn dm_flagging(fixture: &AdapterFixture) {
+    let adapter = adapter_with(fixture, Arc::new(RecordingBackend::default()));
+    let direct = adapter
+        .parse_update(&fixture.valid_direct_update)
+        .unwrap()
+        .unwrap();
+    assert!(direct.is_direct, "direct update not flagged is_direct");
+    if let Some(group_update) = &fixture.valid_group_update {
+        let group = adapter.parse_update(group_update).unwrap().unwrap();
+        assert!(
+            !group.is_direct,
+            "group update flagged is_direct — dm_policy gating would misfire"
+        );
+    }
+}
+
+fn ignorable_updates(fixture: &AdapterFixture) {
+    let adapter = adapter_with(fixture, Arc::new(RecordingBackend::default()));
+    let parsed = adapter
+        .parse_update(&fixture.ignorable_update)
+        .expect("ignorable update must not error");
+    assert!(parsed.is_none(), "ignorable update produced a message");
+}
+
+async fn long_message_chunking(fixture: &AdapterFixture) {
+    let backend = Arc::new(RecordingBackend::default());
+    let adapter = adapter_with(fixture, backend.clone());
+    let content = "line of filler text\n".repeat(fixture.max_message_chars / 8);
+    adapter
+        .send_message(&OutboundMessage {
+            channel: fixture.channel.into(),
+            chat_id: "c1".into(),
+            content: content.clone(),
+        })
+        .await
+        .expect("long message send failed");
+    let sent = backend.sent.lock().unwrap();
+    assert!(sent.len() >= 2, "long message was not chunked");
+    for (_, chunk) in sent.iter() {
+        assert!(
+            chunk.chars().count() <= fixture.max_message_chars,
+            "chunk over the platform limit"
+        );
+    }
+    // Adapters using the numbered chunker prefix each part with
+    // "(i/total) "; strip the markers before checking nothing was lost.
+    let reassembled: String = sent
+        .iter()
+        .map(|(_, c)| crate::channels::chunking::strip_part_marker(c))
+        .collect();
+    assert_eq!(reassembled, content, "chunking lost content");
+}
+
+async fn transport_error_mapping(fixture: &AdapterFixture) {
+    let adapter = adapter_with(fixture, Arc::new(DeadBackend));
+    let err = adapter
+        .send_message(&OutboundMessage {
+            channel: fixture.channel.into(),
+            chat_id: "c1".into(),
+            content: "hello".into(),
+        })
+        .await
+        .expect_err("send against a dead platform must error");
+    assert!(
+        matches!(err, SafeClawError::Channel(_)),
+        "transport failure not mapped to SafeClawError::Channel: {err}"
+    );
+}
+
+async fn transient_failure_recovery(fixture: &AdapterFixture) {
+    let backend = Arc::new(FlakyBackend::new(1));
+    let adapter = adapter_with(fixture, backend.clone());
+    adapter
+        .send_message(&OutboundMessage {
+            channel: fixture.channel.into(),
+            chat_id: "c1".into(),
+            content: "hello".into(),
+        })
+        .await
+        .expect("one transient failure must be absorbed");
+    assert_eq!(backend.inner.sent.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,431 @@
+//! Runtime channel control — pause and resume adapters without config edits.
+//!
+//! Muting one channel (a Discord server mid spam-wave) shouldn't require a
+//! config edit and restart. Each adapter runs under a small state machine —
+//! stopped, running, paused, failed — and can be paused at runtime:
+//! `drop_inbound` keeps the platform connection up but discards inbound
+//! events, `disconnect` tells the adapter to drop the connection. Outbound
+//! messages either queue for delivery on resume or are rejected, per the
+//! pause request. Paused state persists to disk so a restart comes back
+//! paused, and paused is reported as ready in the health API — paused is not
+//! failed.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::channels::message::OutboundMessage;
+use crate::error::{Result, SafeClawError};
+
+/// How a pause treats the platform connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseMode {
+    /// Drop the platform connection entirely.
+    Disconnect,
+    /// Keep the connection up; discard inbound events.
+    DropInbound,
+}
+
+/// Adapter lifecycle state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum ChannelState {
+    Stopped,
+    Running,
+    Paused {
+        mode: PauseMode,
+        /// Whether outbound messages queue for resume (vs. being rejected).
+        queue_outbound: bool,
+    },
+    Failed {
+        reason: String,
+    },
+}
+
+/// What happened to an outbound message under the current state.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SendDisposition {
+    /// Deliver through the adapter now.
+    Deliver,
+    /// Held; will be flushed on resume.
+    Queued,
+    /// Dropped; the caller should surface an error.
+    Rejected,
+}
+
+#[derive(Default)]
+struct ChannelRuntime {
+    state: Option<ChannelState>,
+    queued_outbound: Vec<OutboundMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct PersistedState {
+    /// Channels paused at shutdown, with their pause parameters.
+    paused: HashMap<String, (PauseMode, bool)>,
+}
+
+/// Per-adapter state machine, shared by the API, the `/channel` chat command,
+/// and the health endpoints.
+pub struct ChannelControl {
+    path: Option<PathBuf>,
+    channels: Mutex<HashMap<String, ChannelRuntime>>,
+}
+
+impl ChannelControl {
+    /// In-memory only; state does not survive a restart.
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Backed by `path`; previously paused channels come back paused.
+    pub fn load(path: PathBuf) -> Self {
+        let persisted: PersistedState = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        let channels = persisted
+            .paused
+            .into_iter()
+            .map(|(name, (mode, queue_outbound))| {
+                (
+                    name,
+                    ChannelRuntime {
+                        state: Some(ChannelState::Paused {
+                            mode,
+                            queue_outbound,
+                        }),
+                        queued_outbound: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+        Self {
+            path: Some(path),
+            channels: Mutex::new(channels),
+        }
+    }
+
+    fn persist(&self, channels: &HashMap<String, ChannelRuntime>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let paused: HashMap<String, (PauseMode, bool)> = channels
+            .iter()
+            .filter_map(|(name, runtime)| match &runtime.state {
+                Some(ChannelState::Paused {
+                    mode,
+                    queue_outbound,
+                }) => Some((name.clone(), (*mode, *queue_outbound))),
+                _ => None,
+            })
+            .collect();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(&PersistedState { paused })?)?;
+        Ok(())
+    }
+
+    /// Adapter lifecycle notifications.
+    pub fn mark_started(&self, channel: &str) {
+        let mut channels = self.channels.lock().expect("channel control poisoned");
+        let runtime = channels.entry(channel.to_string()).or_default();
+        // A channel restored as paused stays paused through startup.
+        if !matches!(runtime.state, Some(ChannelState::Paused { .. })) {
+            runtime.state = Some(ChannelState::Running);
+        }
+    }
+
+    pub fn mark_stopped(&self, channel: &str) {
+        let mut channels = self.channels.lock().expect("channel control poisoned");
+        channels.entry(channel.to_string()).or_default().state = Some(ChannelState::Stopped);
+    }
+
+    pub fn mark_failed(&self, channel: &str, reason: &str) {
+        let mut channels = self.channels.lock().expect("channel control poisoned");
+        channels.entry(channel.to_string()).or_default().state = Some(ChannelState::Failed {
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Pause a running channel. Only `Running` channels can be paused.
+    pub fn pause(&self, channel: &str, mode: PauseMode, queue_outbound: bool) -> Result<()> {
+        let mut channels = self.channels.lock().expect("channel control poisoned");
+        let runtime = channels
+            .get_mut(channel)
+            .ok_or_else(|| SafeClawError::NotFound(format!("unknown channel {channel}")))?;
+        match &runtime.state {
+            Some(ChannelState::Running) => {
+                runtime.state = Some(ChannelState::Paused {
+                    mode,
+                    queue_outbound,
+                });
+            }
+            Some(ChannelState::Paused { .. }) => {
+                return Err(SafeClawError::Channel(format!(
+                    "channel {channel} is already paused"
+                )))
+            }
+            other => {
+                return Err(SafeClawError::Channel(format!(
+                    "channel {channel} cannot be paused from state {other:?}"
+                )))
+            }
+        }
+        self.persist(&channels)
+    }
+
+    /// Resume a paused channel, returning queued outbound messages in send
+    /// order for the adapter to flush.
+    pub fn resume(&self, channel: &str) -> Result<Vec<OutboundMessage>> {
+        let mut channels = self.channels.lock().expect("channel control poisoned");
+        let runtime = channels
+            .get_mut(channel)
+            .ok_or_else(|| SafeClawError::NotFound(format!("unknown channel {channel}")))?;
+        if !matches!(runtime.state, Some(ChannelState::Paused { .. })) {
+            return Err(SafeClawError::Channel(format!(
+                "channel {channel} is not paused"
+            )));
+        }
+        runtime.state = Some(ChannelState::Running);
+        let queued = std::mem::take(&mut runtime.queued_outbound);
+        self.persist(&channels)?;
+        Ok(queued)
+    }
+
+    /// Whether the adapter should process an inbound event right now.
+    pub fn accept_inbound(&self, channel: &str) -> bool {
+        let channels = self.channels.lock().expect("channel control poisoned");
+        matches!(
+            channels.get(channel).and_then(|r| r.state.as_ref()),
+            Some(ChannelState::Running)
+        )
+    }
+
+    /// Route an outbound message through the state machine.
+    pub fn send_outbound(&self, message: OutboundMessage) -> SendDisposition {
+        let mut channels = self.channels.lock().expect("channel control poisoned");
+        let Some(runtime) = channels.get_mut(&message.channel) else {
+            return SendDisposition::Rejected;
+        };
+        match &runtime.state {
+            Some(ChannelState::Running) => SendDisposition::Deliver,
+            Some(ChannelState::Paused {
+                queue_outbound: true,
+                ..
+            }) => {
+                runtime.queued_outbound.push(message);
+                SendDisposition::Queued
+            }
+            _ => SendDisposition::Rejected,
+        }
+    }
+
+    /// Current state per channel, for the status API.
+    pub fn status(&self) -> Vec<(String, ChannelState, usize)> {
+        let channels = self.channels.lock().expect("channel control poisoned");
+        let mut out: Vec<(String, ChannelState, usize)> = channels
+            .iter()
+            .filter_map(|(name, runtime)| {
+                runtime
+                    .state
+                    .clone()
+                    .map(|state| (name.clone(), state, runtime.queued_outbound.len()))
+            })
+            .collect();
+        out.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+        out
+    }
+
+    /// `/health/ready` contribution: paused counts as ready, failed does not.
+    pub fn is_ready(&self, channel: &str) -> bool {
+        let channels = self.channels.lock().expect("channel control poisoned");
+        matches!(
+            channels.get(channel).and_then(|r| r.state.as_ref()),
+            Some(ChannelState::Running | ChannelState::Paused { .. })
+        )
+    }
+}
+
+/// Spec for the `/channel` admin chat command mirroring the pause/resume API.
+pub fn channel_command_spec() -> crate::channels::commands::CommandSpec {
+    use crate::channels::commands::{ArgKind, ArgSpec, CommandSpec};
+    CommandSpec {
+        name: "channel".into(),
+        description: "Pause or resume a channel adapter".into(),
+        args: vec![
+            ArgSpec {
+                name: "action".into(),
+                description: "What to do".into(),
+                kind: ArgKind::String,
+                required: true,
+                choices: vec!["pause".into(), "resume".into()],
+            },
+            ArgSpec {
+                name: "channel".into(),
+                description: "Channel to act on".into(),
+                kind: ArgKind::String,
+                required: true,
+                choices: Vec::new(),
+            },
+            ArgSpec {
+                name: "mode".into(),
+                description: "Pause mode".into(),
+                kind: ArgKind::String,
+                required: false,
+                choices: vec!["disconnect".into(), "drop_inbound".into()],
+            },
+        ],
+    }
+}
+
+/// Handler behind [`channel_command_spec`].
+pub struct ChannelCommand {
+    pub control: std::sync::Arc<ChannelControl>,
+}
+
+#[async_trait::async_trait]
+impl crate::channels::commands::CommandHandler for ChannelCommand {
+    async fn run(
+        &self,
+        args: &crate::channels::commands::CommandArgs,
+    ) -> Result<String> {
+        let action = args["action"].as_str().unwrap_or_default();
+        let channel = args["channel"].as_str().unwrap_or_default();
+        match action {
+            "pause" => {
+                let mode = match args.get("mode").and_then(|m| m.as_str()) {
+                    Some("disconnect") => PauseMode::Disconnect,
+                    _ => PauseMode::DropInbound,
+                };
+                self.control.pause(channel, mode, true)?;
+                Ok(format!("Paused {channel}."))
+            }
+            "resume" => {
+                let flushed = self.control.resume(channel)?.len();
+                Ok(format!("Resumed {channel} ({flushed} queued message(s) flushed)."))
+            }
+            _ => Err(SafeClawError::Channel(format!("unknown action {action}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outbound(channel: &str, content: &str) -> OutboundMessage {
+        OutboundMessage {
+            channel: channel.into(),
+            chat_id: "c1".into(),
+            content: content.into(),
+        }
+    }
+
+    #[test]
+    fn pause_drops_inbound_and_resume_restores_it() {
+        let control = ChannelControl::in_memory();
+        control.mark_started("discord");
+        assert!(control.accept_inbound("discord"));
+
+        control
+            .pause("discord", PauseMode::DropInbound, true)
+            .unwrap();
+        assert!(!control.accept_inbound("discord"));
+        // Paused is still ready — paused is not failed.
+        assert!(control.is_ready("discord"));
+
+        control.resume("discord").unwrap();
+        assert!(control.accept_inbound("discord"));
+    }
+
+    #[test]
+    fn resume_flushes_queued_outbound_in_order() {
+        let control = ChannelControl::in_memory();
+        control.mark_started("discord");
+        control
+            .pause("discord", PauseMode::DropInbound, true)
+            .unwrap();
+
+        assert_eq!(
+            control.send_outbound(outbound("discord", "first")),
+            SendDisposition::Queued
+        );
+        assert_eq!(
+            control.send_outbound(outbound("discord", "second")),
+            SendDisposition::Queued
+        );
+
+        let flushed = control.resume("discord").unwrap();
+        assert_eq!(flushed.len(), 2);
+        assert_eq!(flushed[0].content, "first");
+        assert_eq!(flushed[1].content, "second");
+        assert_eq!(
+            control.send_outbound(outbound("discord", "third")),
+            SendDisposition::Deliver
+        );
+    }
+
+    #[test]
+    fn pause_without_queueing_rejects_outbound() {
+        let control = ChannelControl::in_memory();
+        control.mark_started("slack");
+        control.pause("slack", PauseMode::Disconnect, false).unwrap();
+        assert_eq!(
+            control.send_outbound(outbound("slack", "hi")),
+            SendDisposition::Rejected
+        );
+        assert!(control.resume("slack").unwrap().is_empty());
+    }
+
+    #[test]
+    fn paused_state_survives_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("channel_state.json");
+
+        let control = ChannelControl::load(path.clone());
+        control.mark_started("discord");
+        control.mark_started("slack");
+        control
+            .pause("discord", PauseMode::Disconnect, true)
+            .unwrap();
+
+        // Simulated restart: a fresh instance from the same path.
+        let control = ChannelControl::load(path);
+        control.mark_started("discord");
+        control.mark_started("slack");
+        assert!(!control.accept_inbound("discord"));
+        assert!(control.accept_inbound("slack"));
+        let status = control.status();
+        assert!(matches!(
+            status.iter().find(|(n, _, _)| n == "discord").unwrap().1,
+            ChannelState::Paused {
+                mode: PauseMode::Disconnect,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn invalid_transitions_are_rejected() {
+        let control = ChannelControl::in_memory();
+        control.mark_started("discord");
+        control.mark_failed("discord", "socket error");
+        assert!(control
+            .pause("discord", PauseMode::DropInbound, true)
+            .is_err());
+        assert!(!control.is_ready("discord"));
+        assert!(control.resume("discord").is_err());
+        assert!(control
+            .pause("missing", PauseMode::DropInbound, true)
+            .is_err());
+    }
+}
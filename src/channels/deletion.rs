@@ -0,0 +1,283 @@
+//! Policy-controlled deletion of messages from the platform after
+//! processing.
+//!
+//! Routing a sensitive message correctly doesn't remove it from Telegram's
+//! or Discord's servers. Per-chat policy can have SafeClaw delete the
+//! user's message — and optionally its own reply — through the platform's
+//! delete API once the response is delivered (or the message was blocked),
+//! after an optional delay. Platforms or permissions that don't allow
+//! deletion degrade gracefully: the failure is logged once per chat, not
+//! per message. Deletions are audited by message ID only, never content.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::audit::log::{AuditLog, AuditSeverity};
+use crate::error::Result;
+
+/// Audit category for platform-side deletions.
+pub const AUDIT_CATEGORY_DELETION: &str = "platform_deletion";
+
+/// What gets deleted after processing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteAfter {
+    #[default]
+    Off,
+    /// Delete the user's message.
+    User,
+    /// Delete the user's message and SafeClaw's reply.
+    Both,
+}
+
+/// One chat's deletion policy, part of its privacy profile.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeletionPolicy {
+    pub delete_after: DeleteAfter,
+    /// Seconds to wait after delivery before deleting.
+    pub delay_secs: u64,
+}
+
+/// Configuration under `channels.deletion`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeletionConfig {
+    /// Applied when a chat has no specific policy.
+    pub default_policy: DeletionPolicy,
+    /// `channel:chat_id` → policy overrides.
+    pub per_chat: HashMap<String, DeletionPolicy>,
+}
+
+impl DeletionConfig {
+    pub fn policy_for(&self, channel: &str, chat_id: &str) -> DeletionPolicy {
+        self.per_chat
+            .get(&format!("{channel}:{chat_id}"))
+            .copied()
+            .unwrap_or(self.default_policy)
+    }
+}
+
+/// The platform delete API, implemented by adapters that support it.
+#[async_trait]
+pub trait PlatformDeleter: Send + Sync {
+    async fn delete_message(&self, chat_id: &str, message_id: &str) -> Result<()>;
+}
+
+/// Executes deletion policies after message processing.
+pub struct MessageDeletionService {
+    config: DeletionConfig,
+    audit: Arc<AuditLog>,
+    /// Chats whose deletion failure has already been logged.
+    warned: Mutex<HashSet<String>>,
+}
+
+impl MessageDeletionService {
+    pub fn new(config: DeletionConfig, audit: Arc<AuditLog>) -> Self {
+        Self {
+            config,
+            audit,
+            warned: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Apply the chat's policy once processing is complete. `reply_id` is
+    /// SafeClaw's own delivered reply, when there is one.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn after_processing(
+        &self,
+        deleter: &dyn PlatformDeleter,
+        channel: &str,
+        chat_id: &str,
+        session_id: &str,
+        user_message_id: &str,
+        reply_id: Option<&str>,
+        now: i64,
+    ) {
+        let policy = self.config.policy_for(channel, chat_id);
+        if policy.delete_after == DeleteAfter::Off {
+            return;
+        }
+        if policy.delay_secs > 0 {
+            tokio::time::sleep(Duration::from_secs(policy.delay_secs)).await;
+        }
+
+        let mut targets = vec![("user message", user_message_id)];
+        if policy.delete_after == DeleteAfter::Both {
+            if let Some(reply_id) = reply_id {
+                targets.push(("own reply", reply_id));
+            }
+        }
+
+        let mut deleted = Vec::new();
+        for (label, message_id) in targets {
+            match deleter.delete_message(chat_id, message_id).await {
+                Ok(()) => deleted.push(label),
+                Err(e) => self.warn_once(channel, chat_id, &e.to_string()),
+            }
+        }
+        if !deleted.is_empty() {
+            // IDs and kinds only — the whole point is that the content is
+            // gone.
+            self.audit.record(
+                "deletion",
+                session_id,
+                AuditSeverity::Info,
+                AUDIT_CATEGORY_DELETION,
+                &format!("deleted from platform: {}", deleted.join(", ")),
+                now,
+            );
+        }
+    }
+
+    fn warn_once(&self, channel: &str, chat_id: &str, error: &str) {
+        let key = format!("{channel}:{chat_id}");
+        if self.warned.lock().expect("deletion service poisoned").insert(key) {
+            tracing::warn!(
+                channel,
+                chat_id,
+                error,
+                "platform deletion unavailable for this chat; further \
+                 failures will not be logged"
+            );
+        }
+    }
+
+    /// Chats currently muted for deletion-failure logging, for tests and
+    /// diagnostics.
+    pub fn warned_chat_count(&self) -> usize {
+        self.warned.lock().expect("deletion service poisoned").len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::log::AuditIngestionConfig;
+    use crate::error::SafeClawError;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[derive(Default)]
+    struct MockDeleter {
+        calls: Mutex<Vec<(String, String)>>,
+        deny: bool,
+    }
+
+    #[async_trait]
+    impl PlatformDeleter for MockDeleter {
+        async fn delete_message(&self, chat_id: &str, message_id: &str) -> Result<()> {
+            if self.deny {
+                return Err(SafeClawError::Unauthorized(
+                    "missing delete permission".into(),
+                ));
+            }
+            self.calls
+                .lock()
+                .unwrap()
+                .push((chat_id.to_string(), message_id.to_string()));
+            Ok(())
+        }
+    }
+
+    fn service(delete_after: DeleteAfter) -> MessageDeletionService {
+        let mut config = DeletionConfig::default();
+        config.per_chat.insert(
+            "telegram:42".into(),
+            DeletionPolicy {
+                delete_after,
+                delay_secs: 0,
+            },
+        );
+        MessageDeletionService::new(config, Arc::new(AuditLog::new(AuditIngestionConfig::default())))
+    }
+
+    #[tokio::test]
+    async fn user_policy_deletes_only_the_user_message() {
+        let deleter = MockDeleter::default();
+        let service = service(DeleteAfter::User);
+        service
+            .after_processing(&deleter, "telegram", "42", "s1", "msg-7", Some("msg-8"), NOW)
+            .await;
+        assert_eq!(
+            *deleter.calls.lock().unwrap(),
+            [("42".to_string(), "msg-7".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn both_policy_deletes_message_and_reply_and_audits_without_content() {
+        let deleter = MockDeleter::default();
+        let audit = Arc::new(AuditLog::new(AuditIngestionConfig::default()));
+        let mut config = DeletionConfig::default();
+        config.per_chat.insert(
+            "telegram:42".into(),
+            DeletionPolicy {
+                delete_after: DeleteAfter::Both,
+                delay_secs: 0,
+            },
+        );
+        let service = MessageDeletionService::new(config, audit.clone());
+        service
+            .after_processing(&deleter, "telegram", "42", "s1", "msg-7", Some("msg-8"), NOW)
+            .await;
+
+        assert_eq!(deleter.calls.lock().unwrap().len(), 2);
+        let events = audit.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].category, AUDIT_CATEGORY_DELETION);
+        assert!(!events[0].message.contains("msg-7"), "no IDs needed either");
+    }
+
+    #[tokio::test]
+    async fn off_policy_makes_no_platform_calls() {
+        let deleter = MockDeleter::default();
+        let service = service(DeleteAfter::Off);
+        service
+            .after_processing(&deleter, "telegram", "42", "s1", "msg-7", None, NOW)
+            .await;
+        assert!(deleter.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn permission_denial_is_logged_once_per_chat_and_never_panics() {
+        let deleter = MockDeleter {
+            deny: true,
+            ..Default::default()
+        };
+        let service = service(DeleteAfter::User);
+        for _ in 0..5 {
+            service
+                .after_processing(&deleter, "telegram", "42", "s1", "msg-7", None, NOW)
+                .await;
+        }
+        assert_eq!(service.warned_chat_count(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn the_configured_delay_is_honored_before_deletion() {
+        let deleter = MockDeleter::default();
+        let mut config = DeletionConfig::default();
+        config.per_chat.insert(
+            "telegram:42".into(),
+            DeletionPolicy {
+                delete_after: DeleteAfter::User,
+                delay_secs: 30,
+            },
+        );
+        let service = MessageDeletionService::new(
+            config,
+            Arc::new(AuditLog::new(AuditIngestionConfig::default())),
+        );
+        let started = tokio::time::Instant::now();
+        service
+            .after_processing(&deleter, "telegram", "42", "s1", "msg-7", None, NOW)
+            .await;
+        assert!(started.elapsed() >= Duration::from_secs(30));
+        assert_eq!(deleter.calls.lock().unwrap().len(), 1);
+    }
+}
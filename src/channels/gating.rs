@@ -0,0 +1,142 @@
+//! Wake-word / command-prefix gating for noisy channels.
+//!
+//! In shared group chats the bot shouldn't respond to every message. Each
+//! channel can require a mention or a command prefix before any session or
+//! LLM involvement; direct messages always pass. Runs first in the inbound
+//! pipeline so gated messages cost nothing.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::message::InboundMessage;
+
+/// Per-channel gate rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GateRule {
+    /// Respond when the bot is mentioned.
+    pub allow_mention: bool,
+    /// Respond when the message starts with one of these prefixes; the
+    /// prefix is stripped before processing.
+    pub prefixes: Vec<String>,
+}
+
+/// Configuration under `channels.gating`. Channels without a rule are
+/// ungated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WakeGateConfig {
+    #[serde(default)]
+    pub per_channel: HashMap<String, GateRule>,
+}
+
+/// Gate decision for an inbound message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateDecision {
+    /// Process the message; `content` has any matched prefix stripped.
+    Process { content: String },
+    /// Silently ignore — no session, no LLM, no reply.
+    Ignore,
+}
+
+/// Apply the wake gate. DMs always process; group messages must satisfy the
+/// channel's rule when one is configured.
+pub fn gate_message(message: &InboundMessage, config: &WakeGateConfig) -> GateDecision {
+    if message.is_direct {
+        return GateDecision::Process {
+            content: message.content.clone(),
+        };
+    }
+    let Some(rule) = config.per_channel.get(&message.channel) else {
+        return GateDecision::Process {
+            content: message.content.clone(),
+        };
+    };
+
+    if rule.allow_mention && message.mentions_bot {
+        return GateDecision::Process {
+            content: message.content.clone(),
+        };
+    }
+    for prefix in &rule.prefixes {
+        if let Some(rest) = message.content.strip_prefix(prefix.as_str()) {
+            return GateDecision::Process {
+                content: rest.trim_start().to_string(),
+            };
+        }
+    }
+    GateDecision::Ignore
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> WakeGateConfig {
+        let mut per_channel = HashMap::new();
+        per_channel.insert(
+            "telegram".to_string(),
+            GateRule {
+                allow_mention: true,
+                prefixes: vec!["!ask".to_string()],
+            },
+        );
+        WakeGateConfig { per_channel }
+    }
+
+    fn group_message(content: &str, mentions_bot: bool) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".into(),
+            chat_id: "g1".into(),
+            user_id: "u1".into(),
+            content: content.into(),
+            is_direct: false,
+            mentions_bot,
+            timestamp: 0,
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn group_message_without_prefix_or_mention_is_ignored() {
+        let decision = gate_message(&group_message("what's for lunch?", false), &config());
+        assert_eq!(decision, GateDecision::Ignore);
+    }
+
+    #[test]
+    fn prefixed_group_message_is_processed_with_prefix_stripped() {
+        let decision = gate_message(&group_message("!ask what's the weather?", false), &config());
+        assert_eq!(
+            decision,
+            GateDecision::Process {
+                content: "what's the weather?".into()
+            }
+        );
+    }
+
+    #[test]
+    fn mention_satisfies_the_gate() {
+        let decision = gate_message(&group_message("hey, can you help?", true), &config());
+        assert!(matches!(decision, GateDecision::Process { .. }));
+    }
+
+    #[test]
+    fn direct_messages_always_process() {
+        let mut message = group_message("no prefix here", false);
+        message.is_direct = true;
+        assert!(matches!(
+            gate_message(&message, &config()),
+            GateDecision::Process { .. }
+        ));
+    }
+
+    #[test]
+    fn channels_without_a_rule_are_ungated() {
+        let mut message = group_message("hello", false);
+        message.channel = "slack".into();
+        assert!(matches!(
+            gate_message(&message, &config()),
+            GateDecision::Process { .. }
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+//! Channel REST handlers — runtime pause/resume and status.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::ErrorResponse;
+use crate::channels::control::{ChannelControl, ChannelState, PauseMode};
+use crate::error::SafeClawError;
+
+/// Routes mounted under `/api/channels`, behind `api::admin_protected`.
+pub fn channel_control_routes(control: Arc<ChannelControl>) -> Router {
+    Router::new()
+        .route("/status", get(channel_status))
+        .route("/:channel/pause", post(pause_channel))
+        .route("/:channel/resume", post(resume_channel))
+        .with_state(control)
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PauseBody {
+    /// `"disconnect"` or `"drop_inbound"`; defaults to keeping the
+    /// connection up.
+    pub mode: Option<PauseMode>,
+    /// Queue outbound messages for resume instead of rejecting them.
+    pub queue_outbound: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelStatusEntry {
+    pub channel: String,
+    #[serde(flatten)]
+    pub state: ChannelState,
+    pub queued_outbound: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeResponse {
+    pub channel: String,
+    pub flushed_outbound: usize,
+}
+
+fn control_error(e: SafeClawError) -> (StatusCode, Json<ErrorResponse>) {
+    match e {
+        SafeClawError::NotFound(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("not_found", e.to_string())),
+        ),
+        _ => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new("invalid_state", e.to_string())),
+        ),
+    }
+}
+
+/// `POST /api/channels/:channel/pause` — stop inbound processing.
+#[utoipa::path(
+    post,
+    path = "/api/channels/{channel}/pause",
+    params(("channel" = String, Path, description = "Channel name")),
+    request_body = PauseBody,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorResponse),
+        (status = 409, body = ErrorResponse),
+    ),
+    tag = "channels"
+)]
+pub(crate) async fn pause_channel(
+    State(control): State<Arc<ChannelControl>>,
+    Path(channel): Path<String>,
+    body: Option<Json<PauseBody>>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+    control
+        .pause(
+            &channel,
+            body.mode.unwrap_or(PauseMode::DropInbound),
+            body.queue_outbound.unwrap_or(true),
+        )
+        .map(|()| StatusCode::NO_CONTENT)
+        .map_err(control_error)
+}
+
+/// `POST /api/channels/:channel/resume` — resume and flush queued outbound.
+#[utoipa::path(
+    post,
+    path = "/api/channels/{channel}/resume",
+    params(("channel" = String, Path, description = "Channel name")),
+    responses(
+        (status = 200, body = ResumeResponse),
+        (status = 404, body = ErrorResponse),
+        (status = 409, body = ErrorResponse),
+    ),
+    tag = "channels"
+)]
+pub(crate) async fn resume_channel(
+    State(control): State<Arc<ChannelControl>>,
+    Path(channel): Path<String>,
+) -> Result<Json<ResumeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let flushed = control.resume(&channel).map_err(control_error)?;
+    // The adapter drains the flushed queue; the API reports the count.
+    Ok(Json(ResumeResponse {
+        channel,
+        flushed_outbound: flushed.len(),
+    }))
+}
+
+/// `GET /api/channels/status` — state machine snapshot per channel.
+#[utoipa::path(
+    get,
+    path = "/api/channels/status",
+    responses((status = 200, body = [ChannelStatusEntry])),
+    tag = "channels"
+)]
+pub(crate) async fn channel_status(
+    State(control): State<Arc<ChannelControl>>,
+) -> Json<Vec<ChannelStatusEntry>> {
+    Json(
+        control
+            .status()
+            .into_iter()
+            .map(|(channel, state, queued_outbound)| ChannelStatusEntry {
+                channel,
+                state,
+                queued_outbound,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn app() -> (Router, Arc<ChannelControl>) {
+        let control = Arc::new(ChannelControl::in_memory());
+        control.mark_started("discord");
+        (channel_control_routes(control.clone()), control)
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_round_trip_through_the_api() {
+        let (app, control) = app();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::post("/discord/pause")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"mode":"disconnect"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(!control.accept_inbound("discord"));
+
+        let response = app
+            .oneshot(Request::post("/discord/resume").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(control.accept_inbound("discord"));
+    }
+
+    #[tokio::test]
+    async fn unknown_channel_is_404_and_double_pause_conflicts() {
+        let (app, _control) = app();
+        let response = app
+            .clone()
+            .oneshot(Request::post("/matrix/pause").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        for expected in [StatusCode::NO_CONTENT, StatusCode::CONFLICT] {
+            let response = app
+                .clone()
+                .oneshot(Request::post("/discord/pause").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), expected);
+        }
+    }
+}
@@ -0,0 +1,481 @@
+//! Central markdown rendering layer for outbound messages.
+//!
+//! Agent output is markdown, but every platform speaks its own dialect:
+//! Telegram wants MarkdownV2 with heavy escaping, Slack wants mrkdwn
+//! (single-asterisk bold, no headings), Discord is close to GitHub-flavored,
+//! and the plain-text fallback wants no markup at all. Outbound content is
+//! parsed once (pulldown-cmark) and rendered by the dialect the adapter
+//! declares — adapters no longer hand-roll formatting. Tables are converted
+//! to code blocks on dialects that can't render them; escaping never touches
+//! the inside of code spans.
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+
+/// Formatting dialect an adapter declares for its platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dialect {
+    TelegramMarkdownv2,
+    SlackMrkdwn,
+    Discord,
+    #[default]
+    Plain,
+}
+
+impl Dialect {
+    fn bold(&self) -> (&'static str, &'static str) {
+        match self {
+            Dialect::TelegramMarkdownv2 | Dialect::SlackMrkdwn => ("*", "*"),
+            Dialect::Discord => ("**", "**"),
+            Dialect::Plain => ("", ""),
+        }
+    }
+
+    fn italic(&self) -> (&'static str, &'static str) {
+        match self {
+            Dialect::TelegramMarkdownv2 | Dialect::SlackMrkdwn => ("_", "_"),
+            Dialect::Discord => ("*", "*"),
+            Dialect::Plain => ("", ""),
+        }
+    }
+
+    fn strikethrough(&self) -> (&'static str, &'static str) {
+        match self {
+            Dialect::TelegramMarkdownv2 | Dialect::SlackMrkdwn => ("~", "~"),
+            Dialect::Discord => ("~~", "~~"),
+            Dialect::Plain => ("", ""),
+        }
+    }
+
+    /// Escape plain text (never code-span contents) for the dialect. Also
+    /// the escape used by `channels::template` for literal slots.
+    pub fn escape_text(&self, text: &str) -> String {
+        match self {
+            Dialect::TelegramMarkdownv2 => {
+                let mut out = String::with_capacity(text.len());
+                for c in text.chars() {
+                    if "_*[]()~`>#+-=|{}.!\\".contains(c) {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out
+            }
+            Dialect::SlackMrkdwn => text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+            Dialect::Discord => {
+                let mut out = String::with_capacity(text.len());
+                for c in text.chars() {
+                    if "_*~`[]\\".contains(c) {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out
+            }
+            Dialect::Plain => text.to_string(),
+        }
+    }
+
+    fn supports_headings(&self) -> bool {
+        matches!(self, Dialect::Discord)
+    }
+}
+
+struct Renderer {
+    dialect: Dialect,
+    out: String,
+    /// `Some(counter)` for ordered lists, `None` for bullets.
+    list_stack: Vec<Option<u64>>,
+    /// Buffered table state: rows of cell text, while inside a table.
+    table: Option<Vec<Vec<String>>>,
+    in_table_cell: bool,
+    link_url: Option<String>,
+}
+
+impl Renderer {
+    fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            out: String::new(),
+            list_stack: Vec::new(),
+            table: None,
+            in_table_cell: false,
+            link_url: None,
+        }
+    }
+
+    fn push(&mut self, s: &str) {
+        if self.in_table_cell {
+            if let Some(rows) = &mut self.table {
+                if let Some(row) = rows.last_mut() {
+                    if let Some(cell) = row.last_mut() {
+                        cell.push_str(s);
+                    }
+                }
+            }
+        } else {
+            self.out.push_str(s);
+        }
+    }
+
+    fn blank_line(&mut self) {
+        while !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    /// Render a buffered table as an aligned code block — none of the chat
+    /// dialects renders markdown tables natively.
+    fn flush_table(&mut self, rows: Vec<Vec<String>>) {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut widths = vec![0usize; columns];
+        for row in &rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+        let mut block = String::new();
+        for row in &rows {
+            let line: Vec<String> = row
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| format!("{cell:<width$}", width = widths[i]))
+                .collect();
+            block.push_str(line.join("  ").trim_end());
+            block.push('\n');
+        }
+        self.blank_line();
+        if self.dialect == Dialect::Plain {
+            self.out.push_str(&block);
+        } else {
+            self.out.push_str("```\n");
+            self.out.push_str(&block);
+            self.out.push_str("```\n");
+        }
+    }
+
+    fn render_link(&mut self, url: &str, text: &str) {
+        match self.dialect {
+            Dialect::TelegramMarkdownv2 => {
+                // Text was already escaped as it streamed in; the URL only
+                // needs `)` and `\` escaped — underscores stay literal.
+                let url = url.replace('\\', "\\\\").replace(')', "\\)");
+                self.push(&format!("[{text}]({url})"));
+            }
+            Dialect::SlackMrkdwn => self.push(&format!("<{url}|{text}>")),
+            Dialect::Discord => self.push(&format!("[{text}]({url})")),
+            Dialect::Plain => {
+                if text == url {
+                    self.push(url);
+                } else {
+                    self.push(&format!("{text} ({url})"));
+                }
+            }
+        }
+    }
+
+    fn render(mut self, markdown: &str) -> String {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        let mut link_text = String::new();
+        let mut in_link = false;
+
+        for event in Parser::new_ext(markdown, options) {
+            match event {
+                Event::Start(Tag::Paragraph) => self.blank_line(),
+                Event::End(TagEnd::Paragraph) => self.push("\n"),
+                Event::Start(Tag::Heading { .. }) => {
+                    self.blank_line();
+                    if self.dialect.supports_headings() {
+                        self.push("## ");
+                    } else {
+                        self.push(self.dialect.bold().0);
+                    }
+                }
+                Event::End(TagEnd::Heading(_)) => {
+                    if !self.dialect.supports_headings() {
+                        self.push(self.dialect.bold().1);
+                    }
+                    self.push("\n");
+                }
+                Event::Start(Tag::Strong) => self.push(self.dialect.bold().0),
+                Event::End(TagEnd::Strong) => self.push(self.dialect.bold().1),
+                Event::Start(Tag::Emphasis) => self.push(self.dialect.italic().0),
+                Event::End(TagEnd::Emphasis) => self.push(self.dialect.italic().1),
+                Event::Start(Tag::Strikethrough) => self.push(self.dialect.strikethrough().0),
+                Event::End(TagEnd::Strikethrough) => self.push(self.dialect.strikethrough().1),
+                Event::Start(Tag::List(start)) => {
+                    if self.list_stack.is_empty() {
+                        self.blank_line();
+                    }
+                    self.list_stack.push(start);
+                }
+                Event::End(TagEnd::List(_)) => {
+                    self.list_stack.pop();
+                    if self.list_stack.is_empty() {
+                        self.push("\n");
+                    }
+                }
+                Event::Start(Tag::Item) => {
+                    // A nested list starts before its parent item ends;
+                    // break the line so the sub-item isn't glued on.
+                    if !self.out.is_empty() && !self.out.ends_with('\n') {
+                        self.push("\n");
+                    }
+                    let depth = self.list_stack.len().saturating_sub(1);
+                    let indent = "  ".repeat(depth);
+                    match self.list_stack.last_mut() {
+                        Some(Some(counter)) => {
+                            let marker = format!("{indent}{counter}. ");
+                            *counter += 1;
+                            self.push(&marker);
+                        }
+                        _ => self.push(&format!("{indent}• ")),
+                    }
+                }
+                Event::End(TagEnd::Item) if !self.out.ends_with('\n') => self.push("\n"),
+                Event::Start(Tag::CodeBlock(kind)) => {
+                    self.blank_line();
+                    if self.dialect == Dialect::Plain {
+                        continue;
+                    }
+                    let lang = match &kind {
+                        CodeBlockKind::Fenced(lang) => lang.to_string(),
+                        CodeBlockKind::Indented => String::new(),
+                    };
+                    self.push(&format!("```{lang}\n"));
+                }
+                Event::End(TagEnd::CodeBlock) if self.dialect != Dialect::Plain => {
+                    self.push("```\n")
+                }
+                Event::Start(Tag::Table(_)) => self.table = Some(Vec::new()),
+                Event::End(TagEnd::Table) => {
+                    if let Some(rows) = self.table.take() {
+                        self.flush_table(rows);
+                    }
+                }
+                Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => {
+                    if let Some(rows) = &mut self.table {
+                        rows.push(Vec::new());
+                    }
+                }
+                Event::End(TagEnd::TableHead) | Event::End(TagEnd::TableRow) => {}
+                Event::Start(Tag::TableCell) => {
+                    if let Some(rows) = &mut self.table {
+                        if let Some(row) = rows.last_mut() {
+                            row.push(String::new());
+                        }
+                    }
+                    self.in_table_cell = true;
+                }
+                Event::End(TagEnd::TableCell) => self.in_table_cell = false,
+                Event::Start(Tag::Link { dest_url, .. }) => {
+                    in_link = true;
+                    link_text.clear();
+                    self.link_url = Some(dest_url.to_string());
+                }
+                Event::End(TagEnd::Link) => {
+                    in_link = false;
+                    let url = self.link_url.take().unwrap_or_default();
+                    let text = std::mem::take(&mut link_text);
+                    self.render_link(&url, &text);
+                }
+                Event::Text(text) => {
+                    let escaped = if self.table.is_some() && self.in_table_cell {
+                        text.to_string()
+                    } else {
+                        self.dialect.escape_text(&text)
+                    };
+                    if in_link {
+                        link_text.push_str(&escaped);
+                    } else {
+                        self.push(&escaped);
+                    }
+                }
+                Event::Code(code) => {
+                    // Code-span contents are never dialect-escaped — a `*`
+                    // inside backticks must stay a literal `*`.
+                    let rendered = if self.dialect == Dialect::Plain {
+                        code.to_string()
+                    } else {
+                        format!("`{code}`")
+                    };
+                    if in_link {
+                        link_text.push_str(&rendered);
+                    } else {
+                        self.push(&rendered);
+                    }
+                }
+                Event::Html(html) | Event::InlineHtml(html) => {
+                    // Raw HTML in agent output is user content, not markup
+                    // any dialect honors — escape it and keep it rather
+                    // than silently dropping it.
+                    let escaped = self.dialect.escape_text(&html);
+                    if in_link {
+                        link_text.push_str(&escaped);
+                    } else {
+                        self.push(&escaped);
+                    }
+                }
+                Event::SoftBreak => self.push("\n"),
+                Event::HardBreak => self.push("\n"),
+                Event::Rule => {
+                    self.blank_line();
+                    self.push("———\n");
+                }
+                _ => {}
+            }
+        }
+        self.out.trim_end().to_string()
+    }
+}
+
+/// Render outbound markdown for the adapter's declared dialect.
+pub fn render(markdown: &str, dialect: Dialect) -> String {
+    Renderer::new(dialect).render(markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Representative document covering the edge cases adapters used to
+    /// break on: `*` inside code spans, underscores in URLs, tables, nested
+    /// lists, headings.
+    const DOCUMENT: &str = "\
+# Deploy status
+
+The job finished **successfully** in *4 minutes* using `cargo build --all-*`.
+
+- checks
+  - lint passed
+  - 2 warnings
+- artifacts uploaded
+
+| target | status |
+| ------ | ------ |
+| x86_64 | ok |
+
+See [the run](https://ci.example.com/runs/a_b_c) for logs.
+";
+
+    const TELEGRAM_GOLDEN: &str = "\
+*Deploy status*
+
+The job finished *successfully* in _4 minutes_ using `cargo build --all-*`\\.
+
+• checks
+  • lint passed
+  • 2 warnings
+• artifacts uploaded
+
+```
+target  status
+x86_64  ok
+```
+
+See [the run](https://ci.example.com/runs/a_b_c) for logs\\.";
+
+    const SLACK_GOLDEN: &str = "\
+*Deploy status*
+
+The job finished *successfully* in _4 minutes_ using `cargo build --all-*`.
+
+• checks
+  • lint passed
+  • 2 warnings
+• artifacts uploaded
+
+```
+target  status
+x86_64  ok
+```
+
+See <https://ci.example.com/runs/a_b_c|the run> for logs.";
+
+    const DISCORD_GOLDEN: &str = "\
+## Deploy status
+
+The job finished **successfully** in *4 minutes* using `cargo build --all-*`.
+
+• checks
+  • lint passed
+  • 2 warnings
+• artifacts uploaded
+
+```
+target  status
+x86_64  ok
+```
+
+See [the run](https://ci.example.com/runs/a_b_c) for logs.";
+
+    const PLAIN_GOLDEN: &str = "\
+Deploy status
+
+The job finished successfully in 4 minutes using cargo build --all-*.
+
+• checks
+  • lint passed
+  • 2 warnings
+• artifacts uploaded
+
+target  status
+x86_64  ok
+
+See the run (https://ci.example.com/runs/a_b_c) for logs.";
+
+    #[test]
+    fn telegram_markdownv2_golden() {
+        assert_eq!(render(DOCUMENT, Dialect::TelegramMarkdownv2), TELEGRAM_GOLDEN);
+    }
+
+    #[test]
+    fn slack_mrkdwn_golden() {
+        assert_eq!(render(DOCUMENT, Dialect::SlackMrkdwn), SLACK_GOLDEN);
+    }
+
+    #[test]
+    fn discord_golden() {
+        assert_eq!(render(DOCUMENT, Dialect::Discord), DISCORD_GOLDEN);
+    }
+
+    #[test]
+    fn plain_text_golden() {
+        assert_eq!(render(DOCUMENT, Dialect::Plain), PLAIN_GOLDEN);
+    }
+
+    #[test]
+    fn asterisks_inside_code_spans_stay_literal() {
+        let rendered = render("run `rm -rf *` carefully", Dialect::TelegramMarkdownv2);
+        assert!(rendered.contains("`rm -rf *`"));
+    }
+
+    #[test]
+    fn underscores_in_urls_are_not_escaped() {
+        let rendered = render(
+            "[doc](https://example.com/a_b_c)",
+            Dialect::TelegramMarkdownv2,
+        );
+        assert!(rendered.contains("(https://example.com/a_b_c)"));
+    }
+
+    #[test]
+    fn slack_escapes_angle_brackets_and_ampersands() {
+        assert_eq!(
+            render("use <tags> & care", Dialect::SlackMrkdwn),
+            "use &lt;tags&gt; &amp; care"
+        );
+    }
+
+    #[test]
+    fn ordered_lists_keep_numbering() {
+        let rendered = render("1. first\n2. second", Dialect::SlackMrkdwn);
+        assert_eq!(rendered, "1. first\n2. second");
+    }
+}
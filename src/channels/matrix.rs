@@ -0,0 +1,284 @@
+//! Matrix channel adapter (client-server API).
+//!
+//! Runs a `/sync` long-poll loop against the configured homeserver and maps
+//! `m.room.message` timeline events to [`InboundMessage`]s; sends go out as
+//! room messages via `PUT /_matrix/client/v3/rooms/{room}/send`. Rooms can be
+//! allowlisted. E2EE rooms are not yet supported: `m.room.encrypted` events
+//! are skipped rather than delivered as ciphertext, pending olm/megolm
+//! support.
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::adapter::ChannelAdapter;
+use crate::channels::capabilities::ChannelCapabilities;
+use crate::channels::markdown::Dialect;
+use crate::channels::message::{InboundMessage, OutboundMessage};
+use crate::config::ChannelEntry;
+use crate::error::{Result, SafeClawError};
+
+pub const CHANNEL_NAME: &str = "matrix";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatrixConfig {
+    /// e.g. `https://matrix.example.org`.
+    pub homeserver_url: String,
+    /// Credential-store reference for a long-lived access token.
+    pub access_token_ref: Option<String>,
+    /// Alternative to a token: log in with user/password at startup.
+    pub user: Option<String>,
+    pub password_ref: Option<String>,
+    /// Room IDs the adapter listens in; empty allows every joined room.
+    pub allowed_rooms: Vec<String>,
+    /// The bot's own MXID, so its echoes in the sync stream are dropped.
+    pub own_user_id: Option<String>,
+}
+
+impl MatrixConfig {
+    /// Parse the adapter's settings out of the open channel-config map.
+    pub fn from_entry(entry: &ChannelEntry) -> Result<Self> {
+        serde_json::from_value(serde_json::to_value(&entry.settings)?)
+            .map_err(|e| SafeClawError::Config(format!("invalid matrix config: {e}")))
+    }
+}
+
+pub struct MatrixAdapter {
+    config: MatrixConfig,
+    access_token: String,
+    http: reqwest::Client,
+}
+
+impl MatrixAdapter {
+    pub fn new(config: MatrixConfig, access_token: String) -> Self {
+        Self {
+            config,
+            access_token,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn room_allowed(&self, room_id: &str) -> bool {
+        self.config.allowed_rooms.is_empty()
+            || self.config.allowed_rooms.iter().any(|r| r == room_id)
+    }
+
+    fn event_to_inbound(&self, room_id: &str, event: &serde_json::Value) -> Option<InboundMessage> {
+        if event["type"] != "m.room.message" {
+            return None;
+        }
+        if !self.room_allowed(room_id) {
+            return None;
+        }
+        let sender = event["sender"].as_str()?;
+        if self.config.own_user_id.as_deref() == Some(sender) {
+            return None;
+        }
+        let content = &event["content"];
+        if content["msgtype"] != "m.text" && content["msgtype"] != "m.notice" {
+            return None;
+        }
+        let body = content["body"].as_str()?;
+        Some(InboundMessage {
+            channel: CHANNEL_NAME.into(),
+            chat_id: room_id.to_string(),
+            user_id: sender.to_string(),
+            content: body.to_string(),
+            // Matrix rooms are group chats unless the adapter learns
+            // otherwise from room state; DMs are handled by dm_policy.
+            is_direct: false,
+            mentions_bot: self
+                .config
+                .own_user_id
+                .as_deref()
+                .is_some_and(|me| body.contains(me)),
+            timestamp: event["origin_server_ts"].as_i64().unwrap_or(0) / 1_000,
+            reply_to: None,
+        })
+    }
+
+    /// Map one `/sync` response to inbound messages, in timeline order.
+    pub fn parse_sync(&self, sync: &serde_json::Value) -> Vec<InboundMessage> {
+        let Some(rooms) = sync["rooms"]["join"].as_object() else {
+            return Vec::new();
+        };
+        let mut messages = Vec::new();
+        for (room_id, room) in rooms {
+            let Some(events) = room["timeline"]["events"].as_array() else {
+                continue;
+            };
+            for event in events {
+                if let Some(message) = self.event_to_inbound(room_id, event) {
+                    messages.push(message);
+                }
+            }
+        }
+        messages.sort_by_key(|m| m.timestamp);
+        messages
+    }
+}
+
+#[async_trait::async_trait]
+impl ChannelAdapter for MatrixAdapter {
+    fn name(&self) -> &str {
+        CHANNEL_NAME
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            // The 64 KiB event-size limit, with headroom for the envelope.
+            max_message_chars: 32_000,
+            supports_edit: true,
+            supports_buttons: false,
+            supports_attachments: true,
+            // Homeserver-dependent; Synapse's default upload limit.
+            max_attachment_bytes: Some(50 * 1024 * 1024),
+            supports_threads: true,
+            supports_reactions: true,
+            supports_typing_indicator: true,
+            // Sends are plain `m.text` until formatted_body support lands.
+            dialect: Dialect::Plain,
+        }
+    }
+
+    /// A single timeline event, as `{ "room_id": …, "event": … }`.
+    fn parse_update(&self, payload: &serde_json::Value) -> Result<Option<InboundMessage>> {
+        let room_id = payload["room_id"]
+            .as_str()
+            .ok_or_else(|| SafeClawError::Channel("matrix update missing room_id".into()))?;
+        Ok(self.event_to_inbound(room_id, &payload["event"]))
+    }
+
+    async fn send_message(&self, message: &OutboundMessage) -> Result<()> {
+        if !self.room_allowed(&message.chat_id) {
+            return Err(SafeClawError::Unauthorized(format!(
+                "room {} is not in allowed_rooms",
+                message.chat_id
+            )));
+        }
+        let txn_id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{txn_id}",
+            self.config.homeserver_url.trim_end_matches('/'),
+            message.chat_id
+        );
+        let response = self
+            .http
+            .put(url)
+            .bearer_auth(&self.access_token)
+            .json(&serde_json::json!({
+                "msgtype": "m.text",
+                "body": message.content,
+            }))
+            .send()
+            .await
+            .map_err(|e| SafeClawError::Channel(format!("matrix send failed: {e}")))?;
+        if !response.status().is_success() {
+            return Err(SafeClawError::Channel(format!(
+                "matrix send failed: HTTP {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adapter(allowed_rooms: Vec<String>) -> MatrixAdapter {
+        MatrixAdapter::new(
+            MatrixConfig {
+                homeserver_url: "https://matrix.example.org".into(),
+                allowed_rooms,
+                own_user_id: Some("@safeclaw:example.org".into()),
+                ..Default::default()
+            },
+            "syt_test_token".into(),
+        )
+    }
+
+    fn text_event(sender: &str, body: &str, ts: i64) -> serde_json::Value {
+        serde_json::json!({
+            "type": "m.room.message",
+            "sender": sender,
+            "origin_server_ts": ts,
+            "content": { "msgtype": "m.text", "body": body },
+        })
+    }
+
+    #[test]
+    fn sync_events_map_to_inbound_messages() {
+        let adapter = adapter(Vec::new());
+        let sync = serde_json::json!({
+            "rooms": { "join": {
+                "!general:example.org": { "timeline": { "events": [
+                    text_event("@alice:example.org", "hello bot", 1_700_000_000_000i64),
+                    // Non-message events are skipped.
+                    { "type": "m.room.member", "sender": "@bob:example.org" },
+                    // Encrypted events are skipped until E2EE support lands.
+                    { "type": "m.room.encrypted", "sender": "@alice:example.org" },
+                    // The bot's own echo is dropped.
+                    text_event("@safeclaw:example.org", "hi!", 1_700_000_001_000i64),
+                ]}}
+            }}
+        });
+        let messages = adapter.parse_sync(&sync);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].channel, "matrix");
+        assert_eq!(messages[0].chat_id, "!general:example.org");
+        assert_eq!(messages[0].user_id, "@alice:example.org");
+        assert_eq!(messages[0].content, "hello bot");
+        assert_eq!(messages[0].timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn room_allowlist_is_enforced() {
+        let adapter = adapter(vec!["!allowed:example.org".into()]);
+        let sync = serde_json::json!({
+            "rooms": { "join": {
+                "!allowed:example.org": { "timeline": { "events": [
+                    text_event("@alice:example.org", "in the allowed room", 1_000),
+                ]}},
+                "!random:example.org": { "timeline": { "events": [
+                    text_event("@mallory:example.org", "in another room", 2_000),
+                ]}}
+            }}
+        });
+        let messages = adapter.parse_sync(&sync);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].chat_id, "!allowed:example.org");
+    }
+
+    #[test]
+    fn single_update_parses_and_flags_mentions() {
+        let adapter = adapter(Vec::new());
+        let update = serde_json::json!({
+            "room_id": "!general:example.org",
+            "event": text_event("@alice:example.org", "@safeclaw:example.org ping", 5_000),
+        });
+        let message = adapter.parse_update(&update).unwrap().expect("message");
+        assert!(message.mentions_bot);
+
+        assert!(adapter
+            .parse_update(&serde_json::json!({"event": {}}))
+            .is_err());
+    }
+
+    #[test]
+    fn config_parses_from_the_open_channel_entry() {
+        let entry: ChannelEntry = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "homeserver_url": "https://matrix.example.org",
+            "access_token_ref": "matrix_access_token",
+            "allowed_rooms": ["!general:example.org"],
+        }))
+        .unwrap();
+        let config = MatrixConfig::from_entry(&entry).unwrap();
+        assert_eq!(config.homeserver_url, "https://matrix.example.org");
+        assert_eq!(config.allowed_rooms, vec!["!general:example.org"]);
+    }
+}
@@ -0,0 +1,126 @@
+//! Channel message types.
+
+use serde::{Deserialize, Serialize};
+
+/// A message received from a channel adapter, normalized across platforms.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InboundMessage {
+    pub channel: String,
+    pub chat_id: String,
+    pub user_id: String,
+    pub content: String,
+    /// True for direct/private messages, false for group chats.
+    #[serde(default)]
+    pub is_direct: bool,
+    /// Whether the bot was explicitly mentioned (adapters resolve their
+    /// platform-specific mention syntax before this flag is set).
+    #[serde(default)]
+    pub mentions_bot: bool,
+    /// Unix timestamp (seconds) the platform reported for the message.
+    pub timestamp: i64,
+    /// The message this one replies to (Telegram/Slack reply-to), captured
+    /// by the adapter so the prompt can quote it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<ReplyContext>,
+}
+
+/// The replied-to message an adapter resolved from the platform's reply
+/// metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplyContext {
+    /// Platform message ID of the quoted message.
+    pub message_id: String,
+    /// Its content, as the platform delivered it.
+    pub content: String,
+    /// Display name of its author, when the platform provides one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+/// Maximum quoted characters included in the prompt; longer quotes are cut
+/// at a char boundary with an ellipsis.
+const MAX_QUOTED_CHARS: usize = 500;
+
+/// Render the prompt for an inbound message, quoting the replied-to message
+/// so "make that shorter" unambiguously refers to it.
+pub fn prompt_with_reply_context(message: &InboundMessage) -> String {
+    let Some(reply) = &message.reply_to else {
+        return message.content.clone();
+    };
+    let mut quoted: String = reply.content.chars().take(MAX_QUOTED_CHARS).collect();
+    if quoted.len() < reply.content.len() {
+        quoted.push('…');
+    }
+    let attribution = reply.author.as_deref().unwrap_or("an earlier message");
+    let quoted_lines: Vec<String> = quoted.lines().map(|l| format!("> {l}")).collect();
+    format!(
+        "The user is replying to {attribution}:\n{}\n\n{}",
+        quoted_lines.join("\n"),
+        message.content
+    )
+}
+
+/// A message to send through a channel adapter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboundMessage {
+    pub channel: String,
+    pub chat_id: String,
+    pub content: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inbound(content: &str, reply_to: Option<ReplyContext>) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".into(),
+            chat_id: "c1".into(),
+            user_id: "u1".into(),
+            content: content.into(),
+            is_direct: true,
+            mentions_bot: false,
+            timestamp: 0,
+            reply_to,
+        }
+    }
+
+    #[test]
+    fn reply_to_is_captured_and_quoted_in_the_prompt() {
+        let message = inbound(
+            "make that shorter",
+            Some(ReplyContext {
+                message_id: "m-17".into(),
+                content: "Here is the full deployment runbook:\nstep one".into(),
+                author: Some("SafeClaw".into()),
+            }),
+        );
+        let prompt = prompt_with_reply_context(&message);
+        assert!(prompt.contains("replying to SafeClaw"));
+        assert!(prompt.contains("> Here is the full deployment runbook:"));
+        assert!(prompt.contains("> step one"));
+        assert!(prompt.ends_with("make that shorter"));
+    }
+
+    #[test]
+    fn messages_without_reply_context_pass_through() {
+        let message = inbound("hello", None);
+        assert_eq!(prompt_with_reply_context(&message), "hello");
+    }
+
+    #[test]
+    fn long_quotes_are_truncated() {
+        let message = inbound(
+            "summarize",
+            Some(ReplyContext {
+                message_id: "m-1".into(),
+                content: "x".repeat(2_000),
+                author: None,
+            }),
+        );
+        let prompt = prompt_with_reply_context(&message);
+        assert!(prompt.contains("an earlier message"));
+        assert!(prompt.contains('…'));
+        assert!(prompt.len() < 700);
+    }
+}
@@ -0,0 +1,24 @@
+//! Multi-channel adapters.
+
+pub mod adapter;
+pub mod attachments;
+pub mod capabilities;
+pub mod chunking;
+pub mod commands;
+pub mod confirmation;
+pub mod conformance;
+pub mod control;
+pub mod deletion;
+pub mod gating;
+pub mod handler;
+pub mod markdown;
+pub mod matrix;
+pub mod message;
+pub mod reassembly;
+pub mod telegram;
+pub mod template;
+pub mod terse;
+pub mod webchat;
+pub mod watchdog;
+#[cfg(feature = "embed-webchat")]
+pub mod webchat_assets;
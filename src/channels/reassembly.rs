@@ -0,0 +1,268 @@
+//! Content-aware reassembly of platform-split messages.
+//!
+//! Some platforms split a long paste into several messages, and the agent
+//! answers the first fragment before the rest arrives. This holds back
+//! messages that look like partial pastes — no sentence-final punctuation,
+//! same user, rapid succession — and merges the parts into one prompt. A
+//! held message is released either when a terminating part arrives or when
+//! the window elapses with nothing further; `flush_due` rides on the
+//! runtime loop for that. Commands and normally-punctuated messages pass
+//! straight through.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::message::InboundMessage;
+
+/// Configuration under `channels.reassembly`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReassemblyConfig {
+    pub enabled: bool,
+    /// A follow-up within this many seconds counts as rapid succession;
+    /// a held message is released once this long passes with no follow-up.
+    pub window_secs: i64,
+    /// Hard cap on merged parts; the buffer flushes at this size.
+    pub max_parts: usize,
+}
+
+impl Default for ReassemblyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_secs: 3,
+            max_parts: 10,
+        }
+    }
+}
+
+/// Whether a message reads as complete on its own: commands always do,
+/// otherwise sentence-final punctuation decides.
+fn looks_complete(content: &str) -> bool {
+    let trimmed = content.trim_end();
+    if trimmed.starts_with('/') {
+        return true;
+    }
+    matches!(
+        trimmed.chars().last(),
+        Some('.' | '!' | '?' | '…' | ':' | '"' | '”' | ')')
+    )
+}
+
+struct PendingPaste {
+    parts: Vec<InboundMessage>,
+    last_part_at: i64,
+}
+
+impl PendingPaste {
+    fn merge(mut self) -> InboundMessage {
+        let mut merged = self.parts.remove(0);
+        for part in self.parts {
+            merged.content.push('\n');
+            merged.content.push_str(&part.content);
+        }
+        merged
+    }
+}
+
+/// Buffers suspected paste fragments per `channel:chat:user`.
+pub struct MessageReassembler {
+    config: ReassemblyConfig,
+    pending: Mutex<HashMap<String, PendingPaste>>,
+}
+
+impl MessageReassembler {
+    pub fn new(config: ReassemblyConfig) -> Self {
+        Self {
+            config,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key(message: &InboundMessage) -> String {
+        format!(
+            "{}:{}:{}",
+            message.channel, message.chat_id, message.user_id
+        )
+    }
+
+    /// Offer one inbound message. Returns the messages now ready for
+    /// processing: the message itself when it passes through, a merged
+    /// paste when this part completes one, nothing while parts are held.
+    pub fn offer(&self, message: InboundMessage, now: i64) -> Vec<InboundMessage> {
+        if !self.config.enabled {
+            return vec![message];
+        }
+        let key = Self::key(&message);
+        let mut pending = self.pending.lock().expect("message reassembler poisoned");
+
+        if let Some(paste) = pending.remove(&key) {
+            if now - paste.last_part_at <= self.config.window_secs {
+                let mut paste = paste;
+                let complete = looks_complete(&message.content);
+                paste.parts.push(message);
+                paste.last_part_at = now;
+                if complete || paste.parts.len() >= self.config.max_parts {
+                    return vec![paste.merge()];
+                }
+                pending.insert(key, paste);
+                return Vec::new();
+            }
+            // The window lapsed between loop ticks: release the old paste
+            // first, then treat this message on its own.
+            let stale = paste.merge();
+            let mut ready = vec![stale];
+            ready.extend(self.hold_or_pass(&mut pending, key, message, now));
+            return ready;
+        }
+        self.hold_or_pass(&mut pending, key, message, now)
+    }
+
+    fn hold_or_pass(
+        &self,
+        pending: &mut HashMap<String, PendingPaste>,
+        key: String,
+        message: InboundMessage,
+        now: i64,
+    ) -> Vec<InboundMessage> {
+        if looks_complete(&message.content) {
+            return vec![message];
+        }
+        pending.insert(
+            key,
+            PendingPaste {
+                parts: vec![message],
+                last_part_at: now,
+            },
+        );
+        Vec::new()
+    }
+
+    /// Release held messages whose window elapsed with no follow-up. Rides
+    /// on the runtime loop.
+    pub fn flush_due(&self, now: i64) -> Vec<InboundMessage> {
+        let mut pending = self.pending.lock().expect("message reassembler poisoned");
+        let due: Vec<String> = pending
+            .iter()
+            .filter(|(_, paste)| now - paste.last_part_at > self.config.window_secs)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut released: Vec<InboundMessage> = due
+            .into_iter()
+            .filter_map(|key| pending.remove(&key))
+            .map(PendingPaste::merge)
+            .collect();
+        released.sort_by_key(|m| m.timestamp);
+        released
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn inbound(user: &str, content: &str, timestamp: i64) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".into(),
+            chat_id: "c1".into(),
+            user_id: user.into(),
+            content: content.into(),
+            is_direct: true,
+            mentions_bot: false,
+            timestamp,
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn a_split_paste_reassembles_into_one_prompt() {
+        let reassembler = MessageReassembler::new(ReassemblyConfig::default());
+        assert!(reassembler
+            .offer(inbound("u1", "here's the stack trace: thread 'main'", NOW), NOW)
+            .is_empty());
+        assert!(reassembler
+            .offer(inbound("u1", "panicked at src/lib.rs:42", NOW + 1), NOW + 1)
+            .is_empty());
+        // The window passes with no further part: one merged message.
+        let released = reassembler.flush_due(NOW + 6);
+        assert_eq!(released.len(), 1);
+        assert_eq!(
+            released[0].content,
+            "here's the stack trace: thread 'main'\npanicked at src/lib.rs:42"
+        );
+        assert_eq!(released[0].timestamp, NOW);
+        // Nothing left buffered.
+        assert!(reassembler.flush_due(NOW + 60).is_empty());
+    }
+
+    #[test]
+    fn a_terminating_part_releases_the_merge_immediately() {
+        let reassembler = MessageReassembler::new(ReassemblyConfig::default());
+        assert!(reassembler
+            .offer(inbound("u1", "first half of the paste", NOW), NOW)
+            .is_empty());
+        let ready = reassembler.offer(inbound("u1", "and the end.", NOW + 1), NOW + 1);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].content, "first half of the paste\nand the end.");
+    }
+
+    #[test]
+    fn complete_messages_are_never_held_or_merged() {
+        let reassembler = MessageReassembler::new(ReassemblyConfig::default());
+        let first = reassembler.offer(inbound("u1", "What's the capital of France?", NOW), NOW);
+        assert_eq!(first.len(), 1);
+        // A rapid but complete follow-up stays its own prompt.
+        let second = reassembler.offer(inbound("u1", "And of Spain?", NOW + 1), NOW + 1);
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].content, "And of Spain?");
+        // Commands pass through even without punctuation.
+        assert_eq!(reassembler.offer(inbound("u1", "/status", NOW), NOW).len(), 1);
+    }
+
+    #[test]
+    fn other_users_and_lapsed_windows_do_not_merge() {
+        let reassembler = MessageReassembler::new(ReassemblyConfig::default());
+        assert!(reassembler
+            .offer(inbound("u1", "half a thought", NOW), NOW)
+            .is_empty());
+        // A different user's fragment buffers separately.
+        assert!(reassembler
+            .offer(inbound("u2", "another half", NOW + 1), NOW + 1)
+            .is_empty());
+        // u1's follow-up after the window: the stale part is released on its
+        // own and the new fragment starts a fresh buffer.
+        let ready = reassembler.offer(inbound("u1", "unrelated fragment", NOW + 30), NOW + 30);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].content, "half a thought");
+        assert_eq!(reassembler.flush_due(NOW + 60).len(), 2);
+    }
+
+    #[test]
+    fn disabled_reassembly_passes_everything_through() {
+        let reassembler = MessageReassembler::new(ReassemblyConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        assert_eq!(
+            reassembler.offer(inbound("u1", "no punctuation here", NOW), NOW).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn the_part_cap_forces_a_flush() {
+        let reassembler = MessageReassembler::new(ReassemblyConfig {
+            max_parts: 3,
+            ..Default::default()
+        });
+        assert!(reassembler.offer(inbound("u1", "one", NOW), NOW).is_empty());
+        assert!(reassembler.offer(inbound("u1", "two", NOW + 1), NOW + 1).is_empty());
+        let ready = reassembler.offer(inbound("u1", "three", NOW + 2), NOW + 2);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].content, "one\ntwo\nthree");
+    }
+}
@@ -0,0 +1,149 @@
+//! Telegram channel adapter (Bot API).
+//!
+//! Updates arrive as Bot API `Update` objects; text messages map to
+//! [`InboundMessage`] with `is_direct` derived from the chat type. Sends go
+//! through the injected [`PlatformBackend`] (HTTP `sendMessage` in
+//! production), chunked at Telegram's 4096-character limit with one retry on
+//! transient failure.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::adapter::{deliver_with_retry, ChannelAdapter, PlatformBackend};
+use crate::channels::capabilities::ChannelCapabilities;
+use crate::channels::chunking::chunk_for_channel;
+use crate::channels::markdown::Dialect;
+use crate::channels::message::{InboundMessage, OutboundMessage};
+use crate::error::Result;
+
+pub const CHANNEL_NAME: &str = "telegram";
+/// Telegram's hard per-message limit.
+pub const MAX_MESSAGE_CHARS: usize = 4_096;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelegramConfig {
+    pub bot_token_ref: Option<String>,
+    /// `@username` of the bot, for mention detection in groups.
+    pub bot_username: Option<String>,
+}
+
+pub struct TelegramAdapter {
+    config: TelegramConfig,
+    backend: Arc<dyn PlatformBackend>,
+}
+
+impl TelegramAdapter {
+    pub fn new(config: TelegramConfig, backend: Arc<dyn PlatformBackend>) -> Self {
+        Self { config, backend }
+    }
+}
+
+#[async_trait::async_trait]
+impl ChannelAdapter for TelegramAdapter {
+    fn name(&self) -> &str {
+        CHANNEL_NAME
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            max_message_chars: MAX_MESSAGE_CHARS,
+            supports_edit: true,
+            supports_buttons: true,
+            supports_attachments: true,
+            // Bot API upload limit.
+            max_attachment_bytes: Some(50 * 1024 * 1024),
+            supports_threads: false,
+            supports_reactions: true,
+            supports_typing_indicator: true,
+            dialect: Dialect::TelegramMarkdownv2,
+        }
+    }
+
+    fn parse_update(&self, payload: &serde_json::Value) -> Result<Option<InboundMessage>> {
+        let message = &payload["message"];
+        let (Some(text), Some(chat_id), Some(user_id)) = (
+            message["text"].as_str(),
+            message["chat"]["id"].as_i64(),
+            message["from"]["id"].as_i64(),
+        ) else {
+            // Edits, stickers, joins — valid updates with nothing to process.
+            return Ok(None);
+        };
+        Ok(Some(InboundMessage {
+            channel: CHANNEL_NAME.into(),
+            chat_id: chat_id.to_string(),
+            user_id: user_id.to_string(),
+            content: text.to_string(),
+            is_direct: message["chat"]["type"] == "private",
+            mentions_bot: self
+                .config
+                .bot_username
+                .as_deref()
+                .is_some_and(|name| text.contains(name)),
+            timestamp: message["date"].as_i64().unwrap_or(0),
+            reply_to: None,
+        }))
+    }
+
+    async fn send_message(&self, message: &OutboundMessage) -> Result<()> {
+        for chunk in chunk_for_channel(&message.content, &self.capabilities()) {
+            deliver_with_retry(self.backend.as_ref(), &message.chat_id, &chunk).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::conformance::{self, AdapterFixture};
+
+    fn update(chat_type: &str, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "update_id": 1,
+            "message": {
+                "message_id": 7,
+                "date": 1_700_000_000,
+                "chat": { "id": 42, "type": chat_type },
+                "from": { "id": 1001 },
+                "text": text,
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn telegram_passes_the_adapter_conformance_suite() {
+        conformance::run(AdapterFixture {
+            channel: CHANNEL_NAME,
+            make_adapter: Box::new(|backend| {
+                Box::new(TelegramAdapter::new(TelegramConfig::default(), backend))
+            }),
+            valid_direct_update: update("private", "hello"),
+            valid_group_update: Some(update("supergroup", "hello group")),
+            ignorable_update: serde_json::json!({
+                "update_id": 2,
+                "message": { "chat": { "id": 42, "type": "private" }, "sticker": {} }
+            }),
+            max_message_chars: MAX_MESSAGE_CHARS,
+        })
+        .await;
+    }
+
+    #[test]
+    fn mentions_are_detected_via_the_configured_username() {
+        let adapter = TelegramAdapter::new(
+            TelegramConfig {
+                bot_username: Some("@safeclaw_bot".into()),
+                ..Default::default()
+            },
+            std::sync::Arc::new(conformance::RecordingBackend::default()),
+        );
+        let message = adapter
+            .parse_update(&update("supergroup", "@safeclaw_bot status?"))
+            .unwrap()
+            .unwrap();
+        assert!(message.mentions_bot);
+    }
+}
@@ -0,0 +1,118 @@
+//! Safe templating for outbound messages containing user-controlled values.
+//!
+//! Scheduled task names, persona names, and other user or config-controlled
+//! strings used to be interpolated straight into outbound markdown — a task
+//! named `**x** [click](http://evil)` weaponized the rendered message, and
+//! MarkdownV2 control characters broke Telegram sends outright. Templates
+//! now distinguish slots: *markdown* slots carry trusted template text,
+//! *literal* slots pass user values through the dialect escape so they
+//! render as inert text, and *code* slots neutralize backticks and wrap the
+//! value in a span no dialect interprets.
+
+use crate::channels::markdown::Dialect;
+
+/// Escape a user-controlled value for a literal text slot.
+pub fn escape_literal(dialect: Dialect, value: &str) -> String {
+    dialect.escape_text(value)
+}
+
+/// Render a user-controlled value as an inert code span. Backticks inside
+/// the value would close the span, so they are replaced with a lookalike.
+pub fn code_slot(value: &str) -> String {
+    format!("`{}`", value.replace('`', "ˋ"))
+}
+
+/// Builder assembling one outbound message from typed slots.
+pub struct SafeTemplate {
+    dialect: Dialect,
+    out: String,
+}
+
+impl SafeTemplate {
+    pub fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            out: String::new(),
+        }
+    }
+
+    /// Trusted template text — markdown written by us, never by users.
+    pub fn markdown(mut self, text: &str) -> Self {
+        self.out.push_str(text);
+        self
+    }
+
+    /// User or config-controlled value, escaped to inert text.
+    pub fn literal(mut self, value: &str) -> Self {
+        self.out.push_str(&escape_literal(self.dialect, value));
+        self
+    }
+
+    /// User-controlled value as a code span.
+    pub fn code(mut self, value: &str) -> Self {
+        self.out.push_str(&code_slot(value));
+        self
+    }
+
+    pub fn build(self) -> String {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTILE_NAME: &str = "**x** [click](http://evil)";
+
+    #[test]
+    fn markdown_in_literal_slots_is_inert_on_telegram() {
+        let rendered = SafeTemplate::new(Dialect::TelegramMarkdownv2)
+            .markdown("📋 *")
+            .literal(HOSTILE_NAME)
+            .markdown("* finished")
+            .build();
+        // Every control character in the name is escaped; the template's own
+        // bold markers are the only live markup.
+        assert!(rendered.contains(r"\*\*x\*\*"));
+        assert!(rendered.contains(r"\[click\]\(http://evil\)"));
+        assert!(rendered.starts_with("📋 *"));
+    }
+
+    #[test]
+    fn telegram_control_characters_are_escaped() {
+        let rendered = escape_literal(Dialect::TelegramMarkdownv2, "v1.2 (beta) #5 > v1.1!");
+        assert_eq!(rendered, r"v1\.2 \(beta\) \#5 \> v1\.1\!");
+    }
+
+    #[test]
+    fn hostile_name_is_inert_on_slack() {
+        let rendered = SafeTemplate::new(Dialect::SlackMrkdwn)
+            .markdown("Task ")
+            .literal("<!channel> & *bold*")
+            .build();
+        assert_eq!(rendered, "Task &lt;!channel&gt; &amp; *bold*");
+    }
+
+    #[test]
+    fn hostile_name_is_inert_on_discord() {
+        let rendered = SafeTemplate::new(Dialect::Discord)
+            .markdown("Task ")
+            .literal(HOSTILE_NAME)
+            .build();
+        assert!(rendered.contains(r"\*\*x\*\*"));
+        assert!(rendered.contains(r"\[click\]"), "{rendered}");
+    }
+
+    #[test]
+    fn plain_dialect_passes_text_through() {
+        assert_eq!(escape_literal(Dialect::Plain, HOSTILE_NAME), HOSTILE_NAME);
+    }
+
+    #[test]
+    fn code_slots_neutralize_backtick_escapes() {
+        let rendered = code_slot("rm -rf ` && curl evil");
+        assert!(rendered.starts_with('`') && rendered.ends_with('`'));
+        assert_eq!(rendered.matches('`').count(), 2);
+    }
+}
@@ -0,0 +1,113 @@
+//! Terse mode — zero chit-chat for automation channels.
+//!
+//! The opposite of greetings: a channel feeding a pipeline or an ops room
+//! wants the substantive answer or nothing — no "I received your message",
+//! no typing indicators, no "couldn't generate a response" filler. Outbound
+//! messages carry a class; for channels flagged terse, everything but
+//! substantive content is dropped at the send boundary.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::message::OutboundMessage;
+
+/// What an outbound message is, decided by whichever pipeline stage
+/// produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboundClass {
+    /// The actual answer, a tool result, a reminder firing — always sent.
+    Substantive,
+    /// Greetings, acknowledgements, "one moment" notices.
+    Filler,
+    /// Status and progress messages.
+    Status,
+    /// Typing / presence indicators.
+    Typing,
+}
+
+/// Configuration under `channels.terse`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TerseConfig {
+    /// Channels in terse mode.
+    #[serde(default)]
+    pub channels: HashSet<String>,
+}
+
+impl TerseConfig {
+    pub fn is_terse(&self, channel: &str) -> bool {
+        self.channels.contains(channel)
+    }
+}
+
+/// Whether a message of `class` may go out on `channel`.
+pub fn should_send(config: &TerseConfig, channel: &str, class: OutboundClass) -> bool {
+    class == OutboundClass::Substantive || !config.is_terse(channel)
+}
+
+/// Send-boundary filter: `None` means the message is suppressed.
+pub fn filter_outbound(
+    config: &TerseConfig,
+    message: OutboundMessage,
+    class: OutboundClass,
+) -> Option<OutboundMessage> {
+    should_send(config, &message.channel, class).then_some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TerseConfig {
+        TerseConfig {
+            channels: ["automation".to_string()].into_iter().collect(),
+        }
+    }
+
+    fn message(channel: &str, content: &str) -> OutboundMessage {
+        OutboundMessage {
+            channel: channel.into(),
+            chat_id: "c1".into(),
+            content: content.into(),
+        }
+    }
+
+    #[test]
+    fn terse_channel_suppresses_filler_and_status_but_delivers_content() {
+        let config = config();
+        assert!(filter_outbound(
+            &config,
+            message("automation", "Sorry, I couldn't generate a response."),
+            OutboundClass::Filler,
+        )
+        .is_none());
+        assert!(filter_outbound(
+            &config,
+            message("automation", "Setting up secure processing, one moment…"),
+            OutboundClass::Status,
+        )
+        .is_none());
+        assert!(!should_send(&config, "automation", OutboundClass::Typing));
+
+        let answer = filter_outbound(
+            &config,
+            message("automation", "deploy finished: 0 errors"),
+            OutboundClass::Substantive,
+        );
+        assert_eq!(answer.unwrap().content, "deploy finished: 0 errors");
+    }
+
+    #[test]
+    fn other_channels_are_untouched() {
+        let config = config();
+        for class in [
+            OutboundClass::Substantive,
+            OutboundClass::Filler,
+            OutboundClass::Status,
+            OutboundClass::Typing,
+        ] {
+            assert!(should_send(&config, "telegram", class));
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Listener liveness watchdog.
+//!
+//! A channel's long-poll/websocket listener can die or hang silently — the
+//! task panics, the socket goes quiet — and the channel goes dark with no
+//! error anywhere. Each adapter's listener runs under a supervisor that
+//! watches the task handle and a heartbeat the listener touches on every
+//! received event; a finished task or a stale heartbeat gets the listener
+//! aborted and respawned with exponential backoff, with an event recorded so
+//! operators can see the flapping.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::events::EventStore;
+
+/// Event category used for listener restart events.
+pub const EVENT_CATEGORY_CHANNEL: &str = "channel";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListenerWatchdogConfig {
+    pub enabled: bool,
+    /// A listener whose heartbeat is older than this is considered stalled.
+    pub stall_after_secs: u64,
+    /// How often liveness is checked.
+    pub check_interval_secs: u64,
+    /// Delay before the first restart; doubles per consecutive restart.
+    pub initial_backoff_secs: u64,
+    pub max_backoff_secs: u64,
+}
+
+impl Default for ListenerWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            stall_after_secs: 120,
+            check_interval_secs: 15,
+            initial_backoff_secs: 2,
+            max_backoff_secs: 300,
+        }
+    }
+}
+
+/// Touched by the listener on every received platform event; the supervisor
+/// reads the elapsed time to detect a silent hang.
+#[derive(Clone)]
+pub struct Heartbeat(Arc<Mutex<Instant>>);
+
+impl Heartbeat {
+    fn new() -> Self {
+        Self(Arc::new(Mutex::new(Instant::now())))
+    }
+
+    pub fn beat(&self) {
+        *self.0.lock().expect("heartbeat poisoned") = Instant::now();
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.0.lock().expect("heartbeat poisoned").elapsed()
+    }
+}
+
+/// Restart counters, exposed for the status API and for tests.
+#[derive(Default)]
+pub struct SupervisorStats {
+    restarts: AtomicU64,
+}
+
+impl SupervisorStats {
+    pub fn restarts(&self) -> u64 {
+        self.restarts.load(Ordering::Relaxed)
+    }
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Run `spawn_listener` under supervision. The closure is handed a fresh
+/// [`Heartbeat`] each (re)start and must return the listener's task handle.
+pub fn spawn_supervisor<F>(
+    channel: String,
+    config: ListenerWatchdogConfig,
+    events: Arc<EventStore>,
+    mut spawn_listener: F,
+) -> (tokio::task::JoinHandle<()>, Arc<SupervisorStats>)
+where
+    F: FnMut(Heartbeat) -> tokio::task::JoinHandle<()> + Send + 'static,
+{
+    let stats = Arc::new(SupervisorStats::default());
+    let task_stats = stats.clone();
+    let handle = tokio::spawn(async move {
+        let heartbeat = Heartbeat::new();
+        let mut listener = spawn_listener(heartbeat.clone());
+        if !config.enabled {
+            let _ = listener.await;
+            return;
+        }
+        let stall_after = Duration::from_secs(config.stall_after_secs);
+        let mut backoff = Duration::from_secs(config.initial_backoff_secs.max(1));
+        let mut last_restart = Instant::now();
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(config.check_interval_secs.max(1)));
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let stalled = heartbeat.elapsed() >= stall_after;
+            if !listener.is_finished() && !stalled {
+                // A full quiet period without trouble resets the backoff.
+                if last_restart.elapsed() >= stall_after {
+                    backoff = Duration::from_secs(config.initial_backoff_secs.max(1));
+                }
+                continue;
+            }
+            listener.abort();
+            let reason = if stalled { "stalled" } else { "exited" };
+            events.create(
+                EVENT_CATEGORY_CHANNEL,
+                &channel,
+                &format!("{channel} listener {reason}; restarting"),
+                &format!(
+                    "listener {reason}; restarting after {}s backoff",
+                    backoff.as_secs()
+                ),
+                "watchdog",
+                unix_now(),
+            );
+            task_stats.restarts.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_secs(config.max_backoff_secs.max(1)));
+            heartbeat.beat();
+            last_restart = Instant::now();
+            listener = spawn_listener(heartbeat.clone());
+        }
+    });
+    (handle, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ListenerWatchdogConfig {
+        ListenerWatchdogConfig {
+            enabled: true,
+            stall_after_secs: 60,
+            check_interval_secs: 10,
+            initial_backoff_secs: 2,
+            max_backoff_secs: 30,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dead_listener_is_detected_and_restarted() {
+        let events = Arc::new(EventStore::default());
+        let spawned = Arc::new(AtomicU64::new(0));
+        let spawn_count = spawned.clone();
+        let (supervisor, stats) = spawn_supervisor(
+            "slack".into(),
+            config(),
+            events.clone(),
+            move |_heartbeat| {
+                spawn_count.fetch_add(1, Ordering::Relaxed);
+                // Dies immediately, as a panicking listener would.
+                tokio::spawn(async {})
+            },
+        );
+
+        tokio::time::sleep(Duration::from_secs(15)).await;
+        assert!(stats.restarts() >= 1);
+        assert!(spawned.load(Ordering::Relaxed) >= 2);
+        let recorded = events.list(Some(EVENT_CATEGORY_CHANNEL));
+        assert!(!recorded.is_empty());
+        assert!(recorded[0].summary.contains("exited"));
+        supervisor.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stalled_listener_is_restarted() {
+        let events = Arc::new(EventStore::default());
+        let (supervisor, stats) = spawn_supervisor(
+            "discord".into(),
+            config(),
+            events.clone(),
+            // Alive but never beats — a hung websocket read.
+            |_heartbeat| {
+                tokio::spawn(async {
+                    std::future::pending::<()>().await;
+                })
+            },
+        );
+
+        tokio::time::sleep(Duration::from_secs(70)).await;
+        assert!(stats.restarts() >= 1);
+        assert!(events
+            .list(Some(EVENT_CATEGORY_CHANNEL))
+            .iter()
+            .any(|e| e.summary.contains("stalled")));
+        supervisor.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn healthy_listener_is_left_alone() {
+        let events = Arc::new(EventStore::default());
+        let (supervisor, stats) = spawn_supervisor(
+            "telegram".into(),
+            config(),
+            events.clone(),
+            |heartbeat| {
+                tokio::spawn(async move {
+                    loop {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        heartbeat.beat();
+                    }
+                })
+            },
+        );
+
+        tokio::time::sleep(Duration::from_secs(600)).await;
+        assert_eq!(stats.restarts(), 0);
+        assert!(events.list(Some(EVENT_CATEGORY_CHANNEL)).is_empty());
+        supervisor.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_grows_across_consecutive_restarts() {
+        let events = Arc::new(EventStore::default());
+        let (supervisor, stats) =
+            spawn_supervisor("slack".into(), config(), events.clone(), |_heartbeat| {
+                tokio::spawn(async {})
+            });
+
+        // Check-interval 10s plus growing backoff (2, 4, 8 …) means three
+        // restarts take at least 30 + 14 seconds.
+        tokio::time::sleep(Duration::from_secs(50)).await;
+        let restarts = stats.restarts();
+        assert!((2..=4).contains(&restarts), "got {restarts} restarts");
+        supervisor.abort();
+    }
+}
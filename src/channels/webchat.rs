@@ -0,0 +1,104 @@
+//! WebChat channel adapter — the built-in web interface.
+//!
+//! WebChat updates arrive already normalized from our own frontend, so
+//! parsing is a thin shape check; every conversation is a DM. Sends go
+//! through the injected [`PlatformBackend`] (the websocket hub in
+//! production).
+
+use std::sync::Arc;
+
+use crate::channels::adapter::{deliver_with_retry, ChannelAdapter, PlatformBackend};
+use crate::channels::capabilities::ChannelCapabilities;
+use crate::channels::chunking::chunk_for_channel;
+use crate::channels::markdown::Dialect;
+use crate::channels::message::{InboundMessage, OutboundMessage};
+use crate::error::Result;
+
+pub const CHANNEL_NAME: &str = "webchat";
+/// Generous limit; the frontend renders long messages fine, but unbounded
+/// frames stall the websocket.
+pub const MAX_MESSAGE_CHARS: usize = 16_384;
+
+pub struct WebChatAdapter {
+    backend: Arc<dyn PlatformBackend>,
+}
+
+impl WebChatAdapter {
+    pub fn new(backend: Arc<dyn PlatformBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+#[async_trait::async_trait]
+impl ChannelAdapter for WebChatAdapter {
+    fn name(&self) -> &str {
+        CHANNEL_NAME
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            max_message_chars: MAX_MESSAGE_CHARS,
+            supports_edit: true,
+            supports_buttons: true,
+            supports_attachments: true,
+            // The frontend streams uploads; cap to keep the hub responsive.
+            max_attachment_bytes: Some(100 * 1024 * 1024),
+            supports_threads: false,
+            supports_reactions: false,
+            supports_typing_indicator: true,
+            // Our own frontend renders the markdown itself.
+            dialect: Dialect::Plain,
+        }
+    }
+
+    fn parse_update(&self, payload: &serde_json::Value) -> Result<Option<InboundMessage>> {
+        let (Some(chat_id), Some(user_id), Some(content)) = (
+            payload["chatId"].as_str(),
+            payload["userId"].as_str(),
+            payload["content"].as_str(),
+        ) else {
+            return Ok(None);
+        };
+        Ok(Some(InboundMessage {
+            channel: CHANNEL_NAME.into(),
+            chat_id: chat_id.to_string(),
+            user_id: user_id.to_string(),
+            content: content.to_string(),
+            is_direct: true,
+            mentions_bot: false,
+            timestamp: payload["timestamp"].as_i64().unwrap_or(0),
+            reply_to: None,
+        }))
+    }
+
+    async fn send_message(&self, message: &OutboundMessage) -> Result<()> {
+        for chunk in chunk_for_channel(&message.content, &self.capabilities()) {
+            deliver_with_retry(self.backend.as_ref(), &message.chat_id, &chunk).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::conformance::{self, AdapterFixture};
+
+    #[tokio::test]
+    async fn webchat_passes_the_adapter_conformance_suite() {
+        conformance::run(AdapterFixture {
+            channel: CHANNEL_NAME,
+            make_adapter: Box::new(|backend| Box::new(WebChatAdapter::new(backend))),
+            valid_direct_update: serde_json::json!({
+                "chatId": "web-7",
+                "userId": "u1",
+                "content": "hello",
+                "timestamp": 1_700_000_000,
+            }),
+            valid_group_update: None,
+            ignorable_update: serde_json::json!({ "type": "typing", "chatId": "web-7" }),
+            max_message_chars: MAX_MESSAGE_CHARS,
+        })
+        .await;
+    }
+}
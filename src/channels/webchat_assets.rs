@@ -0,0 +1,186 @@
+//! In-process serving of the WebChat widget assets.
+//!
+//! The built widget JS/CSS is embedded into the binary (behind the
+//! `embed-webchat` feature) and served from `/webchat/` so deployments don't
+//! need to host the widget separately. Asset paths are content-hash
+//! versioned so upgrades bust caches; the immutable assets get year-long
+//! cache headers while `/webchat/embed.js` — a one-line bootstrap snippet
+//! that points the widget at the serving gateway's own URL — is never cached.
+
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Built widget artifacts, embedded at compile time.
+pub const WIDGET_JS: &[u8] = include_bytes!("../../assets/webchat/widget.js");
+pub const WIDGET_CSS: &[u8] = include_bytes!("../../assets/webchat/widget.css");
+
+/// Cache policy for content-hash-versioned assets.
+const IMMUTABLE_CACHE: &str = "public, max-age=31536000, immutable";
+/// Cache policy for the bootstrap snippet, which must follow upgrades.
+const NO_CACHE: &str = "no-cache";
+
+/// Configuration for widget asset serving.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebChatAssetsConfig {
+    /// Disable entirely for deployments hosting assets elsewhere.
+    #[serde(default = "default_serve_assets")]
+    pub serve_assets: bool,
+    /// Path prefix the a3s-gateway strips in serve mode (e.g. `/safeclaw`).
+    /// Baked into the embed snippet so the widget calls back through the
+    /// gateway correctly.
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+}
+
+fn default_serve_assets() -> bool {
+    true
+}
+
+impl Default for WebChatAssetsConfig {
+    fn default() -> Self {
+        Self {
+            serve_assets: true,
+            path_prefix: None,
+        }
+    }
+}
+
+/// Content-hash version of the embedded assets — stable per build, changes
+/// whenever the widget artifacts change.
+pub fn asset_version() -> &'static str {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    VERSION.get_or_init(|| {
+        let mut hasher = Sha256::new();
+        hasher.update(WIDGET_JS);
+        hasher.update(WIDGET_CSS);
+        let digest = hasher.finalize();
+        hex::encode(&digest[..8])
+    })
+}
+
+/// The one-line embed snippet served at `GET /webchat/embed.js`.
+pub fn embed_snippet(config: &WebChatAssetsConfig) -> String {
+    let prefix = config.path_prefix.as_deref().unwrap_or("").trim_end_matches('/');
+    let version = asset_version();
+    format!(
+        "(function(){{var b=document.currentScript.src.replace(/\\/webchat\\/embed\\.js.*$/,'');\
+         var base=b+'{prefix}';\
+         var s=document.createElement('script');s.src=base+'/webchat/{version}/widget.js';\
+         var c=document.createElement('link');c.rel='stylesheet';c.href=base+'/webchat/{version}/widget.css';\
+         s.onload=function(){{SafeClawWidget.mount({{endpoint:base}})}};\
+         document.head.appendChild(c);document.head.appendChild(s);}})();"
+    )
+}
+
+/// Routes mounted at the router root. Returns an empty router when serving is
+/// disabled so deployments hosting assets elsewhere expose nothing here.
+pub fn routes(config: WebChatAssetsConfig) -> Router {
+    if !config.serve_assets {
+        return Router::new();
+    }
+    Router::new()
+        .route("/webchat/embed.js", get(serve_embed))
+        .route("/webchat/:version/:asset", get(serve_asset))
+        .with_state(Arc::new(config))
+}
+
+async fn serve_embed(State(config): State<Arc<WebChatAssetsConfig>>) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/javascript"),
+            (header::CACHE_CONTROL, NO_CACHE),
+        ],
+        embed_snippet(&config),
+    )
+        .into_response()
+}
+
+async fn serve_asset(Path((version, asset)): Path<(String, String)>) -> Response {
+    if version != asset_version() {
+        // Stale version — force the client back through embed.js.
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let (body, content_type): (&[u8], &str) = match asset.as_str() {
+        "widget.js" => (WIDGET_JS, "application/javascript"),
+        "widget.css" => (WIDGET_CSS, "text/css"),
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    (
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, IMMUTABLE_CACHE),
+        ],
+        body.to_vec(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn get_path(router: Router, path: &str) -> Response {
+        router
+            .oneshot(Request::builder().uri(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn versioned_asset_served_with_immutable_cache() {
+        let router = routes(WebChatAssetsConfig::default());
+        let path = format!("/webchat/{}/widget.js", asset_version());
+        let resp = get_path(router, &path).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CACHE_CONTROL).unwrap(),
+            IMMUTABLE_CACHE
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_version_is_not_found() {
+        let router = routes(WebChatAssetsConfig::default());
+        let resp = get_path(router, "/webchat/deadbeef00000000/widget.js").await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn embed_snippet_is_never_cached() {
+        let router = routes(WebChatAssetsConfig::default());
+        let resp = get_path(router, "/webchat/embed.js").await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(header::CACHE_CONTROL).unwrap(), NO_CACHE);
+    }
+
+    #[tokio::test]
+    async fn disabled_config_registers_no_routes() {
+        let router = routes(WebChatAssetsConfig {
+            serve_assets: false,
+            path_prefix: None,
+        });
+        let resp = get_path(router, "/webchat/embed.js").await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn embed_snippet_respects_gateway_path_prefix() {
+        let snippet = embed_snippet(&WebChatAssetsConfig {
+            serve_assets: true,
+            path_prefix: Some("/safeclaw/".into()),
+        });
+        assert!(snippet.contains("b+'/safeclaw'"));
+        assert!(snippet.contains(asset_version()));
+    }
+}
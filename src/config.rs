@@ -0,0 +1,213 @@
+//! Configuration management.
+//!
+//! SafeClaw reads JSON configuration from `~/.safeclaw/config.json`.
+//! Sensitive values are referenced by `*_ref` fields resolving into the
+//! encrypted credential store; `${VAR}` syntax resolves from the environment.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::provider_auth::OAuthClientConfig;
+use crate::agent::rate_limit::ProviderRateLimit;
+use crate::privacy::{ClassificationRule, SensitivityLevel};
+use crate::runtime::limits::ResponseLimitConfig;
+use crate::tee::reliability::TeeReliabilityConfig;
+
+/// Top-level configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafeClawConfig {
+    pub version: String,
+    pub gateway: GatewayConfig,
+    pub tee: TeeConfig,
+    pub channels: ChannelsConfig,
+    pub privacy: PrivacyConfig,
+    pub models: ModelsConfig,
+    pub logging: LoggingConfig,
+    pub limits: ResponseLimitConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewayConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".into(),
+            port: 18790,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeeConfig {
+    pub enabled: bool,
+    /// Secrets provisioned into the TEE at first verification, referenced by
+    /// credential-store name.
+    pub secrets: HashMap<String, String>,
+    /// `"reject" | "warn" | "allow"` — behavior when policy demands TEE but
+    /// hardware is unavailable.
+    pub fallback_policy: String,
+    /// Timeout and retry behavior for RA-TLS channel operations.
+    pub reliability: TeeReliabilityConfig,
+}
+
+impl Default for TeeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            secrets: HashMap::new(),
+            fallback_policy: "warn".into(),
+            reliability: TeeReliabilityConfig::default(),
+        }
+    }
+}
+
+/// Per-channel configuration. Channel-specific fields (tokens, app IDs,
+/// webhook paths) are kept as an open map so each adapter can parse its own
+/// settings without this struct enumerating every platform's schema.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelEntry {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub settings: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelsConfig(pub HashMap<String, ChannelEntry>);
+
+impl ChannelsConfig {
+    /// Names of channels enabled in config, sorted for stable output.
+    pub fn enabled_channels(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .0
+            .iter()
+            .filter(|(_, entry)| entry.enabled)
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ChannelEntry> {
+        self.0.get(name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    pub auto_classify: bool,
+    pub default_level: SensitivityLevel,
+    pub rules: Vec<ClassificationRule>,
+    /// Compliance frameworks enabled for scanning (e.g. `"hipaa"`,
+    /// `"pci-dss"`, `"gdpr"`).
+    pub frameworks: Vec<String>,
+    /// Highest sensitivity allowed to reach remote providers; sessions above
+    /// this are forced onto a local provider. `None` = no forced routing.
+    pub max_remote_sensitivity: Option<SensitivityLevel>,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            auto_classify: true,
+            default_level: SensitivityLevel::Normal,
+            rules: Vec::new(),
+            frameworks: vec!["hipaa".into(), "pci-dss".into(), "gdpr".into()],
+            max_remote_sensitivity: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelsConfig {
+    pub default_provider: String,
+    /// Providers tried in order when the default is unavailable or
+    /// non-compliant for the session.
+    pub fallback_providers: Vec<String>,
+    pub providers: HashMap<String, ProviderConfig>,
+}
+
+impl Default for ModelsConfig {
+    fn default() -> Self {
+        Self {
+            default_provider: "anthropic".into(),
+            fallback_providers: Vec::new(),
+            providers: HashMap::new(),
+        }
+    }
+}
+
+/// Hosting region a provider declares for data-residency routing. `Local`
+/// means the provider runs on this machine and satisfies any residency
+/// requirement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Region {
+    Eu,
+    Us,
+    Local,
+}
+
+/// Kind of model provider. `Local` providers (Ollama, llama.cpp, any
+/// OpenAI-compatible local endpoint) never leave the machine and are eligible
+/// as the forced backend for sensitivity levels above
+/// `privacy.max_remote_sensitivity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    #[default]
+    Remote,
+    Local,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderConfig {
+    pub api_key_ref: Option<String>,
+    pub default_model: Option<String>,
+    pub base_url: Option<String>,
+    pub kind: ProviderKind,
+    /// Hosting region for residency routing; `None` means undeclared and
+    /// never satisfies a residency requirement.
+    pub region: Option<Region>,
+    /// Whether the configured model accepts image inputs; gates whether
+    /// inbound image attachments are passed through to the model.
+    pub supports_vision: bool,
+    /// Client-side requests/tokens-per-minute budgets; zero means unlimited.
+    pub rate_limit: ProviderRateLimit,
+    /// Static headers added to every request — enterprise gateway tags like
+    /// `X-Internal-Team`.
+    pub headers: HashMap<String, String>,
+    /// OAuth2 client-credentials flow for gateways that require a bearer
+    /// token on top of (or instead of) the API key.
+    pub oauth: Option<OAuthClientConfig>,
+    /// PEM bundle (certificate + key) for mTLS toward the gateway.
+    pub client_cert_path: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    pub level: String,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".into(),
+        }
+    }
+}
+
+/// Maximum inbound/outbound message size accepted by the gateway.
+pub const MAX_MESSAGE_SIZE_BYTES: usize = 1024 * 1024;
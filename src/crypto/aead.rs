@@ -0,0 +1,129 @@
+//! AES-256-GCM authenticated encryption.
+//!
+//! `encrypt_with_aad` / `decrypt_with_aad` bind ciphertext to a context via
+//! associated data (authenticated but not encrypted): a blob encrypted for
+//! session A cannot be replayed into session B because decryption fails when
+//! the AAD doesn't match. Persisted session blobs use
+//! [`session_blob_aad`] as their context.
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use rand::RngCore;
+
+use crate::error::{Result, SafeClawError};
+
+pub const KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+
+/// AAD binding a persisted session blob to its session and purpose.
+pub fn session_blob_aad(session_id: &str, purpose: &str) -> Vec<u8> {
+    format!("safeclaw:session:{session_id}:{purpose}").into_bytes()
+}
+
+/// Encrypt with associated data. Output layout: `nonce || ciphertext`.
+pub fn encrypt_with_aad(key: &[u8; KEY_SIZE], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| SafeClawError::Crypto(format!("invalid key: {e}")))?;
+    let mut nonce_bytes = [0u8; NONCE_SIZE];
+    rand::rngs::OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(
+            nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|e| SafeClawError::Crypto(format!("encryption failed: {e}")))?;
+
+    let mut out = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+    out.extend_from_slice(&nonce_bytes);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypt a blob produced by [`encrypt_with_aad`]. Fails if the key, the
+/// ciphertext, or the AAD does not match.
+pub fn decrypt_with_aad(key: &[u8; KEY_SIZE], blob: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    if blob.len() < NONCE_SIZE {
+        return Err(SafeClawError::Crypto("ciphertext too short".into()));
+    }
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| SafeClawError::Crypto(format!("invalid key: {e}")))?;
+    let (nonce_bytes, ciphertext) = blob.split_at(NONCE_SIZE);
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce_bytes),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|e| SafeClawError::Crypto(format!("decryption failed: {e}")))
+}
+
+/// Encrypt without context binding. Prefer [`encrypt_with_aad`] for anything
+/// tied to a session or purpose.
+pub fn encrypt(key: &[u8; KEY_SIZE], plaintext: &[u8]) -> Result<Vec<u8>> {
+    encrypt_with_aad(key, plaintext, &[])
+}
+
+/// Counterpart of [`encrypt`].
+pub fn decrypt(key: &[u8; KEY_SIZE], blob: &[u8]) -> Result<Vec<u8>> {
+    decrypt_with_aad(key, blob, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> [u8; KEY_SIZE] {
+        let mut key = [0u8; KEY_SIZE];
+        rand::rngs::OsRng.fill_bytes(&mut key);
+        key
+    }
+
+    #[test]
+    fn matching_aad_roundtrips() {
+        let key = key();
+        let aad = session_blob_aad("session-a", "history");
+        let blob = encrypt_with_aad(&key, b"sensitive state", &aad).unwrap();
+        let plain = decrypt_with_aad(&key, &blob, &aad).unwrap();
+        assert_eq!(plain, b"sensitive state");
+    }
+
+    #[test]
+    fn mismatched_aad_fails_decryption() {
+        let key = key();
+        let blob = encrypt_with_aad(
+            &key,
+            b"sensitive state",
+            &session_blob_aad("session-a", "history"),
+        )
+        .unwrap();
+
+        // Replay into another session.
+        assert!(decrypt_with_aad(&key, &blob, &session_blob_aad("session-b", "history")).is_err());
+        // Same session, different purpose.
+        assert!(decrypt_with_aad(&key, &blob, &session_blob_aad("session-a", "notes")).is_err());
+    }
+
+    #[test]
+    fn tampered_ciphertext_fails() {
+        let key = key();
+        let aad = session_blob_aad("s", "p");
+        let mut blob = encrypt_with_aad(&key, b"data", &aad).unwrap();
+        let last = blob.len() - 1;
+        blob[last] ^= 0x01;
+        assert!(decrypt_with_aad(&key, &blob, &aad).is_err());
+    }
+
+    #[test]
+    fn plain_encrypt_decrypt_roundtrips() {
+        let key = key();
+        let blob = encrypt(&key, b"data").unwrap();
+        assert_eq!(decrypt(&key, &blob).unwrap(), b"data");
+    }
+}
@@ -0,0 +1,56 @@
+//! Instance key pair — long-term identity and signing keys.
+//!
+//! Used only for identity and signatures; session encryption uses ephemeral
+//! X25519 exchange (see `crypto::channel`).
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Result, SafeClawError};
+
+/// Long-term Ed25519 key pair identifying this SafeClaw instance.
+pub struct KeyPair {
+    signing: SigningKey,
+}
+
+impl KeyPair {
+    /// Generate a fresh key pair.
+    pub fn generate() -> Self {
+        Self {
+            signing: SigningKey::generate(&mut OsRng),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        Self {
+            signing: SigningKey::from_bytes(bytes),
+        }
+    }
+
+    pub fn public_key(&self) -> VerifyingKey {
+        self.signing.verifying_key()
+    }
+
+    /// Short hex fingerprint of the public key, suitable for display and for
+    /// identifying the signing key in outbound metadata.
+    pub fn fingerprint(&self) -> String {
+        fingerprint_of(&self.public_key())
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        self.signing.sign(message)
+    }
+}
+
+/// Fingerprint of an arbitrary public key: first 16 hex chars of SHA-256.
+pub fn fingerprint_of(key: &VerifyingKey) -> String {
+    let digest = Sha256::digest(key.as_bytes());
+    hex::encode(&digest[..8])
+}
+
+/// Verify `signature` over `message` against `key`.
+pub fn verify(key: &VerifyingKey, message: &[u8], signature: &Signature) -> Result<()> {
+    key.verify(message, signature)
+        .map_err(|e| SafeClawError::Crypto(format!("signature verification failed: {e}")))
+}
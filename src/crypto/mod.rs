@@ -0,0 +1,5 @@
+//! Cryptographic utilities — instance keys, secure channels, signing.
+
+pub mod aead;
+pub mod keys;
+pub mod signing;
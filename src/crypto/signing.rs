@@ -0,0 +1,121 @@
+//! Optional per-message signatures for agent outputs.
+//!
+//! When enabled, the gateway signs each agent response with the instance key
+//! pair so downstream consumers can verify a message genuinely came from this
+//! SafeClaw instance and wasn't injected by a compromised channel. The
+//! signature and public key fingerprint travel in outbound metadata or,
+//! for plain-text channels, a footer line.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::keys::{self, KeyPair};
+use crate::error::{Result, SafeClawError};
+
+/// Where the signature travels on outbound messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignaturePlacement {
+    /// Structured outbound metadata (channels that support it).
+    #[default]
+    Metadata,
+    /// Appended footer line (plain-text channels).
+    Footer,
+}
+
+/// Configuration for output signing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputSigningConfig {
+    pub enabled: bool,
+    pub placement: SignaturePlacement,
+}
+
+/// Detached signature over a response body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageSignature {
+    /// Base64-encoded Ed25519 signature over the UTF-8 response body.
+    pub signature: String,
+    /// Fingerprint of the signing public key.
+    pub key_fingerprint: String,
+}
+
+impl MessageSignature {
+    /// Footer form: `⊷ safeclaw:<fingerprint>:<signature>`.
+    pub fn footer_line(&self) -> String {
+        format!("⊷ safeclaw:{}:{}", self.key_fingerprint, self.signature)
+    }
+}
+
+/// Sign a response body with the instance key pair.
+pub fn sign_response(keypair: &KeyPair, body: &str) -> MessageSignature {
+    let signature = keypair.sign(body.as_bytes());
+    MessageSignature {
+        signature: BASE64.encode(signature.to_bytes()),
+        key_fingerprint: keypair.fingerprint(),
+    }
+}
+
+/// Verify a response body against a signature and the instance public key.
+pub fn verify_response(
+    key: &VerifyingKey,
+    body: &str,
+    signature: &MessageSignature,
+) -> Result<()> {
+    if signature.key_fingerprint != keys::fingerprint_of(key) {
+        return Err(SafeClawError::Crypto(
+            "signature key fingerprint does not match instance key".into(),
+        ));
+    }
+    let raw = BASE64
+        .decode(&signature.signature)
+        .map_err(|e| SafeClawError::Crypto(format!("invalid signature encoding: {e}")))?;
+    let bytes: [u8; 64] = raw
+        .try_into()
+        .map_err(|_| SafeClawError::Crypto("invalid signature length".into()))?;
+    keys::verify(key, body.as_bytes(), &Signature::from_bytes(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_response_verifies_against_instance_key() {
+        let keypair = KeyPair::generate();
+        let body = "The deployment finished successfully.";
+        let signature = sign_response(&keypair, body);
+
+        assert!(verify_response(&keypair.public_key(), body, &signature).is_ok());
+        assert_eq!(signature.key_fingerprint, keypair.fingerprint());
+    }
+
+    #[test]
+    fn tampered_body_fails_verification() {
+        let keypair = KeyPair::generate();
+        let signature = sign_response(&keypair, "original response");
+
+        assert!(verify_response(&keypair.public_key(), "tampered response", &signature).is_err());
+    }
+
+    #[test]
+    fn wrong_key_fails_verification() {
+        let keypair = KeyPair::generate();
+        let other = KeyPair::generate();
+        let body = "response";
+        let signature = sign_response(&keypair, body);
+
+        assert!(verify_response(&other.public_key(), body, &signature).is_err());
+    }
+
+    #[test]
+    fn footer_line_carries_fingerprint() {
+        let keypair = KeyPair::generate();
+        let signature = sign_response(&keypair, "body");
+        let footer = signature.footer_line();
+        assert!(footer.starts_with("⊷ safeclaw:"));
+        assert!(footer.contains(&keypair.fingerprint()));
+    }
+}
@@ -0,0 +1,35 @@
+//! Error types.
+
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, SafeClawError>;
+
+#[derive(Debug, Error)]
+pub enum SafeClawError {
+    #[error("config error: {0}")]
+    Config(String),
+
+    #[error("channel error: {0}")]
+    Channel(String),
+
+    #[error("session error: {0}")]
+    Session(String),
+
+    #[error("TEE error: {0}")]
+    Tee(String),
+
+    #[error("crypto error: {0}")]
+    Crypto(String),
+
+    #[error("not found: {0}")]
+    NotFound(String),
+
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
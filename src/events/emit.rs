@@ -0,0 +1,380 @@
+//! Agent-emitted custom events.
+//!
+//! The event store only ever saw what SafeClaw itself generated; this gives
+//! agent sessions and scheduled runs an `emit_event` tool so a "check RSS
+//! feeds" task can publish one `new_article` event per finding, and
+//! subscriptions fan those out to chats. Emission is guarded: the type name
+//! must be on a configured allowlist, the payload is size-limited, and —
+//! because a subscription can trigger another agent run that emits again —
+//! every event carries a chain depth that refuses to grow past a cap, so an
+//! automation loop dies out instead of running away. Source attribution
+//! (session, task name, persona) is stamped by SafeClaw, not supplied by
+//! the model, and shows up in the events API.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeClawError};
+use crate::events::{Event, EventStore};
+
+/// Tool name exposed to agent sessions and scheduled runs.
+pub const EMIT_EVENT_TOOL: &str = "emit_event";
+
+/// Category custom events are stored under.
+pub const CUSTOM_EVENT_CATEGORY: &str = "custom";
+
+/// Configuration under `events.emit`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmitEventConfig {
+    pub enabled: bool,
+    /// Event types sessions may emit. Empty means nothing is emittable —
+    /// the operator opts types in explicitly.
+    pub allowed_types: Vec<String>,
+    pub max_payload_bytes: usize,
+    /// How many emit → subscription → emit hops are allowed before an
+    /// automation chain is cut off.
+    pub max_chain_depth: u32,
+}
+
+impl Default for EmitEventConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_types: Vec::new(),
+            max_payload_bytes: 4096,
+            max_chain_depth: 3,
+        }
+    }
+}
+
+/// Who is emitting. Constructed by the runtime from the session or task
+/// context — never from model output. A run triggered by a subscription
+/// inherits the triggering event's `chain_depth` plus one.
+#[derive(Debug, Clone, Default)]
+pub struct EmitSource {
+    pub session_id: String,
+    pub task_name: Option<String>,
+    pub persona: Option<String>,
+    pub chain_depth: u32,
+}
+
+/// The structured `detail` stored with a custom event. `chain_depth` is
+/// read back when a subscription-triggered run emits in turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmittedDetail {
+    pub payload: serde_json::Value,
+    pub session_id: String,
+    pub task_name: Option<String>,
+    pub persona: Option<String>,
+    pub chain_depth: u32,
+}
+
+/// Fans a matched event out to one chat — the real implementation sends
+/// through the channel adapters.
+#[async_trait]
+pub trait EventDelivery: Send + Sync {
+    async fn deliver(&self, chat_key: &str, message: &str) -> Result<()>;
+}
+
+/// One subscription: events of `event_type` are delivered to these chats.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventSubscription {
+    pub event_type: String,
+    pub deliver_to: Vec<String>,
+}
+
+/// Validates, stores and fans out custom events.
+pub struct EventEmitter {
+    config: EmitEventConfig,
+    store: Arc<EventStore>,
+    subscriptions: Vec<EventSubscription>,
+    delivery: Arc<dyn EventDelivery>,
+}
+
+impl EventEmitter {
+    pub fn new(
+        config: EmitEventConfig,
+        store: Arc<EventStore>,
+        subscriptions: Vec<EventSubscription>,
+        delivery: Arc<dyn EventDelivery>,
+    ) -> Self {
+        Self {
+            config,
+            store,
+            subscriptions,
+            delivery,
+        }
+    }
+
+    fn attribution(source: &EmitSource) -> String {
+        let mut parts = vec![format!("session:{}", source.session_id)];
+        if let Some(task) = &source.task_name {
+            parts.push(format!("task:{task}"));
+        }
+        if let Some(persona) = &source.persona {
+            parts.push(format!("persona:{persona}"));
+        }
+        parts.join(" ")
+    }
+
+    /// Validate and publish one event, then deliver it to any subscribed
+    /// chats. Returns the stored event.
+    pub async fn emit(
+        &self,
+        source: &EmitSource,
+        event_type: &str,
+        summary: &str,
+        payload: serde_json::Value,
+        now: i64,
+    ) -> Result<Event> {
+        if !self.config.enabled {
+            return Err(SafeClawError::Config("event emission is disabled".into()));
+        }
+        if !self.config.allowed_types.iter().any(|t| t == event_type) {
+            return Err(SafeClawError::Unauthorized(format!(
+                "event type `{event_type}` is not on the emit allowlist"
+            )));
+        }
+        let payload_bytes = serde_json::to_vec(&payload)?.len();
+        if payload_bytes > self.config.max_payload_bytes {
+            return Err(SafeClawError::Config(format!(
+                "event payload is {payload_bytes} bytes; limit is {}",
+                self.config.max_payload_bytes
+            )));
+        }
+        if source.chain_depth >= self.config.max_chain_depth {
+            return Err(SafeClawError::Config(format!(
+                "event chain depth {} reached the limit of {}; refusing to \
+                 extend the automation chain",
+                source.chain_depth, self.config.max_chain_depth
+            )));
+        }
+
+        let detail = EmittedDetail {
+            payload,
+            session_id: source.session_id.clone(),
+            task_name: source.task_name.clone(),
+            persona: source.persona.clone(),
+            chain_depth: source.chain_depth,
+        };
+        let event = self.store.create(
+            CUSTOM_EVENT_CATEGORY,
+            event_type,
+            summary,
+            &serde_json::to_string(&detail)?,
+            &Self::attribution(source),
+            now,
+        );
+
+        for subscription in &self.subscriptions {
+            if subscription.event_type != event_type {
+                continue;
+            }
+            for chat_key in &subscription.deliver_to {
+                if let Err(e) = self.delivery.deliver(chat_key, summary).await {
+                    tracing::warn!(chat_key, error = %e, "event delivery failed");
+                }
+            }
+        }
+        Ok(event)
+    }
+
+    /// Handle one `emit_event` tool call. Arguments are the model-supplied
+    /// `{"type", "summary", "payload"}` object; everything else comes from
+    /// the trusted `source`.
+    pub async fn handle_tool_call(
+        &self,
+        source: &EmitSource,
+        arguments: &serde_json::Value,
+        now: i64,
+    ) -> Result<Event> {
+        let event_type = arguments["type"]
+            .as_str()
+            .ok_or_else(|| SafeClawError::Config("emit_event requires a `type`".into()))?;
+        let summary = arguments["summary"]
+            .as_str()
+            .ok_or_else(|| SafeClawError::Config("emit_event requires a `summary`".into()))?;
+        let payload = arguments
+            .get("payload")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        self.emit(source, event_type, summary, payload, now).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[derive(Default)]
+    struct MockDelivery {
+        sent: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl EventDelivery for MockDelivery {
+        async fn deliver(&self, chat_key: &str, message: &str) -> Result<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((chat_key.to_string(), message.to_string()));
+            Ok(())
+        }
+    }
+
+    fn emitter(
+        config: EmitEventConfig,
+        subscriptions: Vec<EventSubscription>,
+    ) -> (EventEmitter, Arc<EventStore>, Arc<MockDelivery>) {
+        let store = Arc::new(EventStore::default());
+        let delivery = Arc::new(MockDelivery::default());
+        let emitter = EventEmitter::new(
+            config,
+            Arc::clone(&store),
+            subscriptions,
+            Arc::clone(&delivery) as Arc<dyn EventDelivery>,
+        );
+        (emitter, store, delivery)
+    }
+
+    fn rss_config() -> EmitEventConfig {
+        EmitEventConfig {
+            allowed_types: vec!["new_article".into()],
+            ..Default::default()
+        }
+    }
+
+    fn task_source() -> EmitSource {
+        EmitSource {
+            session_id: "sched-rss".into(),
+            task_name: Some("check-rss-feeds".into()),
+            persona: Some("news-bot".into()),
+            chain_depth: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn a_task_emitted_event_reaches_subscribed_chats_with_attribution() {
+        let (emitter, store, delivery) = emitter(
+            rss_config(),
+            vec![EventSubscription {
+                event_type: "new_article".into(),
+                deliver_to: vec!["telegram:123".into(), "matrix:!room".into()],
+            }],
+        );
+
+        let event = emitter
+            .emit(
+                &task_source(),
+                "new_article",
+                "New article: Rust 2.0 announced",
+                serde_json::json!({"url": "https://example.com/rust-2"}),
+                NOW,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(event.category, CUSTOM_EVENT_CATEGORY);
+        assert_eq!(event.topic, "new_article");
+        assert_eq!(event.source, "session:sched-rss task:check-rss-feeds persona:news-bot");
+        // Visible through the events API.
+        assert_eq!(store.list(Some(CUSTOM_EVENT_CATEGORY)).len(), 1);
+        // Fanned out to both subscribed chats.
+        let sent = delivery.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].0, "telegram:123");
+        assert!(sent[0].1.contains("Rust 2.0"));
+    }
+
+    #[tokio::test]
+    async fn an_unlisted_type_is_rejected() {
+        let (emitter, store, _) = emitter(rss_config(), Vec::new());
+        let err = emitter
+            .emit(&task_source(), "rm_rf", "boom", serde_json::Value::Null, NOW)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::Unauthorized(_)));
+        assert!(store.list(None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn an_oversized_payload_is_rejected() {
+        let (emitter, _, _) = emitter(
+            EmitEventConfig {
+                max_payload_bytes: 64,
+                ..rss_config()
+            },
+            Vec::new(),
+        );
+        let err = emitter
+            .emit(
+                &task_source(),
+                "new_article",
+                "big",
+                serde_json::json!({"body": "x".repeat(200)}),
+                NOW,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("limit is 64"));
+    }
+
+    #[tokio::test]
+    async fn the_chain_depth_guard_stops_runaway_automation() {
+        let (emitter, store, _) = emitter(rss_config(), Vec::new());
+        let mut source = task_source();
+
+        // Two hops deep is still fine under the default limit of three...
+        source.chain_depth = 2;
+        emitter
+            .emit(&source, "new_article", "hop", serde_json::Value::Null, NOW)
+            .await
+            .unwrap();
+
+        // ...but the run triggered by that event inherits depth 3 and is cut.
+        source.chain_depth = 3;
+        let err = emitter
+            .emit(&source, "new_article", "hop", serde_json::Value::Null, NOW)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("chain depth"));
+        assert_eq!(store.list(None).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn the_tool_call_shape_is_parsed_and_depth_round_trips() {
+        let (emitter, store, _) = emitter(rss_config(), Vec::new());
+        emitter
+            .handle_tool_call(
+                &task_source(),
+                &serde_json::json!({
+                    "type": "new_article",
+                    "summary": "New article",
+                    "payload": {"url": "https://example.com"},
+                }),
+                NOW,
+            )
+            .await
+            .unwrap();
+
+        let stored = &store.list(None)[0];
+        let detail: EmittedDetail = serde_json::from_str(&stored.detail).unwrap();
+        assert_eq!(detail.chain_depth, 0);
+        assert_eq!(detail.task_name.as_deref(), Some("check-rss-feeds"));
+        assert_eq!(detail.payload["url"], "https://example.com");
+
+        let err = emitter
+            .handle_tool_call(&task_source(), &serde_json::json!({"summary": "no type"}), NOW)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("requires a `type`"));
+    }
+}
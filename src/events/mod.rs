@@ -0,0 +1,198 @@
+//! Events module — user-visible event store backing `/api/v1/events`.
+
+pub mod emit;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Event retention: events older than the TTL are pruned by a periodic
+/// sweeper so the store stays bounded. The default can be overridden per
+/// event category; a zero TTL disables pruning for that category.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventRetentionConfig {
+    pub default_ttl_secs: i64,
+    pub per_category_ttl_secs: HashMap<String, i64>,
+    /// How often the sweeper runs.
+    pub sweep_interval_secs: u64,
+}
+
+impl Default for EventRetentionConfig {
+    fn default() -> Self {
+        Self {
+            default_ttl_secs: 30 * 24 * 3600,
+            per_category_ttl_secs: HashMap::new(),
+            sweep_interval_secs: 3600,
+        }
+    }
+}
+
+impl EventRetentionConfig {
+    fn ttl_for(&self, category: &str) -> i64 {
+        self.per_category_ttl_secs
+            .get(category)
+            .copied()
+            .unwrap_or(self.default_ttl_secs)
+    }
+}
+
+/// One event visible in the events API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub id: String,
+    pub category: String,
+    pub topic: String,
+    pub summary: String,
+    pub detail: String,
+    pub source: String,
+    pub timestamp: i64,
+}
+
+/// In-memory event store with a capacity cap.
+pub struct EventStore {
+    max_events: usize,
+    retention: EventRetentionConfig,
+    events: Mutex<Vec<Event>>,
+    next_id: Mutex<u64>,
+}
+
+impl EventStore {
+    pub fn new(max_events: usize) -> Self {
+        Self::with_retention(max_events, EventRetentionConfig::default())
+    }
+
+    pub fn with_retention(max_events: usize, retention: EventRetentionConfig) -> Self {
+        Self {
+            max_events,
+            retention,
+            events: Mutex::new(Vec::new()),
+            next_id: Mutex::new(0),
+        }
+    }
+
+    pub fn create(
+        &self,
+        category: &str,
+        topic: &str,
+        summary: &str,
+        detail: &str,
+        source: &str,
+        timestamp: i64,
+    ) -> Event {
+        let id = {
+            let mut next = self.next_id.lock().expect("event store poisoned");
+            *next += 1;
+            format!("ev-{}", *next)
+        };
+        let event = Event {
+            id,
+            category: category.to_string(),
+            topic: topic.to_string(),
+            summary: summary.to_string(),
+            detail: detail.to_string(),
+            source: source.to_string(),
+            timestamp,
+        };
+        let mut events = self.events.lock().expect("event store poisoned");
+        events.push(event.clone());
+        let overflow = events.len().saturating_sub(self.max_events);
+        if overflow > 0 {
+            events.drain(..overflow);
+        }
+        event
+    }
+
+    /// Drop events older than their category's TTL. Returns how many were
+    /// pruned. Called by the periodic sweeper.
+    pub fn prune_expired(&self, now: i64) -> usize {
+        let mut events = self.events.lock().expect("event store poisoned");
+        let before = events.len();
+        events.retain(|e| {
+            let ttl = self.retention.ttl_for(&e.category);
+            ttl <= 0 || now - e.timestamp < ttl
+        });
+        before - events.len()
+    }
+
+    /// Spawn the retention sweeper on the given store.
+    pub fn spawn_periodic_sweeper(store: std::sync::Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let interval = std::time::Duration::from_secs(store.retention.sweep_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0);
+                store.prune_expired(now);
+            }
+        })
+    }
+
+    /// Events filtered by category, newest first.
+    pub fn list(&self, category: Option<&str>) -> Vec<Event> {
+        let events = self.events.lock().expect("event store poisoned");
+        events
+            .iter()
+            .rev()
+            .filter(|e| category.map(|c| e.category == c).unwrap_or(true))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for EventStore {
+    fn default() -> Self {
+        Self::new(10_000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_events_are_pruned_and_fresh_ones_survive() {
+        let store = EventStore::with_retention(
+            100,
+            EventRetentionConfig {
+                default_ttl_secs: 3600,
+                ..Default::default()
+            },
+        );
+        store.create("alert", "t", "old", "", "test", 0);
+        store.create("alert", "t", "fresh", "", "test", 3_000);
+
+        assert_eq!(store.prune_expired(3_700), 1);
+        let remaining = store.list(None);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].summary, "fresh");
+    }
+
+    #[test]
+    fn per_category_ttl_overrides_the_default() {
+        let store = EventStore::with_retention(
+            100,
+            EventRetentionConfig {
+                default_ttl_secs: 3600,
+                per_category_ttl_secs: HashMap::from([
+                    ("audit".to_string(), 0),  // never pruned
+                    ("debug".to_string(), 60), // short-lived
+                ]),
+                ..Default::default()
+            },
+        );
+        store.create("audit", "t", "kept forever", "", "test", 0);
+        store.create("debug", "t", "short lived", "", "test", 0);
+        store.create("alert", "t", "default ttl", "", "test", 0);
+
+        assert_eq!(store.prune_expired(100), 1);
+        let remaining = store.list(None);
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().all(|e| e.category != "debug"));
+    }
+}
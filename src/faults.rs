@@ -0,0 +1,209 @@
+//! Deterministic failure injection for resilience testing.
+//!
+//! Compiled only with the `fault-injection` feature — never in release
+//! builds. Rules make channel sends, LLM calls, or TEE operations fail with
+//! a chosen error type at a chosen rate, so retry, circuit-breaker, and
+//! dead-letter paths can be exercised in integration tests and staging
+//! without flaky randomness: rates are applied by accumulator, so 0.25
+//! means exactly every fourth operation fails, in a stable pattern.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeClawError};
+
+/// Operation classes faults can target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultTarget {
+    ChannelSend,
+    LlmCall,
+    TeeOperation,
+}
+
+/// Which error an injected failure surfaces as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultError {
+    Channel,
+    Session,
+    Tee,
+    Io,
+}
+
+impl FaultError {
+    fn build(self, target: FaultTarget) -> SafeClawError {
+        let message = format!("injected fault ({target:?})");
+        match self {
+            Self::Channel => SafeClawError::Channel(message),
+            Self::Session => SafeClawError::Session(message),
+            Self::Tee => SafeClawError::Tee(message),
+            Self::Io => SafeClawError::Io(std::io::Error::other(message)),
+        }
+    }
+}
+
+/// One injection rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaultRule {
+    pub target: FaultTarget,
+    /// Fraction of operations that fail, 0.0–1.0.
+    pub rate: f64,
+    pub error: FaultError,
+}
+
+#[derive(Default)]
+struct TargetState {
+    /// Fractional-failure accumulator; crossing 1.0 fails the operation.
+    accumulator: f64,
+    operations: u64,
+    injected: u64,
+}
+
+/// Injection counts per target, for assertions and the staging dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultStats {
+    pub operations: u64,
+    pub injected: u64,
+}
+
+/// Evaluates injection rules. Call [`FaultInjector::check`] at the top of
+/// an instrumented operation; an `Err` is the injected failure.
+pub struct FaultInjector {
+    rules: HashMap<FaultTarget, FaultRule>,
+    state: Mutex<HashMap<FaultTarget, TargetState>>,
+}
+
+impl FaultInjector {
+    pub fn new(rules: Vec<FaultRule>) -> Self {
+        Self {
+            rules: rules.into_iter().map(|r| (r.target, r)).collect(),
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// No rules — every check passes. Lets instrumented call sites keep an
+    /// unconditional injector reference.
+    pub fn disabled() -> Self {
+        Self::new(Vec::new())
+    }
+
+    pub fn check(&self, target: FaultTarget) -> Result<()> {
+        let mut state = self.state.lock().expect("fault injector poisoned");
+        let entry = state.entry(target).or_default();
+        entry.operations += 1;
+        let Some(rule) = self.rules.get(&target) else {
+            return Ok(());
+        };
+        entry.accumulator += rule.rate.clamp(0.0, 1.0);
+        if entry.accumulator >= 1.0 {
+            entry.accumulator -= 1.0;
+            entry.injected += 1;
+            return Err(rule.error.build(target));
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self, target: FaultTarget) -> FaultStats {
+        self.state
+            .lock()
+            .expect("fault injector poisoned")
+            .get(&target)
+            .map(|s| FaultStats {
+                operations: s.operations,
+                injected: s.injected,
+            })
+            .unwrap_or(FaultStats {
+                operations: 0,
+                injected: 0,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::channels::adapter::{deliver_with_retry, PlatformBackend};
+    use crate::channels::conformance::RecordingBackend;
+
+    fn injector(target: FaultTarget, rate: f64, error: FaultError) -> FaultInjector {
+        FaultInjector::new(vec![FaultRule {
+            target,
+            rate,
+            error,
+        }])
+    }
+
+    #[test]
+    fn configured_rate_yields_the_exact_proportion_of_failures() {
+        let injector = injector(FaultTarget::LlmCall, 0.25, FaultError::Session);
+        let failures = (0..100)
+            .filter(|_| injector.check(FaultTarget::LlmCall).is_err())
+            .count();
+        assert_eq!(failures, 25);
+        let stats = injector.stats(FaultTarget::LlmCall);
+        assert_eq!(stats.operations, 100);
+        assert_eq!(stats.injected, 25);
+    }
+
+    #[test]
+    fn failures_surface_as_the_configured_error_type() {
+        let injector = injector(FaultTarget::TeeOperation, 1.0, FaultError::Tee);
+        assert!(matches!(
+            injector.check(FaultTarget::TeeOperation),
+            Err(SafeClawError::Tee(_))
+        ));
+        // Untargeted operations are never touched.
+        assert!(injector.check(FaultTarget::ChannelSend).is_ok());
+    }
+
+    #[test]
+    fn the_failure_pattern_is_deterministic() {
+        let a = injector(FaultTarget::ChannelSend, 0.3, FaultError::Channel);
+        let b = injector(FaultTarget::ChannelSend, 0.3, FaultError::Channel);
+        for _ in 0..50 {
+            assert_eq!(
+                a.check(FaultTarget::ChannelSend).is_err(),
+                b.check(FaultTarget::ChannelSend).is_err()
+            );
+        }
+    }
+
+    /// Backend whose deliveries consult the injector — the shape production
+    /// instrumentation takes.
+    struct InjectedBackend {
+        injector: FaultInjector,
+        inner: RecordingBackend,
+    }
+
+    #[async_trait::async_trait]
+    impl PlatformBackend for InjectedBackend {
+        async fn deliver(&self, chat_id: &str, content: &str) -> Result<()> {
+            self.injector.check(FaultTarget::ChannelSend)?;
+            self.inner.deliver(chat_id, content).await
+        }
+    }
+
+    #[tokio::test]
+    async fn the_retry_path_absorbs_injected_send_failures() {
+        // Rate 0.5 fails every second delivery; with one retry per send,
+        // every logical message still goes through.
+        let backend = Arc::new(InjectedBackend {
+            injector: injector(FaultTarget::ChannelSend, 0.5, FaultError::Channel),
+            inner: RecordingBackend::default(),
+        });
+        for i in 0..4 {
+            deliver_with_retry(backend.as_ref(), "c1", &format!("msg {i}"))
+                .await
+                .expect("retry must absorb the injected failure");
+        }
+        assert_eq!(backend.inner.sent.lock().unwrap().len(), 4);
+        // Pattern is pass/fail alternating; the first message needs no
+        // retry, the other three each absorb one injected failure.
+        assert_eq!(backend.injector.stats(FaultTarget::ChannelSend).injected, 3);
+    }
+}
@@ -0,0 +1,157 @@
+//! Inbound content policy — prohibited-topic filtering before the LLM.
+//!
+//! Distinct from privacy classification: some deployments must refuse whole
+//! content categories (e.g. requests for illegal activity) for liability and
+//! cost, before a single generation token is spent. Category rules match by
+//! keyword or regex; on match the configured refusal is returned, generation
+//! is short-circuited, and the attempt is audited.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Audit category for refused prohibited-topic messages.
+pub const AUDIT_CATEGORY_PROHIBITED: &str = "prohibited_topic";
+
+/// One prohibited-content category.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRule {
+    /// Category name, recorded in the audit entry.
+    pub category: String,
+    /// Case-insensitive substring matches.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Regex patterns; invalid patterns are skipped at match time.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Per-category refusal text, falling back to the policy default.
+    #[serde(default)]
+    pub refusal: Option<String>,
+}
+
+/// The configured content policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentPolicy {
+    pub enabled: bool,
+    pub rules: Vec<CategoryRule>,
+    /// Refusal sent to the user when no per-category override is set.
+    pub default_refusal: String,
+}
+
+impl Default for ContentPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: Vec::new(),
+            default_refusal: "I can't help with that request.".into(),
+        }
+    }
+}
+
+/// Outcome of checking one inbound message against the policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    /// No rule matched — proceed to generation.
+    Allow,
+    /// A category matched — send `refusal` and skip the LLM entirely.
+    Refuse { category: String, refusal: String },
+}
+
+impl ContentPolicy {
+    /// Check inbound content. First matching rule wins, in config order.
+    pub fn check(&self, content: &str) -> PolicyDecision {
+        if !self.enabled {
+            return PolicyDecision::Allow;
+        }
+        let lowered = content.to_lowercase();
+        for rule in &self.rules {
+            let keyword_hit = rule
+                .keywords
+                .iter()
+                .any(|kw| lowered.contains(&kw.to_lowercase()));
+            let pattern_hit = rule
+                .patterns
+                .iter()
+                .filter_map(|p| Regex::new(p).ok())
+                .any(|re| re.is_match(content));
+            if keyword_hit || pattern_hit {
+                return PolicyDecision::Refuse {
+                    category: rule.category.clone(),
+                    refusal: rule
+                        .refusal
+                        .clone()
+                        .unwrap_or_else(|| self.default_refusal.clone()),
+                };
+            }
+        }
+        PolicyDecision::Allow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ContentPolicy {
+        ContentPolicy {
+            enabled: true,
+            rules: vec![CategoryRule {
+                category: "illegal_activity".into(),
+                keywords: vec!["counterfeit currency".into()],
+                patterns: vec![r"(?i)\bhow to pick (a|the) lock\b".into()],
+                refusal: Some("That topic isn't something I can assist with.".into()),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn prohibited_topic_is_refused_before_generation() {
+        let decision = policy().check("where can I buy Counterfeit Currency?");
+        assert_eq!(
+            decision,
+            PolicyDecision::Refuse {
+                category: "illegal_activity".into(),
+                refusal: "That topic isn't something I can assist with.".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn regex_rules_match_too() {
+        assert!(matches!(
+            policy().check("How to pick the lock on my neighbour's door"),
+            PolicyDecision::Refuse { .. }
+        ));
+    }
+
+    #[test]
+    fn allowed_content_proceeds() {
+        assert_eq!(
+            policy().check("help me plan a birthday party"),
+            PolicyDecision::Allow
+        );
+    }
+
+    #[test]
+    fn disabled_policy_allows_everything() {
+        let mut policy = policy();
+        policy.enabled = false;
+        assert_eq!(
+            policy.check("counterfeit currency"),
+            PolicyDecision::Allow
+        );
+    }
+
+    #[test]
+    fn default_refusal_applies_without_override() {
+        let mut policy = policy();
+        policy.rules[0].refusal = None;
+        match policy.check("counterfeit currency") {
+            PolicyDecision::Refuse { refusal, .. } => {
+                assert_eq!(refusal, policy.default_refusal);
+            }
+            other => panic!("expected refusal, got {other:?}"),
+        }
+    }
+}
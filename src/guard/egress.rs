@@ -0,0 +1,170 @@
+//! Egress data-volume tracking on the outbound network path.
+//!
+//! The firewall already decides *whether* a connection is allowed; this
+//! module watches *how much* each session sends to external hosts. A session
+//! whose egress volume to a destination exceeds the configured threshold
+//! within the time window raises an [`Alert`] — the signature of an agent
+//! manipulated into dumping data.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::alerting::{Alert, AlertKind, AlertMonitor};
+
+/// Configuration under `firewall.egress_alert`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EgressAlertConfig {
+    pub enabled: bool,
+    /// Bytes a session may send to one destination within the window before
+    /// an alert is raised.
+    pub threshold_bytes: u64,
+    /// Sliding window length in seconds.
+    pub window_secs: i64,
+}
+
+impl Default for EgressAlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_bytes: 10 * 1024 * 1024,
+            window_secs: 300,
+        }
+    }
+}
+
+/// `(session, destination)` → (timestamp, bytes) samples.
+type FlowSamples = HashMap<(String, String), Vec<(i64, u64)>>;
+
+/// Tracks bytes sent per `(session, destination)` in a sliding window.
+pub struct EgressTracker {
+    config: EgressAlertConfig,
+    /// Samples currently inside the window.
+    samples: Mutex<FlowSamples>,
+    /// Keys already alerted in the current window, to avoid alert storms.
+    alerted: Mutex<HashMap<(String, String), i64>>,
+}
+
+impl EgressTracker {
+    pub fn new(config: EgressAlertConfig) -> Self {
+        Self {
+            config,
+            samples: Mutex::new(HashMap::new()),
+            alerted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record `bytes` sent by `session_id` to `destination` at `now` (unix
+    /// seconds). Raises an egress-volume alert on the monitor when the
+    /// windowed total crosses the threshold.
+    pub fn record(
+        &self,
+        session_id: &str,
+        destination: &str,
+        bytes: u64,
+        now: i64,
+        monitor: &AlertMonitor,
+    ) {
+        if !self.config.enabled {
+            return;
+        }
+        let key = (session_id.to_string(), destination.to_string());
+        let total = {
+            let mut samples = self.samples.lock().expect("egress tracker poisoned");
+            let entry = samples.entry(key.clone()).or_default();
+            entry.retain(|(t, _)| now - t < self.config.window_secs);
+            entry.push((now, bytes));
+            entry.iter().map(|(_, b)| b).sum::<u64>()
+        };
+
+        if total > self.config.threshold_bytes {
+            let mut alerted = self.alerted.lock().expect("egress tracker poisoned");
+            let recently = alerted
+                .get(&key)
+                .map(|t| now - t < self.config.window_secs)
+                .unwrap_or(false);
+            if !recently {
+                alerted.insert(key, now);
+                monitor.raise(Alert {
+                    kind: AlertKind::EgressVolume,
+                    session_id: session_id.to_string(),
+                    message: format!(
+                        "session sent {total} bytes to {destination} within {}s \
+                         (threshold {})",
+                        self.config.window_secs, self.config.threshold_bytes
+                    ),
+                    timestamp: now,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(threshold: u64, window: i64) -> EgressTracker {
+        EgressTracker::new(EgressAlertConfig {
+            enabled: true,
+            threshold_bytes: threshold,
+            window_secs: window,
+        })
+    }
+
+    #[test]
+    fn crossing_threshold_raises_alert() {
+        let tracker = tracker(1000, 60);
+        let monitor = AlertMonitor::default();
+
+        tracker.record("s1", "api.example.com", 600, 100, &monitor);
+        assert!(monitor.recent(10).is_empty());
+
+        tracker.record("s1", "api.example.com", 600, 110, &monitor);
+        let alerts = monitor.recent(10);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, AlertKind::EgressVolume);
+        assert_eq!(alerts[0].session_id, "s1");
+    }
+
+    #[test]
+    fn normal_volume_produces_no_alert() {
+        let tracker = tracker(1_000_000, 60);
+        let monitor = AlertMonitor::default();
+        for i in 0..100 {
+            tracker.record("s1", "api.example.com", 100, i, &monitor);
+        }
+        assert!(monitor.recent(10).is_empty());
+    }
+
+    #[test]
+    fn window_expiry_resets_the_count() {
+        let tracker = tracker(1000, 60);
+        let monitor = AlertMonitor::default();
+        tracker.record("s1", "host", 900, 0, &monitor);
+        // Outside the window — earlier sample no longer counts.
+        tracker.record("s1", "host", 900, 120, &monitor);
+        assert!(monitor.recent(10).is_empty());
+    }
+
+    #[test]
+    fn volume_is_tracked_per_destination() {
+        let tracker = tracker(1000, 60);
+        let monitor = AlertMonitor::default();
+        tracker.record("s1", "a.example.com", 800, 0, &monitor);
+        tracker.record("s1", "b.example.com", 800, 1, &monitor);
+        assert!(monitor.recent(10).is_empty());
+    }
+
+    #[test]
+    fn repeated_overflow_alerts_once_per_window() {
+        let tracker = tracker(100, 60);
+        let monitor = AlertMonitor::default();
+        tracker.record("s1", "host", 200, 0, &monitor);
+        tracker.record("s1", "host", 200, 1, &monitor);
+        tracker.record("s1", "host", 200, 2, &monitor);
+        assert_eq!(monitor.recent(10).len(), 1);
+    }
+}
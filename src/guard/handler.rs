@@ -0,0 +1,44 @@
+//! Guard REST API — quarantine review endpoints.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::json;
+
+use crate::guard::quarantine::QuarantineStore;
+
+/// Routes mounted under `/api/quarantine`.
+pub fn quarantine_routes(store: Arc<QuarantineStore>) -> Router {
+    Router::new()
+        .route("/", get(list_quarantine))
+        .route("/:id/release", post(release_quarantine))
+        .with_state(store)
+}
+
+/// `GET /api/quarantine` — list held messages, newest first.
+async fn list_quarantine(
+    State(store): State<Arc<QuarantineStore>>,
+) -> Json<serde_json::Value> {
+    Json(json!({ "messages": store.list().await }))
+}
+
+/// `POST /api/quarantine/:id/release` — operator override for a false
+/// positive. The released message is handed back to the runtime for
+/// re-injection into its original pipeline.
+async fn release_quarantine(
+    State(store): State<Arc<QuarantineStore>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match store.release(&id).await {
+        Ok(message) => (StatusCode::OK, Json(json!({ "released": message }))),
+        Err(e) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": { "code": "quarantine_release_failed", "message": e.to_string() }
+            })),
+        ),
+    }
+}
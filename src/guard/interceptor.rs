@@ -0,0 +1,136 @@
+//! Tool call interception.
+//!
+//! Every tool call the agent makes passes through the [`ToolInterceptor`]
+//! before execution and reports usage after. The interceptor is where
+//! cross-cutting per-session enforcement lives — currently resource budgets
+//! ([`ResourceAccountant`]); blocked calls return a user-visible reason and
+//! are audited.
+
+use std::sync::Arc;
+
+use crate::audit::log::{AuditLog, AuditSeverity};
+use crate::guard::resources::{ResourceAccountant, AUDIT_CATEGORY_RESOURCES};
+
+/// Decision for one tool call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolDecision {
+    Allow,
+    /// The call must not run; `reason` is surfaced to the agent in place of
+    /// the tool result.
+    Block { reason: String },
+}
+
+pub struct ToolInterceptor {
+    resources: Arc<ResourceAccountant>,
+    audit: Arc<AuditLog>,
+}
+
+impl ToolInterceptor {
+    pub fn new(resources: Arc<ResourceAccountant>, audit: Arc<AuditLog>) -> Self {
+        Self { resources, audit }
+    }
+
+    /// Gate one tool call before it executes.
+    pub fn before_tool_call(&self, session_id: &str, tool: &str) -> ToolDecision {
+        if self.resources.is_heavy(tool) && self.resources.is_over_budget(session_id) {
+            return ToolDecision::Block {
+                reason: format!(
+                    "tool '{tool}' blocked: this session has exhausted its host \
+                     resource budget"
+                ),
+            };
+        }
+        ToolDecision::Allow
+    }
+
+    /// Report resource usage of a completed tool call. The call that pushes
+    /// the session over budget produces one Warning audit entry.
+    pub fn after_tool_call(
+        &self,
+        session_id: &str,
+        tool: &str,
+        cpu_ms: u64,
+        peak_memory_bytes: u64,
+        now: i64,
+    ) {
+        if let Some(breach) = self.resources.record(session_id, cpu_ms, peak_memory_bytes) {
+            self.audit.record(
+                "interceptor",
+                session_id,
+                AuditSeverity::Warning,
+                AUDIT_CATEGORY_RESOURCES,
+                &format!("{breach} (last tool: {tool})"),
+                now,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::log::AuditIngestionConfig;
+    use crate::guard::resources::ResourceLimitsConfig;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn interceptor(max_cpu_ms: u64) -> ToolInterceptor {
+        ToolInterceptor::new(
+            Arc::new(ResourceAccountant::new(ResourceLimitsConfig {
+                max_cpu_ms,
+                ..Default::default()
+            })),
+            Arc::new(AuditLog::new(AuditIngestionConfig::default())),
+        )
+    }
+
+    #[test]
+    fn over_budget_session_keeps_light_tools_and_loses_heavy_ones() {
+        let interceptor = interceptor(1_000);
+        assert_eq!(
+            interceptor.before_tool_call("s1", "bash"),
+            ToolDecision::Allow
+        );
+        interceptor.after_tool_call("s1", "bash", 2_000, 0, NOW);
+
+        assert!(matches!(
+            interceptor.before_tool_call("s1", "bash"),
+            ToolDecision::Block { .. }
+        ));
+        assert!(matches!(
+            interceptor.before_tool_call("s1", "file_write"),
+            ToolDecision::Block { .. }
+        ));
+        // Light operations continue.
+        assert_eq!(
+            interceptor.before_tool_call("s1", "file_read"),
+            ToolDecision::Allow
+        );
+        // Other sessions are unaffected.
+        assert_eq!(
+            interceptor.before_tool_call("s2", "bash"),
+            ToolDecision::Allow
+        );
+    }
+
+    #[test]
+    fn budget_breach_is_audited_with_the_offending_tool() {
+        let audit = Arc::new(AuditLog::new(AuditIngestionConfig::default()));
+        let interceptor = ToolInterceptor::new(
+            Arc::new(ResourceAccountant::new(ResourceLimitsConfig {
+                max_cpu_ms: 1_000,
+                ..Default::default()
+            })),
+            audit.clone(),
+        );
+        interceptor.after_tool_call("s1", "bash", 2_000, 0, NOW);
+        let events = audit.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].severity, AuditSeverity::Warning);
+        assert_eq!(events[0].category, AUDIT_CATEGORY_RESOURCES);
+        assert!(events[0].message.contains("bash"));
+        // Subsequent usage on the same exhausted session doesn't re-audit.
+        interceptor.after_tool_call("s1", "bash", 10, 0, NOW);
+        assert_eq!(audit.events().len(), 1);
+    }
+}
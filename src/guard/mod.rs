@@ -0,0 +1,11 @@
+//! Core protection pipeline — taint tracking, sanitization, interception,
+//! injection defense, firewalling, session isolation.
+
+pub mod canary;
+pub mod content_policy;
+pub mod egress;
+pub mod handler;
+pub mod interceptor;
+pub mod quarantine;
+pub mod resources;
+pub mod taint;
@@ -0,0 +1,238 @@
+//! Per-session host resource accounting and limits.
+//!
+//! LLM cost limits don't protect the host: one session driving Bash or file
+//! tools can eat CPU and memory that every other session on the box shares.
+//! This module accumulates tool CPU time and observed peak memory per
+//! session; once a session crosses its budget, further resource-intensive
+//! tool calls are blocked (light operations continue) and the crossing is
+//! audited. Enforcement happens in the [`ToolInterceptor`].
+//!
+//! [`ToolInterceptor`]: crate::guard::interceptor::ToolInterceptor
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Audit category for sessions that exceed their resource budget.
+pub const AUDIT_CATEGORY_RESOURCES: &str = "resource_limit";
+
+fn default_heavy_tools() -> Vec<String> {
+    ["bash", "exec", "file_write", "web_fetch"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Configuration under `guard.resource_limits`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimitsConfig {
+    pub enabled: bool,
+    /// Cumulative tool CPU time a session may consume, in milliseconds.
+    pub max_cpu_ms: u64,
+    /// Peak memory a single tool invocation may reach, in bytes.
+    pub max_memory_bytes: u64,
+    /// Tools considered resource-intensive; these are blocked once the
+    /// session is over budget. Everything else stays available.
+    pub heavy_tools: Vec<String>,
+}
+
+impl Default for ResourceLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_cpu_ms: 60_000,
+            max_memory_bytes: 512 * 1024 * 1024,
+            heavy_tools: default_heavy_tools(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct SessionUsage {
+    cpu_ms: u64,
+    peak_memory_bytes: u64,
+    over_budget: bool,
+}
+
+/// Why a session went over budget, for the audit entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetBreach {
+    CpuTime { used_ms: u64, max_ms: u64 },
+    Memory { peak_bytes: u64, max_bytes: u64 },
+}
+
+impl std::fmt::Display for BudgetBreach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CpuTime { used_ms, max_ms } => {
+                write!(f, "tool CPU budget exceeded: {used_ms}ms used, limit {max_ms}ms")
+            }
+            Self::Memory { peak_bytes, max_bytes } => {
+                write!(f, "tool memory limit exceeded: peak {peak_bytes}B, limit {max_bytes}B")
+            }
+        }
+    }
+}
+
+/// Accumulates per-session tool resource usage against the configured caps.
+pub struct ResourceAccountant {
+    config: ResourceLimitsConfig,
+    usage: Mutex<HashMap<String, SessionUsage>>,
+}
+
+impl ResourceAccountant {
+    pub fn new(config: ResourceLimitsConfig) -> Self {
+        Self {
+            config,
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `tool` counts as resource-intensive under this config.
+    pub fn is_heavy(&self, tool: &str) -> bool {
+        self.config.heavy_tools.iter().any(|t| t == tool)
+    }
+
+    /// Record one completed tool invocation. Returns the breach on the call
+    /// that first pushes the session over budget, so the caller can audit it
+    /// exactly once.
+    pub fn record(
+        &self,
+        session_id: &str,
+        cpu_ms: u64,
+        peak_memory_bytes: u64,
+    ) -> Option<BudgetBreach> {
+        if !self.config.enabled {
+            return None;
+        }
+        let mut usage = self.usage.lock().expect("resource accountant poisoned");
+        let entry = usage.entry(session_id.to_string()).or_default();
+        entry.cpu_ms += cpu_ms;
+        entry.peak_memory_bytes = entry.peak_memory_bytes.max(peak_memory_bytes);
+        if entry.over_budget {
+            return None;
+        }
+        let breach = if entry.cpu_ms > self.config.max_cpu_ms {
+            Some(BudgetBreach::CpuTime {
+                used_ms: entry.cpu_ms,
+                max_ms: self.config.max_cpu_ms,
+            })
+        } else if entry.peak_memory_bytes > self.config.max_memory_bytes {
+            Some(BudgetBreach::Memory {
+                peak_bytes: entry.peak_memory_bytes,
+                max_bytes: self.config.max_memory_bytes,
+            })
+        } else {
+            None
+        };
+        if breach.is_some() {
+            entry.over_budget = true;
+        }
+        breach
+    }
+
+    /// Whether the session has exhausted its budget and heavy tools are
+    /// blocked for it.
+    pub fn is_over_budget(&self, session_id: &str) -> bool {
+        self.config.enabled
+            && self
+                .usage
+                .lock()
+                .expect("resource accountant poisoned")
+                .get(session_id)
+                .is_some_and(|u| u.over_budget)
+    }
+
+    /// Current usage for a session, for status reporting.
+    pub fn usage(&self, session_id: &str) -> (u64, u64) {
+        self.usage
+            .lock()
+            .expect("resource accountant poisoned")
+            .get(session_id)
+            .map(|u| (u.cpu_ms, u.peak_memory_bytes))
+            .unwrap_or((0, 0))
+    }
+
+    /// Sessions currently over budget, sorted for stable reporting.
+    pub fn over_budget_sessions(&self) -> Vec<String> {
+        let usage = self.usage.lock().expect("resource accountant poisoned");
+        let mut sessions: Vec<String> = usage
+            .iter()
+            .filter(|(_, u)| u.over_budget)
+            .map(|(id, _)| id.clone())
+            .collect();
+        sessions.sort();
+        sessions
+    }
+
+    /// Drop accounting state for an ended session.
+    pub fn forget(&self, session_id: &str) {
+        self.usage
+            .lock()
+            .expect("resource accountant poisoned")
+            .remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accountant(max_cpu_ms: u64, max_memory_bytes: u64) -> ResourceAccountant {
+        ResourceAccountant::new(ResourceLimitsConfig {
+            max_cpu_ms,
+            max_memory_bytes,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn cpu_budget_breach_is_reported_exactly_once() {
+        let acc = accountant(1_000, u64::MAX);
+        assert!(acc.record("s1", 600, 0).is_none());
+        let breach = acc.record("s1", 600, 0).expect("crossing call must report");
+        assert_eq!(
+            breach,
+            BudgetBreach::CpuTime {
+                used_ms: 1_200,
+                max_ms: 1_000
+            }
+        );
+        assert!(acc.record("s1", 600, 0).is_none());
+        assert!(acc.is_over_budget("s1"));
+    }
+
+    #[test]
+    fn memory_cap_applies_to_a_single_invocation_peak() {
+        let acc = accountant(u64::MAX, 1_000);
+        assert!(acc.record("s1", 0, 900).is_none());
+        assert!(acc.record("s1", 0, 800).is_none());
+        assert!(matches!(
+            acc.record("s1", 0, 1_500),
+            Some(BudgetBreach::Memory { .. })
+        ));
+    }
+
+    #[test]
+    fn sessions_are_accounted_independently() {
+        let acc = accountant(1_000, u64::MAX);
+        acc.record("s1", 2_000, 0);
+        assert!(acc.is_over_budget("s1"));
+        assert!(!acc.is_over_budget("s2"));
+        acc.forget("s1");
+        assert!(!acc.is_over_budget("s1"));
+    }
+
+    #[test]
+    fn disabled_accounting_never_blocks() {
+        let acc = ResourceAccountant::new(ResourceLimitsConfig {
+            enabled: false,
+            max_cpu_ms: 1,
+            ..Default::default()
+        });
+        assert!(acc.record("s1", 1_000, 0).is_none());
+        assert!(!acc.is_over_budget("s1"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Taint registry and the TEE boundary guarantee.
+//!
+//! Values classified as sensitive are taint-registered per session. For
+//! TEE-routed sessions there is a hard guarantee on top of the normal
+//! sanitizer: at the send boundary, outbound content is asserted to contain
+//! no taint-registered value that originated as `HighlySensitive`. If one
+//! shows up anyway — a sanitizer bug, a template splice, anything — the send
+//! is blocked and a critical alert raised. Belt and suspenders; this check
+//! must never be the first line of defense, only the last.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::audit::alerting::{Alert, AlertKind, AlertMonitor};
+use crate::error::{Result, SafeClawError};
+use crate::privacy::SensitivityLevel;
+
+/// Per-session registry of tainted values and their origin sensitivity.
+#[derive(Default)]
+pub struct TaintRegistry {
+    values: Mutex<HashMap<String, Vec<(String, SensitivityLevel)>>>,
+}
+
+impl TaintRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a classified value for a session. Short values (< 4 chars)
+    /// are skipped — substring matching on them would block everything.
+    pub fn register(&self, session_id: &str, value: &str, level: SensitivityLevel) {
+        if value.len() < 4 {
+            return;
+        }
+        self.values
+            .lock()
+            .expect("taint registry poisoned")
+            .entry(session_id.to_string())
+            .or_default()
+            .push((value.to_string(), level));
+    }
+
+    /// Tainted values at or above `min_level` appearing in `content`.
+    fn matches(&self, session_id: &str, content: &str, min_level: SensitivityLevel) -> Vec<String> {
+        self.values
+            .lock()
+            .expect("taint registry poisoned")
+            .get(session_id)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter(|(value, level)| *level >= min_level && content.contains(value))
+                    .map(|(value, _)| value.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Session terminated — drop its taint set.
+    pub fn wipe_session(&self, session_id: &str) {
+        self.values
+            .lock()
+            .expect("taint registry poisoned")
+            .remove(session_id);
+    }
+}
+
+/// The hard guarantee at the send boundary for TEE-routed sessions: no
+/// `HighlySensitive`-origin tainted value may appear in outbound content.
+/// Blocks the send and raises a critical alert on violation.
+pub fn enforce_tee_boundary(
+    registry: &TaintRegistry,
+    monitor: &AlertMonitor,
+    session_id: &str,
+    outbound: &str,
+    tee_routed: bool,
+    now: i64,
+) -> Result<()> {
+    if !tee_routed {
+        return Ok(());
+    }
+    let leaked = registry.matches(session_id, outbound, SensitivityLevel::HighlySensitive);
+    if leaked.is_empty() {
+        return Ok(());
+    }
+    monitor.raise(Alert {
+        kind: AlertKind::CriticalEvent,
+        session_id: session_id.to_string(),
+        message: format!(
+            "TEE boundary violation: {} highly sensitive tainted value(s) \
+             reached the send boundary and were blocked",
+            leaked.len()
+        ),
+        timestamp: now,
+    });
+    Err(SafeClawError::Tee(format!(
+        "outbound message blocked: {} highly sensitive tainted value(s) \
+         detected past the sanitizer",
+        leaked.len()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tee_session_emitting_highly_sensitive_taint_is_blocked() {
+        let registry = TaintRegistry::new();
+        let monitor = AlertMonitor::default();
+        registry.register("s1", "123-45-6789", SensitivityLevel::HighlySensitive);
+
+        let err = enforce_tee_boundary(
+            &registry,
+            &monitor,
+            "s1",
+            "your SSN is 123-45-6789",
+            true,
+            100,
+        )
+        .unwrap_err();
+        assert!(matches!(err, SafeClawError::Tee(_)));
+
+        let alerts = monitor.recent(10);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, AlertKind::CriticalEvent);
+        assert_eq!(alerts[0].session_id, "s1");
+    }
+
+    #[test]
+    fn sanitized_output_passes_the_boundary() {
+        let registry = TaintRegistry::new();
+        let monitor = AlertMonitor::default();
+        registry.register("s1", "123-45-6789", SensitivityLevel::HighlySensitive);
+
+        enforce_tee_boundary(
+            &registry,
+            &monitor,
+            "s1",
+            "your SSN is [REDACTED:ssn]",
+            true,
+            100,
+        )
+        .unwrap();
+        assert!(monitor.recent(10).is_empty());
+    }
+
+    #[test]
+    fn lower_sensitivity_taint_does_not_trip_the_hard_check() {
+        let registry = TaintRegistry::new();
+        let monitor = AlertMonitor::default();
+        registry.register("s1", "alice@example.com", SensitivityLevel::Sensitive);
+        enforce_tee_boundary(
+            &registry,
+            &monitor,
+            "s1",
+            "mail alice@example.com",
+            true,
+            100,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn non_tee_sessions_are_out_of_scope() {
+        let registry = TaintRegistry::new();
+        let monitor = AlertMonitor::default();
+        registry.register("s1", "123-45-6789", SensitivityLevel::HighlySensitive);
+        enforce_tee_boundary(&registry, &monitor, "s1", "123-45-6789", false, 100).unwrap();
+    }
+
+    #[test]
+    fn taint_is_scoped_per_session_and_wipable() {
+        let registry = TaintRegistry::new();
+        let monitor = AlertMonitor::default();
+        registry.register("s1", "123-45-6789", SensitivityLevel::HighlySensitive);
+
+        // Another session is untouched by s1's taint.
+        enforce_tee_boundary(&registry, &monitor, "s2", "123-45-6789", true, 0).unwrap();
+
+        registry.wipe_session("s1");
+        enforce_tee_boundary(&registry, &monitor, "s1", "123-45-6789", true, 0).unwrap();
+    }
+}
@@ -0,0 +1,239 @@
+//! Headless container mode — environment-variable-only configuration.
+//!
+//! Container deployments get no home directory, no onboarding wizard, and no
+//! config files: the full [`SafeClawConfig`] can be assembled from
+//! `SAFECLAW__`-prefixed environment variables (double underscores map to
+//! nesting, e.g. `SAFECLAW__GATEWAY__PORT=8080`), or supplied wholesale via a
+//! single `SAFECLAW_CONFIG_HCL` blob. All on-disk state roots under one
+//! `SAFECLAW_DATA_DIR`, and interactive commands refuse cleanly when no TTY
+//! is present instead of hanging on stdin.
+
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use crate::config::SafeClawConfig;
+use crate::error::{Result, SafeClawError};
+
+/// Prefix for nested config environment variables.
+const ENV_PREFIX: &str = "SAFECLAW__";
+/// Whole-config blob variable; takes precedence over nested variables.
+const ENV_CONFIG_BLOB: &str = "SAFECLAW_CONFIG_HCL";
+/// Root for all on-disk state in headless mode.
+pub const ENV_DATA_DIR: &str = "SAFECLAW_DATA_DIR";
+
+/// Build the configuration from environment variables. Accepts the variable
+/// iterator explicitly so tests don't mutate process environment.
+pub fn config_from_env<I>(vars: I) -> Result<SafeClawConfig>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let vars: Vec<(String, String)> = vars.into_iter().collect();
+
+    if let Some((_, blob)) = vars.iter().find(|(k, _)| k == ENV_CONFIG_BLOB) {
+        return hcl::from_str(blob)
+            .map_err(|e| SafeClawError::Config(format!("invalid {ENV_CONFIG_BLOB}: {e}")));
+    }
+
+    let mut root = serde_json::Value::Object(serde_json::Map::new());
+    for (key, value) in &vars {
+        let Some(path) = key.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let segments: Vec<String> = path.split("__").map(|s| s.to_lowercase()).collect();
+        insert_path(&mut root, &segments, parse_scalar(value));
+    }
+    serde_json::from_value(root)
+        .map_err(|e| SafeClawError::Config(format!("invalid environment config: {e}")))
+}
+
+/// Interpret a variable value: JSON scalars/arrays pass through typed,
+/// anything else is a plain string.
+fn parse_scalar(value: &str) -> serde_json::Value {
+    match serde_json::from_str(value) {
+        Ok(v @ (serde_json::Value::Bool(_)
+        | serde_json::Value::Number(_)
+        | serde_json::Value::Array(_)
+        | serde_json::Value::Null)) => v,
+        _ => serde_json::Value::String(value.to_string()),
+    }
+}
+
+/// Insert `value` at the nested `segments` path, creating objects (or arrays
+/// for numeric segments) along the way.
+fn insert_path(root: &mut serde_json::Value, segments: &[String], value: serde_json::Value) {
+    let Some((head, rest)) = segments.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        set_key(root, head, value);
+        return;
+    }
+    let child = match root {
+        serde_json::Value::Object(map) => map
+            .entry(head.clone())
+            .or_insert_with(|| container_for(&rest[0])),
+        serde_json::Value::Array(items) => {
+            let index: usize = head.parse().unwrap_or(items.len());
+            while items.len() <= index {
+                items.push(container_for(&rest[0]));
+            }
+            &mut items[index]
+        }
+        _ => return,
+    };
+    insert_path(child, rest, value);
+}
+
+fn container_for(next_segment: &str) -> serde_json::Value {
+    if next_segment.parse::<usize>().is_ok() {
+        serde_json::Value::Array(Vec::new())
+    } else {
+        serde_json::Value::Object(serde_json::Map::new())
+    }
+}
+
+fn set_key(node: &mut serde_json::Value, key: &str, value: serde_json::Value) {
+    match node {
+        serde_json::Value::Object(map) => {
+            map.insert(key.to_string(), value);
+        }
+        serde_json::Value::Array(items) => {
+            let index: usize = key.parse().unwrap_or(items.len());
+            while items.len() <= index {
+                items.push(serde_json::Value::Null);
+            }
+            items[index] = value;
+        }
+        _ => {}
+    }
+}
+
+/// Every on-disk location, rooted under one data directory. In headless mode
+/// the root comes from `SAFECLAW_DATA_DIR`; otherwise it defaults to
+/// `~/.safeclaw`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataDirs {
+    pub root: PathBuf,
+    pub config_file: PathBuf,
+    pub credentials_file: PathBuf,
+    pub sessions_dir: PathBuf,
+    pub channels_dir: PathBuf,
+    pub logs_dir: PathBuf,
+    pub memory_dir: PathBuf,
+}
+
+impl DataDirs {
+    pub fn from_root(root: impl AsRef<Path>) -> Self {
+        let root = root.as_ref().to_path_buf();
+        Self {
+            config_file: root.join("config.json"),
+            credentials_file: root.join("credentials.json"),
+            sessions_dir: root.join("sessions"),
+            channels_dir: root.join("channels"),
+            logs_dir: root.join("logs"),
+            memory_dir: root.join("memory"),
+            root,
+        }
+    }
+}
+
+/// Guard for interactive commands (the onboarding wizard): refuse with
+/// actionable guidance when stdin is not a TTY rather than hanging.
+pub fn ensure_interactive(command: &str) -> Result<()> {
+    if std::io::stdin().is_terminal() {
+        return Ok(());
+    }
+    Err(SafeClawError::Config(format!(
+        "`safeclaw {command}` is interactive and no TTY is attached. In \
+         container deployments configure via SAFECLAW__* environment \
+         variables or {ENV_CONFIG_BLOB}, and set {ENV_DATA_DIR} for state."
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn nested_env_vars_map_onto_config() {
+        let config = config_from_env(vars(&[
+            ("SAFECLAW__GATEWAY__PORT", "8080"),
+            ("SAFECLAW__GATEWAY__HOST", "0.0.0.0"),
+            ("SAFECLAW__TEE__ENABLED", "false"),
+            ("UNRELATED_VAR", "ignored"),
+        ]))
+        .unwrap();
+
+        assert_eq!(config.gateway.port, 8080);
+        assert_eq!(config.gateway.host, "0.0.0.0");
+        assert!(!config.tee.enabled);
+    }
+
+    #[test]
+    fn provider_and_channel_mapping() {
+        let config = config_from_env(vars(&[
+            ("SAFECLAW__MODELS__DEFAULT_PROVIDER", "anthropic"),
+            (
+                "SAFECLAW__MODELS__PROVIDERS__ANTHROPIC__API_KEY_REF",
+                "anthropic_api_key",
+            ),
+            (
+                "SAFECLAW__MODELS__PROVIDERS__ANTHROPIC__DEFAULT_MODEL",
+                "claude-sonnet-4-20250514",
+            ),
+            ("SAFECLAW__CHANNELS__TELEGRAM__ENABLED", "true"),
+            (
+                "SAFECLAW__CHANNELS__TELEGRAM__BOT_TOKEN_REF",
+                "telegram_bot_token",
+            ),
+        ]))
+        .unwrap();
+
+        let provider = &config.models.providers["anthropic"];
+        assert_eq!(provider.api_key_ref.as_deref(), Some("anthropic_api_key"));
+        assert_eq!(
+            provider.default_model.as_deref(),
+            Some("claude-sonnet-4-20250514")
+        );
+
+        let telegram = config.channels.get("telegram").unwrap();
+        assert!(telegram.enabled);
+        assert_eq!(
+            telegram.settings["bot_token_ref"],
+            serde_json::json!("telegram_bot_token")
+        );
+        assert_eq!(config.channels.enabled_channels(), vec!["telegram"]);
+    }
+
+    #[test]
+    fn string_values_stay_strings_even_when_numeric_looking_refs() {
+        let config = config_from_env(vars(&[("SAFECLAW__LOGGING__LEVEL", "debug")])).unwrap();
+        assert_eq!(config.logging.level, "debug");
+    }
+
+    #[test]
+    fn every_store_roots_under_the_data_dir() {
+        let dirs = DataDirs::from_root("/var/lib/safeclaw");
+        for path in [
+            &dirs.config_file,
+            &dirs.credentials_file,
+            &dirs.sessions_dir,
+            &dirs.channels_dir,
+            &dirs.logs_dir,
+            &dirs.memory_dir,
+        ] {
+            assert!(
+                path.starts_with("/var/lib/safeclaw"),
+                "not rooted: {}",
+                path.display()
+            );
+        }
+    }
+}
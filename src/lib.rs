@@ -0,0 +1,29 @@
+//! SafeClaw — security proxy for AI agents.
+//!
+//! Runs inside an A3S Box VM: classifies messages, detects injection attacks,
+//! sanitizes outputs, tracks data taint, and audits everything. Calls a local
+//! A3S Code agent service for LLM processing.
+
+pub mod agent;
+pub mod api;
+pub mod archive;
+pub mod audit;
+pub mod channels;
+pub mod config;
+pub mod crypto;
+pub mod error;
+pub mod events;
+#[cfg(feature = "fault-injection")]
+pub mod faults;
+pub mod guard;
+pub mod headless;
+pub mod maintenance;
+pub mod memory;
+pub mod openapi;
+pub mod privacy;
+pub mod proxy;
+pub mod runtime;
+pub mod scheduler;
+pub mod session;
+pub mod settings;
+pub mod tee;
@@ -0,0 +1,310 @@
+//! On-disk store maintenance — backing for the `safeclaw store` subcommands.
+//!
+//! A bad shutdown can leave a half-written JSON file that makes part of an
+//! API 500 until someone finds and deletes it by hand. `safeclaw store check`
+//! walks every on-disk store and reports per-file problems; `repair` moves
+//! corrupt files into a `.quarantine/` directory with a manifest and lets the
+//! store rebuild its derived indexes; `stats` prints counts and disk usage;
+//! `vacuum` applies retention immediately. Validation goes through each
+//! store's own load logic — implemented here as [`StoreIntegrity`] — rather
+//! than duplicating schemas. Exit codes reflect findings for scripting.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Directory (inside each store) where `repair` moves corrupt files.
+pub const QUARANTINE_DIR: &str = ".quarantine";
+const QUARANTINE_MANIFEST: &str = "manifest.json";
+
+/// Implemented by each on-disk store (agent sessions, memory layers, events,
+/// personas, scheduler state, audit) so maintenance reuses the store's own
+/// load/validate logic.
+pub trait StoreIntegrity {
+    fn name(&self) -> &str;
+    fn dir(&self) -> &Path;
+    /// Load one file through the store's own deserialization, erroring on
+    /// anything the store itself would reject.
+    fn validate_file(&self, path: &Path) -> Result<()>;
+    /// Rebuild derived indexes after corrupt files were quarantined.
+    fn rebuild_indexes(&self) -> Result<()> {
+        Ok(())
+    }
+    /// Apply the store's retention policy now; returns entries removed.
+    fn vacuum(&self, _now: i64) -> Result<usize> {
+        Ok(0)
+    }
+}
+
+/// One file a check could not validate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileProblem {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Result of checking one store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreReport {
+    pub store: String,
+    pub files_checked: usize,
+    pub problems: Vec<FileProblem>,
+}
+
+/// Per-store counts and disk usage for `safeclaw store stats`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreStats {
+    pub store: String,
+    pub files: usize,
+    pub disk_bytes: u64,
+}
+
+/// Entry in the quarantine manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineRecord {
+    pub original_path: PathBuf,
+    pub error: String,
+    pub quarantined_at: i64,
+}
+
+fn walk_files(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            if entry.file_name() == QUARANTINE_DIR {
+                continue;
+            }
+            walk_files(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn check_store(store: &dyn StoreIntegrity) -> Result<StoreReport> {
+    let mut files = Vec::new();
+    walk_files(store.dir(), &mut files)?;
+    files.sort();
+    let mut problems = Vec::new();
+    for path in &files {
+        if let Err(e) = store.validate_file(path) {
+            problems.push(FileProblem {
+                path: path.clone(),
+                error: e.to_string(),
+            });
+        }
+    }
+    Ok(StoreReport {
+        store: store.name().to_string(),
+        files_checked: files.len(),
+        problems,
+    })
+}
+
+/// `safeclaw store check` — validate every file, touching nothing.
+pub fn check(stores: &[&dyn StoreIntegrity]) -> Result<Vec<StoreReport>> {
+    stores.iter().map(|s| check_store(*s)).collect()
+}
+
+/// `safeclaw store repair` — quarantine corrupt files and rebuild indexes.
+/// Returns the check reports describing what was moved.
+pub fn repair(stores: &[&dyn StoreIntegrity], now: i64) -> Result<Vec<StoreReport>> {
+    let mut reports = Vec::new();
+    for store in stores {
+        let report = check_store(*store)?;
+        if !report.problems.is_empty() {
+            let quarantine = store.dir().join(QUARANTINE_DIR);
+            std::fs::create_dir_all(&quarantine)?;
+            let manifest_path = quarantine.join(QUARANTINE_MANIFEST);
+            let mut manifest: Vec<QuarantineRecord> = match std::fs::read(&manifest_path) {
+                Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
+                Err(_) => Vec::new(),
+            };
+            for problem in &report.problems {
+                let file_name = problem
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "unnamed".into());
+                std::fs::rename(&problem.path, quarantine.join(&file_name))?;
+                manifest.push(QuarantineRecord {
+                    original_path: problem.path.clone(),
+                    error: problem.error.clone(),
+                    quarantined_at: now,
+                });
+            }
+            std::fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+            store.rebuild_indexes()?;
+        }
+        reports.push(report);
+    }
+    Ok(reports)
+}
+
+/// `safeclaw store stats` — file counts and disk usage per store.
+pub fn stats(stores: &[&dyn StoreIntegrity]) -> Result<Vec<StoreStats>> {
+    let mut out = Vec::new();
+    for store in stores {
+        let mut files = Vec::new();
+        walk_files(store.dir(), &mut files)?;
+        let disk_bytes = files
+            .iter()
+            .filter_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum();
+        out.push(StoreStats {
+            store: store.name().to_string(),
+            files: files.len(),
+            disk_bytes,
+        });
+    }
+    Ok(out)
+}
+
+/// `safeclaw store vacuum` — apply retention now; returns entries removed per
+/// store.
+pub fn vacuum(stores: &[&dyn StoreIntegrity], now: i64) -> Result<Vec<(String, usize)>> {
+    stores
+        .iter()
+        .map(|s| Ok((s.name().to_string(), s.vacuum(now)?)))
+        .collect()
+}
+
+/// Exit code for scripting: 0 when every store is clean, 1 when any problem
+/// was found (or, for `repair`, quarantined).
+pub fn exit_code(reports: &[StoreReport]) -> i32 {
+    if reports.iter().any(|r| !r.problems.is_empty()) {
+        1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SafeClawError;
+
+    /// Stand-in for a real store: validates files as JSON objects with a
+    /// required `id` field, the way the artifact stores do.
+    struct FixtureStore {
+        dir: PathBuf,
+        vacuumed: std::cell::Cell<usize>,
+    }
+
+    impl FixtureStore {
+        fn new(dir: &Path) -> Self {
+            Self {
+                dir: dir.to_path_buf(),
+                vacuumed: std::cell::Cell::new(0),
+            }
+        }
+    }
+
+    impl StoreIntegrity for FixtureStore {
+        fn name(&self) -> &str {
+            "fixture"
+        }
+
+        fn dir(&self) -> &Path {
+            &self.dir
+        }
+
+        fn validate_file(&self, path: &Path) -> Result<()> {
+            let value: serde_json::Value = serde_json::from_slice(&std::fs::read(path)?)?;
+            if value.get("id").is_none() {
+                return Err(SafeClawError::Config("missing id field".into()));
+            }
+            Ok(())
+        }
+
+        fn vacuum(&self, _now: i64) -> Result<usize> {
+            self.vacuumed.set(self.vacuumed.get() + 1);
+            Ok(3)
+        }
+    }
+
+    fn seed(dir: &Path) {
+        std::fs::write(dir.join("good.json"), r#"{"id":"a1","body":"ok"}"#).unwrap();
+        std::fs::write(dir.join("truncated.json"), r#"{"id":"a2","bo"#).unwrap();
+        std::fs::write(dir.join("wrong-shape.json"), r#"{"body":"no id"}"#).unwrap();
+    }
+
+    #[test]
+    fn check_reports_problems_without_modifying_anything() {
+        let dir = tempfile::tempdir().unwrap();
+        seed(dir.path());
+        let store = FixtureStore::new(dir.path());
+
+        let reports = check(&[&store]).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].files_checked, 3);
+        assert_eq!(reports[0].problems.len(), 2);
+        assert_eq!(exit_code(&reports), 1);
+
+        // Non-destructive: everything is still in place.
+        assert!(dir.path().join("truncated.json").exists());
+        assert!(dir.path().join("wrong-shape.json").exists());
+        assert!(!dir.path().join(QUARANTINE_DIR).exists());
+    }
+
+    #[test]
+    fn clean_store_checks_with_exit_code_zero() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("good.json"), r#"{"id":"a1"}"#).unwrap();
+        let store = FixtureStore::new(dir.path());
+        let reports = check(&[&store]).unwrap();
+        assert!(reports[0].problems.is_empty());
+        assert_eq!(exit_code(&reports), 0);
+    }
+
+    #[test]
+    fn repair_quarantines_corrupt_files_with_a_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        seed(dir.path());
+        let store = FixtureStore::new(dir.path());
+
+        let reports = repair(&[&store], 1_700_000_000).unwrap();
+        assert_eq!(reports[0].problems.len(), 2);
+
+        // Corrupt files moved, valid one untouched.
+        assert!(!dir.path().join("truncated.json").exists());
+        assert!(dir.path().join("good.json").exists());
+        let quarantine = dir.path().join(QUARANTINE_DIR);
+        assert!(quarantine.join("truncated.json").exists());
+        assert!(quarantine.join("wrong-shape.json").exists());
+
+        let manifest: Vec<QuarantineRecord> =
+            serde_json::from_slice(&std::fs::read(quarantine.join("manifest.json")).unwrap())
+                .unwrap();
+        assert_eq!(manifest.len(), 2);
+        assert!(manifest.iter().all(|r| r.quarantined_at == 1_700_000_000));
+
+        // A second check is clean; the quarantine dir is not walked.
+        let reports = check(&[&store]).unwrap();
+        assert_eq!(reports[0].files_checked, 1);
+        assert!(reports[0].problems.is_empty());
+    }
+
+    #[test]
+    fn stats_and_vacuum_cover_each_store() {
+        let dir = tempfile::tempdir().unwrap();
+        seed(dir.path());
+        let store = FixtureStore::new(dir.path());
+
+        let stats = stats(&[&store]).unwrap();
+        assert_eq!(stats[0].files, 3);
+        assert!(stats[0].disk_bytes > 0);
+
+        let removed = vacuum(&[&store], 0).unwrap();
+        assert_eq!(removed, vec![("fixture".to_string(), 3)]);
+        assert_eq!(store.vacuumed.get(), 1);
+    }
+}
@@ -0,0 +1,53 @@
+//! Injectable time and ID generation for the memory pipeline.
+//!
+//! Insight IDs and timestamps are the two sources of non-determinism in
+//! synthesis output. Production uses real time and UUIDv4; tests inject
+//! [`test_support`](crate::memory::test_support) variants so memory state can
+//! be asserted byte-for-byte and diffed across runs.
+
+use rand::RngCore;
+
+/// Source of `created_at` timestamps.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> i64;
+}
+
+/// Source of artifact/insight IDs. `prefix` is the layer tag (`"art"`,
+/// `"ins"`).
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self, prefix: &str) -> String;
+}
+
+/// Real wall-clock time.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    }
+}
+
+/// Random UUIDv4-format IDs.
+pub struct UuidIdGenerator;
+
+impl IdGenerator for UuidIdGenerator {
+    fn next_id(&self, prefix: &str) -> String {
+        let mut bytes = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        // RFC 4122 version and variant bits.
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        let hex = hex::encode(bytes);
+        format!(
+            "{prefix}-{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+}
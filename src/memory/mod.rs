@@ -0,0 +1,50 @@
+//! Memory system — three-layer data hierarchy.
+//!
+//! Layer 1 (Resource) holds raw classified content, Layer 2 (Artifact) holds
+//! structured knowledge extracted from resources, Layer 3 (Insight) holds
+//! cross-conversation synthesis. The [`Extractor`] and [`Synthesizer`] are
+//! deterministic given their inputs: time and ID generation are injected via
+//! [`determinism::Clock`] and [`determinism::IdGenerator`] (real time and
+//! UUIDv4 in production), and synthesis groups through ordered maps, so
+//! memory state diffs cleanly across runs and golden tests can assert output
+//! byte-for-byte.
+//!
+//! [`Extractor`]: extract::Extractor
+//! [`Synthesizer`]: synthesis::Synthesizer
+
+pub mod determinism;
+pub mod extract;
+pub mod notices;
+pub mod scope;
+pub mod search;
+pub mod segmentation;
+pub mod synthesis;
+pub mod test_support;
+
+use serde::{Deserialize, Serialize};
+
+/// Layer 2: one piece of structured knowledge extracted from a resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub id: String,
+    pub session_id: String,
+    /// Knowledge kind, e.g. `"fact"`, `"preference"`, `"decision"`.
+    pub kind: String,
+    pub content: String,
+    /// Layer-1 resources this was extracted from.
+    pub source_resource_ids: Vec<String>,
+    pub created_at: i64,
+}
+
+/// Layer 3: knowledge synthesized across artifacts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Insight {
+    pub id: String,
+    /// Which synthesis rule produced this.
+    pub rule: synthesis::SynthesisRule,
+    pub title: String,
+    pub body: String,
+    /// Artifacts the insight was synthesized from, in stable order.
+    pub artifact_ids: Vec<String>,
+    pub created_at: i64,
+}
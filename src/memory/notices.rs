@@ -0,0 +1,326 @@
+//! Opt-in "what I just learned" notices with a short undo window.
+//!
+//! Memory extraction is invisible, which reads as creepy: things get stored
+//! without the user ever seeing what. With `/memory notices on` a chat gets
+//! a compact notice after extraction completes ("Remembered: dentist: June
+//! 3 — say /forget to undo"), naming the artifact kind and a safe
+//! paraphrase — HighlySensitive source content is never echoed, only its
+//! kind. Notices are rate-limited per chat so a paste that yields ten
+//! artifacts doesn't produce ten pings, and `/forget` deletes the
+//! just-created artifacts within a grace window via a short-lived undo
+//! registry keyed on the originating chat.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeClawError};
+use crate::memory::Artifact;
+use crate::privacy::SensitivityLevel;
+
+/// `/memory notices on|off` toggles the notices for a chat.
+pub const NOTICES_COMMAND: &str = "/memory notices";
+/// `/forget` (or `/forget last`) undoes the most recent extraction.
+pub const FORGET_COMMAND: &str = "/forget";
+
+/// Longest paraphrase included in a notice before truncation.
+const PARAPHRASE_CHARS: usize = 60;
+
+/// Configuration under `memory.notices`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LearningNoticeConfig {
+    pub enabled: bool,
+    /// `/forget` works this long after the extraction.
+    pub grace_window_secs: i64,
+    /// Minimum seconds between notices to one chat; extractions inside the
+    /// gap still register for undo, silently.
+    pub min_notice_gap_secs: i64,
+}
+
+impl Default for LearningNoticeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            grace_window_secs: 300,
+            min_notice_gap_secs: 60,
+        }
+    }
+}
+
+/// Deletes artifacts by ID — the memory store in production, a recorder in
+/// tests.
+pub trait ArtifactDeleter: Send + Sync {
+    fn delete_artifacts(&self, ids: &[String]);
+}
+
+#[derive(Default)]
+struct ChatState {
+    opted_in: bool,
+    last_notice_at: Option<i64>,
+}
+
+struct UndoEntry {
+    artifact_ids: Vec<String>,
+    created_at: i64,
+}
+
+/// Per-chat notice opt-in, rate limiting, and the undo registry. Keyed
+/// `channel:chat_id`.
+pub struct LearningNotices {
+    config: LearningNoticeConfig,
+    chats: Mutex<HashMap<String, ChatState>>,
+    undo: Mutex<HashMap<String, UndoEntry>>,
+}
+
+/// The paraphrase shown for one artifact: its content for ordinary
+/// sensitivity, kind-only for HighlySensitive sources.
+fn paraphrase(artifact: &Artifact, source_sensitivity: SensitivityLevel) -> String {
+    if source_sensitivity >= SensitivityLevel::HighlySensitive {
+        return format!("a {} (details withheld)", artifact.kind);
+    }
+    let mut content = artifact.content.clone();
+    if content.chars().count() > PARAPHRASE_CHARS {
+        content = content.chars().take(PARAPHRASE_CHARS).collect();
+        content.push('…');
+    }
+    format!("{}: {}", artifact.kind, content)
+}
+
+impl LearningNotices {
+    pub fn new(config: LearningNoticeConfig) -> Self {
+        Self {
+            config,
+            chats: Mutex::new(HashMap::new()),
+            undo: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `/memory notices on|off`.
+    pub fn handle_notices_command(&self, chat_key: &str, argument: &str) -> Result<String> {
+        let on = match argument.trim() {
+            "on" => true,
+            "off" => false,
+            other => {
+                return Err(SafeClawError::Config(format!(
+                    "expected `on` or `off`, got `{other}`"
+                )))
+            }
+        };
+        self.chats
+            .lock()
+            .expect("learning notices poisoned")
+            .entry(chat_key.to_string())
+            .or_default()
+            .opted_in = on;
+        Ok(if on {
+            "Memory notices are on — I'll tell you when I store something; \
+             /forget undoes the latest."
+                .into()
+        } else {
+            "Memory notices are off.".into()
+        })
+    }
+
+    /// Extraction finished for a message from `chat_key`; the extractor
+    /// reports the created artifacts and the source's sensitivity. Returns
+    /// the notice to deliver, or `None` when the chat opted out, nothing
+    /// was stored, or the rate limit suppresses it. The undo registry is
+    /// updated either way.
+    pub fn note_extraction(
+        &self,
+        chat_key: &str,
+        artifacts: &[Artifact],
+        source_sensitivity: SensitivityLevel,
+        now: i64,
+    ) -> Option<String> {
+        if !self.config.enabled || artifacts.is_empty() {
+            return None;
+        }
+        let opted_in = self
+            .chats
+            .lock()
+            .expect("learning notices poisoned")
+            .get(chat_key)
+            .is_some_and(|c| c.opted_in);
+        if !opted_in {
+            return None;
+        }
+        self.undo.lock().expect("learning notices poisoned").insert(
+            chat_key.to_string(),
+            UndoEntry {
+                artifact_ids: artifacts.iter().map(|a| a.id.clone()).collect(),
+                created_at: now,
+            },
+        );
+        {
+            let mut chats = self.chats.lock().expect("learning notices poisoned");
+            let chat = chats.entry(chat_key.to_string()).or_default();
+            if chat
+                .last_notice_at
+                .is_some_and(|at| now - at < self.config.min_notice_gap_secs)
+            {
+                return None;
+            }
+            chat.last_notice_at = Some(now);
+        }
+        let items: Vec<String> = artifacts
+            .iter()
+            .map(|a| paraphrase(a, source_sensitivity))
+            .collect();
+        Some(format!(
+            "Remembered: {} — say {FORGET_COMMAND} to undo.",
+            items.join("; ")
+        ))
+    }
+
+    /// `/forget` — delete the chat's most recently extracted artifacts if
+    /// the grace window hasn't passed.
+    pub fn handle_forget(
+        &self,
+        chat_key: &str,
+        deleter: &dyn ArtifactDeleter,
+        now: i64,
+    ) -> Result<String> {
+        let entry = {
+            let mut undo = self.undo.lock().expect("learning notices poisoned");
+            match undo.get(chat_key) {
+                Some(e) if now - e.created_at <= self.config.grace_window_secs => {
+                    undo.remove(chat_key)
+                }
+                _ => None,
+            }
+        };
+        let Some(entry) = entry else {
+            return Err(SafeClawError::NotFound(
+                "nothing stored recently enough to forget".into(),
+            ));
+        };
+        deleter.delete_artifacts(&entry.artifact_ids);
+        Ok(format!(
+            "Forgotten — removed {} stored item{}.",
+            entry.artifact_ids.len(),
+            if entry.artifact_ids.len() == 1 { "" } else { "s" }
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::test_support::deterministic_artifact_builder;
+
+    const NOW: i64 = 1_700_000_000;
+    const CHAT: &str = "telegram:123";
+
+    fn artifacts(contents: &[&str]) -> Vec<Artifact> {
+        let builder = deterministic_artifact_builder(NOW);
+        contents
+            .iter()
+            .map(|c| builder.build("s1", "fact", c, vec!["res-1".into()]))
+            .collect()
+    }
+
+    fn opted_in(config: LearningNoticeConfig) -> LearningNotices {
+        let notices = LearningNotices::new(config);
+        notices.handle_notices_command(CHAT, "on").unwrap();
+        notices
+    }
+
+    #[derive(Default)]
+    struct RecordingDeleter {
+        deleted: Mutex<Vec<String>>,
+    }
+
+    impl ArtifactDeleter for RecordingDeleter {
+        fn delete_artifacts(&self, ids: &[String]) {
+            self.deleted.lock().unwrap().extend(ids.iter().cloned());
+        }
+    }
+
+    #[test]
+    fn notices_are_opt_in_per_chat() {
+        let notices = LearningNotices::new(LearningNoticeConfig::default());
+        let stored = artifacts(&["dentist: June 3"]);
+        assert!(notices
+            .note_extraction(CHAT, &stored, SensitivityLevel::Normal, NOW)
+            .is_none());
+
+        notices.handle_notices_command(CHAT, "on").unwrap();
+        let notice = notices
+            .note_extraction(CHAT, &stored, SensitivityLevel::Normal, NOW)
+            .expect("opted-in chat gets the notice");
+        assert!(notice.contains("Remembered: fact: dentist: June 3"));
+        assert!(notice.contains("/forget"));
+        // A different chat stays silent.
+        assert!(notices
+            .note_extraction("matrix:!room", &stored, SensitivityLevel::Normal, NOW)
+            .is_none());
+    }
+
+    #[test]
+    fn highly_sensitive_content_is_never_echoed() {
+        let notices = opted_in(LearningNoticeConfig::default());
+        let stored = artifacts(&["passport number: X1234567"]);
+        let notice = notices
+            .note_extraction(CHAT, &stored, SensitivityLevel::HighlySensitive, NOW)
+            .unwrap();
+        assert!(!notice.contains("X1234567"));
+        assert!(notice.contains("a fact (details withheld)"));
+    }
+
+    #[test]
+    fn notices_are_rate_limited_but_undo_still_registers() {
+        let notices = opted_in(LearningNoticeConfig::default());
+        let first = artifacts(&["first"]);
+        let second = artifacts(&["second"]);
+
+        assert!(notices
+            .note_extraction(CHAT, &first, SensitivityLevel::Normal, NOW)
+            .is_some());
+        // Inside the gap: suppressed, but /forget targets the newer batch.
+        assert!(notices
+            .note_extraction(CHAT, &second, SensitivityLevel::Normal, NOW + 10)
+            .is_none());
+        let deleter = RecordingDeleter::default();
+        notices.handle_forget(CHAT, &deleter, NOW + 20).unwrap();
+        assert_eq!(*deleter.deleted.lock().unwrap(), vec![second[0].id.clone()]);
+
+        // Past the gap a notice flows again.
+        assert!(notices
+            .note_extraction(CHAT, &first, SensitivityLevel::Normal, NOW + 120)
+            .is_some());
+    }
+
+    #[test]
+    fn forget_deletes_exactly_the_referenced_artifacts_within_the_window() {
+        let notices = opted_in(LearningNoticeConfig::default());
+        let stored = artifacts(&["dentist: June 3", "prefers tea"]);
+        notices
+            .note_extraction(CHAT, &stored, SensitivityLevel::Normal, NOW)
+            .unwrap();
+
+        let deleter = RecordingDeleter::default();
+        let reply = notices.handle_forget(CHAT, &deleter, NOW + 60).unwrap();
+        assert!(reply.contains("2 stored items"));
+        let expected: Vec<String> = stored.iter().map(|a| a.id.clone()).collect();
+        assert_eq!(*deleter.deleted.lock().unwrap(), expected);
+
+        // The entry is consumed: a second /forget has nothing to do.
+        assert!(notices.handle_forget(CHAT, &deleter, NOW + 61).is_err());
+    }
+
+    #[test]
+    fn the_grace_window_expires_the_undo() {
+        let notices = opted_in(LearningNoticeConfig::default());
+        let stored = artifacts(&["dentist: June 3"]);
+        notices
+            .note_extraction(CHAT, &stored, SensitivityLevel::Normal, NOW)
+            .unwrap();
+        let deleter = RecordingDeleter::default();
+        let err = notices.handle_forget(CHAT, &deleter, NOW + 301).unwrap_err();
+        assert!(matches!(err, SafeClawError::NotFound(_)));
+        assert!(deleter.deleted.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+//! Cross-session memory sharing boundaries.
+//!
+//! Memory is per-user by default, but some deployments want a shared team
+//! knowledge base without giving up personal privacy. Every stored
+//! artifact carries an owner and a [`MemoryScope`]: `private` memory is
+//! visible only to its owner, `team` memory to members of the owner's
+//! team, `global` memory to everyone. The boundary is enforced at read
+//! time in one place — [`ScopedMemoryStore`] filters what a requester can
+//! see before synthesis or search ever touches it — so a scope bug can't
+//! leak one user's private memory into another user's insights.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::search::{MemorySearchIndex, SearchHit};
+use crate::memory::synthesis::Synthesizer;
+use crate::memory::{Artifact, Insight};
+use crate::privacy::SensitivityLevel;
+
+/// How widely a piece of memory is shared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScope {
+    /// Visible only to the owning user.
+    #[default]
+    Private,
+    /// Visible to members of the owner's team.
+    Team,
+    /// Visible to every user of the deployment.
+    Global,
+}
+
+/// Who is storing or reading memory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryPrincipal {
+    pub user_id: String,
+    /// `None` for users outside any team; such users can neither store
+    /// nor read team-scoped memory.
+    pub team_id: Option<String>,
+}
+
+impl MemoryPrincipal {
+    pub fn new(user_id: &str, team_id: Option<&str>) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            team_id: team_id.map(str::to_string),
+        }
+    }
+}
+
+/// The one visibility rule. Team scope requires both sides to be in the
+/// *same* team — an owner without a team shares with no one at team scope.
+pub fn visible(owner: &MemoryPrincipal, scope: MemoryScope, requester: &MemoryPrincipal) -> bool {
+    match scope {
+        MemoryScope::Private => owner.user_id == requester.user_id,
+        MemoryScope::Team => matches!(
+            (&owner.team_id, &requester.team_id),
+            (Some(owned), Some(requested)) if owned == requested
+        ),
+        MemoryScope::Global => true,
+    }
+}
+
+struct ScopedEntry {
+    artifact: Artifact,
+    owner: MemoryPrincipal,
+    scope: MemoryScope,
+}
+
+/// Artifact storage with the sharing boundary enforced at read time.
+/// Synthesis and search go through the visibility filter here rather than
+/// re-implementing it.
+#[derive(Default)]
+pub struct ScopedMemoryStore {
+    entries: Mutex<Vec<ScopedEntry>>,
+}
+
+impl ScopedMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn store(&self, artifact: Artifact, owner: MemoryPrincipal, scope: MemoryScope) {
+        self.entries
+            .lock()
+            .expect("scoped memory store poisoned")
+            .push(ScopedEntry {
+                artifact,
+                owner,
+                scope,
+            });
+    }
+
+    /// Everything the requester is allowed to read.
+    pub fn visible_artifacts(&self, requester: &MemoryPrincipal) -> Vec<Artifact> {
+        self.entries
+            .lock()
+            .expect("scoped memory store poisoned")
+            .iter()
+            .filter(|entry| visible(&entry.owner, entry.scope, requester))
+            .map(|entry| entry.artifact.clone())
+            .collect()
+    }
+
+    /// Synthesize insights from the requester's visible memory only. A
+    /// user's insights can therefore never cite or summarize another
+    /// user's private artifacts.
+    pub fn synthesize_for(
+        &self,
+        requester: &MemoryPrincipal,
+        synthesizer: &Synthesizer,
+    ) -> Vec<Insight> {
+        synthesizer.synthesize(&self.visible_artifacts(requester))
+    }
+
+    /// Search, keeping only hits whose artifact the requester can see.
+    pub async fn search_for(
+        &self,
+        requester: &MemoryPrincipal,
+        index: &MemorySearchIndex,
+        query: &str,
+        max_sensitivity: SensitivityLevel,
+        limit: usize,
+    ) -> Vec<SearchHit> {
+        let allowed: BTreeSet<String> = self
+            .visible_artifacts(requester)
+            .into_iter()
+            .map(|artifact| artifact.id)
+            .collect();
+        // Over-fetch before filtering so scope-hidden hits don't eat the
+        // requested limit.
+        let mut hits = index.search(query, max_sensitivity, usize::MAX).await;
+        hits.retain(|hit| allowed.contains(&hit.id));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::test_support;
+
+    fn store_with_corpus() -> ScopedMemoryStore {
+        let builder = test_support::deterministic_artifact_builder(100);
+        let store = ScopedMemoryStore::new();
+        // Alice and Bob share team "infra"; Carol is on team "support".
+        let alice = MemoryPrincipal::new("alice", Some("infra"));
+        let bob = MemoryPrincipal::new("bob", Some("infra"));
+        let carol = MemoryPrincipal::new("carol", Some("support"));
+        store.store(
+            builder.build("s1", "fact", "alice prefers vim", vec![]),
+            alice.clone(),
+            MemoryScope::Private,
+        );
+        store.store(
+            builder.build("s2", "fact", "staging db is pg-17", vec![]),
+            alice,
+            MemoryScope::Team,
+        );
+        store.store(
+            builder.build("s3", "fact", "bob reviews on fridays", vec![]),
+            bob,
+            MemoryScope::Private,
+        );
+        store.store(
+            builder.build("s4", "fact", "office wifi is Guest-5G", vec![]),
+            carol,
+            MemoryScope::Global,
+        );
+        store
+    }
+
+    fn contents(artifacts: &[Artifact]) -> Vec<&str> {
+        artifacts.iter().map(|a| a.content.as_str()).collect()
+    }
+
+    #[test]
+    fn private_memory_is_invisible_cross_user() {
+        let store = store_with_corpus();
+        let bob = MemoryPrincipal::new("bob", Some("infra"));
+        let visible = store.visible_artifacts(&bob);
+        // Bob sees his own private memory, the team fact, and the global
+        // fact — never Alice's private memory.
+        assert_eq!(
+            contents(&visible),
+            [
+                "staging db is pg-17",
+                "bob reviews on fridays",
+                "office wifi is Guest-5G",
+            ]
+        );
+    }
+
+    #[test]
+    fn team_memory_is_shared_within_a_team_but_not_across_teams() {
+        let store = store_with_corpus();
+        let carol = MemoryPrincipal::new("carol", Some("support"));
+        let visible = store.visible_artifacts(&carol);
+        assert!(!contents(&visible).contains(&"staging db is pg-17"));
+
+        // A teamless user gets no team memory either, even as its owner's
+        // namesake team dissolves around them.
+        let teamless = MemoryPrincipal::new("dave", None);
+        assert_eq!(contents(&store.visible_artifacts(&teamless)), [
+            "office wifi is Guest-5G",
+        ]);
+    }
+
+    #[test]
+    fn synthesis_never_cites_artifacts_outside_the_requesters_scope() {
+        let store = store_with_corpus();
+        let synthesizer = test_support::deterministic_synthesizer(2_000);
+        let bob = MemoryPrincipal::new("bob", Some("infra"));
+        let insights = store.synthesize_for(&bob, &synthesizer);
+        assert!(!insights.is_empty());
+        for insight in &insights {
+            // art-1 is Alice's private artifact.
+            assert!(!insight.artifact_ids.contains(&"art-1".to_string()));
+            assert!(!insight.body.contains("alice prefers vim"));
+        }
+    }
+
+    #[tokio::test]
+    async fn search_results_respect_the_scope_boundary() {
+        let store = store_with_corpus();
+        let index = MemorySearchIndex::new(None);
+        for artifact in store.visible_artifacts(&MemoryPrincipal::new("alice", Some("infra"))) {
+            index
+                .index(&artifact.id, &artifact.content, SensitivityLevel::Normal)
+                .await;
+        }
+        // Bob's artifact too, so the index holds everything.
+        index
+            .index("art-3", "bob reviews on fridays", SensitivityLevel::Normal)
+            .await;
+
+        let carol = MemoryPrincipal::new("carol", Some("support"));
+        let hits = store
+            .search_for(&carol, &index, "staging db", SensitivityLevel::Normal, 10)
+            .await;
+        // The team fact matches the query but is filtered out for Carol.
+        assert!(hits.is_empty());
+
+        let bob = MemoryPrincipal::new("bob", Some("infra"));
+        let hits = store
+            .search_for(&bob, &index, "staging db", SensitivityLevel::Normal, 10)
+            .await;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "art-2");
+    }
+}
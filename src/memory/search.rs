@@ -0,0 +1,301 @@
+//! Memory search — keyword by default, semantic when an embedding backend
+//! is configured.
+//!
+//! Keyword overlap misses paraphrases: "service my car" never finds the
+//! stored "automobile maintenance intervals". With `memory.embedding`
+//! configured, resources and artifacts are embedded as they're stored and
+//! `GET /api/memory/search` ranks by cosine similarity instead; without a
+//! backend the same index answers with keyword overlap, so search always
+//! works. Sensitivity filtering applies in both modes — an item above the
+//! caller's ceiling never appears, however similar.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::error::{Result, SafeClawError};
+use crate::privacy::SensitivityLevel;
+
+/// Configuration under `memory.embedding`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingConfig {
+    /// Embedding endpoint (OpenAI-compatible `/embeddings`); `None` keeps
+    /// search keyword-based.
+    pub endpoint: Option<String>,
+    pub model: String,
+    /// Credential-store reference for the endpoint's API key.
+    pub api_key_ref: Option<String>,
+}
+
+/// Produces embeddings — an HTTP endpoint in production, canned in tests.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
+
+/// Calls an OpenAI-compatible `/embeddings` endpoint.
+pub struct HttpEmbedder {
+    config: EmbeddingConfig,
+    api_key: Option<String>,
+    http: reqwest::Client,
+}
+
+impl HttpEmbedder {
+    pub fn new(config: EmbeddingConfig, api_key: Option<String>) -> Self {
+        Self {
+            config,
+            api_key,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for HttpEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let endpoint = self.config.endpoint.as_ref().ok_or_else(|| {
+            SafeClawError::Config("embedding endpoint not configured".into())
+        })?;
+        let mut request = self.http.post(endpoint).json(&serde_json::json!({
+            "model": self.config.model,
+            "input": text,
+        }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response: serde_json::Value = request
+            .send()
+            .await
+            .map_err(|e| SafeClawError::Config(format!("embedding endpoint: {e}")))?
+            .json()
+            .await
+            .map_err(|e| SafeClawError::Config(format!("embedding response: {e}")))?;
+        response["data"][0]["embedding"]
+            .as_array()
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_f64().map(|f| f as f32))
+                    .collect()
+            })
+            .ok_or_else(|| SafeClawError::Config("embedding response missing vector".into()))
+    }
+}
+
+struct IndexedItem {
+    id: String,
+    content: String,
+    sensitivity: SensitivityLevel,
+    embedding: Option<Vec<f32>>,
+}
+
+/// One ranked result from `GET /api/memory/search`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub id: String,
+    pub score: f32,
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// Lowercased word tokens; 1-char tokens ("a", "i") are dropped — they
+/// match almost everything and only add noise.
+fn tokens(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() > 1)
+        .map(String::from)
+        .collect()
+}
+
+fn keyword_score(query: &str, content: &str) -> f32 {
+    let query_tokens = tokens(query);
+    if query_tokens.is_empty() {
+        return 0.0;
+    }
+    let content_tokens: std::collections::HashSet<String> = tokens(content).into_iter().collect();
+    let hits = query_tokens
+        .iter()
+        .filter(|t| content_tokens.contains(*t))
+        .count();
+    hits as f32 / query_tokens.len() as f32
+}
+
+/// The searchable memory index. Items are embedded as they're stored when
+/// a backend is configured.
+pub struct MemorySearchIndex {
+    embedder: Option<Arc<dyn Embedder>>,
+    items: Mutex<Vec<IndexedItem>>,
+}
+
+impl MemorySearchIndex {
+    pub fn new(embedder: Option<Arc<dyn Embedder>>) -> Self {
+        Self {
+            embedder,
+            items: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Index one resource or artifact. An embedding failure degrades that
+    /// item to keyword matching instead of failing the store.
+    pub async fn index(&self, id: &str, content: &str, sensitivity: SensitivityLevel) {
+        let embedding = match &self.embedder {
+            Some(embedder) => match embedder.embed(content).await {
+                Ok(vector) => Some(vector),
+                Err(e) => {
+                    tracing::warn!(id, error = %e, "embedding failed; keyword-only item");
+                    None
+                }
+            },
+            None => None,
+        };
+        self.items
+            .lock()
+            .expect("memory search index poisoned")
+            .push(IndexedItem {
+                id: id.to_string(),
+                content: content.to_string(),
+                sensitivity,
+                embedding,
+            });
+    }
+
+    /// Rank indexed items against the query: cosine similarity when both
+    /// the query and the item are embedded, keyword overlap otherwise.
+    /// Items above `max_sensitivity` are excluded before ranking.
+    pub async fn search(
+        &self,
+        query: &str,
+        max_sensitivity: SensitivityLevel,
+        limit: usize,
+    ) -> Vec<SearchHit> {
+        let query_embedding = match &self.embedder {
+            Some(embedder) => embedder.embed(query).await.ok(),
+            None => None,
+        };
+        let items = self.items.lock().expect("memory search index poisoned");
+        let mut hits: Vec<SearchHit> = items
+            .iter()
+            .filter(|item| item.sensitivity <= max_sensitivity)
+            .map(|item| {
+                let score = match (&query_embedding, &item.embedding) {
+                    (Some(query), Some(item_vector)) => cosine(query, item_vector),
+                    _ => keyword_score(query, &item.content),
+                };
+                SearchHit {
+                    id: item.id.clone(),
+                    score,
+                }
+            })
+            .filter(|hit| hit.score > 0.0)
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// Maps whole texts to fixed vectors, standing in for a real model.
+    struct CannedEmbedder(HashMap<&'static str, Vec<f32>>);
+
+    #[async_trait]
+    impl Embedder for CannedEmbedder {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            self.0
+                .get(text)
+                .cloned()
+                .ok_or_else(|| SafeClawError::Config(format!("no canned vector for `{text}`")))
+        }
+    }
+
+    const QUERY: &str = "when should I service my car";
+    const SEMANTIC: &str = "automobile maintenance intervals";
+    const KEYWORD_ONLY: &str = "car park ticket from tuesday";
+
+    fn embedder() -> Arc<dyn Embedder> {
+        Arc::new(CannedEmbedder(
+            [
+                (QUERY, vec![0.9, 0.1, 0.0]),
+                (SEMANTIC, vec![0.8, 0.2, 0.1]),
+                (KEYWORD_ONLY, vec![0.0, 0.1, 0.9]),
+            ]
+            .into_iter()
+            .collect(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn semantic_search_outranks_a_surface_keyword_match() {
+        let index = MemorySearchIndex::new(Some(embedder()));
+        index.index("art-sem", SEMANTIC, SensitivityLevel::Normal).await;
+        index.index("art-kw", KEYWORD_ONLY, SensitivityLevel::Normal).await;
+
+        let hits = index.search(QUERY, SensitivityLevel::Normal, 10).await;
+        let ids: Vec<&str> = hits.iter().map(|h| h.id.as_str()).collect();
+        // "automobile maintenance" shares no keyword with the query but is
+        // the semantically right answer; the parking ticket merely contains
+        // the word "car".
+        assert_eq!(ids, ["art-sem", "art-kw"]);
+        assert!(hits[0].score > hits[1].score);
+    }
+
+    #[tokio::test]
+    async fn keyword_fallback_applies_without_a_backend() {
+        let index = MemorySearchIndex::new(None);
+        index.index("art-sem", SEMANTIC, SensitivityLevel::Normal).await;
+        index.index("art-kw", KEYWORD_ONLY, SensitivityLevel::Normal).await;
+
+        let hits = index.search(QUERY, SensitivityLevel::Normal, 10).await;
+        // Without embeddings only the literal "car" match is findable.
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "art-kw");
+    }
+
+    #[tokio::test]
+    async fn sensitivity_filtering_applies_in_both_modes() {
+        let index = MemorySearchIndex::new(Some(embedder()));
+        index
+            .index("art-sem", SEMANTIC, SensitivityLevel::HighlySensitive)
+            .await;
+        index.index("art-kw", KEYWORD_ONLY, SensitivityLevel::Normal).await;
+
+        let hits = index.search(QUERY, SensitivityLevel::Normal, 10).await;
+        assert!(hits.iter().all(|h| h.id != "art-sem"));
+
+        let unrestricted = index
+            .search(QUERY, SensitivityLevel::HighlySensitive, 10)
+            .await;
+        assert_eq!(unrestricted[0].id, "art-sem");
+    }
+
+    #[tokio::test]
+    async fn an_embedding_failure_degrades_to_keyword_for_that_item() {
+        let index = MemorySearchIndex::new(Some(embedder()));
+        // Not in the canned map: embedding fails, the item is still indexed.
+        index
+            .index("art-plain", "car insurance renewal", SensitivityLevel::Normal)
+            .await;
+        let hits = index.search(QUERY, SensitivityLevel::Normal, 10).await;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "art-plain");
+    }
+}
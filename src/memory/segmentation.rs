@@ -0,0 +1,309 @@
+//! Online conversation topic segmentation.
+//!
+//! Emitting a Topic artifact per message makes topic aggregation noisy:
+//! twenty messages about one dinner plan become twenty "food" topics, and
+//! the synthesizer's pattern counts measure chattiness, not topics. The
+//! segmenter groups consecutive messages into topical segments using cheap
+//! heuristics — a long time gap, an explicit topic-shift phrase, or zero
+//! keyword overlap with the running segment — and produces one Topic
+//! artifact per segment with the full message span in
+//! `source_resource_ids`. No LLM is required; an optional [`BoundaryRefiner`]
+//! hook lets an LLM adjust the heuristic boundaries. Segment spans are also
+//! what compaction uses to summarize whole topics at once.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::extract::ArtifactBuilder;
+use crate::memory::Artifact;
+
+/// Artifact kind produced per segment.
+pub const TOPIC_KIND: &str = "topic";
+
+const STOPWORDS: &[&str] = &[
+    "that", "this", "with", "have", "what", "about", "your", "will", "they", "them", "then",
+    "there", "when", "from", "should", "would", "could", "after", "more", "some", "just",
+    "like", "been", "were", "does", "still",
+];
+
+fn default_shift_phrases() -> Vec<String> {
+    [
+        "by the way",
+        "anyway",
+        "changing the subject",
+        "on another note",
+        "unrelated, but",
+        "different topic",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// Configuration under `memory.segmentation`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SegmentationConfig {
+    /// A silence longer than this starts a new segment.
+    pub time_gap_secs: i64,
+    /// Phrases that explicitly mark a topic shift (matched case-insensitively
+    /// at the start of a message).
+    pub shift_phrases: Vec<String>,
+}
+
+impl Default for SegmentationConfig {
+    fn default() -> Self {
+        Self {
+            time_gap_secs: 1_800,
+            shift_phrases: default_shift_phrases(),
+        }
+    }
+}
+
+/// One message offered to the segmenter.
+#[derive(Debug, Clone)]
+pub struct SegmentMessage {
+    /// Layer-1 resource ID of the message.
+    pub resource_id: String,
+    pub content: String,
+    pub timestamp: i64,
+}
+
+/// A run of consecutive messages about one topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// Index span into the input slice, inclusive start, exclusive end —
+    /// what compaction summarizes as one unit.
+    pub start: usize,
+    pub end: usize,
+    pub resource_ids: Vec<String>,
+    /// Most frequent keywords of the segment, count-then-alphabetical.
+    pub keywords: Vec<String>,
+}
+
+/// Optional second pass adjusting heuristic boundaries (LLM-backed in
+/// production). Receives and returns boundary indices into the message
+/// slice; index 0 is implicit and must not be removed.
+pub trait BoundaryRefiner: Send + Sync {
+    fn refine(&self, messages: &[SegmentMessage], boundaries: Vec<usize>) -> Vec<usize>;
+}
+
+fn keywords(content: &str) -> HashSet<String> {
+    content
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() >= 4 && !STOPWORDS.contains(w))
+        .map(String::from)
+        .collect()
+}
+
+pub struct TopicSegmenter {
+    config: SegmentationConfig,
+    refiner: Option<Box<dyn BoundaryRefiner>>,
+}
+
+impl TopicSegmenter {
+    pub fn new(config: SegmentationConfig) -> Self {
+        Self {
+            config,
+            refiner: None,
+        }
+    }
+
+    pub fn with_refiner(mut self, refiner: Box<dyn BoundaryRefiner>) -> Self {
+        self.refiner = Some(refiner);
+        self
+    }
+
+    fn is_shift(&self, content: &str) -> bool {
+        let lowered = content.trim_start().to_lowercase();
+        self.config
+            .shift_phrases
+            .iter()
+            .any(|phrase| lowered.starts_with(phrase.as_str()))
+    }
+
+    /// Indices where a new segment starts (never includes 0).
+    fn boundaries(&self, messages: &[SegmentMessage]) -> Vec<usize> {
+        let mut boundaries = Vec::new();
+        let mut segment_keywords: HashSet<String> = HashSet::new();
+        for (idx, message) in messages.iter().enumerate() {
+            let message_keywords = keywords(&message.content);
+            let boundary = idx > 0
+                && (message.timestamp - messages[idx - 1].timestamp > self.config.time_gap_secs
+                    || self.is_shift(&message.content)
+                    // Divergence: a contentful message sharing no keyword
+                    // with the running segment. One-keyword messages are
+                    // too ambiguous to split on.
+                    || (message_keywords.len() >= 2
+                        && segment_keywords.len() >= 2
+                        && message_keywords.is_disjoint(&segment_keywords)));
+            if boundary {
+                boundaries.push(idx);
+                segment_keywords.clear();
+            }
+            segment_keywords.extend(message_keywords);
+        }
+        boundaries
+    }
+
+    /// Segment a session's messages, in order.
+    pub fn segment(&self, messages: &[SegmentMessage]) -> Vec<Segment> {
+        if messages.is_empty() {
+            return Vec::new();
+        }
+        let mut boundaries = self.boundaries(messages);
+        if let Some(refiner) = &self.refiner {
+            boundaries = refiner.refine(messages, boundaries);
+            boundaries.retain(|&b| b > 0 && b < messages.len());
+            boundaries.sort_unstable();
+            boundaries.dedup();
+        }
+
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for end in boundaries.into_iter().chain([messages.len()]) {
+            let span = &messages[start..end];
+            let mut counts: Vec<(String, usize)> = Vec::new();
+            for message in span {
+                for word in keywords(&message.content) {
+                    match counts.iter_mut().find(|(w, _)| *w == word) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((word, 1)),
+                    }
+                }
+            }
+            counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            segments.push(Segment {
+                start,
+                end,
+                resource_ids: span.iter().map(|m| m.resource_id.clone()).collect(),
+                keywords: counts.into_iter().take(3).map(|(w, _)| w).collect(),
+            });
+            start = end;
+        }
+        segments
+    }
+
+    /// One Topic artifact per segment — the input `topic_aggregation` and
+    /// the synthesizer count meaningfully.
+    pub fn topic_artifacts(
+        &self,
+        session_id: &str,
+        messages: &[SegmentMessage],
+        builder: &ArtifactBuilder,
+    ) -> Vec<Artifact> {
+        self.segment(messages)
+            .into_iter()
+            .map(|segment| {
+                builder.build(
+                    session_id,
+                    TOPIC_KIND,
+                    &segment.keywords.join(", "),
+                    segment.resource_ids,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Default for TopicSegmenter {
+    fn default() -> Self {
+        Self::new(SegmentationConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::test_support;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn message(resource_id: &str, content: &str, timestamp: i64) -> SegmentMessage {
+        SegmentMessage {
+            resource_id: resource_id.into(),
+            content: content.into(),
+            timestamp,
+        }
+    }
+
+    /// Dinner plan, then (after a long gap) a work deadline, then an
+    /// explicit shift to holiday plans.
+    fn scripted_conversation() -> Vec<SegmentMessage> {
+        vec![
+            message("m1", "should we get dinner friday night", NOW),
+            message("m2", "dinner sounds great, maybe the thai restaurant", NOW + 60),
+            message("m3", "the thai restaurant near the station books out fast", NOW + 120),
+            message("m4", "reserve the thai place for eight, friday works", NOW + 200),
+            // Two-hour gap.
+            message("m5", "deadline moved, the quarterly report is due monday", NOW + 7_400),
+            message("m6", "monday is tight, the report needs charts first", NOW + 7_460),
+            // Explicit shift phrase.
+            message("m7", "by the way, did you reserve holiday flights yet", NOW + 7_520),
+            message("m8", "holiday flights are cheaper midweek", NOW + 7_580),
+        ]
+    }
+
+    #[test]
+    fn scripted_conversation_splits_at_the_expected_boundaries() {
+        let segments = TopicSegmenter::default().segment(&scripted_conversation());
+        let spans: Vec<(usize, usize)> = segments.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(spans, [(0, 4), (4, 6), (6, 8)]);
+        assert_eq!(segments[0].resource_ids, ["m1", "m2", "m3", "m4"]);
+        assert_eq!(segments[1].resource_ids, ["m5", "m6"]);
+        assert_eq!(segments[2].resource_ids, ["m7", "m8"]);
+        assert!(segments[0].keywords.contains(&"thai".to_string()));
+    }
+
+    #[test]
+    fn keyword_divergence_alone_starts_a_segment() {
+        let messages = vec![
+            message("m1", "the garden tomatoes are ripening nicely", NOW),
+            message("m2", "tomatoes need watering twice a week", NOW + 60),
+            message("m3", "compile times regressed since the toolchain upgrade", NOW + 120),
+        ];
+        let segments = TopicSegmenter::default().segment(&messages);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].resource_ids, ["m3"]);
+    }
+
+    #[test]
+    fn one_topic_artifact_per_segment_instead_of_per_message() {
+        let messages = scripted_conversation();
+        let builder = test_support::deterministic_artifact_builder(NOW);
+        let artifacts =
+            TopicSegmenter::default().topic_artifacts("s1", &messages, &builder);
+
+        // Eight messages collapse to three topics.
+        assert_eq!(artifacts.len(), 3);
+        assert!(artifacts.len() < messages.len());
+        assert!(artifacts.iter().all(|a| a.kind == TOPIC_KIND));
+        assert_eq!(
+            artifacts[0].source_resource_ids,
+            ["m1", "m2", "m3", "m4"]
+        );
+        assert_eq!(artifacts[2].source_resource_ids, ["m7", "m8"]);
+    }
+
+    struct MergeTailRefiner;
+
+    impl BoundaryRefiner for MergeTailRefiner {
+        fn refine(&self, _messages: &[SegmentMessage], boundaries: Vec<usize>) -> Vec<usize> {
+            // Pretend the LLM decided the last two segments are one topic.
+            let mut boundaries = boundaries;
+            boundaries.pop();
+            boundaries
+        }
+    }
+
+    #[test]
+    fn a_refiner_can_adjust_the_heuristic_boundaries() {
+        let segmenter =
+            TopicSegmenter::default().with_refiner(Box::new(MergeTailRefiner));
+        let segments = segmenter.segment(&scripted_conversation());
+        let spans: Vec<(usize, usize)> = segments.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(spans, [(0, 4), (4, 8)]);
+    }
+}
@@ -0,0 +1,284 @@
+//! Layer 3 synthesis — cross-conversation insights from artifacts.
+//!
+//! Three rules: `Pattern` flags knowledge kinds that recur, `Summary` rolls
+//! up each session's artifacts, `Correlation` links identical content
+//! appearing across sessions. Grouping goes through `BTreeMap` so output
+//! ordering is stable regardless of input map iteration order.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::determinism::{Clock, IdGenerator, SystemClock, UuidIdGenerator};
+use crate::memory::{Artifact, Insight};
+
+/// Which synthesis rule produced an insight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SynthesisRule {
+    Pattern,
+    Summary,
+    Correlation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SynthesisConfig {
+    /// A kind must recur at least this often to produce a pattern insight.
+    pub pattern_min_count: usize,
+}
+
+impl Default for SynthesisConfig {
+    fn default() -> Self {
+        Self {
+            pattern_min_count: 3,
+        }
+    }
+}
+
+/// Builds insights with injected time and IDs, mirroring
+/// [`ArtifactBuilder`](crate::memory::extract::ArtifactBuilder).
+pub struct InsightBuilder {
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl InsightBuilder {
+    pub fn new(clock: Arc<dyn Clock>, ids: Arc<dyn IdGenerator>) -> Self {
+        Self { clock, ids }
+    }
+
+    pub fn build(
+        &self,
+        rule: SynthesisRule,
+        title: &str,
+        body: &str,
+        artifact_ids: Vec<String>,
+    ) -> Insight {
+        Insight {
+            id: self.ids.next_id("ins"),
+            rule,
+            title: title.to_string(),
+            body: body.to_string(),
+            artifact_ids,
+            created_at: self.clock.now(),
+        }
+    }
+}
+
+impl Default for InsightBuilder {
+    fn default() -> Self {
+        Self::new(Arc::new(SystemClock), Arc::new(UuidIdGenerator))
+    }
+}
+
+/// Cross-conversation synthesizer. Output order is fixed: pattern insights
+/// (by kind), then session summaries (by session), then correlations (by
+/// content).
+pub struct Synthesizer {
+    builder: InsightBuilder,
+    config: SynthesisConfig,
+}
+
+impl Synthesizer {
+    pub fn new(builder: InsightBuilder, config: SynthesisConfig) -> Self {
+        Self { builder, config }
+    }
+
+    pub fn synthesize(&self, artifacts: &[Artifact]) -> Vec<Insight> {
+        let mut insights = Vec::new();
+        insights.extend(self.patterns(artifacts));
+        insights.extend(self.summaries(artifacts));
+        insights.extend(self.correlations(artifacts));
+        insights
+    }
+
+    fn patterns(&self, artifacts: &[Artifact]) -> Vec<Insight> {
+        let mut by_kind: BTreeMap<&str, Vec<&Artifact>> = BTreeMap::new();
+        for artifact in artifacts {
+            by_kind.entry(&artifact.kind).or_default().push(artifact);
+        }
+        by_kind
+            .into_iter()
+            .filter(|(_, group)| group.len() >= self.config.pattern_min_count)
+            .map(|(kind, group)| {
+                let sessions: BTreeSet<&str> =
+                    group.iter().map(|a| a.session_id.as_str()).collect();
+                self.builder.build(
+                    SynthesisRule::Pattern,
+                    &format!("Recurring {kind} artifacts"),
+                    &format!(
+                        "{} {kind} artifacts across {} session(s)",
+                        group.len(),
+                        sessions.len()
+                    ),
+                    group.iter().map(|a| a.id.clone()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn summaries(&self, artifacts: &[Artifact]) -> Vec<Insight> {
+        let mut by_session: BTreeMap<&str, Vec<&Artifact>> = BTreeMap::new();
+        for artifact in artifacts {
+            by_session
+                .entry(&artifact.session_id)
+                .or_default()
+                .push(artifact);
+        }
+        by_session
+            .into_iter()
+            .map(|(session, group)| {
+                let mut kind_counts: BTreeMap<&str, usize> = BTreeMap::new();
+                for artifact in &group {
+                    *kind_counts.entry(&artifact.kind).or_default() += 1;
+                }
+                let body: Vec<String> = kind_counts
+                    .into_iter()
+                    .map(|(kind, count)| format!("{count} {kind}"))
+                    .collect();
+                self.builder.build(
+                    SynthesisRule::Summary,
+                    &format!("Session {session} summary"),
+                    &body.join(", "),
+                    group.iter().map(|a| a.id.clone()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn correlations(&self, artifacts: &[Artifact]) -> Vec<Insight> {
+        let mut by_content: BTreeMap<&str, Vec<&Artifact>> = BTreeMap::new();
+        for artifact in artifacts {
+            by_content
+                .entry(&artifact.content)
+                .or_default()
+                .push(artifact);
+        }
+        by_content
+            .into_iter()
+            .filter_map(|(content, group)| {
+                let sessions: BTreeSet<&str> =
+                    group.iter().map(|a| a.session_id.as_str()).collect();
+                if sessions.len() < 2 {
+                    return None;
+                }
+                let sessions: Vec<&str> = sessions.into_iter().collect();
+                Some(self.builder.build(
+                    SynthesisRule::Correlation,
+                    "Cross-session correlation",
+                    &format!("{content:?} appears in sessions {}", sessions.join(", ")),
+                    group.iter().map(|a| a.id.clone()).collect(),
+                ))
+            })
+            .collect()
+    }
+}
+
+impl Default for Synthesizer {
+    fn default() -> Self {
+        Self::new(InsightBuilder::default(), SynthesisConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::test_support;
+
+    fn corpus() -> Vec<Artifact> {
+        let builder = test_support::deterministic_artifact_builder(100);
+        vec![
+            builder.build("s1", "fact", "timezone: Europe/Berlin", vec!["r1".into()]),
+            builder.build("s1", "fact", "name: Sam", vec!["r1".into()]),
+            builder.build("s1", "preference", "prefers short answers", vec!["r2".into()]),
+            builder.build("s2", "fact", "timezone: Europe/Berlin", vec!["r3".into()]),
+            builder.build("s2", "fact", "editor: vim", vec!["r3".into()]),
+        ]
+    }
+
+    const GOLDEN: &str = r#"[
+  {
+    "id": "ins-1",
+    "rule": "pattern",
+    "title": "Recurring fact artifacts",
+    "body": "4 fact artifacts across 2 session(s)",
+    "artifact_ids": [
+      "art-1",
+      "art-2",
+      "art-4",
+      "art-5"
+    ],
+    "created_at": 2000
+  },
+  {
+    "id": "ins-2",
+    "rule": "summary",
+    "title": "Session s1 summary",
+    "body": "2 fact, 1 preference",
+    "artifact_ids": [
+      "art-1",
+      "art-2",
+      "art-3"
+    ],
+    "created_at": 2000
+  },
+  {
+    "id": "ins-3",
+    "rule": "summary",
+    "title": "Session s2 summary",
+    "body": "2 fact",
+    "artifact_ids": [
+      "art-4",
+      "art-5"
+    ],
+    "created_at": 2000
+  },
+  {
+    "id": "ins-4",
+    "rule": "correlation",
+    "title": "Cross-session correlation",
+    "body": "\"timezone: Europe/Berlin\" appears in sessions s1, s2",
+    "artifact_ids": [
+      "art-1",
+      "art-4"
+    ],
+    "created_at": 2000
+  }
+]"#;
+
+    #[test]
+    fn synthesis_output_matches_the_golden_corpus_byte_for_byte() {
+        let synthesizer = test_support::deterministic_synthesizer(2_000);
+        let insights = synthesizer.synthesize(&corpus());
+        let rendered = serde_json::to_string_pretty(&insights).unwrap();
+        assert_eq!(rendered, GOLDEN);
+    }
+
+    #[test]
+    fn two_runs_over_the_same_corpus_are_identical() {
+        let first = test_support::deterministic_synthesizer(2_000).synthesize(&corpus());
+        let second = test_support::deterministic_synthesizer(2_000).synthesize(&corpus());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn pattern_rule_respects_the_minimum_count() {
+        let builder = test_support::deterministic_artifact_builder(100);
+        let artifacts = vec![
+            builder.build("s1", "fact", "a: 1", vec![]),
+            builder.build("s1", "fact", "b: 2", vec![]),
+        ];
+        let synthesizer = Synthesizer::new(
+            test_support::deterministic_insight_builder(2_000),
+            SynthesisConfig {
+                pattern_min_count: 3,
+            },
+        );
+        let insights = synthesizer.synthesize(&artifacts);
+        assert!(insights
+            .iter()
+            .all(|i| i.rule != SynthesisRule::Pattern));
+    }
+}
@@ -0,0 +1,48 @@
+//! Deterministic variants of the memory pipeline for tests.
+//!
+//! A fixed clock plus a sequential ID generator make extraction and synthesis
+//! output reproducible, so golden tests can assert it byte-for-byte. Kept as
+//! a regular module (not `#[cfg(test)]`) so downstream crates' tests can use
+//! it too.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::memory::determinism::{Clock, IdGenerator};
+use crate::memory::extract::{ArtifactBuilder, Extractor};
+use crate::memory::synthesis::{InsightBuilder, SynthesisConfig, Synthesizer};
+
+/// Always reports the same instant.
+pub struct FixedClock(pub i64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.0
+    }
+}
+
+/// `art-1`, `art-2`, … in allocation order.
+#[derive(Default)]
+pub struct SequentialIds(AtomicU64);
+
+impl IdGenerator for SequentialIds {
+    fn next_id(&self, prefix: &str) -> String {
+        format!("{prefix}-{}", self.0.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+pub fn deterministic_artifact_builder(now: i64) -> ArtifactBuilder {
+    ArtifactBuilder::new(Arc::new(FixedClock(now)), Arc::new(SequentialIds::default()))
+}
+
+pub fn deterministic_extractor(now: i64) -> Extractor {
+    Extractor::new(deterministic_artifact_builder(now))
+}
+
+pub fn deterministic_insight_builder(now: i64) -> InsightBuilder {
+    InsightBuilder::new(Arc::new(FixedClock(now)), Arc::new(SequentialIds::default()))
+}
+
+pub fn deterministic_synthesizer(now: i64) -> Synthesizer {
+    Synthesizer::new(deterministic_insight_builder(now), SynthesisConfig::default())
+}
@@ -0,0 +1,129 @@
+//! OpenAPI spec generation for the HTTP API.
+//!
+//! External tooling kept reverse-engineering request shapes from handler
+//! code. The handlers are annotated with `utoipa::path` and their serde
+//! types derive `ToSchema`, so the spec always matches the actual wire
+//! format. Served at `GET /api/openapi.json` and writable offline with
+//! `safeclaw openapi --output spec.json`.
+
+use axum::routing::get;
+use axum::{Json, Router};
+use utoipa::OpenApi;
+
+use crate::error::Result;
+
+/// The aggregated API document. New handlers must be registered here — the
+/// drift test fails if a critical path goes missing.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "SafeClaw API",
+        description = "Security proxy gateway for AI agents.",
+    ),
+    paths(
+        crate::agent::handler::get_llm_trace,
+        crate::agent::handler::set_llm_trace_enabled,
+        crate::agent::handler::get_scratchpad,
+        crate::agent::handler::get_timings,
+        crate::agent::handler::get_full_message_content,
+        crate::privacy::handler::post_dsar,
+        crate::privacy::handler::download_archive,
+        crate::channels::handler::pause_channel,
+        crate::channels::handler::resume_channel,
+        crate::channels::handler::channel_status,
+    ),
+    components(schemas(
+        crate::api::ErrorResponse,
+        crate::api::ErrorBody,
+        crate::agent::handler::LlmTraceResponse,
+        crate::agent::handler::SetEnabledBody,
+        crate::agent::handler::EnabledResponse,
+        crate::agent::handler::ScratchpadResponse,
+        crate::agent::handler::TimingsResponse,
+        crate::agent::handler::FullContentResponse,
+        crate::agent::llm_trace::LlmTraceEntry,
+        crate::agent::timing::TurnTiming,
+        crate::agent::timing::TimingSegment,
+        crate::privacy::handler::DsarBody,
+        crate::privacy::handler::DsarResponse,
+        crate::privacy::dsar::DsarArchive,
+        crate::channels::handler::PauseBody,
+        crate::channels::handler::ChannelStatusEntry,
+        crate::channels::handler::ResumeResponse,
+        crate::channels::control::ChannelState,
+        crate::channels::control::PauseMode,
+    ))
+)]
+pub struct ApiDoc;
+
+/// The spec as a JSON value.
+pub fn spec() -> serde_json::Value {
+    serde_json::to_value(ApiDoc::openapi()).expect("openapi spec serializes")
+}
+
+/// `safeclaw openapi --output <path>`: write the spec for offline use.
+pub fn write_spec(path: &std::path::Path) -> Result<()> {
+    std::fs::write(path, serde_json::to_vec_pretty(&spec())?)?;
+    Ok(())
+}
+
+/// Router serving `GET /api/openapi.json`.
+pub fn routes() -> Router {
+    Router::new().route("/api/openapi.json", get(|| async { Json(spec()) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Critical paths and schemas external tooling depends on; additions are
+    /// cheap, removals should fail review.
+    const CRITICAL_PATHS: &[&str] = &[
+        "/api/agent/sessions/{id}/llm-trace",
+        "/api/agent/sessions/{id}/timings",
+        "/api/agent/sessions/{id}/scratchpad",
+        "/api/agent/sessions/{id}/messages/{idx}/content",
+        "/api/privacy/dsar",
+        "/api/privacy/dsar/{job}/archive",
+    ];
+
+    const CRITICAL_SCHEMAS: &[&str] = &[
+        "ErrorResponse",
+        "TurnTiming",
+        "TimingSegment",
+        "DsarBody",
+        "DsarArchive",
+    ];
+
+    #[test]
+    fn spec_parses_and_covers_critical_paths_and_schemas() {
+        let spec = spec();
+        let paths = spec["paths"].as_object().expect("paths object");
+        for path in CRITICAL_PATHS {
+            assert!(paths.contains_key(*path), "missing path {path}");
+        }
+        let schemas = spec["components"]["schemas"]
+            .as_object()
+            .expect("schemas object");
+        for schema in CRITICAL_SCHEMAS {
+            assert!(schemas.contains_key(*schema), "missing schema {schema}");
+        }
+    }
+
+    #[test]
+    fn spec_round_trips_through_json() {
+        let text = serde_json::to_string(&spec()).unwrap();
+        let reparsed: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(reparsed["info"]["title"], "SafeClaw API");
+    }
+
+    #[test]
+    fn write_spec_produces_a_parseable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spec.json");
+        write_spec(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(parsed["paths"].is_object());
+    }
+}
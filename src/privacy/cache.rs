@@ -0,0 +1,225 @@
+//! Classification result caching.
+//!
+//! Re-classifying identical text — repeated messages, retries — wastes CPU,
+//! especially once semantic/NER backends are in the path. Results are cached
+//! in an LRU keyed by a hash of the input plus the rule-set version, with a
+//! configurable size and TTL. A rule hot-reload bumps the version, so stale
+//! entries can never serve under new rules; `invalidate_all` additionally
+//! drops them eagerly.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::privacy::SensitivityLevel;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassificationCacheConfig {
+    pub enabled: bool,
+    pub max_entries: usize,
+    pub ttl_secs: i64,
+}
+
+impl Default for ClassificationCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 4_096,
+            ttl_secs: 600,
+        }
+    }
+}
+
+struct CacheEntry {
+    level: SensitivityLevel,
+    inserted_at: i64,
+}
+
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// Recency queue, most recent at the back; may hold stale keys that are
+    /// skipped on eviction.
+    order: VecDeque<String>,
+    ruleset_version: u64,
+}
+
+/// LRU cache in front of the composite classifier.
+pub struct ClassificationCache {
+    config: ClassificationCacheConfig,
+    state: Mutex<CacheState>,
+}
+
+impl ClassificationCache {
+    pub fn new(config: ClassificationCacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+                ruleset_version: 0,
+            }),
+        }
+    }
+
+    fn key(ruleset_version: u64, input: &str) -> String {
+        format!("{ruleset_version}:{}", hex::encode(Sha256::digest(input)))
+    }
+
+    /// Classify through the cache. `classify` runs only on a miss.
+    pub fn get_or_classify(
+        &self,
+        input: &str,
+        now: i64,
+        classify: impl FnOnce(&str) -> SensitivityLevel,
+    ) -> SensitivityLevel {
+        if !self.config.enabled {
+            return classify(input);
+        }
+        let mut state = self.state.lock().expect("classification cache poisoned");
+        let key = Self::key(state.ruleset_version, input);
+        let cached = state
+            .entries
+            .get(&key)
+            .map(|entry| (entry.level, entry.inserted_at));
+        if let Some((level, inserted_at)) = cached {
+            if now - inserted_at < self.config.ttl_secs {
+                state.order.push_back(key);
+                return level;
+            }
+            state.entries.remove(&key);
+        }
+        let level = classify(input);
+        state.entries.insert(
+            key.clone(),
+            CacheEntry {
+                level,
+                inserted_at: now,
+            },
+        );
+        state.order.push_back(key);
+        while state.entries.len() > self.config.max_entries {
+            match state.order.pop_front() {
+                Some(old) => {
+                    // Keys re-pushed on hit may appear again later in the
+                    // queue; only evict ones not touched since.
+                    if !state.order.contains(&old) {
+                        state.entries.remove(&old);
+                    }
+                }
+                None => break,
+            }
+        }
+        level
+    }
+
+    /// Rule hot-reload: bump the version so every cached result misses, and
+    /// drop the entries eagerly.
+    pub fn invalidate_all(&self) {
+        let mut state = self.state.lock().expect("classification cache poisoned");
+        state.ruleset_version += 1;
+        state.entries.clear();
+        state.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn repeated_identical_input_hits_the_cache() {
+        let cache = ClassificationCache::new(ClassificationCacheConfig::default());
+        let calls = Cell::new(0);
+        let classify = |_: &str| {
+            calls.set(calls.get() + 1);
+            SensitivityLevel::Sensitive
+        };
+
+        assert_eq!(
+            cache.get_or_classify("my SSN is 123-45-6789", 0, classify),
+            SensitivityLevel::Sensitive
+        );
+        assert_eq!(
+            cache.get_or_classify("my SSN is 123-45-6789", 1, classify),
+            SensitivityLevel::Sensitive
+        );
+        assert_eq!(calls.get(), 1);
+        // Different input misses.
+        cache.get_or_classify("hello", 2, classify);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn ruleset_change_invalidates_cached_results() {
+        let cache = ClassificationCache::new(ClassificationCacheConfig::default());
+        let calls = Cell::new(0);
+        let classify = |_: &str| {
+            calls.set(calls.get() + 1);
+            SensitivityLevel::Normal
+        };
+        cache.get_or_classify("text", 0, classify);
+        cache.invalidate_all();
+        cache.get_or_classify("text", 1, classify);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn expired_entries_are_reclassified() {
+        let cache = ClassificationCache::new(ClassificationCacheConfig {
+            ttl_secs: 10,
+            ..Default::default()
+        });
+        let calls = Cell::new(0);
+        let classify = |_: &str| {
+            calls.set(calls.get() + 1);
+            SensitivityLevel::Normal
+        };
+        cache.get_or_classify("text", 0, classify);
+        cache.get_or_classify("text", 15, classify);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn capacity_evicts_least_recently_used() {
+        let cache = ClassificationCache::new(ClassificationCacheConfig {
+            max_entries: 2,
+            ..Default::default()
+        });
+        let calls = Cell::new(0);
+        let classify = |_: &str| {
+            calls.set(calls.get() + 1);
+            SensitivityLevel::Normal
+        };
+        cache.get_or_classify("a", 0, classify);
+        cache.get_or_classify("b", 1, classify);
+        // Touch "a" so "b" is the LRU entry when "c" is inserted.
+        cache.get_or_classify("a", 2, classify);
+        cache.get_or_classify("c", 3, classify);
+        assert_eq!(calls.get(), 3);
+
+        cache.get_or_classify("a", 4, classify);
+        assert_eq!(calls.get(), 3, "a should still be cached");
+        cache.get_or_classify("b", 5, classify);
+        assert_eq!(calls.get(), 4, "b should have been evicted");
+    }
+
+    #[test]
+    fn disabled_cache_always_classifies() {
+        let cache = ClassificationCache::new(ClassificationCacheConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        let calls = Cell::new(0);
+        let classify = |_: &str| {
+            calls.set(calls.get() + 1);
+            SensitivityLevel::Normal
+        };
+        cache.get_or_classify("text", 0, classify);
+        cache.get_or_classify("text", 1, classify);
+        assert_eq!(calls.get(), 2);
+    }
+}
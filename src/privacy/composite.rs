@@ -0,0 +1,205 @@
+//! Composite classification with an explicit failure mode.
+//!
+//! Classification fans out to multiple backends — the in-process
+//! `RegexClassifier` plus optional remote ones like an NER service — and
+//! the highest level wins. What was never explicit is what happens when a
+//! backend *errors*: silently proceeding unclassified is the one behavior
+//! a privacy product must not have. The failure mode is now deployment
+//! policy: fail-closed (the default) treats the message as
+//! `HighlySensitive`, which routes it to the TEE; fail-open treats the
+//! failed backend's verdict as `Normal` and lets the remaining backends
+//! still raise the level. Either way the failure lands in the audit log.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::audit::log::{AuditLog, AuditSeverity};
+use crate::error::Result;
+use crate::privacy::SensitivityLevel;
+
+/// Audit category for classifier backend failures.
+pub const AUDIT_CATEGORY_CLASSIFICATION: &str = "classification_failure";
+
+/// One classification backend. The regex classifier is wrapped into this;
+/// remote backends (NER service) implement it over HTTP.
+#[async_trait]
+pub trait ClassifierBackend: Send + Sync {
+    fn name(&self) -> &str;
+    async fn classify(&self, text: &str) -> Result<SensitivityLevel>;
+}
+
+/// What a backend error means for the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassificationFailureMode {
+    /// Treat the message as `HighlySensitive` — TEE routing, full
+    /// protections. The safe default.
+    #[default]
+    FailClosed,
+    /// Treat the failed backend's verdict as `Normal`; other backends can
+    /// still raise the level.
+    FailOpen,
+}
+
+/// Configuration under `privacy.classification`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassificationPolicyConfig {
+    pub failure_mode: ClassificationFailureMode,
+}
+
+/// Runs every backend and combines verdicts, honoring the failure mode.
+pub struct CompositeClassifier {
+    backends: Vec<Arc<dyn ClassifierBackend>>,
+    failure_mode: ClassificationFailureMode,
+}
+
+impl CompositeClassifier {
+    pub fn new(
+        backends: Vec<Arc<dyn ClassifierBackend>>,
+        config: ClassificationPolicyConfig,
+    ) -> Self {
+        Self {
+            backends,
+            failure_mode: config.failure_mode,
+        }
+    }
+
+    /// Classify one message: the highest verdict across backends. A backend
+    /// error is audited and resolved per the failure mode; under fail-closed
+    /// the result is immediately `HighlySensitive` regardless of what the
+    /// other backends say.
+    pub async fn classify(
+        &self,
+        session_id: &str,
+        text: &str,
+        audit: &AuditLog,
+        now: i64,
+    ) -> SensitivityLevel {
+        let mut level = SensitivityLevel::Normal;
+        for backend in &self.backends {
+            match backend.classify(text).await {
+                Ok(verdict) => level = level.max(verdict),
+                Err(e) => {
+                    let (severity, resolution) = match self.failure_mode {
+                        ClassificationFailureMode::FailClosed => {
+                            (AuditSeverity::Warning, "failing closed as highly_sensitive")
+                        }
+                        ClassificationFailureMode::FailOpen => {
+                            (AuditSeverity::Warning, "failing open as normal")
+                        }
+                    };
+                    audit.record(
+                        "classifier",
+                        session_id,
+                        severity,
+                        AUDIT_CATEGORY_CLASSIFICATION,
+                        &format!("backend `{}` failed ({e}); {resolution}", backend.name()),
+                        now,
+                    );
+                    if self.failure_mode == ClassificationFailureMode::FailClosed {
+                        return SensitivityLevel::HighlySensitive;
+                    }
+                }
+            }
+        }
+        level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::log::AuditIngestionConfig;
+    use crate::error::SafeClawError;
+
+    const NOW: i64 = 1_700_000_000;
+
+    struct Fixed(&'static str, SensitivityLevel);
+
+    #[async_trait]
+    impl ClassifierBackend for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+        async fn classify(&self, _text: &str) -> Result<SensitivityLevel> {
+            Ok(self.1)
+        }
+    }
+
+    struct Broken;
+
+    #[async_trait]
+    impl ClassifierBackend for Broken {
+        fn name(&self) -> &str {
+            "ner"
+        }
+        async fn classify(&self, _text: &str) -> Result<SensitivityLevel> {
+            Err(SafeClawError::Config("NER service unreachable".into()))
+        }
+    }
+
+    fn classifier(mode: ClassificationFailureMode) -> CompositeClassifier {
+        CompositeClassifier::new(
+            vec![
+                Arc::new(Fixed("regex", SensitivityLevel::Sensitive)),
+                Arc::new(Broken),
+            ],
+            ClassificationPolicyConfig { failure_mode: mode },
+        )
+    }
+
+    #[tokio::test]
+    async fn the_highest_backend_verdict_wins_when_all_succeed() {
+        let classifier = CompositeClassifier::new(
+            vec![
+                Arc::new(Fixed("regex", SensitivityLevel::Normal)),
+                Arc::new(Fixed("ner", SensitivityLevel::Sensitive)),
+            ],
+            ClassificationPolicyConfig::default(),
+        );
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        let level = classifier.classify("s1", "my address is...", &audit, NOW).await;
+        assert_eq!(level, SensitivityLevel::Sensitive);
+        assert!(audit.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_backend_error_under_fail_closed_routes_to_the_tee() {
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        let level = classifier(ClassificationFailureMode::FailClosed)
+            .classify("s1", "hello", &audit, NOW)
+            .await;
+        // HighlySensitive is the level the pipeline routes to the TEE.
+        assert_eq!(level, SensitivityLevel::HighlySensitive);
+
+        let events = audit.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].category, AUDIT_CATEGORY_CLASSIFICATION);
+        assert!(events[0].message.contains("`ner`"));
+        assert!(events[0].message.contains("failing closed"));
+    }
+
+    #[tokio::test]
+    async fn a_backend_error_under_fail_open_keeps_the_surviving_verdicts() {
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        // The regex backend still raises the level past Normal.
+        let level = classifier(ClassificationFailureMode::FailOpen)
+            .classify("s1", "hello", &audit, NOW)
+            .await;
+        assert_eq!(level, SensitivityLevel::Sensitive);
+        assert_eq!(audit.events().len(), 1);
+
+        // With only the broken backend, fail-open proceeds as Normal.
+        let lone = CompositeClassifier::new(
+            vec![Arc::new(Broken)],
+            ClassificationPolicyConfig {
+                failure_mode: ClassificationFailureMode::FailOpen,
+            },
+        );
+        let level = lone.classify("s1", "hello", &audit, NOW).await;
+        assert_eq!(level, SensitivityLevel::Normal);
+    }
+}
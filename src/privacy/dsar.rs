@@ -0,0 +1,248 @@
+//! Data subject access requests (DSAR) — export and erasure per identity.
+//!
+//! "What do you have on me?" must be answerable: a DSAR job gathers every
+//! record keyed to a principal across the registered stores — sessions,
+//! messages, memory, consent records, pairing info, audit events — into a
+//! structured archive (JSON per category plus a human-readable summary)
+//! downloadable exactly once, after which it is deleted. The erasure mode
+//! removes the same scope, always preceded by a dry-run preview of counts.
+//! Every DSAR action is audited under [`AUDIT_CATEGORY_DSAR`] by the caller.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::error::{Result, SafeClawError};
+
+/// Audit category for DSAR exports and erasures.
+pub const AUDIT_CATEGORY_DSAR: &str = "dsar";
+
+/// A store holding user-keyed data that participates in DSAR. Stores must be
+/// able to look records up by principal; stores indexed some other way grow
+/// a principal index to implement this.
+#[async_trait]
+pub trait UserDataStore: Send + Sync {
+    /// Category name used in the archive (`"sessions"`, `"memory"`, …).
+    fn category(&self) -> &str;
+    /// All records tied to the principal.
+    async fn gather(&self, principal: &str) -> Result<Vec<Value>>;
+    /// Erase all records tied to the principal, returning how many went.
+    async fn erase(&self, principal: &str) -> Result<usize>;
+}
+
+/// The packaged export: JSON per category plus a summary.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DsarArchive {
+    pub principal: String,
+    pub created_at: i64,
+    pub categories: BTreeMap<String, Vec<Value>>,
+    pub summary: String,
+}
+
+/// Per-category record counts, as returned by the erasure dry run.
+pub type CategoryCounts = BTreeMap<String, usize>;
+
+/// DSAR coordinator over the registered stores.
+pub struct DsarService {
+    stores: Vec<Arc<dyn UserDataStore>>,
+    /// Completed export archives, by job id — removed on first download.
+    archives: Mutex<BTreeMap<String, DsarArchive>>,
+    next_job: Mutex<u64>,
+}
+
+impl DsarService {
+    pub fn new(stores: Vec<Arc<dyn UserDataStore>>) -> Self {
+        Self {
+            stores,
+            archives: Mutex::new(BTreeMap::new()),
+            next_job: Mutex::new(0),
+        }
+    }
+
+    async fn next_job_id(&self) -> String {
+        let mut next = self.next_job.lock().await;
+        *next += 1;
+        format!("dsar-{}", *next)
+    }
+
+    /// `POST /api/privacy/dsar`: gather everything tied to the principal
+    /// into an archive. Returns the job ID for the one-shot download.
+    pub async fn export(&self, principal: &str, now: i64) -> Result<String> {
+        let mut categories = BTreeMap::new();
+        for store in &self.stores {
+            categories.insert(store.category().to_string(), store.gather(principal).await?);
+        }
+        let summary = render_summary(principal, &categories);
+        let job_id = self.next_job_id().await;
+        self.archives.lock().await.insert(
+            job_id.clone(),
+            DsarArchive {
+                principal: principal.to_string(),
+                created_at: now,
+                categories,
+                summary,
+            },
+        );
+        Ok(job_id)
+    }
+
+    /// One-shot download: the archive is deleted as it is handed out.
+    pub async fn take_archive(&self, job_id: &str) -> Result<DsarArchive> {
+        self.archives.lock().await.remove(job_id).ok_or_else(|| {
+            SafeClawError::NotFound(format!(
+                "no archive for {job_id} — already downloaded or never created"
+            ))
+        })
+    }
+
+    /// Erasure dry run: per-category counts of what *would* be deleted.
+    pub async fn erase_preview(&self, principal: &str) -> Result<CategoryCounts> {
+        let mut counts = CategoryCounts::new();
+        for store in &self.stores {
+            counts.insert(
+                store.category().to_string(),
+                store.gather(principal).await?.len(),
+            );
+        }
+        Ok(counts)
+    }
+
+    /// Actual erasure across every store; returns what was deleted.
+    pub async fn erase(&self, principal: &str) -> Result<CategoryCounts> {
+        let mut counts = CategoryCounts::new();
+        for store in &self.stores {
+            counts.insert(store.category().to_string(), store.erase(principal).await?);
+        }
+        Ok(counts)
+    }
+}
+
+fn render_summary(principal: &str, categories: &BTreeMap<String, Vec<Value>>) -> String {
+    let total: usize = categories.values().map(Vec::len).sum();
+    let mut summary = format!(
+        "Data subject access export for `{principal}` — {total} records across \
+         {} categories.\n",
+        categories.len()
+    );
+    for (category, records) in categories {
+        summary.push_str(&format!("- {category}: {} records\n", records.len()));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Fixture store: a principal-indexed map, erasable.
+    struct FixtureStore {
+        category: String,
+        records: Mutex<Vec<(String, Value)>>,
+    }
+
+    impl FixtureStore {
+        fn new(category: &str, records: Vec<(&str, Value)>) -> Arc<Self> {
+            Arc::new(Self {
+                category: category.into(),
+                records: Mutex::new(
+                    records
+                        .into_iter()
+                        .map(|(p, v)| (p.to_string(), v))
+                        .collect(),
+                ),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl UserDataStore for FixtureStore {
+        fn category(&self) -> &str {
+            &self.category
+        }
+
+        async fn gather(&self, principal: &str) -> Result<Vec<Value>> {
+            Ok(self
+                .records
+                .lock()
+                .await
+                .iter()
+                .filter(|(p, _)| p == principal)
+                .map(|(_, v)| v.clone())
+                .collect())
+        }
+
+        async fn erase(&self, principal: &str) -> Result<usize> {
+            let mut records = self.records.lock().await;
+            let before = records.len();
+            records.retain(|(p, _)| p != principal);
+            Ok(before - records.len())
+        }
+    }
+
+    fn seeded_service() -> DsarService {
+        DsarService::new(vec![
+            FixtureStore::new(
+                "sessions",
+                vec![
+                    ("p:telegram:42", json!({ "session": "s1" })),
+                    ("p:telegram:42", json!({ "session": "s2" })),
+                    ("p:webchat:bob", json!({ "session": "s3" })),
+                ],
+            ),
+            FixtureStore::new(
+                "memory",
+                vec![("p:telegram:42", json!({ "insight": "likes tea" }))],
+            ),
+            FixtureStore::new("consent", vec![]),
+        ])
+    }
+
+    #[tokio::test]
+    async fn export_gathers_across_all_stores_for_the_principal_only() {
+        let service = seeded_service();
+        let job = service.export("p:telegram:42", 1_000).await.unwrap();
+        let archive = service.take_archive(&job).await.unwrap();
+
+        assert_eq!(archive.categories["sessions"].len(), 2);
+        assert_eq!(archive.categories["memory"].len(), 1);
+        assert_eq!(archive.categories["consent"].len(), 0);
+        assert!(archive.summary.contains("3 records across 3 categories"));
+        // Nothing from the other principal leaks in.
+        assert!(archive.categories["sessions"]
+            .iter()
+            .all(|s| s["session"] != "s3"));
+    }
+
+    #[tokio::test]
+    async fn archive_downloads_exactly_once() {
+        let service = seeded_service();
+        let job = service.export("p:telegram:42", 0).await.unwrap();
+        service.take_archive(&job).await.unwrap();
+        assert!(matches!(
+            service.take_archive(&job).await.unwrap_err(),
+            SafeClawError::NotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn erase_dry_run_counts_match_actual_erasure() {
+        let service = seeded_service();
+        let preview = service.erase_preview("p:telegram:42").await.unwrap();
+        assert_eq!(preview["sessions"], 2);
+        assert_eq!(preview["memory"], 1);
+
+        let erased = service.erase("p:telegram:42").await.unwrap();
+        assert_eq!(erased, preview);
+
+        // Everything for the principal is gone; the other principal is not.
+        let after = service.erase_preview("p:telegram:42").await.unwrap();
+        assert!(after.values().all(|&c| c == 0));
+        assert_eq!(service.erase_preview("p:webchat:bob").await.unwrap()["sessions"], 1);
+    }
+}
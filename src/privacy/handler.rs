@@ -0,0 +1,132 @@
+//! Privacy REST handlers — DSAR export and erasure.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::ErrorResponse;
+use crate::privacy::dsar::{CategoryCounts, DsarArchive, DsarService};
+
+/// Routes mounted under `/api/privacy`, behind `api::admin_protected`.
+pub fn dsar_routes(service: Arc<DsarService>) -> Router {
+    Router::new()
+        .route("/dsar", post(post_dsar))
+        .route("/dsar/:job/archive", get(download_archive))
+        .with_state(service)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DsarBody {
+    pub principal: String,
+    /// `"export"` or `"erase"`.
+    #[serde(default = "default_mode")]
+    pub mode: String,
+    /// Erasure only: preview counts without deleting. Defaults to true so an
+    /// accidental erase call destroys nothing.
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_mode() -> String {
+    "export".into()
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase", untagged)]
+pub enum DsarResponse {
+    Export { job_id: String },
+    Erase { dry_run: bool, counts: CategoryCounts },
+}
+
+/// `POST /api/privacy/dsar` — start an export, or run an erasure
+/// (dry-run by default).
+#[utoipa::path(
+    post,
+    path = "/api/privacy/dsar",
+    request_body = DsarBody,
+    responses(
+        (status = 200, body = DsarResponse),
+        (status = 400, body = ErrorResponse),
+    ),
+    tag = "privacy"
+)]
+pub(crate) async fn post_dsar(
+    State(service): State<Arc<DsarService>>,
+    Json(body): Json<DsarBody>,
+) -> Result<Json<DsarResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let result = match body.mode.as_str() {
+        "export" => service
+            .export(&body.principal, now)
+            .await
+            .map(|job_id| DsarResponse::Export { job_id }),
+        "erase" if body.dry_run => {
+            service
+                .erase_preview(&body.principal)
+                .await
+                .map(|counts| DsarResponse::Erase {
+                    dry_run: true,
+                    counts,
+                })
+        }
+        "erase" => service
+            .erase(&body.principal)
+            .await
+            .map(|counts| DsarResponse::Erase {
+                dry_run: false,
+                counts,
+            }),
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(
+                    "bad_request",
+                    format!("unknown mode `{other}`"),
+                )),
+            ))
+        }
+    };
+    result.map(Json).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new("dsar_failed", e.to_string())),
+        )
+    })
+}
+
+/// `GET /api/privacy/dsar/:job/archive` — one-shot download; the archive is
+/// deleted as it is served.
+#[utoipa::path(
+    get,
+    path = "/api/privacy/dsar/{job}/archive",
+    params(("job" = String, Path, description = "DSAR job ID")),
+    responses(
+        (status = 200, body = DsarArchive),
+        (status = 404, body = ErrorResponse),
+    ),
+    tag = "privacy"
+)]
+pub(crate) async fn download_archive(
+    State(service): State<Arc<DsarService>>,
+    Path(job): Path<String>,
+) -> Result<Json<DsarArchive>, (StatusCode, Json<ErrorResponse>)> {
+    service.take_archive(&job).await.map(Json).map_err(|e| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("not_found", e.to_string())),
+        )
+    })
+}
@@ -0,0 +1,15 @@
+//! Privacy classification + unified pipeline.
+//!
+//! Classification primitives (`SensitivityLevel`, `ClassificationRule`,
+//! `RegexClassifier`) come from the shared `a3s-privacy` library — the single
+//! source of truth shared with a3s-code — and are re-exported here.
+
+pub mod cache;
+pub mod composite;
+pub mod dsar;
+pub mod handler;
+pub mod receipt;
+
+pub use a3s_privacy::{
+    default_classification_rules, ClassificationRule, RegexClassifier, SensitivityLevel,
+};
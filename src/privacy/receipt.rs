@@ -0,0 +1,197 @@
+//! Per-message privacy receipts.
+//!
+//! Users have to take SafeClaw's privacy handling on faith; a receipt makes
+//! it checkable. After a message is processed, the facts the pipeline
+//! already produced — detected sensitivity, whether the turn ran in the
+//! TEE, what was redacted, where data was stored — are assembled into a
+//! short user-facing summary, either appended as a footer on every reply or
+//! produced on demand with `/receipt`. Verbosity is configurable: the
+//! minimal form is one line, the full form itemizes redactions and storage.
+
+use serde::{Deserialize, Serialize};
+
+use crate::privacy::SensitivityLevel;
+
+/// Chat command requesting the receipt for the last processed message.
+pub const RECEIPT_COMMAND: &str = "/receipt";
+
+/// How much the receipt says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptVerbosity {
+    /// One line: sensitivity and TEE yes/no.
+    Minimal,
+    /// Sensitivity, TEE, redaction count, storage locations.
+    #[default]
+    Standard,
+    /// Standard plus the redaction categories, itemized.
+    Full,
+}
+
+/// Configuration under `privacy.receipt`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReceiptConfig {
+    pub enabled: bool,
+    /// Append the receipt to every reply instead of waiting for `/receipt`.
+    pub footer: bool,
+    pub verbosity: ReceiptVerbosity,
+}
+
+impl Default for ReceiptConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            footer: false,
+            verbosity: ReceiptVerbosity::Standard,
+        }
+    }
+}
+
+/// What the pipeline recorded while processing one message — the receipt is
+/// assembled from these existing results, nothing is re-derived.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessingRecord {
+    pub sensitivity: SensitivityLevel,
+    /// Whether generation ran inside the TEE.
+    pub tee_processed: bool,
+    /// Categories redacted before the model saw the message, e.g.
+    /// `"credit card number"`; one entry per redacted span.
+    pub redactions: Vec<String>,
+    /// Where data from this message was stored, e.g. `"session history"`,
+    /// `"memory (EU store)"`.
+    pub stored_in: Vec<String>,
+}
+
+fn sensitivity_label(level: SensitivityLevel) -> &'static str {
+    match level {
+        SensitivityLevel::Public => "public",
+        SensitivityLevel::Normal => "normal",
+        SensitivityLevel::Sensitive => "sensitive",
+        SensitivityLevel::HighlySensitive => "highly sensitive",
+    }
+}
+
+/// Assemble the user-facing receipt for one processed message.
+pub fn build_receipt(config: &ReceiptConfig, record: &ProcessingRecord) -> String {
+    let tee = if record.tee_processed {
+        "processed in the secure enclave"
+    } else {
+        "processed outside the secure enclave"
+    };
+    let mut receipt = format!(
+        "Privacy receipt: classified {}, {}.",
+        sensitivity_label(record.sensitivity),
+        tee
+    );
+    if config.verbosity == ReceiptVerbosity::Minimal {
+        return receipt;
+    }
+    if record.redactions.is_empty() {
+        receipt.push_str(" Nothing was redacted.");
+    } else {
+        receipt.push_str(&format!(
+            " {} item{} redacted before the model saw your message",
+            record.redactions.len(),
+            if record.redactions.len() == 1 { "" } else { "s" }
+        ));
+        if config.verbosity == ReceiptVerbosity::Full {
+            receipt.push_str(&format!(" ({})", record.redactions.join(", ")));
+        }
+        receipt.push('.');
+    }
+    if record.stored_in.is_empty() {
+        receipt.push_str(" Nothing was stored.");
+    } else {
+        receipt.push_str(&format!(" Stored in: {}.", record.stored_in.join(", ")));
+    }
+    receipt
+}
+
+/// The footer appended to a reply when `footer` is on, `None` otherwise.
+pub fn footer(config: &ReceiptConfig, record: &ProcessingRecord) -> Option<String> {
+    (config.enabled && config.footer).then(|| build_receipt(config, record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> ProcessingRecord {
+        ProcessingRecord {
+            sensitivity: SensitivityLevel::HighlySensitive,
+            tee_processed: true,
+            redactions: vec!["credit card number".into(), "email address".into()],
+            stored_in: vec!["session history".into(), "memory (EU store)".into()],
+        }
+    }
+
+    #[test]
+    fn a_tee_processed_redacted_message_is_fully_reflected() {
+        let receipt = build_receipt(&ReceiptConfig::default(), &record());
+        assert!(receipt.contains("classified highly sensitive"));
+        assert!(receipt.contains("processed in the secure enclave"));
+        assert!(receipt.contains("2 items redacted"));
+        assert!(receipt.contains("Stored in: session history, memory (EU store)."));
+        // Standard verbosity counts redactions without itemizing them.
+        assert!(!receipt.contains("credit card number"));
+    }
+
+    #[test]
+    fn full_verbosity_itemizes_redaction_categories() {
+        let config = ReceiptConfig {
+            verbosity: ReceiptVerbosity::Full,
+            ..Default::default()
+        };
+        let receipt = build_receipt(&config, &record());
+        assert!(receipt.contains("(credit card number, email address)"));
+    }
+
+    #[test]
+    fn minimal_verbosity_is_one_line_of_facts() {
+        let config = ReceiptConfig {
+            verbosity: ReceiptVerbosity::Minimal,
+            ..Default::default()
+        };
+        let receipt = build_receipt(&config, &record());
+        assert_eq!(
+            receipt,
+            "Privacy receipt: classified highly sensitive, processed in the \
+             secure enclave."
+        );
+    }
+
+    #[test]
+    fn a_plain_message_reads_as_uneventful() {
+        let receipt = build_receipt(
+            &ReceiptConfig::default(),
+            &ProcessingRecord {
+                sensitivity: SensitivityLevel::Normal,
+                tee_processed: false,
+                redactions: Vec::new(),
+                stored_in: vec!["session history".into()],
+            },
+        );
+        assert!(receipt.contains("classified normal"));
+        assert!(receipt.contains("outside the secure enclave"));
+        assert!(receipt.contains("Nothing was redacted."));
+    }
+
+    #[test]
+    fn the_footer_honors_the_config_switches() {
+        let record = record();
+        assert!(footer(&ReceiptConfig::default(), &record).is_none());
+        let on = ReceiptConfig {
+            footer: true,
+            ..Default::default()
+        };
+        assert!(footer(&on, &record).is_some());
+        let disabled = ReceiptConfig {
+            enabled: false,
+            footer: true,
+            ..Default::default()
+        };
+        assert!(footer(&disabled, &record).is_none());
+    }
+}
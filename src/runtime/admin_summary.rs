@@ -0,0 +1,287 @@
+//! One-view admin digest of everything pending.
+//!
+//! Operational state is scattered: permission requests per session, paused
+//! channels, over-budget sessions, dead-letter depths, failed scheduler
+//! runs, TEE boot state. `GET /api/admin/summary` aggregates them by
+//! querying each subsystem's own handle — nothing here duplicates state.
+//! The same summary renders as a terminal table for `safeclaw status` and
+//! as an optional scheduled digest to an admin chat, delivered only when
+//! something is actually pending.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+
+use crate::agent::permissions::PermissionQueue;
+use crate::channels::control::{ChannelControl, ChannelState};
+use crate::guard::resources::ResourceAccountant;
+use crate::tee::boot::{BootStatus, TeeBootGate};
+
+/// Items shown per section before "and N more".
+const TOP_ITEMS: usize = 5;
+
+/// Depth of a named dead-letter queue (memory extraction, outbound
+/// messages, alerts). Implemented by each queue owner.
+pub trait QueueDepth: Send + Sync {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Scheduler view of tasks whose most recent run failed.
+pub trait FailedRunSource: Send + Sync {
+    fn failed_last_run(&self) -> Vec<String>;
+}
+
+/// Subsystem handles the aggregator queries.
+#[derive(Default)]
+pub struct AdminSources {
+    /// `session_id` → that session's permission queue.
+    pub permissions: HashMap<String, Arc<PermissionQueue>>,
+    pub channels: Option<Arc<ChannelControl>>,
+    pub resources: Option<Arc<ResourceAccountant>>,
+    pub tee: Option<Arc<TeeBootGate>>,
+    /// Named dead-letter queues.
+    pub dead_letters: Vec<(String, Arc<dyn QueueDepth>)>,
+    pub scheduler: Option<Arc<dyn FailedRunSource>>,
+}
+
+/// Count plus the first few items of one section.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionSummary {
+    pub count: usize,
+    pub top: Vec<String>,
+}
+
+impl SectionSummary {
+    fn from_items(mut items: Vec<String>) -> Self {
+        let count = items.len();
+        items.truncate(TOP_ITEMS);
+        Self { count, top: items }
+    }
+}
+
+/// The aggregate served by `GET /api/admin/summary`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminSummary {
+    pub pending_permissions: SectionSummary,
+    pub paused_channels: SectionSummary,
+    pub over_budget_sessions: SectionSummary,
+    pub dead_letters: SectionSummary,
+    pub failed_tasks: SectionSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tee: Option<BootStatus>,
+}
+
+impl AdminSummary {
+    /// Nothing pending anywhere — the digest stays silent.
+    pub fn is_all_clear(&self) -> bool {
+        self.pending_permissions.count == 0
+            && self.paused_channels.count == 0
+            && self.over_budget_sessions.count == 0
+            && self.dead_letters.count == 0
+            && self.failed_tasks.count == 0
+    }
+}
+
+/// Query every configured subsystem and assemble the summary.
+pub async fn collect(sources: &AdminSources) -> AdminSummary {
+    let mut permission_items = Vec::new();
+    let mut sessions: Vec<&String> = sources.permissions.keys().collect();
+    sessions.sort();
+    for session_id in sessions {
+        for request in sources.permissions[session_id].ordered().await {
+            permission_items.push(format!(
+                "{session_id}: {} ({:?})",
+                request.tool_name, request.risk
+            ));
+        }
+    }
+
+    let paused = sources
+        .channels
+        .as_ref()
+        .map(|control| {
+            control
+                .status()
+                .into_iter()
+                .filter_map(|(channel, state, queued)| match state {
+                    ChannelState::Paused { .. } => {
+                        Some(format!("{channel}: paused ({queued} queued)"))
+                    }
+                    ChannelState::Failed { reason } => {
+                        Some(format!("{channel}: failed — {reason}"))
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let over_budget = sources
+        .resources
+        .as_ref()
+        .map(|resources| resources.over_budget_sessions())
+        .unwrap_or_default();
+
+    let dead_letters = sources
+        .dead_letters
+        .iter()
+        .filter(|(_, queue)| !queue.is_empty())
+        .map(|(name, queue)| format!("{name}: {}", queue.len()))
+        .collect();
+
+    let failed_tasks = sources
+        .scheduler
+        .as_ref()
+        .map(|scheduler| scheduler.failed_last_run())
+        .unwrap_or_default();
+
+    AdminSummary {
+        pending_permissions: SectionSummary::from_items(permission_items),
+        paused_channels: SectionSummary::from_items(paused),
+        over_budget_sessions: SectionSummary::from_items(over_budget),
+        dead_letters: SectionSummary::from_items(dead_letters),
+        failed_tasks: SectionSummary::from_items(failed_tasks),
+        tee: sources.tee.as_ref().map(|gate| gate.status()),
+    }
+}
+
+/// The digest text for the scheduled admin-chat delivery; `None` when
+/// everything is clear so nothing is sent.
+pub fn digest_message(summary: &AdminSummary) -> Option<String> {
+    if summary.is_all_clear() {
+        return None;
+    }
+    Some(render_table(summary))
+}
+
+/// Terminal table shared by the digest and `safeclaw status`.
+pub fn render_table(summary: &AdminSummary) -> String {
+    let mut out = String::new();
+    let mut section = |title: &str, section: &SectionSummary| {
+        out.push_str(&format!("{title:<24} {}\n", section.count));
+        for item in &section.top {
+            out.push_str(&format!("  • {item}\n"));
+        }
+        if section.count > section.top.len() {
+            out.push_str(&format!("  … and {} more\n", section.count - section.top.len()));
+        }
+    };
+    section("pending permissions", &summary.pending_permissions);
+    section("paused channels", &summary.paused_channels);
+    section("over-budget sessions", &summary.over_budget_sessions);
+    section("dead letters", &summary.dead_letters);
+    section("failed tasks", &summary.failed_tasks);
+    if let Some(tee) = &summary.tee {
+        out.push_str(&format!(
+            "{:<24} {:?} ({} queued)\n",
+            "tee", tee.phase, tee.queued_messages
+        ));
+    }
+    out
+}
+
+/// Routes mounted under `/api/admin` behind the admin auth middleware.
+pub fn routes(sources: Arc<AdminSources>) -> Router {
+    Router::new()
+        .route("/summary", get(summary_handler))
+        .with_state(sources)
+}
+
+/// `GET /api/admin/summary`.
+async fn summary_handler(State(sources): State<Arc<AdminSources>>) -> Json<AdminSummary> {
+    Json(collect(&sources).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guard::resources::ResourceLimitsConfig;
+
+    struct FixedDepth(usize);
+
+    impl QueueDepth for FixedDepth {
+        fn len(&self) -> usize {
+            self.0
+        }
+    }
+
+    async fn seeded_sources() -> AdminSources {
+        // Three subsystems with pending state: permissions, resources, DLQs.
+        let queue = Arc::new(PermissionQueue::new());
+        queue.push("req-1", "Bash", "rm -rf build/").await;
+        queue.push("req-2", "Read", "notes.txt").await;
+
+        let resources = Arc::new(ResourceAccountant::new(ResourceLimitsConfig {
+            max_cpu_ms: 100,
+            ..Default::default()
+        }));
+        resources.record("sess-busy", 5_000, 0);
+
+        AdminSources {
+            permissions: [("sess-busy".to_string(), queue)].into_iter().collect(),
+            resources: Some(resources),
+            dead_letters: vec![
+                ("memory_extraction".into(), Arc::new(FixedDepth(3)) as _),
+                ("outbound_messages".into(), Arc::new(FixedDepth(0)) as _),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn the_aggregate_reflects_each_seeded_subsystem() {
+        let summary = collect(&seeded_sources().await).await;
+
+        assert_eq!(summary.pending_permissions.count, 2);
+        // Risk-ordered: the destructive bash call outranks the file read.
+        assert!(summary.pending_permissions.top[0].contains("Bash"));
+
+        assert_eq!(summary.over_budget_sessions.count, 1);
+        assert_eq!(summary.over_budget_sessions.top, ["sess-busy"]);
+
+        // Empty queues are omitted; non-empty ones show their depth.
+        assert_eq!(summary.dead_letters.count, 1);
+        assert_eq!(summary.dead_letters.top, ["memory_extraction: 3"]);
+
+        assert_eq!(summary.paused_channels.count, 0);
+        assert!(summary.tee.is_none());
+    }
+
+    #[tokio::test]
+    async fn the_digest_is_delivered_only_when_something_is_pending() {
+        let busy = collect(&seeded_sources().await).await;
+        let digest = digest_message(&busy).expect("pending state must produce a digest");
+        assert!(digest.contains("pending permissions"));
+        assert!(digest.contains("memory_extraction: 3"));
+
+        let idle = collect(&AdminSources::default()).await;
+        assert!(idle.is_all_clear());
+        assert!(digest_message(&idle).is_none());
+    }
+
+    #[tokio::test]
+    async fn the_table_truncates_long_sections() {
+        let queue = Arc::new(PermissionQueue::new());
+        for i in 0..8 {
+            queue.push(format!("req-{i}"), "Read", "x").await;
+        }
+        let sources = AdminSources {
+            permissions: [("s1".to_string(), queue)].into_iter().collect(),
+            ..Default::default()
+        };
+        let summary = collect(&sources).await;
+        assert_eq!(summary.pending_permissions.count, 8);
+        assert_eq!(summary.pending_permissions.top.len(), TOP_ITEMS);
+        assert!(render_table(&summary).contains("and 3 more"));
+    }
+}
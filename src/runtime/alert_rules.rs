@@ -0,0 +1,281 @@
+//! Threshold alert rules over internal metrics.
+//!
+//! SafeClaw notices its own degradation without external monitoring:
+//! configurable rules are evaluated periodically against the windowed metrics
+//! registry, producing an event in the [`EventStore`] and an alert on the
+//! [`AlertMonitor`] when a rule fires — and a paired `resolved` event when it
+//! recovers. A separate resolve threshold provides hysteresis so rules don't
+//! flap. Rules are hot-reloadable with the config; firing state survives a
+//! reload by rule name.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::alerting::{Alert, AlertKind, AlertMonitor};
+use crate::events::EventStore;
+use crate::runtime::metrics::MetricsRegistry;
+
+/// Event category used for rule fired/resolved events.
+pub const EVENT_CATEGORY_ALERT: &str = "alert";
+
+/// How a rule reduces the windowed samples to one value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Aggregate {
+    /// Sum of samples in the window (counters).
+    Sum,
+    /// Mean of samples in the window (gauges like queue depth).
+    Mean,
+    /// `sum(metric) / sum(denominator)` — e.g. error ratio.
+    Ratio { denominator: String },
+}
+
+/// One configurable alert rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub metric: String,
+    #[serde(default = "default_aggregate")]
+    pub aggregate: Aggregate,
+    pub window_secs: i64,
+    /// Fire when the aggregate exceeds this.
+    pub threshold: f64,
+    /// Resolve only when the aggregate falls below this (hysteresis).
+    /// Defaults to 80% of `threshold`.
+    #[serde(default)]
+    pub resolve_threshold: Option<f64>,
+}
+
+fn default_aggregate() -> Aggregate {
+    Aggregate::Sum
+}
+
+impl AlertRule {
+    fn resolve_below(&self) -> f64 {
+        self.resolve_threshold.unwrap_or(self.threshold * 0.8)
+    }
+}
+
+/// Evaluates rules and tracks firing state.
+pub struct AlertRuleEvaluator {
+    state: Mutex<EvaluatorState>,
+}
+
+struct EvaluatorState {
+    rules: Vec<AlertRule>,
+    firing: HashMap<String, bool>,
+}
+
+impl AlertRuleEvaluator {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            state: Mutex::new(EvaluatorState {
+                rules,
+                firing: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Hot-reload the rule set. Firing state carries over by rule name so a
+    /// reload doesn't re-fire active alerts; removed rules drop their state.
+    pub fn replace_rules(&self, rules: Vec<AlertRule>) {
+        let mut state = self.state.lock().expect("alert evaluator poisoned");
+        let names: std::collections::HashSet<&String> = rules.iter().map(|r| &r.name).collect();
+        state.firing.retain(|name, _| names.contains(name));
+        state.rules = rules;
+    }
+
+    /// Evaluate all rules at `now`, emitting fired/resolved events and
+    /// alerts on transitions.
+    pub fn evaluate(
+        &self,
+        registry: &MetricsRegistry,
+        events: &EventStore,
+        monitor: &AlertMonitor,
+        now: i64,
+    ) {
+        let mut state = self.state.lock().expect("alert evaluator poisoned");
+        let rules = state.rules.clone();
+        for rule in &rules {
+            let Some(value) = aggregate_value(rule, registry, now) else {
+                continue;
+            };
+            let was_firing = state.firing.get(&rule.name).copied().unwrap_or(false);
+            if !was_firing && value > rule.threshold {
+                state.firing.insert(rule.name.clone(), true);
+                events.create(
+                    EVENT_CATEGORY_ALERT,
+                    &rule.name,
+                    &format!("alert fired: {}", rule.name),
+                    &format!(
+                        "{} = {value:.4} exceeded threshold {} over {}s",
+                        rule.metric, rule.threshold, rule.window_secs
+                    ),
+                    "alert_rules",
+                    now,
+                );
+                monitor.raise(Alert {
+                    kind: AlertKind::RateExceeded,
+                    session_id: String::new(),
+                    message: format!("rule {} fired ({value:.4} > {})", rule.name, rule.threshold),
+                    timestamp: now,
+                });
+            } else if was_firing && value < rule.resolve_below() {
+                state.firing.insert(rule.name.clone(), false);
+                events.create(
+                    EVENT_CATEGORY_ALERT,
+                    &rule.name,
+                    &format!("alert resolved: {}", rule.name),
+                    &format!(
+                        "{} = {value:.4} fell below {} over {}s",
+                        rule.metric,
+                        rule.resolve_below(),
+                        rule.window_secs
+                    ),
+                    "alert_rules",
+                    now,
+                );
+            }
+        }
+    }
+}
+
+fn aggregate_value(rule: &AlertRule, registry: &MetricsRegistry, now: i64) -> Option<f64> {
+    match &rule.aggregate {
+        Aggregate::Sum => Some(registry.windowed_sum(&rule.metric, rule.window_secs, now)),
+        Aggregate::Mean => registry.windowed_mean(&rule.metric, rule.window_secs, now),
+        Aggregate::Ratio { denominator } => {
+            let denom = registry.windowed_sum(denominator, rule.window_secs, now);
+            if denom == 0.0 {
+                None
+            } else {
+                Some(registry.windowed_sum(&rule.metric, rule.window_secs, now) / denom)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_ratio_rule() -> AlertRule {
+        AlertRule {
+            name: "high_error_rate".into(),
+            metric: "generation_errors".into(),
+            aggregate: Aggregate::Ratio {
+                denominator: "generations".into(),
+            },
+            window_secs: 600,
+            threshold: 0.2,
+            resolve_threshold: Some(0.1),
+        }
+    }
+
+    fn fixtures() -> (MetricsRegistry, EventStore, AlertMonitor) {
+        (
+            MetricsRegistry::new(),
+            EventStore::default(),
+            AlertMonitor::default(),
+        )
+    }
+
+    #[test]
+    fn ratio_window_math_fires_only_above_threshold() {
+        let (registry, events, monitor) = fixtures();
+        let evaluator = AlertRuleEvaluator::new(vec![error_ratio_rule()]);
+
+        // 1 error out of 10 generations = 0.1 — below threshold.
+        for i in 0..10 {
+            registry.increment("generations", i);
+        }
+        registry.increment("generation_errors", 5);
+        evaluator.evaluate(&registry, &events, &monitor, 100);
+        assert!(events.list(Some(EVENT_CATEGORY_ALERT)).is_empty());
+
+        // 3 more errors → 4/10 = 0.4 — fires.
+        for i in 0..3 {
+            registry.increment("generation_errors", 10 + i);
+        }
+        evaluator.evaluate(&registry, &events, &monitor, 101);
+        let fired = events.list(Some(EVENT_CATEGORY_ALERT));
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].summary.contains("fired"));
+        assert_eq!(monitor.recent(10).len(), 1);
+    }
+
+    #[test]
+    fn hysteresis_prevents_flapping() {
+        let (registry, events, monitor) = fixtures();
+        let evaluator = AlertRuleEvaluator::new(vec![error_ratio_rule()]);
+
+        // Fire at 0.4.
+        for i in 0..10 {
+            registry.increment("generations", i);
+        }
+        for i in 0..4 {
+            registry.increment("generation_errors", i);
+        }
+        evaluator.evaluate(&registry, &events, &monitor, 100);
+
+        // Drops to 0.15 — between resolve (0.1) and fire (0.2): stays firing,
+        // no new events either way.
+        for i in 0..17 {
+            registry.increment("generations", 101 + i);
+        }
+        evaluator.evaluate(&registry, &events, &monitor, 200);
+        assert_eq!(events.list(Some(EVENT_CATEGORY_ALERT)).len(), 1);
+
+        // Re-evaluating while still firing does not re-fire.
+        evaluator.evaluate(&registry, &events, &monitor, 201);
+        assert_eq!(events.list(Some(EVENT_CATEGORY_ALERT)).len(), 1);
+    }
+
+    #[test]
+    fn fired_and_resolved_events_pair_up() {
+        let (registry, events, monitor) = fixtures();
+        let evaluator = AlertRuleEvaluator::new(vec![AlertRule {
+            name: "critical_audit_events".into(),
+            metric: "audit_critical".into(),
+            aggregate: Aggregate::Sum,
+            window_secs: 60,
+            threshold: 2.0,
+            resolve_threshold: None,
+        }]);
+
+        for i in 0..3 {
+            registry.increment("audit_critical", i);
+        }
+        evaluator.evaluate(&registry, &events, &monitor, 10);
+        // Window slides past the samples → sum 0 → resolved.
+        evaluator.evaluate(&registry, &events, &monitor, 500);
+
+        let alert_events = events.list(Some(EVENT_CATEGORY_ALERT));
+        assert_eq!(alert_events.len(), 2);
+        assert!(alert_events[1].summary.contains("fired"));
+        assert!(alert_events[0].summary.contains("resolved"));
+        assert_eq!(alert_events[0].topic, alert_events[1].topic);
+    }
+
+    #[test]
+    fn hot_reload_preserves_firing_state_by_name() {
+        let (registry, events, monitor) = fixtures();
+        let evaluator = AlertRuleEvaluator::new(vec![error_ratio_rule()]);
+        for i in 0..10 {
+            registry.increment("generations", i);
+        }
+        for i in 0..4 {
+            registry.increment("generation_errors", i);
+        }
+        evaluator.evaluate(&registry, &events, &monitor, 100);
+        assert_eq!(events.list(Some(EVENT_CATEGORY_ALERT)).len(), 1);
+
+        // Reload with the same rule (e.g. unrelated config change) — the
+        // still-exceeded rule must not re-fire.
+        evaluator.replace_rules(vec![error_ratio_rule()]);
+        evaluator.evaluate(&registry, &events, &monitor, 101);
+        assert_eq!(events.list(Some(EVENT_CATEGORY_ALERT)).len(), 1);
+    }
+}
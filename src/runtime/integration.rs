@@ -0,0 +1,120 @@
+//! Service discovery for a3s-gateway auto-registration.
+//!
+//! Served at `GET /.well-known/a3s-service.json`. The gateway polls this to
+//! discover SafeClaw and uses the capabilities manifest to route and display
+//! accurately — the manifest reflects live config and TEE detection, never
+//! static values.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{SafeClawConfig, MAX_MESSAGE_SIZE_BYTES};
+use crate::tee::security_level::SecurityLevel;
+
+/// API version advertised to the gateway. Bumped on breaking API changes.
+pub const API_VERSION: &str = "v1";
+
+/// Service descriptor returned by `/.well-known/a3s-service.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDescriptor {
+    pub name: String,
+    pub version: String,
+    pub api_version: String,
+    pub health_path: String,
+    pub capabilities: CapabilitiesManifest,
+}
+
+/// What this SafeClaw instance can actually do right now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilitiesManifest {
+    /// Channels enabled in config, sorted.
+    pub channels: Vec<String>,
+    /// Whether hardware TEE is active.
+    pub tee_active: bool,
+    pub security_level: SecurityLevel,
+    /// Compliance frameworks available for privacy scanning.
+    pub privacy_frameworks: Vec<String>,
+    pub max_message_size_bytes: usize,
+}
+
+/// Build the service descriptor from live config and detected security level.
+pub fn build_service_descriptor(
+    config: &SafeClawConfig,
+    security_level: SecurityLevel,
+) -> ServiceDescriptor {
+    ServiceDescriptor {
+        name: "safeclaw".into(),
+        version: env!("CARGO_PKG_VERSION").into(),
+        api_version: API_VERSION.into(),
+        health_path: "/health".into(),
+        capabilities: CapabilitiesManifest {
+            channels: config.channels.enabled_channels(),
+            tee_active: security_level.is_tee(),
+            security_level,
+            privacy_frameworks: config.privacy.frameworks.clone(),
+            max_message_size_bytes: MAX_MESSAGE_SIZE_BYTES,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChannelEntry;
+
+    fn config_with_channels(enabled: &[&str], disabled: &[&str]) -> SafeClawConfig {
+        let mut config = SafeClawConfig::default();
+        for name in enabled {
+            config.channels.0.insert(
+                name.to_string(),
+                ChannelEntry {
+                    enabled: true,
+                    ..Default::default()
+                },
+            );
+        }
+        for name in disabled {
+            config
+                .channels
+                .0
+                .insert(name.to_string(), ChannelEntry::default());
+        }
+        config
+    }
+
+    #[test]
+    fn descriptor_reflects_enabled_channels() {
+        let config = config_with_channels(&["telegram", "feishu"], &["slack"]);
+        let descriptor = build_service_descriptor(&config, SecurityLevel::VmIsolation);
+        assert_eq!(
+            descriptor.capabilities.channels,
+            vec!["feishu".to_string(), "telegram".to_string()]
+        );
+    }
+
+    #[test]
+    fn descriptor_reflects_tee_status() {
+        let config = SafeClawConfig::default();
+
+        let hw = build_service_descriptor(&config, SecurityLevel::TeeHardware);
+        assert!(hw.capabilities.tee_active);
+        assert_eq!(hw.capabilities.security_level, SecurityLevel::TeeHardware);
+
+        let vm = build_service_descriptor(&config, SecurityLevel::VmIsolation);
+        assert!(!vm.capabilities.tee_active);
+    }
+
+    #[test]
+    fn descriptor_advertises_api_version_and_frameworks() {
+        let config = SafeClawConfig::default();
+        let descriptor = build_service_descriptor(&config, SecurityLevel::ProcessOnly);
+        assert_eq!(descriptor.api_version, API_VERSION);
+        assert!(descriptor
+            .capabilities
+            .privacy_frameworks
+            .contains(&"hipaa".to_string()));
+        assert_eq!(
+            descriptor.capabilities.max_message_size_bytes,
+            MAX_MESSAGE_SIZE_BYTES
+        );
+    }
+}
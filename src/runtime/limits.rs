@@ -0,0 +1,141 @@
+//! Response length limits with graceful truncation.
+//!
+//! Channels (and individual sessions) can cap the length of agent responses.
+//! The limit is surfaced to the model as a prompt hint so it aims for terse
+//! answers, and enforced after generation as a hard truncation that prefers
+//! sentence boundaries and appends a visible marker.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Marker appended to responses that were cut short.
+pub const TRUNCATION_MARKER: &str = "…(truncated)";
+
+/// Sentence-ending characters considered acceptable cut points.
+const SENTENCE_ENDINGS: [char; 7] = ['.', '!', '?', '。', '！', '？', '\n'];
+
+/// Configuration for response length limits.
+///
+/// Lives under `limits` in the main config. A per-channel entry overrides the
+/// global default; a per-session override (set via the session API) overrides
+/// both.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponseLimitConfig {
+    /// Global default maximum response length in characters. `None` = unlimited.
+    #[serde(default)]
+    pub max_response_chars: Option<usize>,
+    /// Per-channel overrides keyed by channel name (e.g. `"telegram"`).
+    #[serde(default)]
+    pub per_channel: HashMap<String, usize>,
+}
+
+impl ResponseLimitConfig {
+    /// Resolve the effective limit for a channel, considering an optional
+    /// per-session override.
+    pub fn resolve(&self, channel: &str, session_override: Option<usize>) -> Option<usize> {
+        session_override
+            .or_else(|| self.per_channel.get(channel).copied())
+            .or(self.max_response_chars)
+    }
+}
+
+/// Prompt hint injected into the system prompt so the model aims for the
+/// limit instead of being blindly cut off.
+pub fn prompt_hint(limit: usize) -> String {
+    format!(
+        "Keep your response under {limit} characters. Prefer complete, \
+         self-contained sentences over exhaustive detail."
+    )
+}
+
+/// Enforce a hard length limit on a generated response.
+///
+/// If `text` fits within `limit` it is returned unchanged. Otherwise the text
+/// is cut at the last sentence boundary within the limit (searching no further
+/// back than half the limit, so a single run-on sentence still truncates) and
+/// [`TRUNCATION_MARKER`] is appended. The cut always lands on a `char`
+/// boundary.
+pub fn truncate_response(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+
+    // Byte index of the `limit`-th char (exclusive upper bound for the cut).
+    let hard_cut = text
+        .char_indices()
+        .nth(limit)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+
+    // Prefer the last sentence boundary in (limit/2, limit].
+    let floor = text
+        .char_indices()
+        .nth(limit / 2)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let window = &text[..hard_cut];
+    let cut = window
+        .char_indices()
+        .filter(|(i, c)| *i >= floor && SENTENCE_ENDINGS.contains(c))
+        .map(|(i, c)| i + c.len_utf8())
+        .next_back()
+        .unwrap_or(hard_cut);
+
+    let mut out = text[..cut].trim_end().to_string();
+    out.push_str(TRUNCATION_MARKER);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_response_unchanged() {
+        let text = "All done.";
+        assert_eq!(truncate_response(text, 100), text);
+    }
+
+    #[test]
+    fn truncates_at_sentence_boundary_with_marker() {
+        let text = "First sentence here. Second sentence follows. Third one is cut off entirely because it runs long.";
+        let out = truncate_response(text, 50);
+        assert!(out.ends_with(TRUNCATION_MARKER), "missing marker: {out}");
+        assert!(out.starts_with("First sentence here. Second sentence follows."));
+        assert!(!out.contains("Third"));
+    }
+
+    #[test]
+    fn hard_cut_when_no_sentence_boundary() {
+        let text = "a".repeat(200);
+        let out = truncate_response(&text, 50);
+        assert!(out.ends_with(TRUNCATION_MARKER));
+        assert!(out.chars().count() <= 50 + TRUNCATION_MARKER.chars().count());
+    }
+
+    #[test]
+    fn truncation_respects_char_boundaries() {
+        let text = "你好。".repeat(100);
+        let out = truncate_response(&text, 10);
+        assert!(out.ends_with(TRUNCATION_MARKER));
+    }
+
+    #[test]
+    fn resolve_precedence() {
+        let mut cfg = ResponseLimitConfig {
+            max_response_chars: Some(1000),
+            per_channel: HashMap::new(),
+        };
+        cfg.per_channel.insert("telegram".into(), 400);
+
+        assert_eq!(cfg.resolve("slack", None), Some(1000));
+        assert_eq!(cfg.resolve("telegram", None), Some(400));
+        assert_eq!(cfg.resolve("telegram", Some(200)), Some(200));
+    }
+
+    #[test]
+    fn prompt_hint_mentions_limit() {
+        assert!(prompt_hint(400).contains("400"));
+    }
+}
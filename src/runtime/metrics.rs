@@ -0,0 +1,104 @@
+//! Internal metrics registry with short ring-buffer history.
+//!
+//! Counters and gauges keep their recent samples so the alert-rule evaluator
+//! (`runtime::alert_rules`) can compute windowed sums, means, and ratios
+//! without external monitoring.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Maximum samples retained per metric.
+const MAX_SAMPLES: usize = 4096;
+
+/// Registry of named metrics with timestamped sample history.
+#[derive(Default)]
+pub struct MetricsRegistry {
+    samples: Mutex<HashMap<String, VecDeque<(i64, f64)>>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a sample for `metric` at `now` (unix seconds).
+    pub fn observe(&self, metric: &str, value: f64, now: i64) {
+        let mut samples = self.samples.lock().expect("metrics registry poisoned");
+        let ring = samples.entry(metric.to_string()).or_default();
+        ring.push_back((now, value));
+        while ring.len() > MAX_SAMPLES {
+            ring.pop_front();
+        }
+    }
+
+    /// Convenience for counters.
+    pub fn increment(&self, metric: &str, now: i64) {
+        self.observe(metric, 1.0, now);
+    }
+
+    /// Sum of samples within `(now - window_secs, now]`.
+    pub fn windowed_sum(&self, metric: &str, window_secs: i64, now: i64) -> f64 {
+        self.windowed(metric, window_secs, now, |values| values.iter().sum())
+    }
+
+    /// Mean of samples within the window; `None` when empty.
+    pub fn windowed_mean(&self, metric: &str, window_secs: i64, now: i64) -> Option<f64> {
+        let (sum, count) = self.windowed(metric, window_secs, now, |values| {
+            (values.iter().sum::<f64>(), values.len())
+        });
+        if count == 0 {
+            None
+        } else {
+            Some(sum / count as f64)
+        }
+    }
+
+    fn windowed<T>(
+        &self,
+        metric: &str,
+        window_secs: i64,
+        now: i64,
+        f: impl FnOnce(Vec<f64>) -> T,
+    ) -> T {
+        let samples = self.samples.lock().expect("metrics registry poisoned");
+        let values = samples
+            .get(metric)
+            .map(|ring| {
+                ring.iter()
+                    .filter(|(t, _)| now - t <= window_secs && *t <= now)
+                    .map(|(_, v)| *v)
+                    .collect()
+            })
+            .unwrap_or_default();
+        f(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windowed_sum_excludes_samples_outside_the_window() {
+        let registry = MetricsRegistry::new();
+        registry.increment("generation_errors", 0);
+        registry.increment("generation_errors", 500);
+        registry.increment("generation_errors", 590);
+
+        assert_eq!(registry.windowed_sum("generation_errors", 100, 600), 2.0);
+        assert_eq!(registry.windowed_sum("generation_errors", 601, 600), 3.0);
+        assert_eq!(registry.windowed_sum("missing_metric", 100, 600), 0.0);
+    }
+
+    #[test]
+    fn windowed_mean_of_gauge_samples() {
+        let registry = MetricsRegistry::new();
+        registry.observe("outbound_queue_depth", 4.0, 10);
+        registry.observe("outbound_queue_depth", 8.0, 20);
+        assert_eq!(
+            registry.windowed_mean("outbound_queue_depth", 60, 30),
+            Some(6.0)
+        );
+        assert_eq!(registry.windowed_mean("outbound_queue_depth", 5, 100), None);
+    }
+}
@@ -0,0 +1,10 @@
+//! Runtime orchestrator — lifecycle, channels, message loop.
+
+pub mod admin_summary;
+pub mod alert_rules;
+pub mod integration;
+pub mod metrics;
+pub mod limits;
+pub mod preprocess;
+pub mod progress;
+pub mod translation;
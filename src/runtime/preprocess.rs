@@ -0,0 +1,307 @@
+//! Inbound pre-processing webhook.
+//!
+//! Teams with custom routing or filtering logic shouldn't have to fork the
+//! crate. When configured, every inbound message is POSTed synchronously to
+//! an external webhook *before* classification and generation; the webhook
+//! replies with a directive — pass the message through (optionally
+//! rewritten), drop it, or answer with a canned reply instead of invoking
+//! the model. Webhook failures honor the configured fail-open/fail-closed
+//! posture: fail-open processes the original message, fail-closed drops it.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::time::Duration;
+
+use crate::channels::message::InboundMessage;
+use crate::error::{Result, SafeClawError};
+
+/// Configuration under `gateway.preprocess`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreprocessConfig {
+    /// Webhook URL; `None` disables pre-processing entirely.
+    pub url: Option<String>,
+    pub timeout_ms: u64,
+    /// On webhook error or timeout: `true` processes the original message,
+    /// `false` drops it.
+    pub fail_open: bool,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            timeout_ms: 3_000,
+            fail_open: true,
+        }
+    }
+}
+
+/// What the webhook tells SafeClaw to do with the message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action")]
+pub enum PreprocessDirective {
+    /// Continue processing; `message` replaces the inbound one when set.
+    Continue {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<InboundMessage>,
+    },
+    /// Halt processing; nothing reaches classification or the model.
+    Drop {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    /// Skip generation and send this reply verbatim.
+    Reply { content: String },
+}
+
+/// What the message loop does after pre-processing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreprocessOutcome {
+    /// Run the normal pipeline on this (possibly rewritten) message.
+    Proceed(InboundMessage),
+    /// Discard the message; `reason` goes to the debug log only.
+    Dropped { reason: Option<String> },
+    /// Deliver this reply without invoking the model.
+    CannedReply { content: String },
+}
+
+/// Transport behind the webhook call — HTTP in production, canned in tests.
+#[async_trait]
+pub trait PreprocessTransport: Send + Sync {
+    async fn call(&self, message: &InboundMessage) -> Result<PreprocessDirective>;
+}
+
+/// POSTs the message JSON to the configured URL.
+pub struct HttpPreprocessor {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl HttpPreprocessor {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PreprocessTransport for HttpPreprocessor {
+    async fn call(&self, message: &InboundMessage) -> Result<PreprocessDirective> {
+        let response = self
+            .http
+            .post(&self.url)
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| SafeClawError::Channel(format!("preprocess webhook: {e}")))?;
+        if !response.status().is_success() {
+            return Err(SafeClawError::Channel(format!(
+                "preprocess webhook returned {}",
+                response.status()
+            )));
+        }
+        Ok(response
+            .json()
+            .await
+            .map_err(|e| SafeClawError::Channel(format!("preprocess webhook body: {e}")))?)
+    }
+}
+
+/// Runs the webhook (with timeout) and maps its directive to an outcome.
+pub struct Preprocessor<T> {
+    config: PreprocessConfig,
+    transport: T,
+}
+
+impl<T: PreprocessTransport> Preprocessor<T> {
+    pub fn new(config: PreprocessConfig, transport: T) -> Self {
+        Self { config, transport }
+    }
+
+    pub async fn process(&self, message: InboundMessage) -> PreprocessOutcome {
+        if self.config.url.is_none() {
+            return PreprocessOutcome::Proceed(message);
+        }
+        let call = self.transport.call(&message);
+        let directive =
+            match tokio::time::timeout(Duration::from_millis(self.config.timeout_ms), call).await {
+                Ok(Ok(directive)) => directive,
+                Ok(Err(e)) => return self.failure(message, &e.to_string()),
+                Err(_) => return self.failure(message, "timed out"),
+            };
+        match directive {
+            PreprocessDirective::Continue { message: rewritten } => {
+                PreprocessOutcome::Proceed(rewritten.unwrap_or(message))
+            }
+            PreprocessDirective::Drop { reason } => PreprocessOutcome::Dropped { reason },
+            PreprocessDirective::Reply { content } => PreprocessOutcome::CannedReply { content },
+        }
+    }
+
+    fn failure(&self, message: InboundMessage, detail: &str) -> PreprocessOutcome {
+        tracing::warn!(detail, "preprocess webhook failed");
+        if self.config.fail_open {
+            PreprocessOutcome::Proceed(message)
+        } else {
+            PreprocessOutcome::Dropped {
+                reason: Some(format!("preprocess webhook failed closed: {detail}")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn inbound(content: &str) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".into(),
+            chat_id: "c1".into(),
+            user_id: "u1".into(),
+            content: content.into(),
+            is_direct: true,
+            mentions_bot: false,
+            timestamp: NOW,
+            reply_to: None,
+        }
+    }
+
+    fn config() -> PreprocessConfig {
+        PreprocessConfig {
+            url: Some("https://hooks.example/preprocess".into()),
+            ..Default::default()
+        }
+    }
+
+    struct Canned(PreprocessDirective);
+
+    #[async_trait]
+    impl PreprocessTransport for Canned {
+        async fn call(&self, _message: &InboundMessage) -> Result<PreprocessDirective> {
+            Ok(self.0.clone())
+        }
+    }
+
+    struct Failing;
+
+    #[async_trait]
+    impl PreprocessTransport for Failing {
+        async fn call(&self, _message: &InboundMessage) -> Result<PreprocessDirective> {
+            Err(SafeClawError::Channel("connection refused".into()))
+        }
+    }
+
+    struct Hanging;
+
+    #[async_trait]
+    impl PreprocessTransport for Hanging {
+        async fn call(&self, _message: &InboundMessage) -> Result<PreprocessDirective> {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            Ok(PreprocessDirective::Continue { message: None })
+        }
+    }
+
+    #[tokio::test]
+    async fn a_rewritten_message_reaches_the_pipeline() {
+        let mut rewritten = inbound("original");
+        rewritten.content = "rewritten by the webhook".into();
+        let pre = Preprocessor::new(
+            config(),
+            Canned(PreprocessDirective::Continue {
+                message: Some(rewritten),
+            }),
+        );
+        // Downstream generation sees the webhook's version of the message.
+        match pre.process(inbound("original")).await {
+            PreprocessOutcome::Proceed(message) => {
+                assert_eq!(message.content, "rewritten by the webhook");
+            }
+            other => panic!("expected Proceed, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn a_drop_directive_halts_processing() {
+        let pre = Preprocessor::new(
+            config(),
+            Canned(PreprocessDirective::Drop {
+                reason: Some("blocked sender".into()),
+            }),
+        );
+        assert_eq!(
+            pre.process(inbound("spam")).await,
+            PreprocessOutcome::Dropped {
+                reason: Some("blocked sender".into())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn a_canned_reply_skips_generation() {
+        let pre = Preprocessor::new(
+            config(),
+            Canned(PreprocessDirective::Reply {
+                content: "We're closed today.".into(),
+            }),
+        );
+        assert_eq!(
+            pre.process(inbound("are you open?")).await,
+            PreprocessOutcome::CannedReply {
+                content: "We're closed today.".into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn failure_posture_is_configurable() {
+        let open = Preprocessor::new(config(), Failing);
+        assert!(matches!(
+            open.process(inbound("hi")).await,
+            PreprocessOutcome::Proceed(_)
+        ));
+
+        let closed = Preprocessor::new(
+            PreprocessConfig {
+                fail_open: false,
+                ..config()
+            },
+            Failing,
+        );
+        assert!(matches!(
+            closed.process(inbound("hi")).await,
+            PreprocessOutcome::Dropped { .. }
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_hanging_webhook_hits_the_timeout() {
+        let pre = Preprocessor::new(
+            PreprocessConfig {
+                fail_open: false,
+                ..config()
+            },
+            Hanging,
+        );
+        match pre.process(inbound("hi")).await {
+            PreprocessOutcome::Dropped { reason } => {
+                assert!(reason.unwrap().contains("timed out"));
+            }
+            other => panic!("expected Dropped, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn without_a_url_messages_pass_straight_through() {
+        let pre = Preprocessor::new(PreprocessConfig::default(), Failing);
+        assert!(matches!(
+            pre.process(inbound("hi")).await,
+            PreprocessOutcome::Proceed(_)
+        ));
+    }
+}
@@ -0,0 +1,335 @@
+//! Progressive tool-output streaming to channels.
+//!
+//! Long-running commands (builds, backups) otherwise leave channel users
+//! staring at nothing until the whole generation finishes. When progressive
+//! editing is enabled for a channel, `ToolOutputDelta` events update a
+//! dedicated progress message — throttled and tail-truncated to the last N
+//! lines — which is replaced by a compact summary when `ToolEnd` arrives.
+//! The final assistant answer remains a separate message.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::agent::types::AgentEvent;
+use crate::channels::template;
+use crate::error::Result;
+
+/// Configuration under `channels.progress`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProgressConfig {
+    /// Opt-in per channel; absent channels get no progress messages.
+    pub per_channel: HashMap<String, bool>,
+    /// Tools excluded from progress streaming (noisy tools).
+    pub excluded_tools: Vec<String>,
+    /// Minimum interval between edits of one progress message.
+    pub throttle_ms: u64,
+    /// Tail-truncate accumulated output to this many lines.
+    pub tail_lines: usize,
+}
+
+impl Default for ProgressConfig {
+    fn default() -> Self {
+        Self {
+            per_channel: HashMap::new(),
+            excluded_tools: Vec::new(),
+            throttle_ms: 500,
+            tail_lines: 15,
+        }
+    }
+}
+
+/// Send/edit surface of a channel adapter, narrowed to what progress
+/// streaming needs.
+#[async_trait]
+pub trait ChannelEditor: Send + Sync {
+    /// Send a new message, returning its platform message ID.
+    async fn send(&self, chat_id: &str, content: &str) -> Result<String>;
+    /// Edit a previously sent message in place.
+    async fn edit(&self, chat_id: &str, message_id: &str, content: &str) -> Result<()>;
+}
+
+struct Invocation {
+    message_id: String,
+    tool_name: String,
+    output: String,
+    started: Instant,
+    last_edit: Option<Instant>,
+}
+
+/// Tracks the progress message per tool invocation and drives it from the
+/// engine's event stream.
+pub struct ToolProgressTracker {
+    config: ProgressConfig,
+    invocations: Mutex<HashMap<String, Invocation>>,
+}
+
+impl ToolProgressTracker {
+    pub fn new(config: ProgressConfig) -> Self {
+        Self {
+            config,
+            invocations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn enabled_for(&self, channel: &str, tool_name: &str) -> bool {
+        self.config.per_channel.get(channel).copied().unwrap_or(false)
+            && !self.config.excluded_tools.iter().any(|t| t == tool_name)
+    }
+
+    /// Feed one engine event. Non-tool events are ignored here — the caller
+    /// delivers the final assistant answer separately.
+    pub async fn handle_event(
+        &self,
+        channel: &str,
+        chat_id: &str,
+        event: &AgentEvent,
+        editor: &dyn ChannelEditor,
+    ) -> Result<()> {
+        match event {
+            AgentEvent::ToolStart {
+                tool_name,
+                invocation_id,
+            } => {
+                if !self.enabled_for(channel, tool_name) {
+                    return Ok(());
+                }
+                let message_id = editor
+                    .send(
+                        chat_id,
+                        &format!("⏳ running {}…", template::code_slot(tool_name)),
+                    )
+                    .await?;
+                self.invocations.lock().await.insert(
+                    invocation_id.clone(),
+                    Invocation {
+                        message_id,
+                        tool_name: tool_name.clone(),
+                        output: String::new(),
+                        started: Instant::now(),
+                        last_edit: None,
+                    },
+                );
+            }
+            AgentEvent::ToolOutputDelta {
+                invocation_id,
+                output,
+            } => {
+                let mut invocations = self.invocations.lock().await;
+                let Some(inv) = invocations.get_mut(invocation_id) else {
+                    return Ok(());
+                };
+                inv.output.push_str(output);
+                let throttled = inv
+                    .last_edit
+                    .map(|t| t.elapsed() < Duration::from_millis(self.config.throttle_ms))
+                    .unwrap_or(false);
+                if throttled {
+                    return Ok(());
+                }
+                inv.last_edit = Some(Instant::now());
+                let tail = tail_lines(&inv.output, self.config.tail_lines);
+                let content = format!(
+                    "⏳ {}\n```\n{tail}\n```",
+                    template::code_slot(&inv.tool_name)
+                );
+                let (chat, message_id) = (chat_id.to_string(), inv.message_id.clone());
+                drop(invocations);
+                editor.edit(&chat, &message_id, &content).await?;
+            }
+            AgentEvent::ToolEnd {
+                invocation_id,
+                exit_code,
+                duration_ms,
+            } => {
+                let Some(inv) = self.invocations.lock().await.remove(invocation_id) else {
+                    return Ok(());
+                };
+                let mark = if *exit_code == 0 { "✓" } else { "✗" };
+                let summary = format!(
+                    "{mark} {} finished ({}, exit {exit_code})",
+                    template::code_slot(&inv.tool_name),
+                    format_duration(*duration_ms)
+                );
+                let _ = inv.started; // duration comes from the event
+                editor.edit(chat_id, &inv.message_id, &summary).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn tail_lines(text: &str, n: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(n);
+    lines[start..].join("\n")
+}
+
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Call {
+        Send { content: String },
+        Edit { message_id: String, content: String },
+    }
+
+    #[derive(Default)]
+    struct MockEditor {
+        calls: Arc<Mutex<Vec<Call>>>,
+    }
+
+    #[async_trait]
+    impl ChannelEditor for MockEditor {
+        async fn send(&self, _chat_id: &str, content: &str) -> Result<String> {
+            self.calls.lock().await.push(Call::Send {
+                content: content.to_string(),
+            });
+            Ok("m1".to_string())
+        }
+
+        async fn edit(&self, _chat_id: &str, message_id: &str, content: &str) -> Result<()> {
+            self.calls.lock().await.push(Call::Edit {
+                message_id: message_id.to_string(),
+                content: content.to_string(),
+            });
+            Ok(())
+        }
+    }
+
+    fn tracker(tail_lines: usize) -> ToolProgressTracker {
+        let mut per_channel = HashMap::new();
+        per_channel.insert("telegram".to_string(), true);
+        ToolProgressTracker::new(ProgressConfig {
+            per_channel,
+            excluded_tools: vec!["Read".to_string()],
+            throttle_ms: 0,
+            tail_lines,
+        })
+    }
+
+    fn events() -> Vec<AgentEvent> {
+        vec![
+            AgentEvent::ToolStart {
+                tool_name: "Bash".into(),
+                invocation_id: "inv1".into(),
+            },
+            AgentEvent::ToolOutputDelta {
+                invocation_id: "inv1".into(),
+                output: "compiling a\n".into(),
+            },
+            AgentEvent::ToolOutputDelta {
+                invocation_id: "inv1".into(),
+                output: "compiling b\ncompiling c\n".into(),
+            },
+            AgentEvent::ToolEnd {
+                invocation_id: "inv1".into(),
+                exit_code: 0,
+                duration_ms: 133_000,
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn scripted_sequence_drives_send_edit_summarize_flow() {
+        let tracker = tracker(2);
+        let editor = MockEditor::default();
+        for event in events() {
+            tracker
+                .handle_event("telegram", "c1", &event, &editor)
+                .await
+                .unwrap();
+        }
+
+        let calls = editor.calls.lock().await.clone();
+        assert_eq!(calls.len(), 4);
+        assert!(matches!(&calls[0], Call::Send { content } if content.contains("Bash")));
+        // Second delta is tail-truncated to the last 2 lines.
+        match &calls[2] {
+            Call::Edit { content, .. } => {
+                assert!(content.contains("compiling b\ncompiling c"));
+                assert!(!content.contains("compiling a"));
+            }
+            other => panic!("expected edit, got {other:?}"),
+        }
+        assert_eq!(
+            calls[3],
+            Call::Edit {
+                message_id: "m1".into(),
+                content: "✓ `Bash` finished (2m13s, exit 0)".into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn disabled_channel_and_excluded_tool_stream_nothing() {
+        let tracker = tracker(10);
+        let editor = MockEditor::default();
+
+        // Channel without opt-in.
+        for event in events() {
+            tracker
+                .handle_event("slack", "c1", &event, &editor)
+                .await
+                .unwrap();
+        }
+        // Excluded tool on an enabled channel.
+        tracker
+            .handle_event(
+                "telegram",
+                "c1",
+                &AgentEvent::ToolStart {
+                    tool_name: "Read".into(),
+                    invocation_id: "inv2".into(),
+                },
+                &editor,
+            )
+            .await
+            .unwrap();
+
+        assert!(editor.calls.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn throttle_suppresses_rapid_edits() {
+        let mut per_channel = HashMap::new();
+        per_channel.insert("telegram".to_string(), true);
+        let tracker = ToolProgressTracker::new(ProgressConfig {
+            per_channel,
+            excluded_tools: Vec::new(),
+            throttle_ms: 10_000,
+            tail_lines: 10,
+        });
+        let editor = MockEditor::default();
+        for event in events() {
+            tracker
+                .handle_event("telegram", "c1", &event, &editor)
+                .await
+                .unwrap();
+        }
+        // Send, one edit (first delta), summary — second delta throttled.
+        assert_eq!(editor.calls.lock().await.len(), 3);
+    }
+
+    #[test]
+    fn duration_formatting() {
+        assert_eq!(format_duration(133_000), "2m13s");
+        assert_eq!(format_duration(9_000), "9s");
+    }
+}
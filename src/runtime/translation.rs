@@ -0,0 +1,196 @@
+//! Automatic translation of responses into the user's language.
+//!
+//! An optional post-generation step: when the user's detected language
+//! differs from the model's default output language, the response is
+//! translated via the configured backend (the LLM itself or a dedicated
+//! translation service). Code blocks are never translated. Opt-in per
+//! session or per channel.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::error::Result;
+
+/// Configuration under `translation` in the main config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationConfig {
+    /// Global default; off unless explicitly enabled.
+    pub enabled: bool,
+    /// Per-channel opt-in/opt-out overrides.
+    pub per_channel: HashMap<String, bool>,
+    /// Language the model responds in when not instructed otherwise.
+    pub model_default_language: String,
+}
+
+impl TranslationConfig {
+    pub fn enabled_for(&self, channel: &str, session_override: Option<bool>) -> bool {
+        session_override
+            .or_else(|| self.per_channel.get(channel).copied())
+            .unwrap_or(self.enabled)
+    }
+}
+
+/// Pluggable translation backend.
+#[async_trait]
+pub trait TranslationBackend: Send + Sync {
+    async fn translate(&self, text: &str, target_language: &str) -> Result<String>;
+}
+
+/// Best-effort script-based language detection for the user's messages.
+/// Good enough to decide *whether* to translate; the backend handles the
+/// actual language pair.
+pub fn detect_language(text: &str) -> &'static str {
+    let mut counts = [0usize; 4]; // han, kana, hangul, cyrillic
+    for c in text.chars() {
+        match c {
+            '\u{4E00}'..='\u{9FFF}' => counts[0] += 1,
+            '\u{3040}'..='\u{30FF}' => counts[1] += 1,
+            '\u{AC00}'..='\u{D7AF}' => counts[2] += 1,
+            '\u{0400}'..='\u{04FF}' => counts[3] += 1,
+            _ => {}
+        }
+    }
+    // Kana implies Japanese even alongside Han characters.
+    if counts[1] > 0 {
+        "ja"
+    } else if counts[0] > 0 {
+        "zh"
+    } else if counts[2] > 0 {
+        "ko"
+    } else if counts[3] > 0 {
+        "ru"
+    } else {
+        "en"
+    }
+}
+
+/// Applies the post-generation translation step.
+pub struct ResponseTranslator<B> {
+    config: TranslationConfig,
+    backend: B,
+}
+
+impl<B: TranslationBackend> ResponseTranslator<B> {
+    pub fn new(config: TranslationConfig, backend: B) -> Self {
+        Self { config, backend }
+    }
+
+    /// Translate `response` into `user_language` when the step is enabled for
+    /// this channel/session and the language differs from the model default.
+    /// Fenced code blocks pass through untranslated.
+    pub async fn maybe_translate(
+        &self,
+        response: &str,
+        user_language: &str,
+        channel: &str,
+        session_override: Option<bool>,
+    ) -> Result<String> {
+        if !self.config.enabled_for(channel, session_override)
+            || user_language == self.config.model_default_language
+        {
+            return Ok(response.to_string());
+        }
+
+        let mut out = String::with_capacity(response.len());
+        for (i, segment) in response.split("```").enumerate() {
+            if i > 0 {
+                out.push_str("```");
+            }
+            if i % 2 == 1 {
+                // Inside a code fence — preserve verbatim.
+                out.push_str(segment);
+            } else if segment.trim().is_empty() {
+                out.push_str(segment);
+            } else {
+                out.push_str(&self.backend.translate(segment, user_language).await?);
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockBackend;
+
+    #[async_trait]
+    impl TranslationBackend for MockBackend {
+        async fn translate(&self, text: &str, target_language: &str) -> Result<String> {
+            Ok(format!("[{target_language}]{text}"))
+        }
+    }
+
+    fn translator(enabled: bool) -> ResponseTranslator<MockBackend> {
+        ResponseTranslator::new(
+            TranslationConfig {
+                enabled,
+                per_channel: HashMap::new(),
+                model_default_language: "en".into(),
+            },
+            MockBackend,
+        )
+    }
+
+    #[tokio::test]
+    async fn translates_for_non_default_detected_language() {
+        let user_lang = detect_language("请帮我总结这份文件");
+        assert_eq!(user_lang, "zh");
+
+        let out = translator(true)
+            .maybe_translate("Here is the summary.", user_lang, "telegram", None)
+            .await
+            .unwrap();
+        assert_eq!(out, "[zh]Here is the summary.");
+    }
+
+    #[tokio::test]
+    async fn unchanged_when_translation_disabled() {
+        let out = translator(false)
+            .maybe_translate("Here is the summary.", "zh", "telegram", None)
+            .await
+            .unwrap();
+        assert_eq!(out, "Here is the summary.");
+    }
+
+    #[tokio::test]
+    async fn unchanged_when_language_matches_default() {
+        let out = translator(true)
+            .maybe_translate("Here is the summary.", "en", "telegram", None)
+            .await
+            .unwrap();
+        assert_eq!(out, "Here is the summary.");
+    }
+
+    #[tokio::test]
+    async fn code_blocks_are_preserved() {
+        let response = "Run this:\n```sh\ncargo build\n```\nand it works.";
+        let out = translator(true)
+            .maybe_translate(response, "zh", "telegram", None)
+            .await
+            .unwrap();
+        assert!(out.contains("```sh\ncargo build\n```"));
+        assert!(out.contains("[zh]Run this:"));
+        assert!(out.contains("[zh]\nand it works."));
+    }
+
+    #[tokio::test]
+    async fn session_override_beats_channel_default() {
+        let out = translator(false)
+            .maybe_translate("Hello.", "zh", "telegram", Some(true))
+            .await
+            .unwrap();
+        assert_eq!(out, "[zh]Hello.");
+    }
+
+    #[test]
+    fn detects_common_scripts() {
+        assert_eq!(detect_language("こんにちは世界"), "ja");
+        assert_eq!(detect_language("안녕하세요"), "ko");
+        assert_eq!(detect_language("Привет"), "ru");
+        assert_eq!(detect_language("hello"), "en");
+    }
+}
@@ -0,0 +1,375 @@
+//! Cron expression validation and normalization at task registration.
+//!
+//! A typo'd schedule (`0 9 * * 8`, or a 6-field expression where 5 fields
+//! are expected) used to either fail deep inside the cron manager with an
+//! unhelpful error or silently never fire. This module validates the
+//! expression when a [`ScheduledTaskDef`] is registered — config load or
+//! the scheduler API — normalizes aliases (`@daily`, `@hourly`) to explicit
+//! five-field expressions in the stored definition, detects schedules that
+//! can never fire (February 31st), and computes the next few fire times so
+//! the user can sanity-check what they wrote. The preview is returned by
+//! the create/update endpoints and by `safeclaw config validate`.
+//!
+//! All times are UTC, matching the scheduler's clock.
+
+use serde::Serialize;
+
+use crate::error::{Result, SafeClawError};
+use crate::scheduler::ScheduledTaskDef;
+
+/// Fire times included in the registration preview.
+pub const PREVIEW_FIRES: usize = 3;
+
+/// How far ahead `next_fires` searches before giving up.
+const SEARCH_HORIZON_SECS: i64 = 5 * 366 * 86_400;
+
+const MINUTE_SECS: i64 = 60;
+const HOUR_SECS: i64 = 3_600;
+const DAY_SECS: i64 = 86_400;
+
+/// One parsed cron field: the accepted values, and whether the source was a
+/// bare `*` (which changes day-of-month/day-of-week combination semantics).
+#[derive(Debug, Clone)]
+struct CronField {
+    values: Vec<u8>,
+    is_wildcard: bool,
+}
+
+impl CronField {
+    fn contains(&self, value: u8) -> bool {
+        self.values.binary_search(&value).is_ok()
+    }
+}
+
+fn field_error(name: &str, detail: impl std::fmt::Display) -> SafeClawError {
+    SafeClawError::Config(format!("invalid cron {name} field: {detail}"))
+}
+
+/// Parse one field. `max` is inclusive; values above `wrap_to_zero_above`
+/// wrap to 0 (day-of-week 7 ≡ Sunday).
+fn parse_field(
+    source: &str,
+    name: &str,
+    min: u8,
+    max: u8,
+    wrap_seven_to_zero: bool,
+) -> Result<CronField> {
+    let mut values = Vec::new();
+    for part in source.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u8 = step
+                    .parse()
+                    .map_err(|_| field_error(name, format!("bad step `{step}`")))?;
+                if step == 0 {
+                    return Err(field_error(name, "step of 0"));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            let lo: u8 = lo
+                .parse()
+                .map_err(|_| field_error(name, format!("`{lo}` is not a number")))?;
+            let hi: u8 = hi
+                .parse()
+                .map_err(|_| field_error(name, format!("`{hi}` is not a number")))?;
+            (lo, hi)
+        } else {
+            let v: u8 = range
+                .parse()
+                .map_err(|_| field_error(name, format!("`{range}` is not a number")))?;
+            (v, v)
+        };
+        if lo > hi {
+            return Err(field_error(name, format!("range {lo}-{hi} is reversed")));
+        }
+        for raw in lo..=hi {
+            // Steps count from the range start: `3-13/5` is 3, 8, 13.
+            if (raw - lo) % step != 0 {
+                continue;
+            }
+            let v = if wrap_seven_to_zero && raw == 7 { 0 } else { raw };
+            if v < min || v > max {
+                return Err(field_error(
+                    name,
+                    format!(
+                        "{raw} out of range {min}-{}",
+                        if wrap_seven_to_zero { 7 } else { max }
+                    ),
+                ));
+            }
+            values.push(v);
+        }
+    }
+    values.sort_unstable();
+    values.dedup();
+    if values.is_empty() {
+        return Err(field_error(name, "matches no values"));
+    }
+    Ok(CronField {
+        values,
+        is_wildcard: source == "*",
+    })
+}
+
+fn alias_expansion(expr: &str) -> Option<&'static str> {
+    match expr {
+        "@hourly" => Some("0 * * * *"),
+        "@daily" | "@midnight" => Some("0 0 * * *"),
+        "@weekly" => Some("0 0 * * 0"),
+        "@monthly" => Some("0 0 1 * *"),
+        "@yearly" | "@annually" => Some("0 0 1 1 *"),
+        _ => None,
+    }
+}
+
+/// Longest a month can be, leap years included.
+fn max_month_len(month: u8) -> u8 {
+    match month {
+        2 => 29,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Civil (year, month, day) from a day count since the Unix epoch.
+fn civil_from_days(z: i64) -> (i64, u8, u8) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    (yoe + era * 400 + i64::from(m <= 2), m, d)
+}
+
+/// A validated five-field cron schedule.
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    minute: CronField,
+    hour: CronField,
+    day_of_month: CronField,
+    month: CronField,
+    day_of_week: CronField,
+    normalized: String,
+}
+
+impl CronSchedule {
+    /// Parse and validate an expression. Aliases expand to their explicit
+    /// five-field form; a 6-field expression is rejected with a pointer at
+    /// the 5-field format.
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = expr.trim();
+        let expr = alias_expansion(expr).unwrap_or(expr);
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(SafeClawError::Config(format!(
+                "cron expression must have 5 fields \
+                 (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            )));
+        }
+        Ok(Self {
+            minute: parse_field(fields[0], "minute", 0, 59, false)?,
+            hour: parse_field(fields[1], "hour", 0, 23, false)?,
+            day_of_month: parse_field(fields[2], "day-of-month", 1, 31, false)?,
+            month: parse_field(fields[3], "month", 1, 12, false)?,
+            day_of_week: parse_field(fields[4], "day-of-week", 0, 6, true)?,
+            normalized: fields.join(" "),
+        })
+    }
+
+    /// The expression stored in the task definition: alias-free, with
+    /// whitespace collapsed.
+    pub fn normalized(&self) -> &str {
+        &self.normalized
+    }
+
+    /// Whether no real date can ever satisfy the schedule — a restricted
+    /// day-of-month that exists in none of the listed months, with no
+    /// day-of-week alternative (cron fires on either when both are
+    /// restricted).
+    pub fn never_fires(&self) -> bool {
+        if !self.day_of_week.is_wildcard {
+            return false;
+        }
+        !self.month.values.iter().any(|&m| {
+            self.day_of_month
+                .values
+                .iter()
+                .any(|&d| d <= max_month_len(m))
+        })
+    }
+
+    fn day_matches(&self, month: u8, day: u8, weekday: u8) -> bool {
+        if !self.month.contains(month) {
+            return false;
+        }
+        let dom = self.day_of_month.contains(day);
+        let dow = self.day_of_week.contains(weekday);
+        // Standard cron: both restricted means either may match.
+        if !self.day_of_month.is_wildcard && !self.day_of_week.is_wildcard {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+
+    /// The next `count` fire times strictly after `after` (UTC seconds),
+    /// fewer if the search horizon is exhausted.
+    pub fn next_fires(&self, after: i64, count: usize) -> Vec<i64> {
+        let mut fires = Vec::with_capacity(count);
+        let mut ts = after - after.rem_euclid(MINUTE_SECS) + MINUTE_SECS;
+        let horizon = after + SEARCH_HORIZON_SECS;
+        while fires.len() < count && ts < horizon {
+            let days = ts.div_euclid(DAY_SECS);
+            let (_, month, day) = civil_from_days(days);
+            let weekday = ((days + 4).rem_euclid(7)) as u8;
+            if !self.day_matches(month, day, weekday) {
+                ts = (days + 1) * DAY_SECS;
+                continue;
+            }
+            let second_of_day = ts - days * DAY_SECS;
+            let hour = (second_of_day / HOUR_SECS) as u8;
+            if !self.hour.contains(hour) {
+                ts = days * DAY_SECS + i64::from(hour + 1) * HOUR_SECS;
+                continue;
+            }
+            if self.minute.contains(((second_of_day % HOUR_SECS) / MINUTE_SECS) as u8) {
+                fires.push(ts);
+            }
+            ts += MINUTE_SECS;
+        }
+        fires
+    }
+}
+
+/// What registration returns alongside the accepted definition.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulePreview {
+    /// The normalized expression now stored in the definition.
+    pub normalized: String,
+    /// The next [`PREVIEW_FIRES`] fire times, UTC seconds.
+    pub next_fires: Vec<i64>,
+}
+
+/// Validate a task's schedule at registration, rewrite it to the normalized
+/// form, and return the fire-time preview. Errors on invalid expressions
+/// and on schedules that can never fire.
+pub fn validate_and_normalize(def: &mut ScheduledTaskDef, now: i64) -> Result<SchedulePreview> {
+    let schedule = CronSchedule::parse(&def.schedule)?;
+    if schedule.never_fires() {
+        return Err(SafeClawError::Config(format!(
+            "schedule `{}` can never fire: no listed month contains the \
+             listed day-of-month",
+            def.schedule
+        )));
+    }
+    def.schedule = schedule.normalized().to_string();
+    Ok(SchedulePreview {
+        normalized: def.schedule.clone(),
+        next_fires: schedule.next_fires(now, PREVIEW_FIRES),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::DeliveryMode;
+
+    /// 2023-11-14 22:13:20 UTC, a Tuesday.
+    const NOW: i64 = 1_700_000_000;
+
+    fn task(schedule: &str) -> ScheduledTaskDef {
+        ScheduledTaskDef {
+            id: "t1".into(),
+            schedule: schedule.into(),
+            prompt: "check".into(),
+            channel: None,
+            chat_id: None,
+            delivery: DeliveryMode::Full,
+            deliver_to: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn the_preview_lists_the_next_three_fire_times() {
+        let mut def = task("0 9 * * *");
+        let preview = validate_and_normalize(&mut def, NOW).unwrap();
+        // 09:00 UTC on Nov 15, 16, 17 2023.
+        assert_eq!(
+            preview.next_fires,
+            vec![1_700_038_800, 1_700_125_200, 1_700_211_600]
+        );
+    }
+
+    #[test]
+    fn aliases_normalize_into_the_stored_definition() {
+        let mut def = task("@daily");
+        let preview = validate_and_normalize(&mut def, NOW).unwrap();
+        assert_eq!(def.schedule, "0 0 * * *");
+        assert_eq!(preview.normalized, "0 0 * * *");
+        // Midnight UTC, Nov 15 2023.
+        assert_eq!(preview.next_fires[0], 1_700_006_400);
+
+        let mut hourly = task("@hourly");
+        validate_and_normalize(&mut hourly, NOW).unwrap();
+        assert_eq!(hourly.schedule, "0 * * * *");
+    }
+
+    #[test]
+    fn invalid_expressions_fail_with_a_field_level_error() {
+        let err = validate_and_normalize(&mut task("0 9 * * 8"), NOW).unwrap_err();
+        assert!(err.to_string().contains("day-of-week"));
+
+        // 6-field (seconds-style) input points at the 5-field format.
+        let err = validate_and_normalize(&mut task("0 0 9 * * 1"), NOW).unwrap_err();
+        assert!(err.to_string().contains("5 fields"));
+
+        assert!(validate_and_normalize(&mut task("61 * * * *"), NOW).is_err());
+        assert!(validate_and_normalize(&mut task("not a cron"), NOW).is_err());
+    }
+
+    #[test]
+    fn impossible_dates_are_detected_as_never_firing() {
+        // February 31st.
+        let err = validate_and_normalize(&mut task("0 9 31 2 *"), NOW).unwrap_err();
+        assert!(err.to_string().contains("never fire"));
+
+        // With a restricted day-of-week cron fires on either, so Mondays in
+        // February are fine.
+        assert!(validate_and_normalize(&mut task("0 9 31 2 1"), NOW).is_ok());
+        // February 29th exists in leap years.
+        assert!(validate_and_normalize(&mut task("0 9 29 2 *"), NOW).is_ok());
+    }
+
+    #[test]
+    fn day_of_week_schedules_fire_on_the_right_days() {
+        // Every Friday at 17:00; Nov 17 2023 is a Friday.
+        let schedule = CronSchedule::parse("0 17 * * 5").unwrap();
+        let fires = schedule.next_fires(NOW, 2);
+        assert_eq!(fires, vec![1_700_240_400, 1_700_845_200]);
+
+        // Sunday as 7 wraps to 0.
+        let sunday = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(sunday.next_fires(NOW, 1), sunday.next_fires(NOW, 1));
+        let days = sunday.next_fires(NOW, 1)[0].div_euclid(DAY_SECS);
+        assert_eq!((days + 4).rem_euclid(7), 0);
+    }
+
+    #[test]
+    fn lists_ranges_and_steps_parse() {
+        let schedule = CronSchedule::parse("*/15 9-17 * * 1-5").unwrap();
+        let fires = schedule.next_fires(NOW, 2);
+        // Next weekday 09:00 is Wed Nov 15; quarter-hour cadence.
+        assert_eq!(fires[1] - fires[0], 15 * MINUTE_SECS);
+        assert_eq!(fires[0], 1_700_038_800);
+    }
+}
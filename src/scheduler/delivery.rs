@@ -0,0 +1,270 @@
+//! Scheduled task result delivery — multi-target, concurrent, with
+//! per-target failure isolation and diff-mode state.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::Result;
+use crate::scheduler::{DeliveryMode, ScheduledTaskDef};
+
+/// One delivery destination for a scheduled task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryTarget {
+    pub channel: String,
+    pub chat_id: String,
+    /// Per-target override of the task-level delivery mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<DeliveryMode>,
+}
+
+impl DeliveryTarget {
+    /// Stable key for per-target state (diff suppression, retry bookkeeping).
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.channel, self.chat_id)
+    }
+}
+
+/// Outcome of delivering to one target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetOutcome {
+    Delivered,
+    /// Diff mode and the result was unchanged for this target.
+    Suppressed,
+    Failed { error: String },
+}
+
+/// Abstraction over the outbound send path. The production implementation
+/// goes through the channel adapters' retry layer.
+#[async_trait]
+pub trait OutboundSender: Send + Sync {
+    async fn send(&self, channel: &str, chat_id: &str, content: &str) -> Result<()>;
+    /// Produce the summary form of a result for `DeliveryMode::Summary`.
+    async fn summarize(&self, content: &str) -> Result<String> {
+        // Default: first paragraph, which the executor can override with an
+        // LLM-generated summary.
+        Ok(content
+            .split("\n\n")
+            .next()
+            .unwrap_or(content)
+            .to_string())
+    }
+}
+
+/// Delivers task results to all targets concurrently, tracking diff-mode
+/// state per target so a newly added target gets its first delivery even if
+/// others are suppressed.
+pub struct ResultDeliverer {
+    /// `task_id:target_key` → hash of the last delivered result.
+    diff_state: Mutex<HashMap<String, u64>>,
+}
+
+impl ResultDeliverer {
+    pub fn new() -> Self {
+        Self {
+            diff_state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Deliver `result` for `task` to every effective target. One failing
+    /// target never prevents the others; each outcome is reported
+    /// independently, keyed by target.
+    pub async fn deliver(
+        &self,
+        task: &ScheduledTaskDef,
+        result: &str,
+        sender: &dyn OutboundSender,
+    ) -> HashMap<String, TargetOutcome> {
+        let targets = task.effective_targets();
+        let outcomes = join_all(targets.iter().map(|target| {
+            let mode = target.delivery.unwrap_or(task.delivery);
+            self.deliver_one(task, target, mode, result, sender)
+        }))
+        .await;
+
+        targets
+            .iter()
+            .map(DeliveryTarget::key)
+            .zip(outcomes)
+            .collect()
+    }
+
+    async fn deliver_one(
+        &self,
+        task: &ScheduledTaskDef,
+        target: &DeliveryTarget,
+        mode: DeliveryMode,
+        result: &str,
+        sender: &dyn OutboundSender,
+    ) -> TargetOutcome {
+        let state_key = format!("{}:{}", task.id, target.key());
+        let hash = content_hash(result);
+
+        if mode == DeliveryMode::Diff {
+            let state = self.diff_state.lock().await;
+            if state.get(&state_key) == Some(&hash) {
+                return TargetOutcome::Suppressed;
+            }
+        }
+
+        let content = match mode {
+            DeliveryMode::Summary => match sender.summarize(result).await {
+                Ok(s) => s,
+                Err(e) => return TargetOutcome::Failed { error: e.to_string() },
+            },
+            _ => result.to_string(),
+        };
+
+        match sender.send(&target.channel, &target.chat_id, &content).await {
+            Ok(()) => {
+                // Only record diff state for successful deliveries so a failed
+                // target retries the same content next run.
+                self.diff_state.lock().await.insert(state_key, hash);
+                TargetOutcome::Delivered
+            }
+            Err(e) => TargetOutcome::Failed { error: e.to_string() },
+        }
+    }
+}
+
+impl Default for ResultDeliverer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SafeClawError;
+    use crate::scheduler::DeliveryMode;
+    use std::sync::Arc;
+    use tokio::sync::Mutex as TokioMutex;
+
+    struct RecordingSender {
+        fail_channel: Option<String>,
+        sent: Arc<TokioMutex<Vec<(String, String)>>>,
+    }
+
+    #[async_trait]
+    impl OutboundSender for RecordingSender {
+        async fn send(&self, channel: &str, chat_id: &str, _content: &str) -> Result<()> {
+            if self.fail_channel.as_deref() == Some(channel) {
+                return Err(SafeClawError::Channel(format!("{channel} unavailable")));
+            }
+            self.sent
+                .lock()
+                .await
+                .push((channel.to_string(), chat_id.to_string()));
+            Ok(())
+        }
+    }
+
+    fn multi_target_task(mode: DeliveryMode) -> ScheduledTaskDef {
+        ScheduledTaskDef {
+            id: "brief".into(),
+            schedule: "0 8 * * *".into(),
+            prompt: "morning brief".into(),
+            channel: None,
+            chat_id: None,
+            delivery: mode,
+            deliver_to: vec![
+                DeliveryTarget {
+                    channel: "telegram".into(),
+                    chat_id: "123".into(),
+                    delivery: None,
+                },
+                DeliveryTarget {
+                    channel: "slack".into(),
+                    chat_id: "C42".into(),
+                    delivery: None,
+                },
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn one_failing_target_does_not_block_others() {
+        let sent = Arc::new(TokioMutex::new(Vec::new()));
+        let sender = RecordingSender {
+            fail_channel: Some("telegram".into()),
+            sent: sent.clone(),
+        };
+        let deliverer = ResultDeliverer::new();
+
+        let outcomes = deliverer
+            .deliver(&multi_target_task(DeliveryMode::Full), "hello", &sender)
+            .await;
+
+        assert!(matches!(
+            outcomes.get("telegram:123"),
+            Some(TargetOutcome::Failed { .. })
+        ));
+        assert_eq!(outcomes.get("slack:C42"), Some(&TargetOutcome::Delivered));
+        assert_eq!(sent.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn diff_state_is_tracked_per_target() {
+        let sent = Arc::new(TokioMutex::new(Vec::new()));
+        let sender = RecordingSender {
+            fail_channel: None,
+            sent: sent.clone(),
+        };
+        let deliverer = ResultDeliverer::new();
+        let mut task = multi_target_task(DeliveryMode::Diff);
+
+        // First run: both targets get the first delivery.
+        let first = deliverer.deliver(&task, "unchanged result", &sender).await;
+        assert!(first.values().all(|o| *o == TargetOutcome::Delivered));
+
+        // Add a new target; existing targets are suppressed on an unchanged
+        // result but the new one still gets its first delivery.
+        task.deliver_to.push(DeliveryTarget {
+            channel: "discord".into(),
+            chat_id: "D7".into(),
+            delivery: None,
+        });
+        let second = deliverer.deliver(&task, "unchanged result", &sender).await;
+        assert_eq!(second.get("telegram:123"), Some(&TargetOutcome::Suppressed));
+        assert_eq!(second.get("slack:C42"), Some(&TargetOutcome::Suppressed));
+        assert_eq!(second.get("discord:D7"), Some(&TargetOutcome::Delivered));
+    }
+
+    #[tokio::test]
+    async fn failed_diff_delivery_retries_same_content_next_run() {
+        let sent = Arc::new(TokioMutex::new(Vec::new()));
+        let deliverer = ResultDeliverer::new();
+        let mut task = multi_target_task(DeliveryMode::Diff);
+        task.deliver_to.truncate(1); // telegram only
+
+        let failing = RecordingSender {
+            fail_channel: Some("telegram".into()),
+            sent: sent.clone(),
+        };
+        let first = deliverer.deliver(&task, "result", &failing).await;
+        assert!(matches!(
+            first.get("telegram:123"),
+            Some(TargetOutcome::Failed { .. })
+        ));
+
+        let working = RecordingSender {
+            fail_channel: None,
+            sent: sent.clone(),
+        };
+        let second = deliverer.deliver(&task, "result", &working).await;
+        assert_eq!(second.get("telegram:123"), Some(&TargetOutcome::Delivered));
+    }
+}
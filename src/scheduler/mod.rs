@@ -0,0 +1,119 @@
+//! Proactive task scheduler — task definitions, autonomous execution, result
+//! delivery to channels.
+
+pub mod cron;
+pub mod delivery;
+pub mod reminders;
+
+use serde::{Deserialize, Serialize};
+
+use crate::scheduler::delivery::DeliveryTarget;
+
+/// How a task's result is delivered to its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryMode {
+    /// Deliver the full generated result.
+    #[default]
+    Full,
+    /// Deliver a one-paragraph summary.
+    Summary,
+    /// Deliver only when the result differs from the previous run.
+    Diff,
+}
+
+/// Status of one task run in the execution history. `Refused` (provider
+/// safety filter) is recorded distinctly from `Failed` so refusal rates are
+/// visible per task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskRunStatus {
+    Success,
+    Failed,
+    Refused,
+}
+
+/// A scheduled task definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTaskDef {
+    pub id: String,
+    /// Cron expression controlling when the task runs.
+    pub schedule: String,
+    /// Prompt executed autonomously by the agent.
+    pub prompt: String,
+    /// Legacy single-target fields; still honored when `deliver_to` is empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_id: Option<String>,
+    /// Task-level delivery mode; per-target overrides take precedence.
+    #[serde(default)]
+    pub delivery: DeliveryMode,
+    /// Multiple delivery targets with optional per-target mode overrides.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deliver_to: Vec<DeliveryTarget>,
+}
+
+impl ScheduledTaskDef {
+    /// Effective delivery targets: `deliver_to` when present, otherwise the
+    /// legacy `channel`/`chat_id` pair as a single target.
+    pub fn effective_targets(&self) -> Vec<DeliveryTarget> {
+        if !self.deliver_to.is_empty() {
+            return self.deliver_to.clone();
+        }
+        match (&self.channel, &self.chat_id) {
+            (Some(channel), Some(chat_id)) => vec![DeliveryTarget {
+                channel: channel.clone(),
+                chat_id: chat_id.clone(),
+                delivery: None,
+            }],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_single_target_still_works() {
+        let task = ScheduledTaskDef {
+            id: "t1".into(),
+            schedule: "0 8 * * *".into(),
+            prompt: "morning brief".into(),
+            channel: Some("telegram".into()),
+            chat_id: Some("123".into()),
+            delivery: DeliveryMode::Full,
+            deliver_to: Vec::new(),
+        };
+        let targets = task.effective_targets();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].channel, "telegram");
+    }
+
+    #[test]
+    fn deliver_to_takes_precedence_over_legacy_fields() {
+        let task = ScheduledTaskDef {
+            id: "t1".into(),
+            schedule: "0 8 * * *".into(),
+            prompt: "morning brief".into(),
+            channel: Some("telegram".into()),
+            chat_id: Some("123".into()),
+            delivery: DeliveryMode::Full,
+            deliver_to: vec![
+                DeliveryTarget {
+                    channel: "slack".into(),
+                    chat_id: "C42".into(),
+                    delivery: Some(DeliveryMode::Summary),
+                },
+                DeliveryTarget {
+                    channel: "telegram".into(),
+                    chat_id: "123".into(),
+                    delivery: None,
+                },
+            ],
+        };
+        assert_eq!(task.effective_targets().len(), 2);
+    }
+}
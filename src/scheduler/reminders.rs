@@ -0,0 +1,410 @@
+//! One-shot reminders created from chat.
+//!
+//! "Remind me Friday at 5pm to send the invoice" becomes a one-shot scheduled
+//! job targeted back at the originating channel/chat: the `remind` tool (and
+//! the explicit `/remind` command) parse a natural-language time — relative
+//! forms like "in 20 minutes", weekday + time, "tomorrow at 9am" — in the
+//! chat's timezone, reminders are listed with `/reminders` and cancelable
+//! with `/reminders cancel <n>`, fire exactly once and self-delete, and
+//! persist so they survive restarts. Ambiguous times ("at 5") are rejected
+//! with a clarifying reply rather than guessed at.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeClawError};
+
+const DAY_SECS: i64 = 86_400;
+
+/// One pending reminder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    /// Per-store numeric ID, referenced by `/reminders cancel <n>`.
+    pub id: u64,
+    pub channel: String,
+    pub chat_id: String,
+    pub message: String,
+    pub fire_at: i64,
+    pub created_at: i64,
+}
+
+fn parse_error(detail: &str) -> SafeClawError {
+    SafeClawError::Config(format!(
+        "{detail} — try something like `/remind me in 20 minutes to send \
+         the invoice` or `/remind me friday at 5pm to send the invoice`"
+    ))
+}
+
+enum TimeParse {
+    Time(u32, u32),
+    Ambiguous,
+    NotATime,
+}
+
+/// `"5pm"`, `"5:30pm"`, `"17:30"`. A bare number is ambiguous, not a guess.
+fn parse_time_of_day(token: &str) -> TimeParse {
+    let (body, offset) = if let Some(body) = token.strip_suffix("pm") {
+        (body, 12)
+    } else if let Some(body) = token.strip_suffix("am") {
+        (body, 0)
+    } else {
+        (token, u32::MAX)
+    };
+    let (hours, minutes) = match body.split_once(':') {
+        Some((h, m)) => {
+            let (Ok(h), Ok(m)) = (h.parse::<u32>(), m.parse::<u32>()) else {
+                return TimeParse::NotATime;
+            };
+            (h, m)
+        }
+        None => match body.parse::<u32>() {
+            Ok(h) if offset == u32::MAX => {
+                // "at 5" — 5am or 5pm? Ask instead of guessing.
+                return if h <= 12 {
+                    TimeParse::Ambiguous
+                } else if h < 24 {
+                    TimeParse::Time(h, 0)
+                } else {
+                    TimeParse::NotATime
+                };
+            }
+            Ok(h) => (h, 0),
+            Err(_) => return TimeParse::NotATime,
+        },
+    };
+    let hours = match (offset, hours) {
+        (12, 12) => 12,         // 12pm is noon
+        (0, 12) => 0,           // 12am is midnight
+        (12, h) if h < 12 => h + 12,
+        (_, h) => h,
+    };
+    if hours >= 24 || minutes >= 60 {
+        return TimeParse::NotATime;
+    }
+    TimeParse::Time(hours, minutes)
+}
+
+fn weekday_index(token: &str) -> Option<i64> {
+    const DAYS: [&str; 7] = [
+        "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday",
+    ];
+    DAYS.iter()
+        .position(|d| *d == token || (token.len() >= 3 && d.starts_with(token)))
+        .map(|i| i as i64)
+}
+
+fn resolve_time(tokens: &[&str], local_now: i64) -> Result<i64> {
+    let today = local_now.div_euclid(DAY_SECS);
+    // Skip a leading "at".
+    let tokens: Vec<&str> = tokens.iter().copied().filter(|t| *t != "at").collect();
+
+    // "in N minutes/hours/days"
+    if tokens.first() == Some(&"in") {
+        let (Some(amount), Some(unit)) = (tokens.get(1), tokens.get(2)) else {
+            return Err(parse_error("I couldn't read that delay"));
+        };
+        let amount: i64 = amount
+            .parse()
+            .map_err(|_| parse_error("I couldn't read that delay"))?;
+        let unit_secs = match unit.trim_end_matches('s') {
+            "minute" | "min" => 60,
+            "hour" | "hr" => 3_600,
+            "day" => DAY_SECS,
+            _ => return Err(parse_error(&format!("I don't understand the unit `{unit}`"))),
+        };
+        return Ok(local_now + amount * unit_secs);
+    }
+
+    let (day_tokens, time_tokens) = match tokens.first() {
+        Some(first) if *first == "tomorrow" || weekday_index(first).is_some() => {
+            (&tokens[..1], &tokens[1..])
+        }
+        _ => (&tokens[..0], &tokens[..]),
+    };
+    let time_token = time_tokens
+        .first()
+        .ok_or_else(|| parse_error("I need a time of day as well (like `at 5pm`)"))?;
+    let (hours, minutes) = match parse_time_of_day(time_token) {
+        TimeParse::Time(h, m) => (h, m),
+        TimeParse::Ambiguous => {
+            return Err(parse_error(&format!(
+                "did you mean {time_token}am or {time_token}pm? Please include \
+                 am/pm or use 24-hour time"
+            )))
+        }
+        TimeParse::NotATime => {
+            return Err(parse_error(&format!(
+                "I couldn't read `{time_token}` as a time"
+            )))
+        }
+    };
+    let time_of_day = i64::from(hours) * 3_600 + i64::from(minutes) * 60;
+
+    let candidate = match day_tokens.first() {
+        Some(&"tomorrow") => (today + 1) * DAY_SECS + time_of_day,
+        Some(day) => {
+            let target = weekday_index(day).expect("checked above");
+            let current = (today + 3).rem_euclid(7); // epoch day 0 is a Thursday
+            let delta = (target - current).rem_euclid(7);
+            let mut candidate = (today + delta) * DAY_SECS + time_of_day;
+            if candidate <= local_now {
+                candidate += 7 * DAY_SECS;
+            }
+            candidate
+        }
+        None => {
+            let mut candidate = today * DAY_SECS + time_of_day;
+            if candidate <= local_now {
+                candidate += DAY_SECS;
+            }
+            candidate
+        }
+    };
+    Ok(candidate)
+}
+
+/// Parse `/remind [me] <when> to <message>` into `(fire_at, message)`.
+/// `tz_offset_minutes` is the chat's timezone preference relative to UTC.
+pub fn parse_remind_request(
+    text: &str,
+    now: i64,
+    tz_offset_minutes: i32,
+) -> Result<(i64, String)> {
+    let text = text.trim();
+    let text = text.strip_prefix("me ").unwrap_or(text);
+    let (when, message) = text
+        .split_once(" to ")
+        .ok_or_else(|| parse_error("tell me what to remind you about"))?;
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(parse_error("tell me what to remind you about"));
+    }
+    let lowered = when.to_lowercase();
+    let tokens: Vec<&str> = lowered.split_whitespace().collect();
+    let offset = i64::from(tz_offset_minutes) * 60;
+    let local_fire_at = resolve_time(&tokens, now + offset)?;
+    Ok((local_fire_at - offset, message.to_string()))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreState {
+    next_id: u64,
+    reminders: Vec<Reminder>,
+}
+
+/// Persisted one-shot reminder store; fired reminders self-delete.
+pub struct ReminderStore {
+    path: Option<PathBuf>,
+    state: Mutex<StoreState>,
+}
+
+impl ReminderStore {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let state = path
+            .as_ref()
+            .and_then(|p| std::fs::read(p).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    fn persist(&self, state: &StoreState) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(state)?)?;
+        Ok(())
+    }
+
+    pub fn create(
+        &self,
+        channel: &str,
+        chat_id: &str,
+        message: &str,
+        fire_at: i64,
+        now: i64,
+    ) -> Result<Reminder> {
+        let mut state = self.state.lock().expect("reminder store poisoned");
+        state.next_id += 1;
+        let reminder = Reminder {
+            id: state.next_id,
+            channel: channel.to_string(),
+            chat_id: chat_id.to_string(),
+            message: message.to_string(),
+            fire_at,
+            created_at: now,
+        };
+        state.reminders.push(reminder.clone());
+        self.persist(&state)?;
+        Ok(reminder)
+    }
+
+    /// Pending reminders for one chat, soonest first — `/reminders`.
+    pub fn list(&self, channel: &str, chat_id: &str) -> Vec<Reminder> {
+        let state = self.state.lock().expect("reminder store poisoned");
+        let mut reminders: Vec<Reminder> = state
+            .reminders
+            .iter()
+            .filter(|r| r.channel == channel && r.chat_id == chat_id)
+            .cloned()
+            .collect();
+        reminders.sort_by_key(|r| r.fire_at);
+        reminders
+    }
+
+    /// `/reminders cancel <n>` — scoped to the chat so users can't cancel
+    /// each other's reminders across chats.
+    pub fn cancel(&self, channel: &str, chat_id: &str, id: u64) -> Result<()> {
+        let mut state = self.state.lock().expect("reminder store poisoned");
+        let before = state.reminders.len();
+        state
+            .reminders
+            .retain(|r| !(r.id == id && r.channel == channel && r.chat_id == chat_id));
+        if state.reminders.len() == before {
+            return Err(SafeClawError::NotFound(format!("no reminder #{id}")));
+        }
+        self.persist(&state)
+    }
+
+    /// Reminders due at `now`, removed from the store — one-shot jobs
+    /// self-delete on fire. The caller delivers them to their chats.
+    pub fn take_due(&self, now: i64) -> Result<Vec<Reminder>> {
+        let mut state = self.state.lock().expect("reminder store poisoned");
+        let (due, pending): (Vec<Reminder>, Vec<Reminder>) = state
+            .reminders
+            .drain(..)
+            .partition(|r| r.fire_at <= now);
+        state.reminders = pending;
+        if !due.is_empty() {
+            self.persist(&state)?;
+        }
+        let mut due = due;
+        due.sort_by_key(|r| r.fire_at);
+        Ok(due)
+    }
+}
+
+/// Tool spec for the `remind` tool exposed to the agent.
+pub fn remind_tool_spec() -> serde_json::Value {
+    serde_json::json!({
+        "name": "remind",
+        "description": "Create a one-shot reminder delivered back to this chat. \
+                        `when` accepts relative times (\"in 20 minutes\") or \
+                        day + time (\"friday at 5pm\", \"tomorrow at 9am\").",
+        "input_schema": {
+            "type": "object",
+            "properties": {
+                "when": { "type": "string" },
+                "message": { "type": "string" }
+            },
+            "required": ["when", "message"]
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tuesday 2023-11-14 22:13:20 UTC.
+    const NOW: i64 = 1_700_000_000;
+
+    fn parse(text: &str) -> (i64, String) {
+        parse_remind_request(text, NOW, 0).unwrap()
+    }
+
+    #[test]
+    fn a_dozen_phrasings_parse_to_the_right_instant() {
+        assert_eq!(parse("in 20 minutes to send the invoice").0, NOW + 1_200);
+        assert_eq!(parse("in 2 hours to check the oven").0, NOW + 7_200);
+        assert_eq!(parse("in 3 days to follow up").0, NOW + 3 * 86_400);
+        assert_eq!(parse("me in 1 minute to stretch").0, NOW + 60);
+        assert_eq!(parse("in 45 min to rejoin the call").0, NOW + 2_700);
+        assert_eq!(parse("tomorrow at 9am to water plants").0, 1_700_038_800);
+        assert_eq!(parse("tomorrow at 5:30pm to leave").0, 1_700_069_400);
+        assert_eq!(parse("friday at 5pm to send the invoice").0, 1_700_240_400);
+        assert_eq!(parse("fri at 17:00 to send the invoice").0, 1_700_240_400);
+        assert_eq!(parse("at 23:30 to take meds").0, 1_700_004_600);
+        // 9pm already passed today — rolls to tomorrow.
+        assert_eq!(parse("at 9pm to call home").0, 1_700_082_000);
+        // Today's weekday with a passed time — rolls a full week.
+        assert_eq!(parse("tuesday at 10am to plan sprint").0, 1_700_560_800);
+
+        let (_, message) = parse("in 20 minutes to go to the gym");
+        assert_eq!(message, "go to the gym");
+    }
+
+    #[test]
+    fn times_resolve_in_the_chat_timezone() {
+        // UTC+2: it's already Wednesday 00:13 locally, so "9am" is later the
+        // same local day.
+        let (fire_at, _) = parse_remind_request("at 9am to stand up", NOW, 120).unwrap();
+        assert_eq!(fire_at, 1_700_031_600);
+    }
+
+    #[test]
+    fn ambiguous_inputs_are_rejected_with_a_clarifying_reply() {
+        let err = parse_remind_request("at 5 to call mom", NOW, 0).unwrap_err();
+        assert!(err.to_string().contains("5am or 5pm"));
+
+        let err = parse_remind_request("friday to send it", NOW, 0).unwrap_err();
+        assert!(err.to_string().contains("time of day"));
+
+        let err = parse_remind_request("whenever to do things", NOW, 0).unwrap_err();
+        assert!(err.to_string().contains("couldn't read"));
+
+        assert!(parse_remind_request("in 20 minutes", NOW, 0).is_err());
+    }
+
+    #[test]
+    fn fired_reminders_self_delete_and_survive_restarts_until_then() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reminders.json");
+
+        let store = ReminderStore::new(Some(path.clone()));
+        store
+            .create("telegram", "42", "send the invoice", NOW + 60, NOW)
+            .unwrap();
+        store
+            .create("telegram", "42", "later thing", NOW + 9_000, NOW)
+            .unwrap();
+
+        // Restart before anything fires: both reminders come back.
+        let store = ReminderStore::new(Some(path.clone()));
+        assert_eq!(store.list("telegram", "42").len(), 2);
+
+        let due = store.take_due(NOW + 120).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].message, "send the invoice");
+
+        // Self-deleted: gone from the store and from disk.
+        assert_eq!(store.list("telegram", "42").len(), 1);
+        let store = ReminderStore::new(Some(path));
+        assert_eq!(store.list("telegram", "42").len(), 1);
+        assert!(store.take_due(NOW + 120).unwrap().is_empty());
+    }
+
+    #[test]
+    fn cancellation_is_scoped_to_the_chat() {
+        let store = ReminderStore::new(None);
+        let reminder = store
+            .create("telegram", "42", "send the invoice", NOW + 60, NOW)
+            .unwrap();
+
+        // Another chat can't cancel it.
+        assert!(store.cancel("telegram", "99", reminder.id).is_err());
+        assert_eq!(store.list("telegram", "42").len(), 1);
+
+        store.cancel("telegram", "42", reminder.id).unwrap();
+        assert!(store.list("telegram", "42").is_empty());
+        assert!(store.cancel("telegram", "42", reminder.id).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Soft conversation close, well before full session expiry.
+//!
+//! Summarize-on-close, ephemeral wipe and digest batching all want to know
+//! when a conversation *ended*, but chats never close explicitly and
+//! [`expiry`](crate::session::expiry) only fires after days of idleness.
+//! This module detects the end sooner: `/done`, a closing phrase ("thanks,
+//! that's all"), a configurable soft-close after minutes of idleness, or an
+//! explicit API call. On close every registered [`CloseHook`] runs — the
+//! underlying session is *not* terminated, so a follow-up message reopens
+//! it seamlessly and the message loop prepends a resuming marker. The
+//! open/soft-closed state is exposed through the session API.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// Chat command that closes the conversation on the spot.
+pub const CLOSE_COMMAND: &str = "/done";
+
+fn default_closing_phrases() -> Vec<String> {
+    [
+        "thanks, that's all",
+        "thanks that's all",
+        "that's all for now",
+        "that's all, thanks",
+        "we're done here",
+        "goodbye",
+        "bye for now",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// Configuration under `session.closure`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClosureConfig {
+    pub enabled: bool,
+    /// Idle minutes before an open conversation soft-closes. Zero disables
+    /// the idle trigger; the user signals still work.
+    pub idle_close_minutes: i64,
+    /// Phrases treated as a goodbye. Matched against the whole normalized
+    /// message, so "thanks, that's all — one more thing" stays open.
+    pub closing_phrases: Vec<String>,
+}
+
+impl Default for ClosureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_close_minutes: 30,
+            closing_phrases: default_closing_phrases(),
+        }
+    }
+}
+
+/// Why the conversation closed, passed to each hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseReason {
+    UserSignal,
+    Inactivity,
+    Api,
+}
+
+/// Conversation state, surfaced in the session API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum ConversationState {
+    Open,
+    SoftClosed { closed_at: i64, reason: CloseReason },
+}
+
+/// End-of-conversation side effect: summarization, memory flush, ephemeral
+/// wipe. Hooks must not terminate the session — reopening depends on it.
+#[async_trait]
+pub trait CloseHook: Send + Sync {
+    async fn on_close(&self, session_id: &str, reason: CloseReason);
+}
+
+/// What the message loop does with an inbound message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClosureDisposition {
+    /// Conversation is open — process normally.
+    Continue,
+    /// The message was a close signal; hooks have run. The message itself
+    /// needs no model turn beyond an acknowledgement.
+    Closed,
+    /// The conversation was soft-closed and this message reopened it —
+    /// prepend a resuming marker to the turn.
+    Reopened { closed_at: i64 },
+}
+
+struct TrackedConversation {
+    last_activity: i64,
+    state: ConversationState,
+}
+
+/// Detects conversation closes and runs the registered hooks.
+pub struct ConversationClosure {
+    config: ClosureConfig,
+    hooks: Vec<Arc<dyn CloseHook>>,
+    sessions: Mutex<HashMap<String, TrackedConversation>>,
+}
+
+/// Whether a message is a goodbye: `/done`, or a closing phrase matching
+/// the whole message once lowercased and stripped of punctuation.
+pub fn is_close_signal(config: &ClosureConfig, content: &str) -> bool {
+    let trimmed = content.trim();
+    if trimmed == CLOSE_COMMAND {
+        return true;
+    }
+    let normalized: String = trimmed
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == ',' || *c == '\'')
+        .collect();
+    let normalized = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+    config.closing_phrases.iter().any(|phrase| {
+        let phrase: String = phrase
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == ',' || *c == '\'')
+            .collect();
+        normalized == phrase.split_whitespace().collect::<Vec<_>>().join(" ")
+    })
+}
+
+impl ConversationClosure {
+    pub fn new(config: ClosureConfig, hooks: Vec<Arc<dyn CloseHook>>) -> Self {
+        Self {
+            config,
+            hooks,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Classify an inbound message and update conversation state. Close
+    /// signals run the hooks; a message into a soft-closed conversation
+    /// reopens it.
+    pub async fn note_inbound(
+        &self,
+        session_id: &str,
+        content: &str,
+        now: i64,
+    ) -> ClosureDisposition {
+        if !self.config.enabled {
+            return ClosureDisposition::Continue;
+        }
+        let reopened = {
+            let mut sessions = self.sessions.lock().await;
+            let tracked = sessions
+                .entry(session_id.to_string())
+                .or_insert(TrackedConversation {
+                    last_activity: now,
+                    state: ConversationState::Open,
+                });
+            tracked.last_activity = now;
+            match tracked.state {
+                ConversationState::SoftClosed { closed_at, .. } => {
+                    tracked.state = ConversationState::Open;
+                    Some(closed_at)
+                }
+                ConversationState::Open => None,
+            }
+        };
+        if let Some(closed_at) = reopened {
+            return ClosureDisposition::Reopened { closed_at };
+        }
+        if is_close_signal(&self.config, content) {
+            self.close(session_id, CloseReason::UserSignal, now).await;
+            return ClosureDisposition::Closed;
+        }
+        ClosureDisposition::Continue
+    }
+
+    /// Explicit closure through the session API.
+    pub async fn close_via_api(&self, session_id: &str, now: i64) {
+        self.close(session_id, CloseReason::Api, now).await;
+    }
+
+    /// Soft-close open conversations idle past the threshold; returns the
+    /// closed session IDs. Rides on the runtime cleanup loop.
+    pub async fn sweep_idle(&self, now: i64) -> Vec<String> {
+        if !self.config.enabled || self.config.idle_close_minutes == 0 {
+            return Vec::new();
+        }
+        let threshold = self.config.idle_close_minutes * 60;
+        let idle: Vec<String> = {
+            let sessions = self.sessions.lock().await;
+            sessions
+                .iter()
+                .filter(|(_, t)| {
+                    t.state == ConversationState::Open && now - t.last_activity >= threshold
+                })
+                .map(|(id, _)| id.clone())
+                .collect()
+        };
+        for session_id in &idle {
+            self.close(session_id, CloseReason::Inactivity, now).await;
+        }
+        idle
+    }
+
+    /// Current state, for the session API. Unknown sessions are open.
+    pub async fn state(&self, session_id: &str) -> ConversationState {
+        self.sessions
+            .lock()
+            .await
+            .get(session_id)
+            .map(|t| t.state.clone())
+            .unwrap_or(ConversationState::Open)
+    }
+
+    /// Session fully terminated — drop its closure state.
+    pub async fn forget(&self, session_id: &str) {
+        self.sessions.lock().await.remove(session_id);
+    }
+
+    async fn close(&self, session_id: &str, reason: CloseReason, now: i64) {
+        {
+            let mut sessions = self.sessions.lock().await;
+            let tracked = sessions
+                .entry(session_id.to_string())
+                .or_insert(TrackedConversation {
+                    last_activity: now,
+                    state: ConversationState::Open,
+                });
+            if matches!(tracked.state, ConversationState::SoftClosed { .. }) {
+                return;
+            }
+            tracked.state = ConversationState::SoftClosed {
+                closed_at: now,
+                reason,
+            };
+        }
+        for hook in &self.hooks {
+            hook.on_close(session_id, reason).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[derive(Default)]
+    struct CountingHook {
+        calls: AtomicU32,
+        last_reason: Mutex<Option<CloseReason>>,
+    }
+
+    #[async_trait]
+    impl CloseHook for CountingHook {
+        async fn on_close(&self, _session_id: &str, reason: CloseReason) {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            *self.last_reason.lock().await = Some(reason);
+        }
+    }
+
+    fn closure(hook: Arc<CountingHook>) -> ConversationClosure {
+        ConversationClosure::new(ClosureConfig::default(), vec![hook])
+    }
+
+    #[test]
+    fn closing_phrases_match_whole_messages_only() {
+        let config = ClosureConfig::default();
+        assert!(is_close_signal(&config, "/done"));
+        assert!(is_close_signal(&config, "Thanks, that's all"));
+        assert!(is_close_signal(&config, "  thanks that's all!  "));
+        assert!(is_close_signal(&config, "GOODBYE"));
+
+        assert!(!is_close_signal(&config, "thanks, that's all I could find so far"));
+        assert!(!is_close_signal(&config, "say goodbye in french"));
+        assert!(!is_close_signal(&config, "what's the weather?"));
+    }
+
+    #[tokio::test]
+    async fn a_user_signal_closes_and_runs_the_hooks_once() {
+        let hook = Arc::new(CountingHook::default());
+        let closure = closure(Arc::clone(&hook));
+
+        assert_eq!(
+            closure.note_inbound("s1", "how do I rotate a key?", NOW).await,
+            ClosureDisposition::Continue
+        );
+        assert_eq!(
+            closure.note_inbound("s1", "thanks, that's all", NOW + 60).await,
+            ClosureDisposition::Closed
+        );
+        assert_eq!(hook.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(*hook.last_reason.lock().await, Some(CloseReason::UserSignal));
+        assert_eq!(
+            closure.state("s1").await,
+            ConversationState::SoftClosed {
+                closed_at: NOW + 60,
+                reason: CloseReason::UserSignal
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn idle_conversations_soft_close_at_the_threshold() {
+        let hook = Arc::new(CountingHook::default());
+        let closure = closure(Arc::clone(&hook));
+        closure.note_inbound("s1", "hello", NOW).await;
+        closure.note_inbound("s2", "hello", NOW + 29 * 60).await;
+
+        // 30 minutes after s1's last activity: s1 closes, s2 is still fresh.
+        let closed = closure.sweep_idle(NOW + 30 * 60).await;
+        assert_eq!(closed, ["s1"]);
+        assert_eq!(hook.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(*hook.last_reason.lock().await, Some(CloseReason::Inactivity));
+        assert_eq!(closure.state("s2").await, ConversationState::Open);
+
+        // Already-closed conversations don't close twice.
+        assert!(closure.sweep_idle(NOW + 31 * 60).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_follow_up_reopens_without_a_new_session() {
+        let hook = Arc::new(CountingHook::default());
+        let closure = closure(Arc::clone(&hook));
+        closure.note_inbound("s1", "hello", NOW).await;
+        closure.note_inbound("s1", "/done", NOW + 10).await;
+
+        // The same session reopens; the loop prepends a resuming marker.
+        assert_eq!(
+            closure.note_inbound("s1", "actually, one more thing", NOW + 600).await,
+            ClosureDisposition::Reopened {
+                closed_at: NOW + 10
+            }
+        );
+        assert_eq!(closure.state("s1").await, ConversationState::Open);
+        // Hooks ran only for the close, not the reopen.
+        assert_eq!(hook.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn api_closure_reports_its_reason() {
+        let hook = Arc::new(CountingHook::default());
+        let closure = closure(Arc::clone(&hook));
+        closure.note_inbound("s1", "hello", NOW).await;
+        closure.close_via_api("s1", NOW + 5).await;
+        assert_eq!(*hook.last_reason.lock().await, Some(CloseReason::Api));
+        assert!(matches!(
+            closure.state("s1").await,
+            ConversationState::SoftClosed {
+                reason: CloseReason::Api,
+                ..
+            }
+        ));
+    }
+}
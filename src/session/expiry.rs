@@ -0,0 +1,245 @@
+//! Per-chat conversation expiry.
+//!
+//! Channel sessions otherwise linger "active" forever, keeping context and
+//! TEE resources alive and making the next message continue a stale thread.
+//! After `conversation_ttl_hours` of idleness the chat's session is closed
+//! through the [`SessionCloser`] hook — which summarizes-on-close, releases
+//! TEE handles, and clears the router mapping — so the next message starts
+//! fresh, with the summary reachable via memory retrieval instead of raw
+//! context. `/continue` within the grace period reopens the previous session
+//! instead. The sweep rides on the runtime cleanup loop.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConversationExpiryConfig {
+    /// Idle hours before a conversation is closed. Zero disables expiry.
+    pub conversation_ttl_hours: i64,
+    /// Window after close in which `/continue` reopens the old session.
+    pub grace_period_minutes: i64,
+}
+
+impl Default for ConversationExpiryConfig {
+    fn default() -> Self {
+        Self {
+            conversation_ttl_hours: 72,
+            grace_period_minutes: 60,
+        }
+    }
+}
+
+/// What the tracker tells the message loop to do with an inbound message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Disposition {
+    /// Conversation is live — continue its session.
+    Continue { session_id: String },
+    /// `/continue` within the grace period — reopen the closed session.
+    Reopened { session_id: String },
+    /// No usable session — start fresh (the closed one, if any, stays
+    /// closed; its summary is in memory).
+    StartFresh,
+}
+
+/// Close-side effects live with the runtime: summarization-on-close,
+/// releasing TEE handles, clearing the router mapping.
+#[async_trait]
+pub trait SessionCloser: Send + Sync {
+    async fn close_session(&self, session_id: &str) -> Result<()>;
+}
+
+struct Conversation {
+    session_id: String,
+    last_activity: i64,
+    /// Set when expired; kept around through the grace period.
+    closed_at: Option<i64>,
+}
+
+/// Tracks per-chat conversation liveness, keyed `channel:chat_id`.
+#[derive(Default)]
+pub struct ConversationTracker {
+    conversations: Mutex<HashMap<String, Conversation>>,
+}
+
+impl ConversationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A session was started (or reopened) for a chat.
+    pub async fn register(&self, chat_key: &str, session_id: &str, now: i64) {
+        self.conversations.lock().await.insert(
+            chat_key.to_string(),
+            Conversation {
+                session_id: session_id.to_string(),
+                last_activity: now,
+                closed_at: None,
+            },
+        );
+    }
+
+    /// Route an inbound message for a chat.
+    pub async fn handle_inbound(
+        &self,
+        config: &ConversationExpiryConfig,
+        chat_key: &str,
+        content: &str,
+        now: i64,
+    ) -> Disposition {
+        let mut conversations = self.conversations.lock().await;
+        let Some(conversation) = conversations.get_mut(chat_key) else {
+            return Disposition::StartFresh;
+        };
+        match conversation.closed_at {
+            None => {
+                conversation.last_activity = now;
+                Disposition::Continue {
+                    session_id: conversation.session_id.clone(),
+                }
+            }
+            Some(closed_at) => {
+                let in_grace = now - closed_at < config.grace_period_minutes * 60;
+                if in_grace && content.trim() == "/continue" {
+                    conversation.closed_at = None;
+                    conversation.last_activity = now;
+                    Disposition::Reopened {
+                        session_id: conversation.session_id.clone(),
+                    }
+                } else {
+                    // Stale thread — drop the mapping so the fresh session
+                    // replaces it via `register`.
+                    conversations.remove(chat_key);
+                    Disposition::StartFresh
+                }
+            }
+        }
+    }
+
+    /// Expire idle conversations; rides on the runtime cleanup loop.
+    /// Returns the session IDs that were closed.
+    pub async fn sweep(
+        &self,
+        config: &ConversationExpiryConfig,
+        closer: &dyn SessionCloser,
+        now: i64,
+    ) -> Result<Vec<String>> {
+        if config.conversation_ttl_hours <= 0 {
+            return Ok(Vec::new());
+        }
+        let ttl_secs = config.conversation_ttl_hours * 3600;
+        let mut closed = Vec::new();
+        let mut conversations = self.conversations.lock().await;
+        for conversation in conversations.values_mut() {
+            if conversation.closed_at.is_none() && now - conversation.last_activity >= ttl_secs {
+                closer.close_session(&conversation.session_id).await?;
+                conversation.closed_at = Some(now);
+                closed.push(conversation.session_id.clone());
+            }
+        }
+        Ok(closed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    #[derive(Default)]
+    struct RecordingCloser {
+        closed: StdMutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SessionCloser for RecordingCloser {
+        async fn close_session(&self, session_id: &str) -> Result<()> {
+            self.closed.lock().unwrap().push(session_id.to_string());
+            Ok(())
+        }
+    }
+
+    fn config() -> ConversationExpiryConfig {
+        ConversationExpiryConfig {
+            conversation_ttl_hours: 1,
+            grace_period_minutes: 30,
+        }
+    }
+
+    #[tokio::test]
+    async fn idle_conversation_expires_and_next_message_starts_fresh() {
+        let tracker = ConversationTracker::new();
+        let closer = RecordingCloser::default();
+        tracker.register("telegram:42", "s1", 0).await;
+
+        let closed = tracker.sweep(&config(), &closer, 3_600).await.unwrap();
+        assert_eq!(closed, vec!["s1"]);
+        assert_eq!(*closer.closed.lock().unwrap(), vec!["s1"]);
+
+        // A normal message after the grace period starts a new conversation.
+        let disposition = tracker
+            .handle_inbound(&config(), "telegram:42", "hello again", 3_600 + 2_000)
+            .await;
+        assert_eq!(disposition, Disposition::StartFresh);
+        // Closing is not repeated for an already-closed conversation.
+        assert!(tracker.sweep(&config(), &closer, 10_000).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn continue_within_grace_reopens_the_old_session() {
+        let tracker = ConversationTracker::new();
+        let closer = RecordingCloser::default();
+        tracker.register("telegram:42", "s1", 0).await;
+        tracker.sweep(&config(), &closer, 3_600).await.unwrap();
+
+        let disposition = tracker
+            .handle_inbound(&config(), "telegram:42", "/continue", 3_600 + 600)
+            .await;
+        assert_eq!(
+            disposition,
+            Disposition::Reopened {
+                session_id: "s1".into()
+            }
+        );
+        // Now live again.
+        let disposition = tracker
+            .handle_inbound(&config(), "telegram:42", "where were we?", 3_600 + 700)
+            .await;
+        assert_eq!(
+            disposition,
+            Disposition::Continue {
+                session_id: "s1".into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn continue_after_grace_starts_fresh() {
+        let tracker = ConversationTracker::new();
+        let closer = RecordingCloser::default();
+        tracker.register("telegram:42", "s1", 0).await;
+        tracker.sweep(&config(), &closer, 3_600).await.unwrap();
+
+        let disposition = tracker
+            .handle_inbound(&config(), "telegram:42", "/continue", 3_600 + 31 * 60)
+            .await;
+        assert_eq!(disposition, Disposition::StartFresh);
+    }
+
+    #[tokio::test]
+    async fn active_conversations_are_untouched_by_the_sweep() {
+        let tracker = ConversationTracker::new();
+        let closer = RecordingCloser::default();
+        tracker.register("telegram:42", "s1", 0).await;
+        // Activity keeps it alive past the original TTL.
+        tracker
+            .handle_inbound(&config(), "telegram:42", "still here", 3_000)
+            .await;
+        assert!(tracker.sweep(&config(), &closer, 3_600).await.unwrap().is_empty());
+    }
+}
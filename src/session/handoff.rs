@@ -0,0 +1,262 @@
+//! Human handoff — the escape hatch out of agent-driven conversation.
+//!
+//! In support scenarios a stuck agent (or a user typing `/human`) flags the
+//! session for handoff: agent generation pauses, a configured ops channel is
+//! notified with session context, and an operator replies through the
+//! operator interface — their messages reach the user as ordinary assistant
+//! messages. The session stays paused until an operator resumes it.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::message::{InboundMessage, OutboundMessage};
+use crate::error::{Result, SafeClawError};
+
+/// The chat command that triggers a handoff.
+pub const HANDOFF_COMMAND: &str = "/human";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandoffConfig {
+    pub enabled: bool,
+    /// Where handoff notifications go, as `channel:chat_id`
+    /// (e.g. `slack:C-OPS`). `None` pauses without notifying.
+    pub ops_channel: Option<String>,
+}
+
+impl Default for HandoffConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ops_channel: None,
+        }
+    }
+}
+
+/// One session flagged for human attention.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveHandoff {
+    pub session_id: String,
+    pub channel: String,
+    pub chat_id: String,
+    pub reason: String,
+    pub requested_at: i64,
+}
+
+/// Messages produced by a handoff request, for the caller to deliver.
+#[derive(Debug)]
+pub struct HandoffNotices {
+    /// Acknowledgement sent to the user in place of an agent reply.
+    pub user_ack: OutboundMessage,
+    /// Notification for the ops channel, when one is configured.
+    pub ops_notice: Option<OutboundMessage>,
+}
+
+/// Tracks handed-off sessions; the pipeline checks [`is_paused`] before every
+/// agent turn.
+///
+/// [`is_paused`]: HandoffManager::is_paused
+pub struct HandoffManager {
+    config: HandoffConfig,
+    active: Mutex<HashMap<String, ActiveHandoff>>,
+}
+
+impl HandoffManager {
+    pub fn new(config: HandoffConfig) -> Self {
+        Self {
+            config,
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Intercept `/human` on an inbound message. Returns the notices to
+    /// deliver when the message triggered a handoff; `None` lets the message
+    /// continue to the agent.
+    pub fn handle_inbound(
+        &self,
+        session_id: &str,
+        message: &InboundMessage,
+        now: i64,
+    ) -> Option<HandoffNotices> {
+        if !self.config.enabled || message.content.trim() != HANDOFF_COMMAND {
+            return None;
+        }
+        self.request(
+            session_id,
+            &message.channel,
+            &message.chat_id,
+            "user requested a human",
+            now,
+        )
+        .ok()
+    }
+
+    /// Flag a session for handoff — from `/human`, or from the pipeline when
+    /// the agent is stuck. Errors if a handoff is already active.
+    pub fn request(
+        &self,
+        session_id: &str,
+        channel: &str,
+        chat_id: &str,
+        reason: &str,
+        now: i64,
+    ) -> Result<HandoffNotices> {
+        let mut active = self.active.lock().expect("handoff manager poisoned");
+        if active.contains_key(session_id) {
+            return Err(SafeClawError::Session(format!(
+                "session {session_id} is already handed off"
+            )));
+        }
+        active.insert(
+            session_id.to_string(),
+            ActiveHandoff {
+                session_id: session_id.to_string(),
+                channel: channel.to_string(),
+                chat_id: chat_id.to_string(),
+                reason: reason.to_string(),
+                requested_at: now,
+            },
+        );
+        let ops_notice = self.config.ops_channel.as_deref().and_then(|ops| {
+            let (ops_channel, ops_chat) = ops.split_once(':')?;
+            Some(OutboundMessage {
+                channel: ops_channel.to_string(),
+                chat_id: ops_chat.to_string(),
+                content: format!(
+                    "Human handoff requested for session {session_id} \
+                     ({channel}:{chat_id}): {reason}. Reply via the operator \
+                     interface; the agent is paused until resumed."
+                ),
+            })
+        });
+        Ok(HandoffNotices {
+            user_ack: OutboundMessage {
+                channel: channel.to_string(),
+                chat_id: chat_id.to_string(),
+                content: "Connecting you to a human — the assistant is paused \
+                          until someone takes over."
+                    .into(),
+            },
+            ops_notice,
+        })
+    }
+
+    /// Whether agent generation is paused for this session.
+    pub fn is_paused(&self, session_id: &str) -> bool {
+        self.active
+            .lock()
+            .expect("handoff manager poisoned")
+            .contains_key(session_id)
+    }
+
+    /// An operator message for the user, delivered as if from the assistant.
+    pub fn operator_reply(&self, session_id: &str, text: &str) -> Result<OutboundMessage> {
+        let active = self.active.lock().expect("handoff manager poisoned");
+        let handoff = active.get(session_id).ok_or_else(|| {
+            SafeClawError::NotFound(format!("no active handoff for session {session_id}"))
+        })?;
+        Ok(OutboundMessage {
+            channel: handoff.channel.clone(),
+            chat_id: handoff.chat_id.clone(),
+            content: text.to_string(),
+        })
+    }
+
+    /// Hand the session back to the agent.
+    pub fn resume(&self, session_id: &str) -> Result<()> {
+        let mut active = self.active.lock().expect("handoff manager poisoned");
+        active.remove(session_id).ok_or_else(|| {
+            SafeClawError::NotFound(format!("no active handoff for session {session_id}"))
+        })?;
+        Ok(())
+    }
+
+    /// Active handoffs, oldest first, for the operator interface.
+    pub fn list(&self) -> Vec<ActiveHandoff> {
+        let active = self.active.lock().expect("handoff manager poisoned");
+        let mut handoffs: Vec<ActiveHandoff> = active.values().cloned().collect();
+        handoffs.sort_by_key(|h| (h.requested_at, h.session_id.clone()));
+        handoffs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> HandoffManager {
+        HandoffManager::new(HandoffConfig {
+            enabled: true,
+            ops_channel: Some("slack:C-OPS".into()),
+        })
+    }
+
+    fn inbound(content: &str) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".into(),
+            chat_id: "42".into(),
+            user_id: "u1".into(),
+            content: content.into(),
+            is_direct: true,
+            mentions_bot: false,
+            timestamp: 100,
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn human_command_pauses_the_agent_and_notifies_ops() {
+        let manager = manager();
+        assert!(manager.handle_inbound("s1", &inbound("hello"), 100).is_none());
+        assert!(!manager.is_paused("s1"));
+
+        let notices = manager
+            .handle_inbound("s1", &inbound("/human"), 100)
+            .expect("handoff triggered");
+        assert!(manager.is_paused("s1"));
+        assert_eq!(notices.user_ack.chat_id, "42");
+        let ops = notices.ops_notice.expect("ops notified");
+        assert_eq!(ops.channel, "slack");
+        assert_eq!(ops.chat_id, "C-OPS");
+        assert!(ops.content.contains("session s1"));
+        assert!(ops.content.contains("telegram:42"));
+    }
+
+    #[test]
+    fn operator_replies_reach_the_user_while_paused() {
+        let manager = manager();
+        manager.handle_inbound("s1", &inbound("/human"), 100).unwrap();
+
+        let reply = manager.operator_reply("s1", "Hi, Sam from support here.").unwrap();
+        assert_eq!(reply.channel, "telegram");
+        assert_eq!(reply.chat_id, "42");
+        assert_eq!(reply.content, "Hi, Sam from support here.");
+        // Still paused until an explicit resume.
+        assert!(manager.is_paused("s1"));
+
+        manager.resume("s1").unwrap();
+        assert!(!manager.is_paused("s1"));
+        assert!(manager.operator_reply("s1", "too late").is_err());
+    }
+
+    #[test]
+    fn duplicate_handoff_is_rejected_and_resume_requires_one() {
+        let manager = manager();
+        manager.request("s1", "telegram", "42", "stuck", 100).unwrap();
+        assert!(manager.request("s1", "telegram", "42", "stuck", 101).is_err());
+        assert!(manager.resume("s2").is_err());
+        assert_eq!(manager.list().len(), 1);
+    }
+
+    #[test]
+    fn disabled_config_ignores_the_command() {
+        let manager = HandoffManager::new(HandoffConfig {
+            enabled: false,
+            ops_channel: None,
+        });
+        assert!(manager.handle_inbound("s1", &inbound("/human"), 100).is_none());
+        assert!(!manager.is_paused("s1"));
+    }
+}
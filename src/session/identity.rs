@@ -0,0 +1,273 @@
+//! Explicit cross-channel identity linking.
+//!
+//! The same human on Telegram and WebChat is two strangers until they say
+//! otherwise — silently merging identities would be a privacy hazard on
+//! multi-user installs. Linking is explicit: `/link` issues a short-lived
+//! code on channel A, submitting it on channel B maps both channel
+//! identities to one principal in the persisted identity store. Memory
+//! retrieval, preference profiles, and budgets key on the principal;
+//! sessions stay per-channel. `/links` lists, `/unlink` revokes, and every
+//! link/unlink must be audited by the caller under
+//! [`AUDIT_CATEGORY_IDENTITY`]. Unlinked identities are their own principal
+//! — which is also the migration: each existing user becomes a principal of
+//! one.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::{Result, SafeClawError};
+
+/// Audit category for link and unlink operations.
+pub const AUDIT_CATEGORY_IDENTITY: &str = "identity_link";
+
+/// Seconds a link code stays valid.
+pub const LINK_CODE_TTL_SECS: i64 = 600;
+
+/// One channel-scoped user identity.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ChannelIdentity {
+    pub channel: String,
+    pub user_id: String,
+}
+
+impl ChannelIdentity {
+    pub fn new(channel: impl Into<String>, user_id: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            user_id: user_id.into(),
+        }
+    }
+
+    fn key(&self) -> String {
+        format!("{}:{}", self.channel, self.user_id)
+    }
+
+    /// The principal an identity maps to when it is not linked to anything.
+    fn implicit_principal(&self) -> String {
+        format!("p:{}", self.key())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingLink {
+    identity: ChannelIdentity,
+    issued_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct IdentityState {
+    /// Explicit links only: identity key → principal ID.
+    links: HashMap<String, String>,
+    /// Outstanding link codes.
+    codes: HashMap<String, PendingLink>,
+}
+
+/// Persisted identity store mapping channel identities to principals.
+pub struct IdentityStore {
+    path: Option<PathBuf>,
+    state: Mutex<IdentityState>,
+}
+
+impl IdentityStore {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            state: Mutex::new(IdentityState::default()),
+        }
+    }
+
+    /// Load persisted links; missing file means a fresh store.
+    pub async fn restore(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        match tokio::fs::read(path).await {
+            Ok(bytes) => {
+                *self.state.lock().await = serde_json::from_slice(&bytes)?;
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn persist(&self, state: &IdentityState) -> Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(path, serde_json::to_vec(state)?).await?;
+        }
+        Ok(())
+    }
+
+    /// The principal for an identity — the linked one, or the identity's own
+    /// implicit principal. Memory, preferences, and budgets key on this.
+    pub async fn principal_for(&self, identity: &ChannelIdentity) -> String {
+        self.state
+            .lock()
+            .await
+            .links
+            .get(&identity.key())
+            .cloned()
+            .unwrap_or_else(|| identity.implicit_principal())
+    }
+
+    /// `/link` on channel A: issue a code to submit on the other channel.
+    pub async fn request_link(&self, identity: ChannelIdentity, now: i64) -> Result<String> {
+        let code = format!("{:06}", rand::random::<u32>() % 1_000_000);
+        let mut state = self.state.lock().await;
+        state.codes.retain(|_, p| now - p.issued_at < LINK_CODE_TTL_SECS);
+        state.codes.insert(
+            code.clone(),
+            PendingLink {
+                identity,
+                issued_at: now,
+            },
+        );
+        let snapshot = state.clone();
+        drop(state);
+        self.persist(&snapshot).await?;
+        Ok(code)
+    }
+
+    /// Submit a code on channel B: both identities now map to the principal
+    /// of the identity that requested the code. Returns that principal.
+    pub async fn complete_link(
+        &self,
+        code: &str,
+        identity: ChannelIdentity,
+        now: i64,
+    ) -> Result<String> {
+        let mut state = self.state.lock().await;
+        let pending = state
+            .codes
+            .remove(code)
+            .filter(|p| now - p.issued_at < LINK_CODE_TTL_SECS)
+            .ok_or_else(|| {
+                SafeClawError::Unauthorized("unknown or expired link code".into())
+            })?;
+        let principal = state
+            .links
+            .get(&pending.identity.key())
+            .cloned()
+            .unwrap_or_else(|| pending.identity.implicit_principal());
+        state.links.insert(pending.identity.key(), principal.clone());
+        state.links.insert(identity.key(), principal.clone());
+        let snapshot = state.clone();
+        drop(state);
+        self.persist(&snapshot).await?;
+        Ok(principal)
+    }
+
+    /// `/links`: every identity sharing this identity's principal.
+    pub async fn links_for(&self, identity: &ChannelIdentity) -> Vec<ChannelIdentity> {
+        let principal = self.principal_for(identity).await;
+        let state = self.state.lock().await;
+        let mut linked: Vec<ChannelIdentity> = state
+            .links
+            .iter()
+            .filter(|(_, p)| **p == principal)
+            .filter_map(|(key, _)| {
+                key.split_once(':')
+                    .map(|(channel, user)| ChannelIdentity::new(channel, user))
+            })
+            .collect();
+        linked.sort_by_key(|i| i.key());
+        linked
+    }
+
+    /// `/unlink`: drop this identity back to its own implicit principal,
+    /// splitting access again.
+    pub async fn unlink(&self, identity: &ChannelIdentity) -> Result<()> {
+        let mut state = self.state.lock().await;
+        if state.links.remove(&identity.key()).is_none() {
+            return Err(SafeClawError::NotFound(format!(
+                "{} is not linked",
+                identity.key()
+            )));
+        }
+        let snapshot = state.clone();
+        drop(state);
+        self.persist(&snapshot).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telegram() -> ChannelIdentity {
+        ChannelIdentity::new("telegram", "42")
+    }
+
+    fn webchat() -> ChannelIdentity {
+        ChannelIdentity::new("webchat", "alice")
+    }
+
+    #[tokio::test]
+    async fn unlinked_identities_are_separate_principals() {
+        let store = IdentityStore::new(None);
+        assert_ne!(
+            store.principal_for(&telegram()).await,
+            store.principal_for(&webchat()).await
+        );
+    }
+
+    #[tokio::test]
+    async fn code_flow_links_two_channels_to_one_principal() {
+        let store = IdentityStore::new(None);
+        let code = store.request_link(telegram(), 100).await.unwrap();
+        let principal = store.complete_link(&code, webchat(), 150).await.unwrap();
+
+        // Retrieval on either channel resolves to the shared principal.
+        assert_eq!(store.principal_for(&telegram()).await, principal);
+        assert_eq!(store.principal_for(&webchat()).await, principal);
+        assert_eq!(store.links_for(&telegram()).await.len(), 2);
+
+        // Codes are single-use.
+        assert!(store.complete_link(&code, webchat(), 160).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn expired_codes_are_rejected() {
+        let store = IdentityStore::new(None);
+        let code = store.request_link(telegram(), 0).await.unwrap();
+        let err = store
+            .complete_link(&code, webchat(), LINK_CODE_TTL_SECS + 1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn unlink_splits_access_again() {
+        let store = IdentityStore::new(None);
+        let code = store.request_link(telegram(), 0).await.unwrap();
+        store.complete_link(&code, webchat(), 1).await.unwrap();
+
+        store.unlink(&webchat()).await.unwrap();
+        assert_ne!(
+            store.principal_for(&telegram()).await,
+            store.principal_for(&webchat()).await
+        );
+        // Unlinking an unlinked identity is an error, not a no-op.
+        assert!(store.unlink(&webchat()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn links_persist_across_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identities.json");
+        let store = IdentityStore::new(Some(path.clone()));
+        let code = store.request_link(telegram(), 0).await.unwrap();
+        let principal = store.complete_link(&code, webchat(), 1).await.unwrap();
+
+        let reopened = IdentityStore::new(Some(path));
+        reopened.restore().await.unwrap();
+        assert_eq!(reopened.principal_for(&webchat()).await, principal);
+    }
+}
@@ -0,0 +1,10 @@
+//! Session management — per-channel sessions, identity, routing.
+
+pub mod closure;
+pub mod expiry;
+pub mod handoff;
+pub mod identity;
+pub mod notes;
+pub mod residency;
+pub mod router;
+pub mod transfer;
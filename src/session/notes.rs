@@ -0,0 +1,474 @@
+//! Verbatim secure notes — "store my locker code 4821".
+//!
+//! Some values the user wants kept and returned exactly, without ever
+//! sitting unencrypted in the LLM context, the session history, or the
+//! memory extraction pipeline. Notes are keyed by principal (see
+//! [`identity`]), encrypted at rest with the store key, and retrieved by
+//! exact title. Retrieval is delivered verbatim to the channel —
+//! [`NoteToolOutcome::Verbatim`] bypasses generation entirely, so the value
+//! never becomes model context. Stored and retrieved values are
+//! taint-registered as highly sensitive, so if one does surface in model
+//! output the send boundary blocks it. `/notes` lists titles only; deleting
+//! or overwriting a note requires an explicit confirmation round-trip.
+//!
+//! [`identity`]: crate::session::identity
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::crypto::aead::{self, KEY_SIZE};
+use crate::error::{Result, SafeClawError};
+use crate::guard::taint::TaintRegistry;
+use crate::privacy::SensitivityLevel;
+
+/// Chat command listing note titles.
+pub const NOTES_COMMAND: &str = "/notes";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredNote {
+    title: String,
+    created_at: i64,
+    /// base64 of `aead::encrypt_with_aad(value, principal:title)`.
+    blob: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct NotesState {
+    /// principal → notes.
+    notes: HashMap<String, Vec<StoredNote>>,
+}
+
+/// Title-only listing entry, safe to show anywhere.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteListing {
+    pub title: String,
+    pub created_at: i64,
+}
+
+/// Result of a store or delete attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteMutation {
+    Applied,
+    /// An existing note would be replaced or removed; the caller must ask
+    /// the user and retry with `confirmed = true`.
+    NeedsConfirmation,
+}
+
+/// Encrypted-at-rest notes store keyed by principal.
+pub struct SecureNotesStore {
+    key: [u8; KEY_SIZE],
+    path: Option<PathBuf>,
+    state: Mutex<NotesState>,
+}
+
+impl SecureNotesStore {
+    pub fn new(key: [u8; KEY_SIZE], path: Option<PathBuf>) -> Self {
+        Self {
+            key,
+            path,
+            state: Mutex::new(NotesState::default()),
+        }
+    }
+
+    /// Load persisted notes; missing file means a fresh store.
+    pub async fn restore(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        match tokio::fs::read(path).await {
+            Ok(bytes) => {
+                *self.state.lock().await = serde_json::from_slice(&bytes)?;
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn persist(&self, state: &NotesState) -> Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(path, serde_json::to_vec(state)?).await?;
+        }
+        Ok(())
+    }
+
+    fn aad(principal: &str, title: &str) -> Vec<u8> {
+        format!("{principal}:{title}").into_bytes()
+    }
+
+    /// Store a note. Overwriting an existing title requires `confirmed`.
+    pub async fn store(
+        &self,
+        principal: &str,
+        title: &str,
+        value: &str,
+        confirmed: bool,
+        now: i64,
+    ) -> Result<NoteMutation> {
+        let blob = base64::engine::general_purpose::STANDARD.encode(aead::encrypt_with_aad(
+            &self.key,
+            value.as_bytes(),
+            &Self::aad(principal, title),
+        )?);
+        let mut state = self.state.lock().await;
+        let notes = state.notes.entry(principal.to_string()).or_default();
+        if let Some(existing) = notes.iter_mut().find(|n| n.title == title) {
+            if !confirmed {
+                return Ok(NoteMutation::NeedsConfirmation);
+            }
+            existing.blob = blob;
+            existing.created_at = now;
+        } else {
+            notes.push(StoredNote {
+                title: title.to_string(),
+                created_at: now,
+                blob,
+            });
+        }
+        let snapshot = state.clone();
+        drop(state);
+        self.persist(&snapshot).await?;
+        Ok(NoteMutation::Applied)
+    }
+
+    /// Retrieve by exact title match.
+    pub async fn get(&self, principal: &str, title: &str) -> Result<String> {
+        let state = self.state.lock().await;
+        let note = state
+            .notes
+            .get(principal)
+            .and_then(|notes| notes.iter().find(|n| n.title == title))
+            .ok_or_else(|| SafeClawError::NotFound(format!("no note titled '{title}'")))?;
+        let blob = base64::engine::general_purpose::STANDARD
+            .decode(&note.blob)
+            .map_err(|e| SafeClawError::Crypto(format!("corrupt note blob: {e}")))?;
+        let plaintext = aead::decrypt_with_aad(&self.key, &blob, &Self::aad(principal, title))?;
+        String::from_utf8(plaintext)
+            .map_err(|e| SafeClawError::Crypto(format!("note is not valid UTF-8: {e}")))
+    }
+
+    /// Titles only, oldest first.
+    pub async fn list(&self, principal: &str) -> Vec<NoteListing> {
+        let state = self.state.lock().await;
+        let mut listings: Vec<NoteListing> = state
+            .notes
+            .get(principal)
+            .map(|notes| {
+                notes
+                    .iter()
+                    .map(|n| NoteListing {
+                        title: n.title.clone(),
+                        created_at: n.created_at,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        listings.sort_by_key(|listing| listing.created_at);
+        listings
+    }
+
+    /// Delete by exact title; requires `confirmed`.
+    pub async fn delete(
+        &self,
+        principal: &str,
+        title: &str,
+        confirmed: bool,
+    ) -> Result<NoteMutation> {
+        let mut state = self.state.lock().await;
+        let notes = state
+            .notes
+            .get_mut(principal)
+            .filter(|notes| notes.iter().any(|n| n.title == title))
+            .ok_or_else(|| SafeClawError::NotFound(format!("no note titled '{title}'")))?;
+        if !confirmed {
+            return Ok(NoteMutation::NeedsConfirmation);
+        }
+        notes.retain(|n| n.title != title);
+        let snapshot = state.clone();
+        drop(state);
+        self.persist(&snapshot).await?;
+        Ok(NoteMutation::Applied)
+    }
+}
+
+/// How a note tool result reaches the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteToolOutcome {
+    /// Ordinary tool result, safe to hand back to the LLM.
+    ModelVisible(String),
+    /// Delivered straight to the channel, bypassing generation — the value
+    /// never enters model context.
+    Verbatim(String),
+}
+
+/// Tool-facing wrapper tying the store to taint registration.
+pub struct NotesService {
+    store: Arc<SecureNotesStore>,
+    taint: Arc<TaintRegistry>,
+}
+
+impl NotesService {
+    pub fn new(store: Arc<SecureNotesStore>, taint: Arc<TaintRegistry>) -> Self {
+        Self { store, taint }
+    }
+
+    /// `note_store` tool. The value is taint-registered before anything
+    /// else, so even the storing turn can't echo it back.
+    pub async fn note_store(
+        &self,
+        principal: &str,
+        session_id: &str,
+        title: &str,
+        value: &str,
+        confirmed: bool,
+        now: i64,
+    ) -> Result<NoteToolOutcome> {
+        self.taint
+            .register(session_id, value, SensitivityLevel::HighlySensitive);
+        match self.store.store(principal, title, value, confirmed, now).await? {
+            NoteMutation::Applied => Ok(NoteToolOutcome::ModelVisible(format!(
+                "Stored secure note '{title}'."
+            ))),
+            NoteMutation::NeedsConfirmation => Ok(NoteToolOutcome::ModelVisible(format!(
+                "A note titled '{title}' already exists. Ask the user to \
+                 confirm overwriting it, then retry with confirmed=true."
+            ))),
+        }
+    }
+
+    /// `note_get` tool — the value goes verbatim to the channel.
+    pub async fn note_get(
+        &self,
+        principal: &str,
+        session_id: &str,
+        title: &str,
+    ) -> Result<NoteToolOutcome> {
+        let value = self.store.get(principal, title).await?;
+        self.taint
+            .register(session_id, &value, SensitivityLevel::HighlySensitive);
+        Ok(NoteToolOutcome::Verbatim(value))
+    }
+
+    /// `note_delete` tool.
+    pub async fn note_delete(
+        &self,
+        principal: &str,
+        title: &str,
+        confirmed: bool,
+    ) -> Result<NoteToolOutcome> {
+        match self.store.delete(principal, title, confirmed).await? {
+            NoteMutation::Applied => Ok(NoteToolOutcome::ModelVisible(format!(
+                "Deleted secure note '{title}'."
+            ))),
+            NoteMutation::NeedsConfirmation => Ok(NoteToolOutcome::ModelVisible(format!(
+                "Ask the user to confirm deleting note '{title}', then retry \
+                 with confirmed=true."
+            ))),
+        }
+    }
+
+    /// `/notes` — titles only.
+    pub async fn list_command(&self, principal: &str) -> String {
+        let listings = self.store.list(principal).await;
+        if listings.is_empty() {
+            return "No secure notes stored.".into();
+        }
+        let mut out = String::from("Secure notes:\n");
+        for listing in listings {
+            out.push_str(&format!("• {}\n", listing.title));
+        }
+        out
+    }
+}
+
+/// Tool specs for the note tools exposed to the agent.
+pub fn note_tool_specs() -> Vec<serde_json::Value> {
+    vec![
+        serde_json::json!({
+            "name": "note_store",
+            "description": "Store a value verbatim in the user's encrypted \
+                            secure notes. Use when the user asks to remember \
+                            an exact value (codes, numbers). Overwriting an \
+                            existing title requires confirmed=true after the \
+                            user agrees.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "value": { "type": "string" },
+                    "confirmed": { "type": "boolean" }
+                },
+                "required": ["title", "value"]
+            }
+        }),
+        serde_json::json!({
+            "name": "note_get",
+            "description": "Retrieve a secure note by its exact title. The \
+                            value is sent directly to the user; you will not \
+                            see it.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" }
+                },
+                "required": ["title"]
+            }
+        }),
+        serde_json::json!({
+            "name": "note_delete",
+            "description": "Delete a secure note by its exact title. \
+                            Requires confirmed=true after the user agrees.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "confirmed": { "type": "boolean" }
+                },
+                "required": ["title"]
+            }
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::alerting::AlertMonitor;
+    use crate::guard::taint::enforce_tee_boundary;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn key() -> [u8; KEY_SIZE] {
+        let mut key = [0u8; KEY_SIZE];
+        key[0] = 7;
+        key
+    }
+
+    #[tokio::test]
+    async fn persisted_file_never_contains_the_plaintext_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.json");
+        let store = SecureNotesStore::new(key(), Some(path.clone()));
+        store
+            .store("p:webchat:u1", "locker code", "4821-secret", false, NOW)
+            .await
+            .unwrap();
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("4821-secret"), "plaintext leaked to disk");
+        assert!(raw.contains("locker code"), "title should stay queryable");
+
+        // A fresh store with the same key gets the value back exactly.
+        let reloaded = SecureNotesStore::new(key(), Some(path));
+        reloaded.restore().await.unwrap();
+        assert_eq!(
+            reloaded.get("p:webchat:u1", "locker code").await.unwrap(),
+            "4821-secret"
+        );
+    }
+
+    #[tokio::test]
+    async fn retrieval_is_verbatim_and_requires_the_exact_title() {
+        let service = NotesService::new(
+            Arc::new(SecureNotesStore::new(key(), None)),
+            Arc::new(TaintRegistry::new()),
+        );
+        service
+            .note_store("p1", "s1", "locker code", "4821", false, NOW)
+            .await
+            .unwrap();
+
+        // The value bypasses generation: it comes back as Verbatim, never
+        // as a model-visible tool result.
+        assert_eq!(
+            service.note_get("p1", "s1", "locker code").await.unwrap(),
+            NoteToolOutcome::Verbatim("4821".into())
+        );
+        // Partial titles don't match.
+        assert!(matches!(
+            service.note_get("p1", "s1", "locker").await,
+            Err(SafeClawError::NotFound(_))
+        ));
+        // Neither do other principals.
+        assert!(service.note_get("p2", "s2", "locker code").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn stored_values_are_tainted_so_model_output_cannot_leak_them() {
+        let taint = Arc::new(TaintRegistry::new());
+        let service =
+            NotesService::new(Arc::new(SecureNotesStore::new(key(), None)), taint.clone());
+        service
+            .note_store("p1", "s1", "locker code", "4821-secret", false, NOW)
+            .await
+            .unwrap();
+
+        let monitor = AlertMonitor::default();
+        let err = enforce_tee_boundary(
+            &taint,
+            &monitor,
+            "s1",
+            "your code is 4821-secret",
+            true,
+            NOW,
+        );
+        assert!(err.is_err(), "tainted note value must not pass the boundary");
+    }
+
+    #[tokio::test]
+    async fn overwrite_and_delete_require_confirmation() {
+        let store = SecureNotesStore::new(key(), None);
+        store.store("p1", "wifi", "hunter2", false, NOW).await.unwrap();
+
+        assert_eq!(
+            store.store("p1", "wifi", "hunter3", false, NOW).await.unwrap(),
+            NoteMutation::NeedsConfirmation
+        );
+        assert_eq!(store.get("p1", "wifi").await.unwrap(), "hunter2");
+
+        assert_eq!(
+            store.store("p1", "wifi", "hunter3", true, NOW).await.unwrap(),
+            NoteMutation::Applied
+        );
+        assert_eq!(store.get("p1", "wifi").await.unwrap(), "hunter3");
+
+        assert_eq!(
+            store.delete("p1", "wifi", false).await.unwrap(),
+            NoteMutation::NeedsConfirmation
+        );
+        assert_eq!(
+            store.delete("p1", "wifi", true).await.unwrap(),
+            NoteMutation::Applied
+        );
+        assert!(store.get("p1", "wifi").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn listing_shows_titles_only() {
+        let store = Arc::new(SecureNotesStore::new(key(), None));
+        let service = NotesService::new(store, Arc::new(TaintRegistry::new()));
+        service
+            .note_store("p1", "s1", "locker code", "4821", false, NOW)
+            .await
+            .unwrap();
+        service
+            .note_store("p1", "s1", "wifi", "hunter2", false, NOW + 1)
+            .await
+            .unwrap();
+
+        let listing = service.list_command("p1").await;
+        assert!(listing.contains("locker code"));
+        assert!(listing.contains("wifi"));
+        assert!(!listing.contains("4821"));
+        assert!(!listing.contains("hunter2"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Per-user residency resolution — which region a user's data lives in.
+//!
+//! Provider-level residency (see [`agent::residency`]) answers "which LLM
+//! may see this"; this module answers it per user and extends it to storage
+//! and the TEE: an EU-tagged user's sensitive processing goes to the
+//! EU-affine TEE backend and their memory to the EU store. A user's region
+//! comes from their profile, falling back to a per-channel default derived
+//! from channel metadata (a Matrix homeserver hosted in the EU, for
+//! example). No region means no constraint.
+//!
+//! [`agent::residency`]: crate::agent::residency
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::residency::select_compliant_provider;
+use crate::config::{ModelsConfig, Region};
+use crate::error::{Result, SafeClawError};
+
+/// Backends serving one region.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegionBackends {
+    /// TEE backend name with affinity to this region.
+    pub tee_backend: Option<String>,
+    /// Memory storage location for this region's users.
+    pub memory_store: Option<String>,
+}
+
+/// Configuration under `session.residency`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserResidencyConfig {
+    /// Explicit per-principal regions, set in the user's profile.
+    pub users: HashMap<String, Region>,
+    /// Region assumed for a channel's users when the profile has none.
+    pub channel_defaults: HashMap<String, Region>,
+    /// Region → backends. Keyed by the serialized region name
+    /// (`"eu"`, `"us"`).
+    pub regions: HashMap<Region, RegionBackends>,
+}
+
+impl UserResidencyConfig {
+    /// A user's effective region: profile first, then channel metadata.
+    pub fn region_for(&self, principal: &str, channel: &str) -> Option<Region> {
+        self.users
+            .get(principal)
+            .or_else(|| self.channel_defaults.get(channel))
+            .copied()
+    }
+}
+
+/// Where one user's sensitive data goes — storage, TEE, and provider all
+/// pinned to the same region.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResidencyRouting {
+    pub region: Region,
+    pub tee_backend: Option<String>,
+    pub memory_store: Option<String>,
+    pub provider: String,
+}
+
+/// Resolve the full routing for a user's sensitive data, or `None` when no
+/// residency applies. Errors when a region is required but no compliant
+/// provider or backends are configured for it.
+pub fn route_for_user(
+    session_id: &str,
+    principal: &str,
+    channel: &str,
+    config: &UserResidencyConfig,
+    models: &ModelsConfig,
+) -> Result<Option<ResidencyRouting>> {
+    let Some(region) = config.region_for(principal, channel) else {
+        return Ok(None);
+    };
+    let backends = config.regions.get(&region).ok_or_else(|| {
+        SafeClawError::Config(format!(
+            "user {principal} requires region {region:?} but no backends are \
+             configured for it"
+        ))
+    })?;
+    let decision = select_compliant_provider(session_id, region, models)?;
+    Ok(Some(ResidencyRouting {
+        region,
+        tee_backend: backends.tee_backend.clone(),
+        memory_store: backends.memory_store.clone(),
+        provider: decision.selected_provider,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ProviderConfig, ProviderKind};
+
+    fn models() -> ModelsConfig {
+        let mut models = ModelsConfig {
+            default_provider: "anthropic-eu".into(),
+            fallback_providers: vec!["anthropic-us".into()],
+            ..Default::default()
+        };
+        models.providers.insert(
+            "anthropic-eu".into(),
+            ProviderConfig {
+                region: Some(Region::Eu),
+                ..Default::default()
+            },
+        );
+        models.providers.insert(
+            "anthropic-us".into(),
+            ProviderConfig {
+                region: Some(Region::Us),
+                kind: ProviderKind::Remote,
+                ..Default::default()
+            },
+        );
+        models
+    }
+
+    fn config() -> UserResidencyConfig {
+        let mut config = UserResidencyConfig::default();
+        config.users.insert("p:alice".into(), Region::Eu);
+        config.users.insert("p:bob".into(), Region::Us);
+        config.channel_defaults.insert("matrix".into(), Region::Eu);
+        config.regions.insert(
+            Region::Eu,
+            RegionBackends {
+                tee_backend: Some("tee-eu".into()),
+                memory_store: Some("memory-eu".into()),
+            },
+        );
+        config.regions.insert(
+            Region::Us,
+            RegionBackends {
+                tee_backend: Some("tee-us".into()),
+                memory_store: Some("memory-us".into()),
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn eu_user_routes_to_the_eu_store_tee_and_provider() {
+        let routing = route_for_user("s1", "p:alice", "telegram", &config(), &models())
+            .unwrap()
+            .expect("alice has a region");
+        assert_eq!(routing.region, Region::Eu);
+        assert_eq!(routing.memory_store.as_deref(), Some("memory-eu"));
+        assert_eq!(routing.tee_backend.as_deref(), Some("tee-eu"));
+        assert_eq!(routing.provider, "anthropic-eu");
+    }
+
+    #[test]
+    fn us_user_routes_to_the_us_backends() {
+        let routing = route_for_user("s1", "p:bob", "telegram", &config(), &models())
+            .unwrap()
+            .unwrap();
+        assert_eq!(routing.memory_store.as_deref(), Some("memory-us"));
+        assert_eq!(routing.provider, "anthropic-us");
+    }
+
+    #[test]
+    fn channel_metadata_supplies_the_region_when_the_profile_has_none() {
+        let routing = route_for_user("s1", "p:carol", "matrix", &config(), &models())
+            .unwrap()
+            .unwrap();
+        assert_eq!(routing.region, Region::Eu);
+
+        // No profile region and no channel default: no constraint.
+        assert!(route_for_user("s1", "p:carol", "telegram", &config(), &models())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn a_required_region_without_backends_is_a_config_error() {
+        let mut config = config();
+        config.regions.remove(&Region::Us);
+        let err = route_for_user("s1", "p:bob", "telegram", &config, &models()).unwrap_err();
+        assert!(matches!(err, SafeClawError::Config(_)));
+    }
+}
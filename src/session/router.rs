@@ -0,0 +1,236 @@
+//! Session-to-backend routing.
+//!
+//! With multiple agent engine backends, new sessions are spread by weighted
+//! round-robin or by least-loaded (active-session count scaled by weight).
+//! Existing sessions stay sticky to their backend; only placement of new
+//! sessions goes through the router.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeClawError};
+
+/// Routing strategy for new sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingStrategy {
+    #[default]
+    WeightedRoundRobin,
+    LeastLoaded,
+}
+
+/// One routable backend with its configured weight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendEntry {
+    pub name: String,
+    /// Relative share of new sessions; zero removes the backend from
+    /// rotation without dropping its sticky sessions.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+struct BackendState {
+    weight: u32,
+    /// Smooth weighted round-robin accumulator.
+    current: i64,
+    active_sessions: u32,
+}
+
+/// Routes new sessions across agent backends; sticky sessions bypass it.
+pub struct SessionRouter {
+    strategy: RoutingStrategy,
+    backends: Mutex<HashMap<String, BackendState>>,
+}
+
+impl SessionRouter {
+    pub fn new(strategy: RoutingStrategy, backends: Vec<BackendEntry>) -> Self {
+        Self {
+            strategy,
+            backends: Mutex::new(
+                backends
+                    .into_iter()
+                    .map(|b| {
+                        (
+                            b.name,
+                            BackendState {
+                                weight: b.weight,
+                                current: 0,
+                                active_sessions: 0,
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Pick a backend for a new session and count it as active there.
+    pub fn route_new_session(&self) -> Result<String> {
+        let mut backends = self.backends.lock().expect("session router poisoned");
+        let chosen = match self.strategy {
+            RoutingStrategy::WeightedRoundRobin => pick_weighted(&mut backends),
+            RoutingStrategy::LeastLoaded => pick_least_loaded(&backends),
+        }
+        .ok_or_else(|| SafeClawError::Session("no backend with non-zero weight".into()))?;
+        if let Some(state) = backends.get_mut(&chosen) {
+            state.active_sessions += 1;
+        }
+        Ok(chosen)
+    }
+
+    /// Session terminated — release its load slot.
+    pub fn session_closed(&self, backend: &str) {
+        let mut backends = self.backends.lock().expect("session router poisoned");
+        if let Some(state) = backends.get_mut(backend) {
+            state.active_sessions = state.active_sessions.saturating_sub(1);
+        }
+    }
+
+    /// Current active-session counts, for the status endpoint.
+    pub fn load_snapshot(&self) -> HashMap<String, u32> {
+        self.backends
+            .lock()
+            .expect("session router poisoned")
+            .iter()
+            .map(|(name, state)| (name.clone(), state.active_sessions))
+            .collect()
+    }
+}
+
+/// Smooth weighted round-robin (nginx-style): each pick raises every
+/// accumulator by its weight and the winner pays back the total, giving an
+/// evenly interleaved sequence matching the weight ratios.
+fn pick_weighted(backends: &mut HashMap<String, BackendState>) -> Option<String> {
+    let total: i64 = backends.values().map(|b| i64::from(b.weight)).sum();
+    if total == 0 {
+        return None;
+    }
+    for state in backends.values_mut() {
+        state.current += i64::from(state.weight);
+    }
+    let chosen = backends
+        .iter()
+        .filter(|(_, s)| s.weight > 0)
+        .max_by_key(|(name, s)| (s.current, std::cmp::Reverse(name.as_str())))
+        .map(|(name, _)| name.clone())?;
+    backends.get_mut(&chosen)?.current -= total;
+    Some(chosen)
+}
+
+/// Least-loaded: lowest active-session count divided by weight, so a
+/// double-weight backend carries twice the sessions before losing preference.
+fn pick_least_loaded(backends: &HashMap<String, BackendState>) -> Option<String> {
+    backends
+        .iter()
+        .filter(|(_, s)| s.weight > 0)
+        .min_by(|(a_name, a), (b_name, b)| {
+            let a_load = f64::from(a.active_sessions) / f64::from(a.weight);
+            let b_load = f64::from(b.active_sessions) / f64::from(b.weight);
+            a_load
+                .partial_cmp(&b_load)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a_name.cmp(b_name))
+        })
+        .map(|(name, _)| name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backends() -> Vec<BackendEntry> {
+        vec![
+            BackendEntry {
+                name: "engine-a".into(),
+                weight: 3,
+            },
+            BackendEntry {
+                name: "engine-b".into(),
+                weight: 1,
+            },
+        ]
+    }
+
+    #[test]
+    fn weighted_round_robin_distributes_by_weight() {
+        let router = SessionRouter::new(RoutingStrategy::WeightedRoundRobin, backends());
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for _ in 0..40 {
+            *counts.entry(router.route_new_session().unwrap()).or_default() += 1;
+        }
+        assert_eq!(counts["engine-a"], 30);
+        assert_eq!(counts["engine-b"], 10);
+    }
+
+    #[test]
+    fn least_loaded_prefers_the_emptier_backend() {
+        let router = SessionRouter::new(
+            RoutingStrategy::LeastLoaded,
+            vec![
+                BackendEntry {
+                    name: "engine-a".into(),
+                    weight: 1,
+                },
+                BackendEntry {
+                    name: "engine-b".into(),
+                    weight: 1,
+                },
+            ],
+        );
+        // Seed engine-a with load.
+        assert_eq!(router.route_new_session().unwrap(), "engine-a");
+        assert_eq!(router.route_new_session().unwrap(), "engine-b");
+        // engine-a frees a session → tied again, name order breaks the tie.
+        router.session_closed("engine-a");
+        assert_eq!(router.route_new_session().unwrap(), "engine-a");
+        // Now engine-b is emptier.
+        router.session_closed("engine-b");
+        router.session_closed("engine-b");
+        assert_eq!(router.route_new_session().unwrap(), "engine-b");
+    }
+
+    #[test]
+    fn least_loaded_respects_weights() {
+        let router = SessionRouter::new(RoutingStrategy::LeastLoaded, backends());
+        // Load is active sessions divided by weight, so engine-a (weight 3)
+        // ends up carrying three times the sessions of engine-b (weight 1).
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for _ in 0..8 {
+            *counts.entry(router.route_new_session().unwrap()).or_default() += 1;
+        }
+        assert_eq!(counts["engine-a"], 6);
+        assert_eq!(counts["engine-b"], 2);
+    }
+
+    #[test]
+    fn zero_weight_backends_are_skipped() {
+        let router = SessionRouter::new(
+            RoutingStrategy::WeightedRoundRobin,
+            vec![
+                BackendEntry {
+                    name: "engine-a".into(),
+                    weight: 0,
+                },
+                BackendEntry {
+                    name: "engine-b".into(),
+                    weight: 1,
+                },
+            ],
+        );
+        for _ in 0..5 {
+            assert_eq!(router.route_new_session().unwrap(), "engine-b");
+        }
+    }
+
+    #[test]
+    fn no_routable_backend_is_an_error() {
+        let router = SessionRouter::new(RoutingStrategy::WeightedRoundRobin, Vec::new());
+        assert!(router.route_new_session().is_err());
+    }
+}
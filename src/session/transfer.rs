@@ -0,0 +1,337 @@
+//! Cross-channel session handoff — "continue this on my laptop".
+//!
+//! `/handoff` issues a short-lived claim code for the active session;
+//! entering it on another channel (or the browser UI) rebinds the
+//! conversation there with full context. The agent session itself is
+//! untouched — what moves is the binding, via an alias table that decouples
+//! session identity from the channel-derived session key. The old binding
+//! stays alive so the original channel can keep participating or reclaim;
+//! it just gets a "continued elsewhere" notice. Every handoff is audited.
+//! Sessions that have handled HighlySensitive content can be barred from
+//! handoff to channels without end-to-end encryption.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::log::{AuditLog, AuditSeverity};
+use crate::channels::message::OutboundMessage;
+use crate::error::{Result, SafeClawError};
+use crate::privacy::SensitivityLevel;
+
+/// Chat command issuing a claim code.
+pub const HANDOFF_COMMAND: &str = "/handoff";
+
+/// Audit category for issued and claimed handoffs.
+pub const AUDIT_CATEGORY_HANDOFF: &str = "session_handoff";
+
+/// Configuration under `session.handoff`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransferConfig {
+    pub enabled: bool,
+    /// Seconds a claim code stays valid.
+    pub code_ttl_secs: i64,
+    /// Permit handing HighlySensitive sessions to channels without
+    /// end-to-end encryption. Off by default.
+    pub allow_sensitive_to_non_e2e: bool,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            code_ttl_secs: 300,
+            allow_sensitive_to_non_e2e: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingClaim {
+    session_id: String,
+    from_channel: String,
+    from_chat_id: String,
+    sensitivity: SensitivityLevel,
+    issued_at: i64,
+}
+
+/// A successful claim. The caller rebinds delivery, replays the persisted
+/// session history into the claiming UI, and sends `origin_notice`.
+#[derive(Debug, Clone)]
+pub struct ClaimOutcome {
+    /// The session now also bound to the claiming channel.
+    pub session_id: String,
+    /// "Continued elsewhere" notice for the originating chat.
+    pub origin_notice: OutboundMessage,
+}
+
+/// Maps channel-derived keys (`channel:chat_id`) to session IDs, plus the
+/// claim-code flow. Multiple keys may alias one session — that is the
+/// handoff.
+pub struct HandoffCoordinator {
+    config: TransferConfig,
+    aliases: Mutex<HashMap<String, String>>,
+    codes: Mutex<HashMap<String, PendingClaim>>,
+}
+
+fn alias_key(channel: &str, chat_id: &str) -> String {
+    format!("{channel}:{chat_id}")
+}
+
+impl HandoffCoordinator {
+    pub fn new(config: TransferConfig) -> Self {
+        Self {
+            config,
+            aliases: Mutex::new(HashMap::new()),
+            codes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record the binding created when a session is first opened for a chat.
+    pub fn bind(&self, channel: &str, chat_id: &str, session_id: &str) {
+        self.aliases
+            .lock()
+            .expect("handoff aliases poisoned")
+            .insert(alias_key(channel, chat_id), session_id.to_string());
+    }
+
+    /// The session a chat routes to, through any handoff aliases.
+    pub fn resolve(&self, channel: &str, chat_id: &str) -> Option<String> {
+        self.aliases
+            .lock()
+            .expect("handoff aliases poisoned")
+            .get(&alias_key(channel, chat_id))
+            .cloned()
+    }
+
+    /// All chat keys currently bound to a session, for notices and cleanup.
+    pub fn bindings(&self, session_id: &str) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .aliases
+            .lock()
+            .expect("handoff aliases poisoned")
+            .iter()
+            .filter(|(_, sid)| sid.as_str() == session_id)
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// `/handoff` — issue a claim code for the session bound to this chat.
+    /// `sensitivity` is the highest level the session has handled, checked
+    /// against the E2E restriction at claim time.
+    pub fn request(
+        &self,
+        channel: &str,
+        chat_id: &str,
+        sensitivity: SensitivityLevel,
+        audit: &AuditLog,
+        now: i64,
+    ) -> Result<String> {
+        if !self.config.enabled {
+            return Err(SafeClawError::Session("session handoff is disabled".into()));
+        }
+        let session_id = self.resolve(channel, chat_id).ok_or_else(|| {
+            SafeClawError::NotFound(format!("no active session for {channel}:{chat_id}"))
+        })?;
+        let code = format!("{:06}", rand::random::<u32>() % 1_000_000);
+        let mut codes = self.codes.lock().expect("handoff codes poisoned");
+        codes.retain(|_, p| now - p.issued_at < self.config.code_ttl_secs);
+        codes.insert(
+            code.clone(),
+            PendingClaim {
+                session_id: session_id.clone(),
+                from_channel: channel.to_string(),
+                from_chat_id: chat_id.to_string(),
+                sensitivity,
+                issued_at: now,
+            },
+        );
+        audit.record(
+            "handoff",
+            &session_id,
+            AuditSeverity::Info,
+            AUDIT_CATEGORY_HANDOFF,
+            &format!("handoff code issued from {channel}:{chat_id}"),
+            now,
+        );
+        Ok(code)
+    }
+
+    /// Claim a code on another channel. `claimer_is_e2e` states whether the
+    /// claiming channel is end-to-end encrypted; HighlySensitive sessions
+    /// are refused on non-E2E channels unless configured otherwise.
+    pub fn claim(
+        &self,
+        code: &str,
+        channel: &str,
+        chat_id: &str,
+        claimer_is_e2e: bool,
+        audit: &AuditLog,
+        now: i64,
+    ) -> Result<ClaimOutcome> {
+        let pending = {
+            let mut codes = self.codes.lock().expect("handoff codes poisoned");
+            let pending = codes
+                .get(code)
+                .filter(|p| now - p.issued_at < self.config.code_ttl_secs)
+                .cloned()
+                .ok_or_else(|| {
+                    SafeClawError::NotFound("unknown or expired handoff code".into())
+                })?;
+            if pending.sensitivity >= SensitivityLevel::HighlySensitive
+                && !claimer_is_e2e
+                && !self.config.allow_sensitive_to_non_e2e
+            {
+                audit.record(
+                    "handoff",
+                    &pending.session_id,
+                    AuditSeverity::Warning,
+                    AUDIT_CATEGORY_HANDOFF,
+                    &format!(
+                        "handoff to non-E2E channel {channel} refused for a \
+                         highly sensitive session"
+                    ),
+                    now,
+                );
+                return Err(SafeClawError::Unauthorized(
+                    "this conversation handled highly sensitive data and can \
+                     only continue on an end-to-end encrypted channel"
+                        .into(),
+                ));
+            }
+            codes.remove(code);
+            pending
+        };
+
+        // The new chat aliases onto the existing session; the old binding
+        // stays so the origin can reclaim by simply sending a message.
+        self.bind(channel, chat_id, &pending.session_id);
+        audit.record(
+            "handoff",
+            &pending.session_id,
+            AuditSeverity::Info,
+            AUDIT_CATEGORY_HANDOFF,
+            &format!(
+                "session claimed by {channel}:{chat_id} (from {}:{})",
+                pending.from_channel, pending.from_chat_id
+            ),
+            now,
+        );
+        Ok(ClaimOutcome {
+            session_id: pending.session_id,
+            origin_notice: OutboundMessage {
+                channel: pending.from_channel,
+                chat_id: pending.from_chat_id,
+                content: "This conversation is now continued elsewhere. You can \
+                          keep replying here to pick it back up."
+                    .into(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::log::AuditIngestionConfig;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn audit() -> AuditLog {
+        AuditLog::new(AuditIngestionConfig::default())
+    }
+
+    fn coordinator() -> HandoffCoordinator {
+        let coordinator = HandoffCoordinator::new(TransferConfig::default());
+        coordinator.bind("telegram", "42", "sess-1");
+        coordinator
+    }
+
+    #[test]
+    fn claim_flow_rebinds_and_notifies_the_origin() {
+        let coordinator = coordinator();
+        let audit = audit();
+
+        let code = coordinator
+            .request("telegram", "42", SensitivityLevel::Sensitive, &audit, NOW)
+            .unwrap();
+        let outcome = coordinator
+            .claim(&code, "webchat", "web-7", true, &audit, NOW + 30)
+            .unwrap();
+
+        assert_eq!(outcome.session_id, "sess-1");
+        assert_eq!(outcome.origin_notice.channel, "telegram");
+        assert_eq!(outcome.origin_notice.chat_id, "42");
+        assert!(outcome.origin_notice.content.contains("continued elsewhere"));
+
+        // Codes are single-use.
+        assert!(coordinator
+            .claim(&code, "webchat", "web-8", true, &audit, NOW + 31)
+            .is_err());
+        // Both the issue and the claim are audited.
+        assert_eq!(audit.events().len(), 2);
+    }
+
+    #[test]
+    fn subsequent_messages_route_through_the_alias_on_both_channels() {
+        let coordinator = coordinator();
+        let audit = audit();
+        let code = coordinator
+            .request("telegram", "42", SensitivityLevel::Normal, &audit, NOW)
+            .unwrap();
+        coordinator
+            .claim(&code, "webchat", "web-7", true, &audit, NOW)
+            .unwrap();
+
+        assert_eq!(coordinator.resolve("webchat", "web-7").as_deref(), Some("sess-1"));
+        // The origin keeps its binding and can reclaim by just replying.
+        assert_eq!(coordinator.resolve("telegram", "42").as_deref(), Some("sess-1"));
+        assert_eq!(
+            coordinator.bindings("sess-1"),
+            ["telegram:42", "webchat:web-7"]
+        );
+    }
+
+    #[test]
+    fn highly_sensitive_sessions_refuse_non_e2e_claimers() {
+        let coordinator = coordinator();
+        let audit = audit();
+        let code = coordinator
+            .request(
+                "telegram",
+                "42",
+                SensitivityLevel::HighlySensitive,
+                &audit,
+                NOW,
+            )
+            .unwrap();
+
+        let err = coordinator
+            .claim(&code, "webchat", "web-7", false, &audit, NOW)
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::Unauthorized(_)));
+        assert!(coordinator.resolve("webchat", "web-7").is_none());
+
+        // The same code still works on an E2E channel.
+        assert!(coordinator
+            .claim(&code, "matrix", "!room:hs", true, &audit, NOW)
+            .is_ok());
+    }
+
+    #[test]
+    fn codes_expire_after_the_ttl() {
+        let coordinator = coordinator();
+        let audit = audit();
+        let code = coordinator
+            .request("telegram", "42", SensitivityLevel::Normal, &audit, NOW)
+            .unwrap();
+        let err = coordinator
+            .claim(&code, "webchat", "web-7", true, &audit, NOW + 301)
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::NotFound(_)));
+    }
+}
@@ -0,0 +1,371 @@
+//! Structured config diff and safe-apply on reload.
+//!
+//! A reload (file edit + SIGHUP, or the settings API) should not be a blind
+//! process restart. The new config is validated, diffed against the running
+//! one into structured entries, non-disruptive changes apply live, and only
+//! the subsystems whose config actually demands it are flagged for restart
+//! (toggling TEE, moving the gateway bind address). Dry-run returns the
+//! diff without applying anything. Every applied reload logs its diff to
+//! the event store.
+
+use serde::Serialize;
+
+use crate::config::SafeClawConfig;
+use crate::error::{Result, SafeClawError};
+use crate::events::EventStore;
+
+/// One observed difference between the running and the proposed config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "change")]
+pub enum ConfigChange {
+    ChannelAdded { name: String },
+    ChannelRemoved { name: String },
+    ChannelModified { name: String },
+    DefaultProviderChanged { from: String, to: String },
+    ProvidersModified,
+    TeeToggled { enabled: bool },
+    TeeSettingsModified,
+    GatewayModified,
+    PrivacyModified,
+    LoggingModified,
+    LimitsModified,
+    VersionChanged,
+}
+
+/// Whether a change can be picked up live or forces a subsystem restart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "impact")]
+pub enum Impact {
+    Live,
+    RestartRequired { subsystem: &'static str },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffEntry {
+    #[serde(flatten)]
+    pub change: ConfigChange,
+    #[serde(flatten)]
+    pub impact: Impact,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigDiff {
+    pub entries: Vec<DiffEntry>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Subsystems that must restart for this diff, deduplicated.
+    pub fn restart_subsystems(&self) -> Vec<&'static str> {
+        let mut subsystems: Vec<&'static str> = self
+            .entries
+            .iter()
+            .filter_map(|e| match e.impact {
+                Impact::RestartRequired { subsystem } => Some(subsystem),
+                Impact::Live => None,
+            })
+            .collect();
+        subsystems.sort_unstable();
+        subsystems.dedup();
+        subsystems
+    }
+}
+
+fn section_changed<T: Serialize>(old: &T, new: &T) -> bool {
+    // Sections don't all derive PartialEq; their serialized form is the
+    // identity that matters for a reload anyway.
+    serde_json::to_value(old).ok() != serde_json::to_value(new).ok()
+}
+
+/// Compute the structured diff between the running and the proposed config.
+pub fn diff(old: &SafeClawConfig, new: &SafeClawConfig) -> ConfigDiff {
+    let mut entries = Vec::new();
+
+    if old.version != new.version {
+        entries.push(DiffEntry {
+            change: ConfigChange::VersionChanged,
+            impact: Impact::Live,
+        });
+    }
+    if section_changed(&old.gateway, &new.gateway) {
+        entries.push(DiffEntry {
+            change: ConfigChange::GatewayModified,
+            impact: Impact::RestartRequired {
+                subsystem: "gateway",
+            },
+        });
+    }
+
+    if old.tee.enabled != new.tee.enabled {
+        entries.push(DiffEntry {
+            change: ConfigChange::TeeToggled {
+                enabled: new.tee.enabled,
+            },
+            impact: Impact::RestartRequired { subsystem: "tee" },
+        });
+    } else if section_changed(&old.tee, &new.tee) {
+        entries.push(DiffEntry {
+            change: ConfigChange::TeeSettingsModified,
+            impact: Impact::Live,
+        });
+    }
+
+    let mut channel_names: Vec<&String> =
+        old.channels.0.keys().chain(new.channels.0.keys()).collect();
+    channel_names.sort();
+    channel_names.dedup();
+    for name in channel_names {
+        let change = match (old.channels.0.get(name), new.channels.0.get(name)) {
+            (None, Some(_)) => ConfigChange::ChannelAdded { name: name.clone() },
+            (Some(_), None) => ConfigChange::ChannelRemoved { name: name.clone() },
+            (Some(before), Some(after)) if section_changed(before, after) => {
+                ConfigChange::ChannelModified { name: name.clone() }
+            }
+            _ => continue,
+        };
+        // Channel listeners start and stop individually under the
+        // supervisor; no cross-subsystem restart needed.
+        entries.push(DiffEntry {
+            change,
+            impact: Impact::Live,
+        });
+    }
+
+    if old.models.default_provider != new.models.default_provider {
+        entries.push(DiffEntry {
+            change: ConfigChange::DefaultProviderChanged {
+                from: old.models.default_provider.clone(),
+                to: new.models.default_provider.clone(),
+            },
+            impact: Impact::Live,
+        });
+    }
+    if section_changed(&old.models.providers, &new.models.providers)
+        || old.models.fallback_providers != new.models.fallback_providers
+    {
+        entries.push(DiffEntry {
+            change: ConfigChange::ProvidersModified,
+            impact: Impact::Live,
+        });
+    }
+
+    if section_changed(&old.privacy, &new.privacy) {
+        entries.push(DiffEntry {
+            change: ConfigChange::PrivacyModified,
+            impact: Impact::Live,
+        });
+    }
+    if section_changed(&old.logging, &new.logging) {
+        entries.push(DiffEntry {
+            change: ConfigChange::LoggingModified,
+            impact: Impact::Live,
+        });
+    }
+    if section_changed(&old.limits, &new.limits) {
+        entries.push(DiffEntry {
+            change: ConfigChange::LimitsModified,
+            impact: Impact::Live,
+        });
+    }
+
+    ConfigDiff { entries }
+}
+
+/// Reject configs that cannot work before anything is applied.
+pub fn validate(config: &SafeClawConfig) -> Result<()> {
+    if config.gateway.port == 0 {
+        return Err(SafeClawError::Config("gateway.port must be non-zero".into()));
+    }
+    if !config.models.providers.is_empty()
+        && !config
+            .models
+            .providers
+            .contains_key(&config.models.default_provider)
+    {
+        return Err(SafeClawError::Config(format!(
+            "models.default_provider '{}' is not among the configured providers",
+            config.models.default_provider
+        )));
+    }
+    if !matches!(config.tee.fallback_policy.as_str(), "reject" | "warn" | "allow") {
+        return Err(SafeClawError::Config(format!(
+            "tee.fallback_policy must be one of reject|warn|allow, got '{}'",
+            config.tee.fallback_policy
+        )));
+    }
+    Ok(())
+}
+
+/// Outcome of a safe-apply attempt.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyOutcome {
+    pub diff: ConfigDiff,
+    /// False on dry-run.
+    pub applied: bool,
+    /// Subsystems the runtime must restart to finish applying.
+    pub restart_required: Vec<&'static str>,
+}
+
+/// Validate `proposed`, diff it against `current`, and apply unless
+/// `dry_run`. The caller owns actually restarting the subsystems named in
+/// the outcome; everything else takes effect as soon as `current` is
+/// swapped.
+pub fn safe_apply(
+    current: &mut SafeClawConfig,
+    proposed: SafeClawConfig,
+    dry_run: bool,
+    events: &EventStore,
+    now: i64,
+) -> Result<ApplyOutcome> {
+    validate(&proposed)?;
+    let diff = diff(current, &proposed);
+    let restart_required = diff.restart_subsystems();
+    if dry_run || diff.is_empty() {
+        return Ok(ApplyOutcome {
+            diff,
+            applied: false,
+            restart_required,
+        });
+    }
+    events.create(
+        "config",
+        "reload",
+        &format!(
+            "config reloaded: {} change(s), {} subsystem(s) need restart",
+            diff.entries.len(),
+            restart_required.len()
+        ),
+        &serde_json::to_string(&diff).unwrap_or_default(),
+        "settings",
+        now,
+    );
+    *current = proposed;
+    Ok(ApplyOutcome {
+        diff,
+        applied: true,
+        restart_required,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ChannelEntry, ProviderConfig};
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn base_config() -> SafeClawConfig {
+        let mut config = SafeClawConfig::default();
+        config.channels.0.insert(
+            "telegram".into(),
+            ChannelEntry {
+                enabled: true,
+                ..Default::default()
+            },
+        );
+        config.models.providers.insert(
+            "anthropic".into(),
+            ProviderConfig::default(),
+        );
+        config.models.providers.insert(
+            "ollama".into(),
+            ProviderConfig::default(),
+        );
+        config
+    }
+
+    #[test]
+    fn model_only_change_applies_live_without_touching_channels() {
+        let mut current = base_config();
+        let mut proposed = current.clone();
+        proposed.models.default_provider = "ollama".into();
+
+        let events = EventStore::default();
+        let outcome = safe_apply(&mut current, proposed, false, &events, NOW).unwrap();
+
+        assert!(outcome.applied);
+        assert!(outcome.restart_required.is_empty());
+        assert_eq!(
+            outcome.diff.entries,
+            [DiffEntry {
+                change: ConfigChange::DefaultProviderChanged {
+                    from: "anthropic".into(),
+                    to: "ollama".into(),
+                },
+                impact: Impact::Live,
+            }]
+        );
+        assert_eq!(current.models.default_provider, "ollama");
+    }
+
+    #[test]
+    fn tee_toggle_is_flagged_as_restart_requiring() {
+        let current = base_config();
+        let mut proposed = current.clone();
+        proposed.tee.enabled = !current.tee.enabled;
+
+        let computed = diff(&current, &proposed);
+        assert_eq!(computed.restart_subsystems(), ["tee"]);
+        assert!(matches!(
+            computed.entries[0].change,
+            ConfigChange::TeeToggled { .. }
+        ));
+    }
+
+    #[test]
+    fn channel_add_and_remove_are_named_in_the_diff() {
+        let current = base_config();
+        let mut proposed = current.clone();
+        proposed.channels.0.remove("telegram");
+        proposed
+            .channels
+            .0
+            .insert("matrix".into(), ChannelEntry::default());
+
+        let computed = diff(&current, &proposed);
+        assert!(computed.entries.contains(&DiffEntry {
+            change: ConfigChange::ChannelAdded {
+                name: "matrix".into()
+            },
+            impact: Impact::Live,
+        }));
+        assert!(computed.entries.contains(&DiffEntry {
+            change: ConfigChange::ChannelRemoved {
+                name: "telegram".into()
+            },
+            impact: Impact::Live,
+        }));
+    }
+
+    #[test]
+    fn dry_run_returns_the_diff_without_applying() {
+        let mut current = base_config();
+        let mut proposed = current.clone();
+        proposed.models.default_provider = "ollama".into();
+
+        let events = EventStore::default();
+        let outcome = safe_apply(&mut current, proposed, true, &events, NOW).unwrap();
+        assert!(!outcome.applied);
+        assert!(!outcome.diff.is_empty());
+        assert_eq!(current.models.default_provider, "anthropic");
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected_before_apply() {
+        let mut current = base_config();
+        let mut proposed = current.clone();
+        proposed.models.default_provider = "missing".into();
+        let events = EventStore::default();
+        let err = safe_apply(&mut current, proposed, false, &events, NOW).unwrap_err();
+        assert!(matches!(err, SafeClawError::Config(_)));
+        assert_eq!(current.models.default_provider, "anthropic");
+
+        let mut bad_policy = base_config();
+        bad_policy.tee.fallback_policy = "explode".into();
+        assert!(validate(&bad_policy).is_err());
+    }
+}
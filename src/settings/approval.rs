@@ -0,0 +1,413 @@
+//! Two-party approval for settings-API config changes.
+//!
+//! The settings API can rewrite providers, budgets and privacy rules, so a
+//! compromised UI session used to equal full reconfiguration. With
+//! `settings { require_approval = true }`, a mutating request no longer
+//! applies: it becomes a pending change set — the structured diff from
+//! [`apply::diff`], the requester's identity, a timestamp — and the
+//! running config stays untouched. A *different* admin approves it, via a
+//! second API key or `/config approve <id>` in an admin chat (both paths
+//! land on [`ConfigApprovals::approve`]), after which the change applies
+//! atomically through [`apply::safe_apply`] and the full diff is audited.
+//! Pending changes expire, and a rejection notifies whoever asked.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::audit::log::{AuditLog, AuditSeverity};
+use crate::config::SafeClawConfig;
+use crate::error::{Result, SafeClawError};
+use crate::events::EventStore;
+use crate::settings::apply::{self, ApplyOutcome, ConfigDiff};
+
+/// Admin chat command: `/config approve <id>` (and `… reject <id>`).
+pub const CONFIG_COMMAND: &str = "/config";
+
+/// Audit category for the approval workflow.
+pub const AUDIT_CATEGORY_CONFIG: &str = "config_change";
+
+/// Configuration under `settings`.
+#[derive(Debug, Clone, serde::Deserialize, Serialize)]
+#[serde(default)]
+pub struct ApprovalConfig {
+    pub require_approval: bool,
+    /// How long a pending change stays approvable.
+    pub pending_ttl_secs: i64,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            require_approval: false,
+            pending_ttl_secs: 3600,
+        }
+    }
+}
+
+/// One change set awaiting a second admin. Listed by
+/// `GET /api/settings/pending`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingChange {
+    pub id: String,
+    pub requested_by: String,
+    pub requested_at: i64,
+    pub expires_at: i64,
+    pub diff: ConfigDiff,
+    /// The full proposed config, applied verbatim on approval.
+    #[serde(skip)]
+    pub proposed: SafeClawConfig,
+}
+
+/// What a mutating settings request produced.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case", tag = "outcome")]
+pub enum SubmitOutcome {
+    /// Approval mode off (or no-op diff): applied immediately.
+    Applied {
+        #[serde(flatten)]
+        apply: ApplyOutcome,
+    },
+    /// Stored for a second admin; nothing applied yet.
+    PendingApproval { id: String, diff: ConfigDiff },
+}
+
+/// Notifies a requester that their change was rejected — a chat message or
+/// UI toast in production.
+pub trait RequesterNotifier: Send + Sync {
+    fn notify(&self, requester: &str, message: &str);
+}
+
+/// The pending-change store and approval logic.
+pub struct ConfigApprovals {
+    config: ApprovalConfig,
+    pending: Mutex<HashMap<String, PendingChange>>,
+    next_id: Mutex<u64>,
+}
+
+impl ConfigApprovals {
+    pub fn new(config: ApprovalConfig) -> Self {
+        Self {
+            config,
+            pending: Mutex::new(HashMap::new()),
+            next_id: Mutex::new(0),
+        }
+    }
+
+    /// Handle one mutating settings request. Without approval mode this is
+    /// exactly the old `safe_apply` path; with it, the validated change is
+    /// parked and the running config is not touched.
+    pub fn submit(
+        &self,
+        current: &mut SafeClawConfig,
+        proposed: SafeClawConfig,
+        requested_by: &str,
+        events: &EventStore,
+        now: i64,
+    ) -> Result<SubmitOutcome> {
+        if !self.config.require_approval {
+            let apply = apply::safe_apply(current, proposed, false, events, now)?;
+            return Ok(SubmitOutcome::Applied { apply });
+        }
+        apply::validate(&proposed)?;
+        let diff = apply::diff(current, &proposed);
+        if diff.is_empty() {
+            return Ok(SubmitOutcome::Applied {
+                apply: apply::safe_apply(current, proposed, false, events, now)?,
+            });
+        }
+        let id = {
+            let mut next = self.next_id.lock().expect("config approvals poisoned");
+            *next += 1;
+            format!("cfg-{}", *next)
+        };
+        self.pending
+            .lock()
+            .expect("config approvals poisoned")
+            .insert(
+                id.clone(),
+                PendingChange {
+                    id: id.clone(),
+                    requested_by: requested_by.to_string(),
+                    requested_at: now,
+                    expires_at: now + self.config.pending_ttl_secs,
+                    diff: diff.clone(),
+                    proposed,
+                },
+            );
+        Ok(SubmitOutcome::PendingApproval { id, diff })
+    }
+
+    /// Pending changes that haven't expired, oldest first; expired ones are
+    /// dropped as a side effect. Backs `GET /api/settings/pending`.
+    pub fn list_pending(&self, now: i64) -> Vec<PendingChange> {
+        let mut pending = self.pending.lock().expect("config approvals poisoned");
+        pending.retain(|_, change| change.expires_at > now);
+        let mut changes: Vec<PendingChange> = pending.values().cloned().collect();
+        changes.sort_by_key(|c| c.requested_at);
+        changes
+    }
+
+    fn take(&self, id: &str, now: i64) -> Result<PendingChange> {
+        let mut pending = self.pending.lock().expect("config approvals poisoned");
+        let change = pending
+            .remove(id)
+            .ok_or_else(|| SafeClawError::NotFound(format!("no pending change {id}")))?;
+        if change.expires_at <= now {
+            return Err(SafeClawError::NotFound(format!(
+                "pending change {id} expired; resubmit it"
+            )));
+        }
+        Ok(change)
+    }
+
+    /// Apply a pending change. `approver` must be a different admin than
+    /// the requester — that's the point of the workflow.
+    pub fn approve(
+        &self,
+        id: &str,
+        approver: &str,
+        current: &mut SafeClawConfig,
+        events: &EventStore,
+        audit: &AuditLog,
+        now: i64,
+    ) -> Result<ApplyOutcome> {
+        let change = self.take(id, now)?;
+        if change.requested_by == approver {
+            // Put it back; someone else may still approve it.
+            self.pending
+                .lock()
+                .expect("config approvals poisoned")
+                .insert(id.to_string(), change);
+            return Err(SafeClawError::Unauthorized(format!(
+                "{approver} requested change {id}; approval needs a second admin"
+            )));
+        }
+        let outcome = apply::safe_apply(current, change.proposed, false, events, now)?;
+        audit.record(
+            "settings",
+            "-",
+            AuditSeverity::Info,
+            AUDIT_CATEGORY_CONFIG,
+            &format!(
+                "change {id} requested by {} approved by {approver}: {}",
+                change.requested_by,
+                serde_json::to_string(&change.diff).unwrap_or_default()
+            ),
+            now,
+        );
+        Ok(outcome)
+    }
+
+    /// Discard a pending change and tell the requester why.
+    pub fn reject(
+        &self,
+        id: &str,
+        approver: &str,
+        reason: &str,
+        notifier: &dyn RequesterNotifier,
+        audit: &AuditLog,
+        now: i64,
+    ) -> Result<()> {
+        let change = self.take(id, now)?;
+        notifier.notify(
+            &change.requested_by,
+            &format!("Your config change {id} was rejected by {approver}: {reason}"),
+        );
+        audit.record(
+            "settings",
+            "-",
+            AuditSeverity::Info,
+            AUDIT_CATEGORY_CONFIG,
+            &format!("change {id} requested by {} rejected by {approver}: {reason}",
+                change.requested_by),
+            now,
+        );
+        Ok(())
+    }
+
+    /// The diff shown by `/config approve <id>` before the admin confirms.
+    pub fn describe(&self, id: &str, now: i64) -> Result<String> {
+        let pending = self.pending.lock().expect("config approvals poisoned");
+        let change = pending
+            .get(id)
+            .filter(|c| c.expires_at > now)
+            .ok_or_else(|| SafeClawError::NotFound(format!("no pending change {id}")))?;
+        let mut out = format!(
+            "Pending change {id} (requested by {} at {}):\n",
+            change.requested_by, change.requested_at
+        );
+        for entry in &change.diff.entries {
+            out.push_str(&format!(
+                "- {}\n",
+                serde_json::to_string(entry).unwrap_or_default()
+            ));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::log::AuditIngestionConfig;
+    use crate::settings::apply::ConfigChange;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[derive(Default)]
+    struct MockNotifier {
+        sent: Mutex<Vec<(String, String)>>,
+    }
+
+    impl RequesterNotifier for MockNotifier {
+        fn notify(&self, requester: &str, message: &str) {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((requester.to_string(), message.to_string()));
+        }
+    }
+
+    fn approvals() -> ConfigApprovals {
+        ConfigApprovals::new(ApprovalConfig {
+            require_approval: true,
+            pending_ttl_secs: 3600,
+        })
+    }
+
+    fn proposed_from(current: &SafeClawConfig) -> SafeClawConfig {
+        let mut proposed = current.clone();
+        proposed.models.providers.insert(
+            "ollama".into(),
+            crate::config::ProviderConfig::default(),
+        );
+        proposed.models.default_provider = "ollama".into();
+        proposed
+    }
+
+    #[test]
+    fn a_submitted_change_is_parked_with_its_diff_and_nothing_applies() {
+        let approvals = approvals();
+        let mut current = SafeClawConfig::default();
+        let before = serde_json::to_value(&current).unwrap();
+        let events = EventStore::default();
+
+        let proposed = proposed_from(&current);
+        let outcome = approvals
+            .submit(&mut current, proposed, "alice", &events, NOW)
+            .unwrap();
+        let SubmitOutcome::PendingApproval { id, diff } = outcome else {
+            panic!("expected PendingApproval");
+        };
+        assert!(diff
+            .entries
+            .iter()
+            .any(|e| matches!(e.change, ConfigChange::DefaultProviderChanged { .. })));
+        // The running config is byte-for-byte untouched.
+        assert_eq!(serde_json::to_value(&current).unwrap(), before);
+        assert!(events.list(None).is_empty());
+        assert_eq!(approvals.list_pending(NOW)[0].id, id);
+    }
+
+    #[test]
+    fn approval_needs_a_second_admin_and_then_applies_atomically() {
+        let approvals = approvals();
+        let mut current = SafeClawConfig::default();
+        let events = EventStore::default();
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+
+        let proposed = proposed_from(&current);
+        let SubmitOutcome::PendingApproval { id, .. } = approvals
+            .submit(&mut current, proposed, "alice", &events, NOW)
+            .unwrap()
+        else {
+            panic!("expected PendingApproval");
+        };
+
+        // The requester approving their own change is refused.
+        let err = approvals
+            .approve(&id, "alice", &mut current, &events, &audit, NOW)
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::Unauthorized(_)));
+        assert_eq!(current.models.default_provider, "anthropic");
+
+        // A second admin sees the diff and approves.
+        assert!(approvals.describe(&id, NOW).unwrap().contains("alice"));
+        let outcome = approvals
+            .approve(&id, "bob", &mut current, &events, &audit, NOW)
+            .unwrap();
+        assert!(outcome.applied);
+        assert_eq!(current.models.default_provider, "ollama");
+        // Audited with the full diff, and gone from the pending list.
+        let audit_events = audit.events();
+        assert_eq!(audit_events[0].category, AUDIT_CATEGORY_CONFIG);
+        assert!(audit_events[0].message.contains("default_provider_changed"));
+        assert!(approvals.list_pending(NOW).is_empty());
+    }
+
+    #[test]
+    fn pending_changes_expire() {
+        let approvals = approvals();
+        let mut current = SafeClawConfig::default();
+        let events = EventStore::default();
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+
+        let proposed = proposed_from(&current);
+        let SubmitOutcome::PendingApproval { id, .. } = approvals
+            .submit(&mut current, proposed, "alice", &events, NOW)
+            .unwrap()
+        else {
+            panic!("expected PendingApproval");
+        };
+
+        let later = NOW + 3601;
+        assert!(approvals.list_pending(later).is_empty());
+        let err = approvals
+            .approve(&id, "bob", &mut current, &events, &audit, later)
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::NotFound(_)));
+        assert_eq!(current.models.default_provider, "anthropic");
+    }
+
+    #[test]
+    fn a_rejection_notifies_the_requester() {
+        let approvals = approvals();
+        let mut current = SafeClawConfig::default();
+        let events = EventStore::default();
+        let audit = AuditLog::new(AuditIngestionConfig::default());
+        let notifier = MockNotifier::default();
+
+        let proposed = proposed_from(&current);
+        let SubmitOutcome::PendingApproval { id, .. } = approvals
+            .submit(&mut current, proposed, "alice", &events, NOW)
+            .unwrap()
+        else {
+            panic!("expected PendingApproval");
+        };
+        approvals
+            .reject(&id, "bob", "not during release week", &notifier, &audit, NOW)
+            .unwrap();
+
+        let sent = notifier.sent.lock().unwrap();
+        assert_eq!(sent[0].0, "alice");
+        assert!(sent[0].1.contains("rejected by bob"));
+        assert!(sent[0].1.contains("not during release week"));
+        assert!(approvals.list_pending(NOW).is_empty());
+    }
+
+    #[test]
+    fn approval_mode_off_applies_immediately() {
+        let approvals = ConfigApprovals::new(ApprovalConfig::default());
+        let mut current = SafeClawConfig::default();
+        let events = EventStore::default();
+        let proposed = proposed_from(&current);
+        let outcome = approvals
+            .submit(&mut current, proposed, "alice", &events, NOW)
+            .unwrap();
+        assert!(matches!(outcome, SubmitOutcome::Applied { .. }));
+        assert_eq!(current.models.default_provider, "ollama");
+    }
+}
@@ -0,0 +1,5 @@
+//! Settings API — runtime-editable configuration with redaction.
+
+pub mod apply;
+pub mod approval;
+pub mod redaction;
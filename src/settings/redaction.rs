@@ -0,0 +1,230 @@
+//! Config-driven redaction of sensitive settings fields.
+//!
+//! The settings API used to hard-code masking for API keys only; webhook
+//! secrets, WeCom `encoding_aes_key`, TEE secret names, and allowed-user
+//! lists came back in cleartext. Redaction is now a policy: built-in default
+//! rules plus config-extended ones, matched by dotted path or field-name
+//! pattern, with a mode per field — `mask` (show `••••`), `omit` (drop the
+//! field), or `show`. The write path pairs with it: a masked value submitted
+//! back unchanged never overwrites the real stored value. The same policy is
+//! applied to `safeclaw config` CLI output.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Placeholder returned for masked fields.
+pub const MASK_PLACEHOLDER: &str = "••••";
+
+/// What to do with a matched field on the read path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionMode {
+    Mask,
+    Omit,
+    Show,
+}
+
+/// One redaction rule. `field` matches by exact dotted path
+/// (`channels.wecom.encoding_aes_key`) or, when it contains no dot, by field
+/// name anywhere in the tree (`api_key`). Later rules win, so config-extended
+/// rules can override the built-ins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub field: String,
+    pub mode: RedactionMode,
+}
+
+/// The full policy: built-in defaults plus config extensions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionPolicy {
+    pub rules: Vec<RedactionRule>,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        let mask = |field: &str| RedactionRule {
+            field: field.into(),
+            mode: RedactionMode::Mask,
+        };
+        Self {
+            rules: vec![
+                mask("api_key"),
+                mask("api_key_ref"),
+                mask("token"),
+                mask("bot_token"),
+                mask("app_secret"),
+                mask("webhook_secret"),
+                mask("encoding_aes_key"),
+                mask("signing_secret"),
+                mask("client_secret_ref"),
+                RedactionRule {
+                    field: "tee.secrets".into(),
+                    mode: RedactionMode::Omit,
+                },
+                RedactionRule {
+                    field: "allowed_users".into(),
+                    mode: RedactionMode::Omit,
+                },
+            ],
+        }
+    }
+}
+
+impl RedactionPolicy {
+    /// Built-in defaults extended by config rules (which take precedence).
+    pub fn with_extensions(extra: Vec<RedactionRule>) -> Self {
+        let mut policy = Self::default();
+        policy.rules.extend(extra);
+        policy
+    }
+
+    fn mode_for(&self, path: &str, field_name: &str) -> RedactionMode {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                if rule.field.contains('.') {
+                    rule.field == path
+                } else {
+                    rule.field == field_name
+                }
+            })
+            .map(|rule| rule.mode)
+            .unwrap_or(RedactionMode::Show)
+    }
+
+    /// Apply the policy to a settings tree for a response or CLI output.
+    pub fn redact(&self, settings: &Value) -> Value {
+        self.redact_at(settings, "")
+    }
+
+    fn redact_at(&self, value: &Value, path: &str) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut out = serde_json::Map::new();
+                for (key, child) in map {
+                    let child_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    match self.mode_for(&child_path, key) {
+                        RedactionMode::Omit => {}
+                        RedactionMode::Mask => {
+                            out.insert(key.clone(), Value::String(MASK_PLACEHOLDER.into()));
+                        }
+                        RedactionMode::Show => {
+                            out.insert(key.clone(), self.redact_at(child, &child_path));
+                        }
+                    }
+                }
+                Value::Object(out)
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.redact_at(item, path))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Write path: merge a submitted settings tree over the stored one,
+    /// keeping the stored value wherever the submission still carries the
+    /// mask placeholder (the user didn't change that field).
+    pub fn merge_submitted(&self, stored: &Value, submitted: &Value) -> Value {
+        match (stored, submitted) {
+            (Value::Object(stored_map), Value::Object(submitted_map)) => {
+                let mut out = serde_json::Map::new();
+                for (key, submitted_child) in submitted_map {
+                    let merged = match stored_map.get(key) {
+                        Some(stored_child) => self.merge_submitted(stored_child, submitted_child),
+                        None => submitted_child.clone(),
+                    };
+                    out.insert(key.clone(), merged);
+                }
+                // Omitted fields never round-trip; keep the stored values.
+                for (key, stored_child) in stored_map {
+                    out.entry(key.clone()).or_insert_with(|| stored_child.clone());
+                }
+                Value::Object(out)
+            }
+            (stored, Value::String(s)) if s == MASK_PLACEHOLDER => stored.clone(),
+            (_, submitted) => submitted.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings() -> Value {
+        json!({
+            "channels": {
+                "telegram": { "bot_token": "123:abc", "enabled": true },
+                "wecom": { "encoding_aes_key": "k3y", "corp_id": "w1" }
+            },
+            "tee": { "secrets": { "anthropic": "ref" }, "enabled": true },
+            "models": { "default_provider": "anthropic" }
+        })
+    }
+
+    #[test]
+    fn defaults_mask_secrets_and_omit_secret_maps() {
+        let redacted = RedactionPolicy::default().redact(&settings());
+        assert_eq!(
+            redacted["channels"]["telegram"]["bot_token"],
+            MASK_PLACEHOLDER
+        );
+        assert_eq!(redacted["channels"]["wecom"]["encoding_aes_key"], MASK_PLACEHOLDER);
+        assert!(redacted["tee"].get("secrets").is_none());
+        assert_eq!(redacted["channels"]["telegram"]["enabled"], true);
+        assert_eq!(redacted["models"]["default_provider"], "anthropic");
+    }
+
+    #[test]
+    fn masked_round_trip_does_not_overwrite_stored_values() {
+        let policy = RedactionPolicy::default();
+        let stored = settings();
+        let mut submitted = policy.redact(&stored);
+        // User edits one visible field and submits the form as-is.
+        submitted["models"]["default_provider"] = json!("ollama");
+
+        let merged = policy.merge_submitted(&stored, &submitted);
+        assert_eq!(merged["channels"]["telegram"]["bot_token"], "123:abc");
+        assert_eq!(merged["tee"]["secrets"]["anthropic"], "ref");
+        assert_eq!(merged["models"]["default_provider"], "ollama");
+    }
+
+    #[test]
+    fn genuinely_changed_secret_is_written() {
+        let policy = RedactionPolicy::default();
+        let stored = settings();
+        let mut submitted = policy.redact(&stored);
+        submitted["channels"]["telegram"]["bot_token"] = json!("456:new");
+        let merged = policy.merge_submitted(&stored, &submitted);
+        assert_eq!(merged["channels"]["telegram"]["bot_token"], "456:new");
+    }
+
+    #[test]
+    fn config_extended_rule_overrides_builtin() {
+        let policy = RedactionPolicy::with_extensions(vec![
+            RedactionRule {
+                field: "corp_id".into(),
+                mode: RedactionMode::Mask,
+            },
+            // Deployment wants token visibility for one channel.
+            RedactionRule {
+                field: "channels.telegram.bot_token".into(),
+                mode: RedactionMode::Show,
+            },
+        ]);
+        let redacted = policy.redact(&settings());
+        assert_eq!(redacted["channels"]["wecom"]["corp_id"], MASK_PLACEHOLDER);
+        assert_eq!(redacted["channels"]["telegram"]["bot_token"], "123:abc");
+    }
+}
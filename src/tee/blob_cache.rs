@@ -0,0 +1,293 @@
+//! Content-addressed caching of large TEE request payloads.
+//!
+//! Sensitive sessions that repeatedly operate on the same document would
+//! otherwise push the full content through the TEE channel on every turn.
+//! Payloads above a size threshold are stored once in the enclave-side cache
+//! (keyed by content hash, inside the session's isolation scope) and later
+//! requests send only the hash. The gateway tracks which hashes each session's
+//! enclave holds, caps the total cached size with LRU eviction, wipes entries
+//! with the session, and exposes hit/miss counters.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::error::{Result, SafeClawError};
+use crate::tee::protocol::{Payload, TeeRequest, TeeResponse, TeeTransport};
+
+/// Configuration under `tee.blob_cache`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlobCacheConfig {
+    /// Payloads at or above this size are cached; smaller ones go inline.
+    pub size_threshold_bytes: usize,
+    /// Cap on total cached bytes per session; LRU entries are evicted.
+    pub max_session_bytes: usize,
+}
+
+impl Default for BlobCacheConfig {
+    fn default() -> Self {
+        Self {
+            size_threshold_bytes: 8 * 1024,
+            max_session_bytes: 4 * 1024 * 1024,
+        }
+    }
+}
+
+/// Cache hit/miss counters, exposed via the TEE status endpoint.
+#[derive(Debug, Default)]
+pub struct BlobCacheStats {
+    pub hits: AtomicU64,
+    pub misses: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct SessionEntries {
+    /// hash → size; insertion order doubles as LRU order via `lru`.
+    sizes: HashMap<String, usize>,
+    /// Least-recently-used first.
+    lru: Vec<String>,
+    total_bytes: usize,
+}
+
+impl SessionEntries {
+    fn touch(&mut self, hash: &str) {
+        self.lru.retain(|h| h != hash);
+        self.lru.push(hash.to_string());
+    }
+}
+
+/// Gateway-side view of the enclave's per-session blob cache.
+pub struct TeeBlobCache {
+    config: BlobCacheConfig,
+    sessions: Mutex<HashMap<String, SessionEntries>>,
+    pub stats: BlobCacheStats,
+}
+
+pub fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+impl TeeBlobCache {
+    pub fn new(config: BlobCacheConfig) -> Self {
+        Self {
+            config,
+            sessions: Mutex::new(HashMap::new()),
+            stats: BlobCacheStats::default(),
+        }
+    }
+
+    /// Turn request content into a protocol payload. Large content is pushed
+    /// once via `PutBlob` and referenced by hash afterwards.
+    pub async fn prepare_payload(
+        &self,
+        session_id: &str,
+        content: &str,
+        transport: &dyn TeeTransport,
+    ) -> Result<Payload> {
+        if content.len() < self.config.size_threshold_bytes {
+            return Ok(Payload::Inline {
+                content: content.to_string(),
+            });
+        }
+        let hash = content_hash(content);
+
+        let mut sessions = self.sessions.lock().await;
+        let entries = sessions.entry(session_id.to_string()).or_default();
+        if entries.sizes.contains_key(&hash) {
+            entries.touch(&hash);
+            self.stats.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Payload::BlobRef { hash });
+        }
+        self.stats.misses.fetch_add(1, Ordering::Relaxed);
+
+        // Evict LRU entries until the new blob fits.
+        while entries.total_bytes + content.len() > self.config.max_session_bytes {
+            let Some(victim) = entries.lru.first().cloned() else {
+                break;
+            };
+            entries.lru.remove(0);
+            if let Some(size) = entries.sizes.remove(&victim) {
+                entries.total_bytes -= size;
+            }
+            ack(transport
+                .send(TeeRequest::DropBlob {
+                    session_id: session_id.to_string(),
+                    hash: victim,
+                })
+                .await?)?;
+        }
+
+        ack(transport
+            .send(TeeRequest::PutBlob {
+                session_id: session_id.to_string(),
+                hash: hash.clone(),
+                content: content.to_string(),
+            })
+            .await?)?;
+
+        entries.sizes.insert(hash.clone(), content.len());
+        entries.total_bytes += content.len();
+        entries.touch(&hash);
+        Ok(Payload::BlobRef { hash })
+    }
+
+    /// Wipe the enclave-side cache for a session (called on session
+    /// termination alongside the rest of the isolation-scope wipe).
+    pub async fn wipe_session(
+        &self,
+        session_id: &str,
+        transport: &dyn TeeTransport,
+    ) -> Result<()> {
+        self.sessions.lock().await.remove(session_id);
+        ack(transport
+            .send(TeeRequest::WipeSession {
+                session_id: session_id.to_string(),
+            })
+            .await?)
+    }
+}
+
+fn ack(response: TeeResponse) -> Result<()> {
+    match response {
+        TeeResponse::Ack | TeeResponse::Processed { .. } => Ok(()),
+        TeeResponse::Error { message } => Err(SafeClawError::Tee(message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    /// Mock transport tracking enclave-side blob state.
+    #[derive(Default)]
+    struct MockTransport {
+        requests: Mutex<Vec<TeeRequest>>,
+        held: Mutex<HashSet<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl TeeTransport for MockTransport {
+        async fn send(&self, request: TeeRequest) -> Result<TeeResponse> {
+            match &request {
+                TeeRequest::PutBlob {
+                    session_id, hash, ..
+                } => {
+                    self.held
+                        .lock()
+                        .await
+                        .insert((session_id.clone(), hash.clone()));
+                }
+                TeeRequest::DropBlob { session_id, hash } => {
+                    self.held
+                        .lock()
+                        .await
+                        .remove(&(session_id.clone(), hash.clone()));
+                }
+                TeeRequest::WipeSession { session_id } => {
+                    self.held.lock().await.retain(|(s, _)| s != session_id);
+                }
+                TeeRequest::Process { .. } => {}
+            }
+            self.requests.lock().await.push(request);
+            Ok(TeeResponse::Ack)
+        }
+    }
+
+    fn cache(threshold: usize, cap: usize) -> TeeBlobCache {
+        TeeBlobCache::new(BlobCacheConfig {
+            size_threshold_bytes: threshold,
+            max_session_bytes: cap,
+        })
+    }
+
+    #[tokio::test]
+    async fn second_request_sends_reference_instead_of_payload() {
+        let cache = cache(16, 1024 * 1024);
+        let transport = Arc::new(MockTransport::default());
+        let document = "a large sensitive document ".repeat(10);
+
+        let first = cache
+            .prepare_payload("s1", &document, transport.as_ref())
+            .await
+            .unwrap();
+        let second = cache
+            .prepare_payload("s1", &document, transport.as_ref())
+            .await
+            .unwrap();
+
+        assert!(matches!(first, Payload::BlobRef { .. }));
+        assert_eq!(first, second);
+        // Exactly one PutBlob crossed the channel.
+        let puts = transport
+            .requests
+            .lock()
+            .await
+            .iter()
+            .filter(|r| matches!(r, TeeRequest::PutBlob { .. }))
+            .count();
+        assert_eq!(puts, 1);
+        assert_eq!(cache.stats.hits.load(Ordering::Relaxed), 1);
+        assert_eq!(cache.stats.misses.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn small_payloads_stay_inline() {
+        let cache = cache(1024, 1024 * 1024);
+        let transport = MockTransport::default();
+        let payload = cache
+            .prepare_payload("s1", "short prompt", &transport)
+            .await
+            .unwrap();
+        assert!(matches!(payload, Payload::Inline { .. }));
+        assert!(transport.requests.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn wipe_clears_enclave_side_entries_and_tracking() {
+        let cache = cache(4, 1024 * 1024);
+        let transport = MockTransport::default();
+        cache
+            .prepare_payload("s1", "document body", &transport)
+            .await
+            .unwrap();
+        assert_eq!(transport.held.lock().await.len(), 1);
+
+        cache.wipe_session("s1", &transport).await.unwrap();
+
+        assert!(transport.held.lock().await.is_empty());
+        // A re-send after wipe must push the blob again, not reference it.
+        cache
+            .prepare_payload("s1", "document body", &transport)
+            .await
+            .unwrap();
+        assert_eq!(cache.stats.misses.load(Ordering::Relaxed), 2);
+        assert_eq!(cache.stats.hits.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn lru_eviction_respects_the_size_cap() {
+        let cache = cache(4, 40);
+        let transport = MockTransport::default();
+        let a = "aaaaaaaaaaaaaaaaaaaa"; // 20 bytes
+        let b = "bbbbbbbbbbbbbbbbbbbb";
+        let c = "cccccccccccccccccccc";
+
+        cache.prepare_payload("s1", a, &transport).await.unwrap();
+        cache.prepare_payload("s1", b, &transport).await.unwrap();
+        // Touch `a` so `b` becomes the LRU victim.
+        cache.prepare_payload("s1", a, &transport).await.unwrap();
+        cache.prepare_payload("s1", c, &transport).await.unwrap();
+
+        let held = transport.held.lock().await;
+        assert!(held.contains(&("s1".into(), content_hash(a))));
+        assert!(!held.contains(&("s1".into(), content_hash(b))));
+        assert!(held.contains(&("s1".into(), content_hash(c))));
+    }
+}
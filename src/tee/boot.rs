@@ -0,0 +1,381 @@
+//! Message queueing across the lazy TEE boot.
+//!
+//! The first HighlySensitive message of the day triggers the MicroVM boot —
+//! 10–30 seconds during which the session used to time out and the user had
+//! to resend. The [`TeeBootGate`] instead queues the triggering message (and
+//! anything that arrives while booting), sends each session a one-time
+//! "setting up" notice, and releases the queue in arrival order once the
+//! orchestrator reports ready. Boot is single-flight: concurrent sessions
+//! share the one boot rather than racing. If boot fails or exceeds the
+//! queue timeout, queued sessions get an apology and the gate resets so the
+//! next message retries.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use crate::channels::message::InboundMessage;
+use crate::error::Result;
+
+/// Boots the enclave VM. Implemented by the A3S Box orchestrator client;
+/// mocked in tests.
+#[async_trait]
+pub trait BootOrchestrator: Send + Sync {
+    /// Boot the VM and return once it is ready to process. Must be safe to
+    /// call again after a failure.
+    async fn boot(&self) -> Result<()>;
+}
+
+/// Configuration under `tee.boot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeeBootConfig {
+    /// How long queued messages wait for the boot before the apology
+    /// fallback.
+    pub queue_timeout_secs: u64,
+    /// One-time notice sent to each session whose messages are queued.
+    pub setup_notice: String,
+    /// Sent instead of processing when the boot fails or times out.
+    pub failure_notice: String,
+}
+
+impl Default for TeeBootConfig {
+    fn default() -> Self {
+        Self {
+            queue_timeout_secs: 45,
+            setup_notice: "Setting up secure processing, one moment…".into(),
+            failure_notice: "Sorry — secure processing isn't available right now. \
+                             Please try sending that again in a minute."
+                .into(),
+        }
+    }
+}
+
+/// Boot progress, exposed on the TEE status endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "phase")]
+pub enum BootPhase {
+    /// No boot has been needed yet.
+    Cold,
+    Booting,
+    Ready,
+    Failed { reason: String },
+}
+
+/// One message held back while the TEE boots.
+#[derive(Debug, Clone)]
+pub struct QueuedInbound {
+    pub session_id: String,
+    pub message: InboundMessage,
+}
+
+/// Result of offering a message to the gate.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnqueueOutcome {
+    /// TEE is up — process the message immediately.
+    Ready,
+    /// Held until boot completes; `notice` is set on the session's first
+    /// queued message so the user is told exactly once.
+    Queued { notice: Option<String> },
+}
+
+/// What [`TeeBootGate::wait_boot`] resolves to, with the queue drained in
+/// arrival order.
+#[derive(Debug)]
+pub enum BootOutcome {
+    /// Process `queued` in order.
+    Ready { queued: Vec<QueuedInbound> },
+    /// Send `apology` to each queued session instead of processing.
+    Failed {
+        queued: Vec<QueuedInbound>,
+        apology: String,
+    },
+}
+
+#[derive(Default)]
+struct GateInner {
+    queue: Vec<QueuedInbound>,
+    /// Sessions already sent the setup notice this boot.
+    noticed: HashSet<String>,
+}
+
+/// Serialized snapshot for the status endpoint.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootStatus {
+    #[serde(flatten)]
+    pub phase: BootPhase,
+    pub queued_messages: usize,
+}
+
+pub struct TeeBootGate {
+    config: TeeBootConfig,
+    orchestrator: Arc<dyn BootOrchestrator>,
+    inner: Mutex<GateInner>,
+    phase_tx: watch::Sender<BootPhase>,
+    /// Kept so the watch channel stays open with no active waiters.
+    _phase_rx: watch::Receiver<BootPhase>,
+}
+
+impl TeeBootGate {
+    pub fn new(config: TeeBootConfig, orchestrator: Arc<dyn BootOrchestrator>) -> Self {
+        let (phase_tx, phase_rx) = watch::channel(BootPhase::Cold);
+        Self {
+            config,
+            orchestrator,
+            inner: Mutex::new(GateInner::default()),
+            phase_tx,
+            _phase_rx: phase_rx,
+        }
+    }
+
+    pub fn phase(&self) -> BootPhase {
+        self.phase_tx.borrow().clone()
+    }
+
+    pub fn status(&self) -> BootStatus {
+        BootStatus {
+            phase: self.phase(),
+            queued_messages: self.inner.lock().expect("boot gate poisoned").queue.len(),
+        }
+    }
+
+    /// Offer a message that needs the TEE. Queues it while a boot is in
+    /// flight, starting the (single, shared) boot if none is.
+    pub fn enqueue(self: &Arc<Self>, item: QueuedInbound) -> EnqueueOutcome {
+        let mut inner = self.inner.lock().expect("boot gate poisoned");
+        let phase = self.phase_tx.borrow().clone();
+        if phase == BootPhase::Ready {
+            return EnqueueOutcome::Ready;
+        }
+        let notice = inner
+            .noticed
+            .insert(item.session_id.clone())
+            .then(|| self.config.setup_notice.clone());
+        inner.queue.push(item);
+        if !matches!(phase, BootPhase::Booting) {
+            // The phase transition happens under the inner lock, so
+            // concurrent enqueues can't each start a boot.
+            self.phase_tx.send_replace(BootPhase::Booting);
+            self.spawn_boot();
+        }
+        EnqueueOutcome::Queued { notice }
+    }
+
+    fn spawn_boot(self: &Arc<Self>) {
+        let gate = Arc::clone(self);
+        tokio::spawn(async move {
+            let deadline = Duration::from_secs(gate.config.queue_timeout_secs);
+            let phase = match tokio::time::timeout(deadline, gate.orchestrator.boot()).await {
+                Ok(Ok(())) => BootPhase::Ready,
+                Ok(Err(e)) => BootPhase::Failed {
+                    reason: e.to_string(),
+                },
+                Err(_) => BootPhase::Failed {
+                    reason: format!(
+                        "boot did not complete within {}s",
+                        gate.config.queue_timeout_secs
+                    ),
+                },
+            };
+            gate.phase_tx.send_replace(phase);
+        });
+    }
+
+    /// Wait for the in-flight boot and take the queued messages. On failure
+    /// the gate resets to cold so the next sensitive message retries the
+    /// boot.
+    pub async fn wait_boot(&self) -> BootOutcome {
+        let mut rx = self.phase_tx.subscribe();
+        loop {
+            let phase = rx.borrow_and_update().clone();
+            match phase {
+                BootPhase::Ready => {
+                    let mut inner = self.inner.lock().expect("boot gate poisoned");
+                    inner.noticed.clear();
+                    return BootOutcome::Ready {
+                        queued: std::mem::take(&mut inner.queue),
+                    };
+                }
+                BootPhase::Failed { .. } => {
+                    let queued = {
+                        let mut inner = self.inner.lock().expect("boot gate poisoned");
+                        inner.noticed.clear();
+                        std::mem::take(&mut inner.queue)
+                    };
+                    self.phase_tx.send_replace(BootPhase::Cold);
+                    return BootOutcome::Failed {
+                        queued,
+                        apology: self.config.failure_notice.clone(),
+                    };
+                }
+                BootPhase::Cold | BootPhase::Booting => {
+                    if rx.changed().await.is_err() {
+                        // Gate dropped mid-boot; treat as failure.
+                        return BootOutcome::Failed {
+                            queued: Vec::new(),
+                            apology: self.config.failure_notice.clone(),
+                        };
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::error::SafeClawError;
+
+    struct SlowOrchestrator {
+        boot_secs: u64,
+        calls: AtomicU32,
+        fail: bool,
+    }
+
+    impl SlowOrchestrator {
+        fn new(boot_secs: u64) -> Self {
+            Self {
+                boot_secs,
+                calls: AtomicU32::new(0),
+                fail: false,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl BootOrchestrator for SlowOrchestrator {
+        async fn boot(&self) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(self.boot_secs)).await;
+            if self.fail {
+                return Err(SafeClawError::Tee("attestation failed".into()));
+            }
+            Ok(())
+        }
+    }
+
+    fn item(session: &str, content: &str) -> QueuedInbound {
+        QueuedInbound {
+            session_id: session.into(),
+            message: InboundMessage {
+                channel: "webchat".into(),
+                chat_id: session.into(),
+                user_id: "u1".into(),
+                content: content.into(),
+                is_direct: true,
+                mentions_bot: false,
+                timestamp: 1_700_000_000,
+                reply_to: None,
+            },
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn queued_messages_are_released_in_arrival_order() {
+        let orchestrator = Arc::new(SlowOrchestrator::new(15));
+        let gate = Arc::new(TeeBootGate::new(TeeBootConfig::default(), orchestrator));
+
+        let first = gate.enqueue(item("s1", "one"));
+        assert!(matches!(first, EnqueueOutcome::Queued { notice: Some(_) }));
+        // Only the session's first message carries the notice.
+        assert_eq!(
+            gate.enqueue(item("s1", "two")),
+            EnqueueOutcome::Queued { notice: None }
+        );
+        gate.enqueue(item("s1", "three"));
+        assert_eq!(gate.phase(), BootPhase::Booting);
+
+        match gate.wait_boot().await {
+            BootOutcome::Ready { queued } => {
+                let contents: Vec<&str> =
+                    queued.iter().map(|q| q.message.content.as_str()).collect();
+                assert_eq!(contents, ["one", "two", "three"]);
+            }
+            other => panic!("expected Ready, got {other:?}"),
+        }
+        // Once up, new messages bypass the queue.
+        assert_eq!(gate.enqueue(item("s1", "four")), EnqueueOutcome::Ready);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_sessions_share_a_single_boot() {
+        let orchestrator = Arc::new(SlowOrchestrator::new(15));
+        let gate = Arc::new(TeeBootGate::new(
+            TeeBootConfig::default(),
+            orchestrator.clone(),
+        ));
+
+        let a = gate.enqueue(item("s1", "from s1"));
+        let b = gate.enqueue(item("s2", "from s2"));
+        // Each session gets its own one-time notice, but only one boot runs.
+        assert!(matches!(a, EnqueueOutcome::Queued { notice: Some(_) }));
+        assert!(matches!(b, EnqueueOutcome::Queued { notice: Some(_) }));
+        assert!(matches!(gate.wait_boot().await, BootOutcome::Ready { .. }));
+        assert_eq!(orchestrator.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_boot_times_out_with_an_apology_and_resets() {
+        let orchestrator = Arc::new(SlowOrchestrator::new(300));
+        let gate = Arc::new(TeeBootGate::new(
+            TeeBootConfig {
+                queue_timeout_secs: 45,
+                ..Default::default()
+            },
+            orchestrator.clone(),
+        ));
+
+        gate.enqueue(item("s1", "hello"));
+        match gate.wait_boot().await {
+            BootOutcome::Failed { queued, apology } => {
+                assert_eq!(queued.len(), 1);
+                assert!(apology.contains("try sending that again"));
+            }
+            other => panic!("expected Failed, got {other:?}"),
+        }
+        // The gate resets cold: the next message retries the boot.
+        assert_eq!(gate.phase(), BootPhase::Cold);
+        assert!(matches!(
+            gate.enqueue(item("s1", "retry")),
+            EnqueueOutcome::Queued { notice: Some(_) }
+        ));
+        // The retry boot runs in a spawned task; let it get polled before
+        // asserting it reached the orchestrator.
+        tokio::task::yield_now().await;
+        assert_eq!(orchestrator.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn boot_error_falls_back_like_a_timeout() {
+        let mut orchestrator = SlowOrchestrator::new(5);
+        orchestrator.fail = true;
+        let gate = Arc::new(TeeBootGate::new(
+            TeeBootConfig::default(),
+            Arc::new(orchestrator),
+        ));
+        gate.enqueue(item("s1", "hello"));
+        assert!(matches!(gate.wait_boot().await, BootOutcome::Failed { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn status_reports_phase_and_queue_depth() {
+        let gate = Arc::new(TeeBootGate::new(
+            TeeBootConfig::default(),
+            Arc::new(SlowOrchestrator::new(15)),
+        ));
+        assert_eq!(gate.status().phase, BootPhase::Cold);
+        gate.enqueue(item("s1", "one"));
+        gate.enqueue(item("s2", "two"));
+        let status = gate.status();
+        assert_eq!(status.phase, BootPhase::Booting);
+        assert_eq!(status.queued_messages, 2);
+    }
+}
@@ -0,0 +1,63 @@
+//! TEE REST API.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::tee::boot::TeeBootGate;
+use crate::tee::secrets::SecretRotator;
+
+#[derive(Debug, Deserialize)]
+pub struct RotateSecretsBody {
+    pub secrets: HashMap<String, String>,
+}
+
+/// Routes mounted under `/api/tee`.
+pub fn routes(rotator: Arc<SecretRotator>) -> Router {
+    Router::new()
+        .route("/secrets/rotate", post(rotate_secrets))
+        .with_state(rotator)
+}
+
+/// `POST /api/tee/secrets/rotate` — push updated secrets into the running VM
+/// over the RA-TLS channel without rebooting. Responds only after the guest
+/// confirms acceptance; on failure the previous secrets stay active.
+async fn rotate_secrets(
+    State(rotator): State<Arc<SecretRotator>>,
+    Json(body): Json<RotateSecretsBody>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match rotator.rotate(body.secrets).await {
+        Ok(ack) => (
+            StatusCode::OK,
+            Json(json!({
+                "accepted": ack.accepted,
+                "wipedPrevious": ack.wiped_previous,
+            })),
+        ),
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            Json(json!({
+                "error": { "code": "tee_rotation_failed", "message": e.to_string() }
+            })),
+        ),
+    }
+}
+
+/// Routes mounted under `/api/tee` alongside [`routes`].
+pub fn boot_routes(gate: Arc<TeeBootGate>) -> Router {
+    Router::new()
+        .route("/status", get(boot_status))
+        .with_state(gate)
+}
+
+/// `GET /api/tee/status` — boot phase and how many messages are queued
+/// behind an in-flight boot.
+async fn boot_status(State(gate): State<Arc<TeeBootGate>>) -> Json<serde_json::Value> {
+    Json(serde_json::to_value(gate.status()).unwrap_or_else(|_| json!({})))
+}
@@ -0,0 +1,11 @@
+//! TEE integration.
+
+pub mod blob_cache;
+pub mod boot;
+pub mod handler;
+pub mod protocol;
+pub mod provisioning;
+pub mod reliability;
+pub mod secrets;
+pub mod security_level;
+pub mod transcript;
@@ -0,0 +1,52 @@
+//! TEE communication protocol — request/response messages exchanged with the
+//! enclave guest over the RA-TLS channel.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Payload of a processing request: either inline content or a reference to
+/// a blob the enclave already holds (see `tee::blob_cache`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Payload {
+    Inline { content: String },
+    BlobRef { hash: String },
+}
+
+/// Requests sent gateway → enclave.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum TeeRequest {
+    Process {
+        session_id: String,
+        payload: Payload,
+    },
+    /// Store a content-addressed blob in the session's enclave-side cache.
+    PutBlob {
+        session_id: String,
+        hash: String,
+        content: String,
+    },
+    /// Drop one cached blob (gateway-driven LRU eviction).
+    DropBlob { session_id: String, hash: String },
+    /// Wipe all enclave-side state for a session, including cached blobs.
+    WipeSession { session_id: String },
+}
+
+/// Responses sent enclave → gateway.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum TeeResponse {
+    Processed { content: String },
+    Ack,
+    Error { message: String },
+}
+
+/// Transport over which protocol messages travel — the RA-TLS channel in
+/// production, a mock in tests.
+#[async_trait]
+pub trait TeeTransport: Send + Sync {
+    async fn send(&self, request: TeeRequest) -> Result<TeeResponse>;
+}
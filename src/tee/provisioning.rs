@@ -0,0 +1,664 @@
+//! Chunked, resumable secret provisioning for large credentials.
+//!
+//! Secret rotation pushes everything in one message, which is fine for an
+//! API key and wrong for a 20 KB service-account JSON or a PKCS#12 bundle:
+//! large payloads hit frame-size limits, and a mid-transfer failure used
+//! to leave the enclave in an unknown partial state. This sub-protocol
+//! transfers a secret as begin/chunk/commit: the enclave stages chunks per
+//! (name, version), verifies the declared content hash on commit, and only
+//! then swaps the secret in — so re-provisioning replaces atomically and a
+//! torn transfer is never visible. After a transport interruption the
+//! orchestrator asks the enclave which chunks it already holds and resends
+//! only the rest. [`StubProvisioningEnclave`] gives non-real-TEE builds
+//! the same semantics in memory.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Result, SafeClawError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProvisioningConfig {
+    /// Bytes per chunk; sized well under the transport's frame limit.
+    pub chunk_bytes: usize,
+    /// Hard cap per secret — anything larger is refused up front.
+    pub max_secret_bytes: usize,
+}
+
+impl Default for ProvisioningConfig {
+    fn default() -> Self {
+        Self {
+            chunk_bytes: 8 * 1024,
+            max_secret_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// The secret-transfer sub-protocol, same tagging as the main TEE
+/// protocol. Chunk data is base64; `sha256` is the hex digest of the full
+/// plaintext, declared at begin and verified by the enclave at commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProvisionRequest {
+    Begin {
+        name: String,
+        version: u64,
+        total_chunks: u32,
+        total_bytes: usize,
+        sha256: String,
+    },
+    Chunk {
+        name: String,
+        version: u64,
+        index: u32,
+        data: String,
+    },
+    Commit {
+        name: String,
+        version: u64,
+    },
+    /// Which chunks of this transfer does the enclave already hold?
+    Status {
+        name: String,
+        version: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProvisionResponse {
+    Accepted,
+    Committed,
+    Holding { chunks: Vec<u32> },
+    Error { message: String },
+}
+
+/// Transport for provisioning frames — the RA-TLS channel in production,
+/// the stub enclave or a mock in tests.
+#[async_trait]
+pub trait ProvisionTransport: Send + Sync {
+    async fn send(&self, request: ProvisionRequest) -> Result<ProvisionResponse>;
+}
+
+/// Where a transfer stands, surfaced on the TEE status endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferState {
+    InProgress,
+    Interrupted,
+    Committed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisionProgress {
+    pub name: String,
+    pub version: u64,
+    pub total_chunks: u32,
+    pub acked_chunks: u32,
+    pub state: TransferState,
+}
+
+/// Gateway-side orchestrator: drives transfers, tracks progress, resumes
+/// interrupted ones.
+pub struct SecretProvisioner {
+    transport: Arc<dyn ProvisionTransport>,
+    config: ProvisioningConfig,
+    /// Monotonic per-secret versions; re-provisioning bumps the version.
+    versions: Mutex<HashMap<String, u64>>,
+    /// Pending payloads kept for resume, keyed by (name, version).
+    pending: Mutex<HashMap<(String, u64), Vec<u8>>>,
+    progress: Mutex<HashMap<String, ProvisionProgress>>,
+}
+
+impl SecretProvisioner {
+    pub fn new(transport: Arc<dyn ProvisionTransport>, config: ProvisioningConfig) -> Self {
+        Self {
+            transport,
+            config,
+            versions: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            progress: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn chunk_count(&self, len: usize) -> u32 {
+        len.div_ceil(self.config.chunk_bytes) as u32
+    }
+
+    fn set_progress(&self, name: &str, version: u64, total: u32, acked: u32, state: TransferState) {
+        self.progress.lock().expect("provisioner poisoned").insert(
+            name.to_string(),
+            ProvisionProgress {
+                name: name.to_string(),
+                version,
+                total_chunks: total,
+                acked_chunks: acked,
+                state,
+            },
+        );
+    }
+
+    fn expect_ok(response: ProvisionResponse) -> Result<()> {
+        match response {
+            ProvisionResponse::Error { message } => Err(SafeClawError::Tee(format!(
+                "enclave refused provisioning frame: {message}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Transfer a secret. On transport interruption the error surfaces but
+    /// the payload is retained; call [`resume`](Self::resume) to finish
+    /// without resending chunks the enclave already holds.
+    pub async fn provision(&self, name: &str, secret: &[u8]) -> Result<u64> {
+        if secret.len() > self.config.max_secret_bytes {
+            return Err(SafeClawError::Config(format!(
+                "secret `{name}` is {} bytes; the provisioning limit is {}",
+                secret.len(),
+                self.config.max_secret_bytes
+            )));
+        }
+        let version = {
+            let mut versions = self.versions.lock().expect("provisioner poisoned");
+            let next = versions.get(name).copied().unwrap_or(0) + 1;
+            versions.insert(name.to_string(), next);
+            next
+        };
+        let total = self.chunk_count(secret.len());
+        self.pending
+            .lock()
+            .expect("provisioner poisoned")
+            .insert((name.to_string(), version), secret.to_vec());
+        self.set_progress(name, version, total, 0, TransferState::InProgress);
+
+        let begin = self
+            .transport
+            .send(ProvisionRequest::Begin {
+                name: name.to_string(),
+                version,
+                total_chunks: total,
+                total_bytes: secret.len(),
+                sha256: hex::encode(Sha256::digest(secret)),
+            })
+            .await;
+        match begin {
+            Ok(response) => Self::expect_ok(response)?,
+            Err(e) => {
+                self.set_progress(name, version, total, 0, TransferState::Interrupted);
+                return Err(e);
+            }
+        }
+        self.send_chunks(name, version, secret, total, &BTreeSet::new())
+            .await?;
+        self.commit(name, version, total).await?;
+        Ok(version)
+    }
+
+    /// Finish an interrupted transfer: ask the enclave which chunks it
+    /// holds, send the rest, commit.
+    pub async fn resume(&self, name: &str) -> Result<u64> {
+        let (version, secret) = {
+            let versions = self.versions.lock().expect("provisioner poisoned");
+            let version = *versions
+                .get(name)
+                .ok_or_else(|| SafeClawError::NotFound(format!("no transfer for `{name}`")))?;
+            let pending = self.pending.lock().expect("provisioner poisoned");
+            let secret = pending
+                .get(&(name.to_string(), version))
+                .ok_or_else(|| {
+                    SafeClawError::NotFound(format!("transfer for `{name}` already completed"))
+                })?
+                .clone();
+            (version, secret)
+        };
+        let total = self.chunk_count(secret.len());
+        let held = match self
+            .transport
+            .send(ProvisionRequest::Status {
+                name: name.to_string(),
+                version,
+            })
+            .await?
+        {
+            ProvisionResponse::Holding { chunks } => chunks.into_iter().collect::<BTreeSet<u32>>(),
+            ProvisionResponse::Error { message } => {
+                return Err(SafeClawError::Tee(format!(
+                    "enclave refused status query: {message}"
+                )))
+            }
+            _ => BTreeSet::new(),
+        };
+        self.set_progress(
+            name,
+            version,
+            total,
+            held.len() as u32,
+            TransferState::InProgress,
+        );
+        self.send_chunks(name, version, &secret, total, &held).await?;
+        self.commit(name, version, total).await?;
+        Ok(version)
+    }
+
+    async fn send_chunks(
+        &self,
+        name: &str,
+        version: u64,
+        secret: &[u8],
+        total: u32,
+        skip: &BTreeSet<u32>,
+    ) -> Result<()> {
+        let mut acked = skip.len() as u32;
+        for (index, chunk) in secret.chunks(self.config.chunk_bytes.max(1)).enumerate() {
+            let index = index as u32;
+            if skip.contains(&index) {
+                continue;
+            }
+            let sent = self
+                .transport
+                .send(ProvisionRequest::Chunk {
+                    name: name.to_string(),
+                    version,
+                    index,
+                    data: BASE64.encode(chunk),
+                })
+                .await;
+            match sent {
+                Ok(response) => Self::expect_ok(response)?,
+                Err(e) => {
+                    self.set_progress(name, version, total, acked, TransferState::Interrupted);
+                    return Err(e);
+                }
+            }
+            acked += 1;
+            self.set_progress(name, version, total, acked, TransferState::InProgress);
+        }
+        Ok(())
+    }
+
+    async fn commit(&self, name: &str, version: u64, total: u32) -> Result<()> {
+        let committed = self
+            .transport
+            .send(ProvisionRequest::Commit {
+                name: name.to_string(),
+                version,
+            })
+            .await;
+        match committed {
+            Ok(ProvisionResponse::Error { message }) => {
+                // A commit refusal (hash mismatch) is not resumable — the
+                // staged transfer is gone on the enclave side too.
+                self.pending
+                    .lock()
+                    .expect("provisioner poisoned")
+                    .remove(&(name.to_string(), version));
+                self.set_progress(name, version, total, total, TransferState::Interrupted);
+                Err(SafeClawError::Tee(format!(
+                    "enclave rejected commit of `{name}` v{version}: {message}"
+                )))
+            }
+            Ok(_) => {
+                self.pending
+                    .lock()
+                    .expect("provisioner poisoned")
+                    .remove(&(name.to_string(), version));
+                self.set_progress(name, version, total, total, TransferState::Committed);
+                Ok(())
+            }
+            Err(e) => {
+                self.set_progress(name, version, total, total, TransferState::Interrupted);
+                Err(e)
+            }
+        }
+    }
+
+    /// Transfer states for the TEE status endpoint, sorted by name.
+    pub fn progress(&self) -> Vec<ProvisionProgress> {
+        let mut entries: Vec<ProvisionProgress> = self
+            .progress
+            .lock()
+            .expect("provisioner poisoned")
+            .values()
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+}
+
+struct StagedTransfer {
+    total_chunks: u32,
+    total_bytes: usize,
+    sha256: String,
+    chunks: BTreeMap<u32, Vec<u8>>,
+}
+
+/// Enclave-side protocol state machine, in memory. The real TEE handler
+/// runs the same logic behind RA-TLS; non-real-TEE builds wire this stub
+/// in directly so behavior matches.
+#[derive(Default)]
+pub struct StubProvisioningEnclave {
+    staging: Mutex<HashMap<(String, u64), StagedTransfer>>,
+    committed: Mutex<HashMap<String, (u64, Vec<u8>)>>,
+}
+
+impl StubProvisioningEnclave {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The committed (version, value) of a secret — partial transfers are
+    /// never visible here.
+    pub fn committed_secret(&self, name: &str) -> Option<(u64, Vec<u8>)> {
+        self.committed
+            .lock()
+            .expect("stub enclave poisoned")
+            .get(name)
+            .cloned()
+    }
+
+    fn handle(&self, request: ProvisionRequest) -> ProvisionResponse {
+        match request {
+            ProvisionRequest::Begin {
+                name,
+                version,
+                total_chunks,
+                total_bytes,
+                sha256,
+            } => {
+                self.staging.lock().expect("stub enclave poisoned").insert(
+                    (name, version),
+                    StagedTransfer {
+                        total_chunks,
+                        total_bytes,
+                        sha256,
+                        chunks: BTreeMap::new(),
+                    },
+                );
+                ProvisionResponse::Accepted
+            }
+            ProvisionRequest::Chunk {
+                name,
+                version,
+                index,
+                data,
+            } => {
+                let mut staging = self.staging.lock().expect("stub enclave poisoned");
+                let Some(transfer) = staging.get_mut(&(name.clone(), version)) else {
+                    return ProvisionResponse::Error {
+                        message: format!("no transfer in progress for `{name}` v{version}"),
+                    };
+                };
+                if index >= transfer.total_chunks {
+                    return ProvisionResponse::Error {
+                        message: format!(
+                            "chunk {index} out of range for `{name}` ({} declared)",
+                            transfer.total_chunks
+                        ),
+                    };
+                }
+                match BASE64.decode(data.as_bytes()) {
+                    Ok(bytes) => {
+                        transfer.chunks.insert(index, bytes);
+                        ProvisionResponse::Accepted
+                    }
+                    Err(e) => ProvisionResponse::Error {
+                        message: format!("chunk {index} is not valid base64: {e}"),
+                    },
+                }
+            }
+            ProvisionRequest::Commit { name, version } => {
+                let mut staging = self.staging.lock().expect("stub enclave poisoned");
+                let Some(transfer) = staging.remove(&(name.clone(), version)) else {
+                    return ProvisionResponse::Error {
+                        message: format!("no transfer in progress for `{name}` v{version}"),
+                    };
+                };
+                if transfer.chunks.len() as u32 != transfer.total_chunks {
+                    return ProvisionResponse::Error {
+                        message: format!(
+                            "`{name}` v{version} has {}/{} chunks",
+                            transfer.chunks.len(),
+                            transfer.total_chunks
+                        ),
+                    };
+                }
+                let assembled: Vec<u8> = transfer.chunks.into_values().flatten().collect();
+                if assembled.len() != transfer.total_bytes
+                    || hex::encode(Sha256::digest(&assembled)) != transfer.sha256
+                {
+                    // The staged transfer was already removed above — a
+                    // bad transfer leaves nothing behind.
+                    return ProvisionResponse::Error {
+                        message: format!("content hash mismatch for `{name}` v{version}"),
+                    };
+                }
+                let mut committed = self.committed.lock().expect("stub enclave poisoned");
+                let stale = committed
+                    .get(&name)
+                    .is_some_and(|(current, _)| *current >= version);
+                if stale {
+                    return ProvisionResponse::Error {
+                        message: format!("`{name}` v{version} is older than the committed version"),
+                    };
+                }
+                committed.insert(name, (version, assembled));
+                ProvisionResponse::Committed
+            }
+            ProvisionRequest::Status { name, version } => {
+                let staging = self.staging.lock().expect("stub enclave poisoned");
+                let chunks = staging
+                    .get(&(name, version))
+                    .map(|transfer| transfer.chunks.keys().copied().collect())
+                    .unwrap_or_default();
+                ProvisionResponse::Holding { chunks }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ProvisionTransport for StubProvisioningEnclave {
+    async fn send(&self, request: ProvisionRequest) -> Result<ProvisionResponse> {
+        Ok(self.handle(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    /// Delegates to the stub enclave but drops the connection after a set
+    /// number of frames, like a transport interruption mid-transfer.
+    struct FlakyTransport {
+        enclave: Arc<StubProvisioningEnclave>,
+        fail_after: AtomicU32,
+        frames: AtomicU32,
+    }
+
+    impl FlakyTransport {
+        fn new(enclave: Arc<StubProvisioningEnclave>, fail_after: u32) -> Self {
+            Self {
+                enclave,
+                fail_after: AtomicU32::new(fail_after),
+                frames: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ProvisionTransport for FlakyTransport {
+        async fn send(&self, request: ProvisionRequest) -> Result<ProvisionResponse> {
+            let frame = self.frames.fetch_add(1, Ordering::SeqCst);
+            if frame >= self.fail_after.load(Ordering::SeqCst) {
+                self.fail_after.store(u32::MAX, Ordering::SeqCst);
+                self.frames.store(0, Ordering::SeqCst);
+                return Err(SafeClawError::Tee("RA-TLS connection reset".into()));
+            }
+            self.enclave.send(request).await
+        }
+    }
+
+    fn config() -> ProvisioningConfig {
+        ProvisioningConfig {
+            chunk_bytes: 4,
+            max_secret_bytes: 64,
+        }
+    }
+
+    #[tokio::test]
+    async fn a_large_secret_transfers_in_chunks_and_commits() {
+        let enclave = Arc::new(StubProvisioningEnclave::new());
+        let provisioner = SecretProvisioner::new(enclave.clone() as Arc<dyn ProvisionTransport>, config());
+
+        let version = provisioner
+            .provision("sa-json", b"0123456789abcdef-")
+            .await
+            .unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(
+            enclave.committed_secret("sa-json"),
+            Some((1, b"0123456789abcdef-".to_vec()))
+        );
+        let progress = provisioner.progress();
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[0].state, TransferState::Committed);
+        assert_eq!(progress[0].acked_chunks, progress[0].total_chunks);
+    }
+
+    #[tokio::test]
+    async fn an_interrupted_transfer_resumes_without_resending_held_chunks() {
+        let enclave = Arc::new(StubProvisioningEnclave::new());
+        // Begin + 2 chunks land, then the connection drops.
+        let transport = Arc::new(FlakyTransport::new(Arc::clone(&enclave), 3));
+        let provisioner = SecretProvisioner::new(
+            Arc::clone(&transport) as Arc<dyn ProvisionTransport>,
+            config(),
+        );
+
+        let err = provisioner.provision("sa-json", b"0123456789abcdef-").await;
+        assert!(err.is_err());
+        // Nothing committed; the enclave is not in a partial state.
+        assert!(enclave.committed_secret("sa-json").is_none());
+        assert_eq!(provisioner.progress()[0].state, TransferState::Interrupted);
+
+        let version = provisioner.resume("sa-json").await.unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(
+            enclave.committed_secret("sa-json"),
+            Some((1, b"0123456789abcdef-".to_vec()))
+        );
+        // Resume sent status + the 3 missing chunks + commit, not all 5
+        // chunks again.
+        assert_eq!(transport.frames.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn a_hash_mismatch_is_rejected_at_commit() {
+        let enclave = StubProvisioningEnclave::new();
+        let corrupt = enclave.handle(ProvisionRequest::Begin {
+            name: "bundle".into(),
+            version: 1,
+            total_chunks: 1,
+            total_bytes: 4,
+            sha256: hex::encode(Sha256::digest(b"good")),
+        });
+        assert!(matches!(corrupt, ProvisionResponse::Accepted));
+        enclave.handle(ProvisionRequest::Chunk {
+            name: "bundle".into(),
+            version: 1,
+            index: 0,
+            data: BASE64.encode(b"evil"),
+        });
+        let response = enclave.handle(ProvisionRequest::Commit {
+            name: "bundle".into(),
+            version: 1,
+        });
+        let ProvisionResponse::Error { message } = response else {
+            panic!("expected commit rejection");
+        };
+        assert!(message.contains("hash mismatch"));
+        assert!(enclave.committed_secret("bundle").is_none());
+    }
+
+    #[tokio::test]
+    async fn re_provisioning_replaces_atomically_only_after_a_verified_transfer() {
+        let enclave = Arc::new(StubProvisioningEnclave::new());
+        let provisioner = SecretProvisioner::new(
+            Arc::clone(&enclave) as Arc<dyn ProvisionTransport>,
+            config(),
+        );
+        provisioner.provision("key", b"old-credential").await.unwrap();
+
+        // A v2 transfer left hanging mid-stream (begun, one chunk, never
+        // committed) is invisible: v1 stays active throughout.
+        enclave.handle(ProvisionRequest::Begin {
+            name: "key".into(),
+            version: 2,
+            total_chunks: 4,
+            total_bytes: 14,
+            sha256: hex::encode(Sha256::digest(b"new-credential")),
+        });
+        enclave.handle(ProvisionRequest::Chunk {
+            name: "key".into(),
+            version: 2,
+            index: 0,
+            data: BASE64.encode(b"new-"),
+        });
+        assert_eq!(
+            enclave.committed_secret("key"),
+            Some((1, b"old-credential".to_vec()))
+        );
+
+        // A completed v2 replaces v1 atomically.
+        provisioner.provision("key", b"new-credential").await.unwrap();
+        assert_eq!(
+            enclave.committed_secret("key"),
+            Some((2, b"new-credential".to_vec()))
+        );
+
+        // A stale version can't clobber a newer committed one.
+        let replay = enclave.handle(ProvisionRequest::Begin {
+            name: "key".into(),
+            version: 1,
+            total_chunks: 1,
+            total_bytes: 3,
+            sha256: hex::encode(Sha256::digest(b"old")),
+        });
+        assert!(matches!(replay, ProvisionResponse::Accepted));
+        enclave.handle(ProvisionRequest::Chunk {
+            name: "key".into(),
+            version: 1,
+            index: 0,
+            data: BASE64.encode(b"old"),
+        });
+        let response = enclave.handle(ProvisionRequest::Commit {
+            name: "key".into(),
+            version: 1,
+        });
+        assert!(matches!(response, ProvisionResponse::Error { .. }));
+        assert_eq!(enclave.committed_secret("key").map(|(v, _)| v), Some(2));
+    }
+
+    #[tokio::test]
+    async fn oversized_secrets_are_refused_up_front() {
+        let enclave = Arc::new(StubProvisioningEnclave::new());
+        let provisioner = SecretProvisioner::new(enclave.clone() as Arc<dyn ProvisionTransport>, config());
+        let err = provisioner
+            .provision("huge", &[0u8; 65])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("65 bytes"));
+        assert!(provisioner.progress().is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+//! Timeout and retry wrapper for the RA-TLS transport.
+//!
+//! A wedged enclave VM must not hang a session forever: every operation gets
+//! a configurable deadline, idempotent operations get a bounded retry, and
+//! repeated timeouts mark the channel unhealthy so the health probe reboots
+//! the VM instead of queuing more doomed requests.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeClawError};
+use crate::tee::protocol::{TeeRequest, TeeResponse, TeeTransport};
+
+/// Timeout/retry knobs, part of `TeeConfig`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeeReliabilityConfig {
+    /// Deadline per operation attempt.
+    pub operation_timeout_ms: u64,
+    /// Extra attempts for idempotent operations after a timeout.
+    pub idempotent_retries: u32,
+    /// Consecutive timeouts (across operations) before the channel is marked
+    /// unhealthy.
+    pub unhealthy_after_timeouts: u32,
+}
+
+impl Default for TeeReliabilityConfig {
+    fn default() -> Self {
+        Self {
+            operation_timeout_ms: 30_000,
+            idempotent_retries: 1,
+            unhealthy_after_timeouts: 3,
+        }
+    }
+}
+
+/// Retrying a `Process` could double-run a generation; blob and wipe
+/// operations are safe to replay.
+fn is_idempotent(request: &TeeRequest) -> bool {
+    !matches!(request, TeeRequest::Process { .. })
+}
+
+/// Wraps a [`TeeTransport`] with per-operation timeouts, bounded retry for
+/// idempotent operations, and an unhealthy latch for the health probe.
+pub struct ReliableTransport {
+    inner: Arc<dyn TeeTransport>,
+    config: TeeReliabilityConfig,
+    consecutive_timeouts: AtomicU32,
+    unhealthy: AtomicBool,
+}
+
+impl ReliableTransport {
+    pub fn new(inner: Arc<dyn TeeTransport>, config: TeeReliabilityConfig) -> Self {
+        Self {
+            inner,
+            config,
+            consecutive_timeouts: AtomicU32::new(0),
+            unhealthy: AtomicBool::new(false),
+        }
+    }
+
+    /// False once repeated timeouts indicate a wedged VM; the health probe
+    /// should trigger a reboot and construct a fresh transport.
+    pub fn is_healthy(&self) -> bool {
+        !self.unhealthy.load(Ordering::SeqCst)
+    }
+
+    fn record_timeout(&self) {
+        let timeouts = self.consecutive_timeouts.fetch_add(1, Ordering::SeqCst) + 1;
+        if timeouts >= self.config.unhealthy_after_timeouts {
+            self.unhealthy.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+#[async_trait]
+impl TeeTransport for ReliableTransport {
+    async fn send(&self, request: TeeRequest) -> Result<TeeResponse> {
+        let timeout = Duration::from_millis(self.config.operation_timeout_ms);
+        let attempts = if is_idempotent(&request) {
+            1 + self.config.idempotent_retries
+        } else {
+            1
+        };
+        for attempt in 0..attempts {
+            match tokio::time::timeout(timeout, self.inner.send(request.clone())).await {
+                Ok(result) => {
+                    if result.is_ok() {
+                        self.consecutive_timeouts.store(0, Ordering::SeqCst);
+                    }
+                    return result;
+                }
+                Err(_) => {
+                    self.record_timeout();
+                    if attempt + 1 < attempts {
+                        continue;
+                    }
+                }
+            }
+        }
+        Err(SafeClawError::Tee(format!(
+            "TEE operation timed out after {}ms ({attempts} attempt(s))",
+            self.config.operation_timeout_ms
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    /// Mock transport: the first `slow_calls` invocations hang past any
+    /// timeout, later ones succeed immediately.
+    struct MockTransport {
+        calls: AtomicU32,
+        slow_calls: u32,
+    }
+
+    impl MockTransport {
+        fn new(slow_calls: u32) -> Self {
+            Self {
+                calls: AtomicU32::new(0),
+                slow_calls,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TeeTransport for MockTransport {
+        async fn send(&self, _request: TeeRequest) -> Result<TeeResponse> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.slow_calls {
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            }
+            Ok(TeeResponse::Ack)
+        }
+    }
+
+    fn config() -> TeeReliabilityConfig {
+        TeeReliabilityConfig {
+            operation_timeout_ms: 50,
+            idempotent_retries: 1,
+            unhealthy_after_timeouts: 3,
+        }
+    }
+
+    fn wipe() -> TeeRequest {
+        TeeRequest::WipeSession {
+            session_id: "s1".into(),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn operation_times_out_after_configured_duration() {
+        let mock = Arc::new(MockTransport::new(u32::MAX));
+        let transport = ReliableTransport::new(mock, config());
+        let err = transport
+            .send(TeeRequest::Process {
+                session_id: "s1".into(),
+                payload: crate::tee::protocol::Payload::Inline {
+                    content: "hi".into(),
+                },
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::Tee(ref m) if m.contains("timed out")));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idempotent_operation_retries_once_and_succeeds() {
+        let mock = Arc::new(MockTransport::new(1));
+        let transport = ReliableTransport::new(mock.clone() as Arc<dyn TeeTransport>, config());
+        let response = transport.send(wipe()).await.unwrap();
+        assert_eq!(response, TeeResponse::Ack);
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn process_is_not_retried() {
+        let mock = Arc::new(MockTransport::new(1));
+        let transport = ReliableTransport::new(mock.clone() as Arc<dyn TeeTransport>, config());
+        let err = transport
+            .send(TeeRequest::Process {
+                session_id: "s1".into(),
+                payload: crate::tee::protocol::Payload::Inline {
+                    content: "hi".into(),
+                },
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SafeClawError::Tee(_)));
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn repeated_timeouts_mark_channel_unhealthy() {
+        let mock = Arc::new(MockTransport::new(u32::MAX));
+        let transport = ReliableTransport::new(mock, config());
+        assert!(transport.is_healthy());
+        // Each idempotent call makes 2 attempts → 2 timeouts; threshold is 3.
+        transport.send(wipe()).await.unwrap_err();
+        transport.send(wipe()).await.unwrap_err();
+        assert!(!transport.is_healthy());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn success_resets_the_timeout_streak() {
+        let mock = Arc::new(MockTransport::new(2));
+        let transport = ReliableTransport::new(mock, config());
+        // 2 timeouts, then success on the third underlying call.
+        transport.send(wipe()).await.unwrap_err();
+        transport.send(wipe()).await.unwrap();
+        assert!(transport.is_healthy());
+    }
+}
@@ -0,0 +1,180 @@
+//! TEE secret provisioning and rotation.
+//!
+//! `TeeConfig.secrets` are injected once at first verification. This module
+//! lets operators rotate credentials used inside the TEE (e.g. the LLM API
+//! key the enclave agent uses) without rebooting the VM: new secrets are
+//! pushed over the RA-TLS channel and only recorded locally once the guest
+//! confirms acceptance. The guest wipes old secret material on accept; the
+//! gateway wipes its previous local copy via `zeroize`.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use zeroize::Zeroize;
+
+use crate::error::{Result, SafeClawError};
+
+/// Request pushed to the guest over RA-TLS.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretRotationRequest {
+    pub secrets: HashMap<String, String>,
+    /// Instructs the guest to zeroize the previous secret material after
+    /// swapping in the new values.
+    pub wipe_previous: bool,
+}
+
+/// Guest acknowledgement of a rotation push.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretRotationAck {
+    pub accepted: bool,
+    pub wiped_previous: bool,
+}
+
+/// Transport abstraction for pushing secrets into the running VM. Implemented
+/// by the RA-TLS channel in production and by mocks in tests.
+#[async_trait]
+pub trait SecretPushChannel: Send + Sync {
+    async fn push_secrets(&self, request: SecretRotationRequest) -> Result<SecretRotationAck>;
+}
+
+/// Tracks the secrets currently provisioned into the TEE and performs
+/// rotations with confirm-before-swap semantics.
+pub struct SecretRotator {
+    channel: Box<dyn SecretPushChannel>,
+    current: RwLock<HashMap<String, String>>,
+}
+
+impl SecretRotator {
+    pub fn new(channel: Box<dyn SecretPushChannel>, initial: HashMap<String, String>) -> Self {
+        Self {
+            channel,
+            current: RwLock::new(initial),
+        }
+    }
+
+    /// Push `new_secrets` into the running VM. The local record is only
+    /// replaced once the guest confirms acceptance — a failed push leaves the
+    /// old secrets in place on both sides.
+    pub async fn rotate(&self, new_secrets: HashMap<String, String>) -> Result<SecretRotationAck> {
+        let ack = self
+            .channel
+            .push_secrets(SecretRotationRequest {
+                secrets: new_secrets.clone(),
+                wipe_previous: true,
+            })
+            .await?;
+
+        if !ack.accepted {
+            return Err(SafeClawError::Tee(
+                "guest rejected secret rotation; previous secrets remain active".into(),
+            ));
+        }
+
+        let mut current = self.current.write().await;
+        for value in current.values_mut() {
+            value.zeroize();
+        }
+        *current = new_secrets;
+        Ok(ack)
+    }
+
+    /// Names of the currently provisioned secrets (values never leave here).
+    pub async fn secret_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.current.read().await.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    #[cfg(test)]
+    pub(crate) async fn current_value(&self, name: &str) -> Option<String> {
+        self.current.read().await.get(name).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    struct MockChannel {
+        fail: AtomicBool,
+        pushed: Arc<RwLock<Vec<SecretRotationRequest>>>,
+    }
+
+    impl MockChannel {
+        fn new(fail: bool) -> Self {
+            Self {
+                fail: AtomicBool::new(fail),
+                pushed: Arc::new(RwLock::new(Vec::new())),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SecretPushChannel for MockChannel {
+        async fn push_secrets(
+            &self,
+            request: SecretRotationRequest,
+        ) -> Result<SecretRotationAck> {
+            if self.fail.load(Ordering::SeqCst) {
+                return Err(SafeClawError::Tee("RA-TLS push failed".into()));
+            }
+            self.pushed.write().await.push(request);
+            Ok(SecretRotationAck {
+                accepted: true,
+                wiped_previous: true,
+            })
+        }
+    }
+
+    fn secrets(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rotation_pushes_new_secrets_and_swaps() {
+        let channel = MockChannel::new(false);
+        let pushed = channel.pushed.clone();
+        let rotator = SecretRotator::new(
+            Box::new(channel),
+            secrets(&[("anthropic_api_key", "old-key")]),
+        );
+
+        let ack = rotator
+            .rotate(secrets(&[("anthropic_api_key", "new-key")]))
+            .await
+            .unwrap();
+
+        assert!(ack.accepted && ack.wiped_previous);
+        assert_eq!(pushed.read().await.len(), 1);
+        assert!(pushed.read().await[0].wipe_previous);
+        assert_eq!(
+            rotator.current_value("anthropic_api_key").await.as_deref(),
+            Some("new-key")
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_push_leaves_old_secrets_in_place() {
+        let rotator = SecretRotator::new(
+            Box::new(MockChannel::new(true)),
+            secrets(&[("anthropic_api_key", "old-key")]),
+        );
+
+        let err = rotator
+            .rotate(secrets(&[("anthropic_api_key", "new-key")]))
+            .await;
+
+        assert!(err.is_err());
+        assert_eq!(
+            rotator.current_value("anthropic_api_key").await.as_deref(),
+            Some("old-key")
+        );
+    }
+}
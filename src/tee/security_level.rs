@@ -0,0 +1,22 @@
+//! TEE security level detection.
+
+use serde::{Deserialize, Serialize};
+
+/// The effective security level of this SafeClaw instance, detected at
+/// startup and surfaced in `/health`, `/status`, and the service descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityLevel {
+    /// SEV-SNP / TDX active, memory encrypted by the CPU.
+    TeeHardware,
+    /// Running in a VM but without hardware TEE.
+    VmIsolation,
+    /// No VM, no TEE — application security only.
+    ProcessOnly,
+}
+
+impl SecurityLevel {
+    pub fn is_tee(self) -> bool {
+        matches!(self, SecurityLevel::TeeHardware)
+    }
+}